@@ -0,0 +1,397 @@
+//! This module contain a dominator tree analysis of functions.
+//!
+//! The dominator tree is computed by the Lengauer-Tarjan algorithm over the
+//! blocks reachable from the entry block of a function. Following LLVM's
+//! convention, an unreachable block is dominated by every block, and does
+//! not dominate any block other than itself.
+
+use std::collections::HashMap;
+
+use either::Either::Right;
+
+use crate::values::{BasicBlock, FunctionValue};
+
+/// Data structure representing the dominator tree of a function.
+#[derive(Debug, Clone)]
+pub struct DominatorTree<'ctx> {
+    /// The function of the tree.
+    function: FunctionValue<'ctx>,
+
+    /// Reachable blocks, in the depth-first pre-order of the control flow
+    /// graph. The root of the tree is at index 0.
+    blocks: Vec<BasicBlock<'ctx>>,
+
+    /// Indices of reachable blocks in `blocks`.
+    indices: HashMap<BasicBlock<'ctx>, usize>,
+
+    /// Index of the immediate dominator of each block, `None` for the root.
+    idoms: Vec<Option<usize>>,
+
+    /// Indices of the children of each block in the tree.
+    children: Vec<Vec<usize>>,
+
+    /// Pre-order number of each block in the tree.
+    tree_in: Vec<usize>,
+
+    /// Post-order number of each block in the tree.
+    tree_out: Vec<usize>,
+
+    /// Indices of blocks in the pre-order of the tree.
+    tree_preorder: Vec<usize>,
+}
+
+impl<'ctx> DominatorTree<'ctx> {
+    /// Constructor, which computes the dominator tree of a function.
+    pub fn new(function: FunctionValue<'ctx>) -> DominatorTree<'ctx> {
+        let (blocks, parents) = compute_dfs_tree(function);
+        let indices: HashMap<BasicBlock, usize> = blocks
+            .iter()
+            .enumerate()
+            .map(|(idx, blk)| (*blk, idx))
+            .collect();
+        let idoms = compute_idoms(&blocks, &indices, &parents);
+
+        let mut children = vec![vec![]; blocks.len()];
+        for (idx, idom) in idoms.iter().enumerate() {
+            if let Some(idom) = idom {
+                children[*idom].push(idx)
+            }
+        }
+
+        let mut tree = DominatorTree {
+            function,
+            blocks,
+            indices,
+            idoms,
+            children,
+            tree_in: vec![],
+            tree_out: vec![],
+            tree_preorder: vec![],
+        };
+        tree.number_tree();
+        tree
+    }
+
+    /// Get the function of the tree.
+    pub fn get_function(&self) -> FunctionValue<'ctx> {
+        self.function
+    }
+
+    /// Get the root of the tree, i.e., the entry block of the function.
+    pub fn get_root(&self) -> Option<BasicBlock<'ctx>> {
+        self.blocks.first().copied()
+    }
+
+    /// Check if a block is reachable from the entry block.
+    pub fn is_reachable(&self, block: BasicBlock<'ctx>) -> bool {
+        self.indices.contains_key(&block)
+    }
+
+    /// Check if `block_a` dominates `block_b`.
+    ///
+    /// A block dominates itself.
+    pub fn dominates(
+        &self,
+        block_a: BasicBlock<'ctx>,
+        block_b: BasicBlock<'ctx>,
+    ) -> bool {
+        if block_a == block_b {
+            return true;
+        }
+
+        let b = match self.indices.get(&block_b) {
+            Some(b) => *b,
+            None => return true,
+        };
+        let a = match self.indices.get(&block_a) {
+            Some(a) => *a,
+            None => return false,
+        };
+
+        self.tree_in[a] <= self.tree_in[b]
+            && self.tree_out[b] <= self.tree_out[a]
+    }
+
+    /// Check if `block_a` strictly dominates `block_b`, i.e., `block_a`
+    /// dominates `block_b` and they are different.
+    pub fn strictly_dominates(
+        &self,
+        block_a: BasicBlock<'ctx>,
+        block_b: BasicBlock<'ctx>,
+    ) -> bool {
+        block_a != block_b && self.dominates(block_a, block_b)
+    }
+
+    /// Get the immediate dominator of a block.
+    ///
+    /// Return `None` for the root and unreachable blocks.
+    pub fn immediate_dominator(
+        &self,
+        block: BasicBlock<'ctx>,
+    ) -> Option<BasicBlock<'ctx>> {
+        let idx = self.indices.get(&block)?;
+        self.idoms[*idx].map(|idom| self.blocks[idom])
+    }
+
+    /// Get the children of a block in the tree, i.e., the blocks immediately
+    /// dominated by it.
+    pub fn get_children(
+        &self,
+        block: BasicBlock<'ctx>,
+    ) -> Vec<BasicBlock<'ctx>> {
+        match self.indices.get(&block) {
+            Some(idx) => self.children[*idx]
+                .iter()
+                .map(|child| self.blocks[*child])
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Get the depth of a block in the tree, where the root has depth 0.
+    pub fn get_level(&self, block: BasicBlock<'ctx>) -> Option<usize> {
+        self.indices.get(&block)?;
+        Some(self.dominators(block).count() - 1)
+    }
+
+    /// Get the nearest block dominating both `block_a` and `block_b`.
+    ///
+    /// Return `None` if any of the blocks is unreachable.
+    pub fn nearest_common_dominator(
+        &self,
+        block_a: BasicBlock<'ctx>,
+        block_b: BasicBlock<'ctx>,
+    ) -> Option<BasicBlock<'ctx>> {
+        self.indices.get(&block_b)?;
+        self.dominators(block_a)
+            .find(|blk| self.dominates(*blk, block_b))
+    }
+
+    /// Iterate over the dominators of a block, from the block itself up to
+    /// the root.
+    pub fn dominators(
+        &self,
+        block: BasicBlock<'ctx>,
+    ) -> DominatorIter<'_, 'ctx> {
+        DominatorIter {
+            tree: self,
+            current: self.indices.get(&block).copied(),
+        }
+    }
+
+    /// Iterate over the reachable blocks in the pre-order of the tree, i.e.,
+    /// each block is visited before the blocks it dominates.
+    pub fn iter_preorder(&self) -> impl Iterator<Item = BasicBlock<'ctx>> + '_ {
+        self.tree_preorder.iter().map(move |idx| self.blocks[*idx])
+    }
+
+    /// Iterate over the reachable blocks in the post-order of the tree,
+    /// i.e., each block is visited after the blocks it dominates.
+    pub fn iter_postorder(
+        &self,
+    ) -> impl Iterator<Item = BasicBlock<'ctx>> + '_ {
+        let mut order: Vec<usize> = (0..self.blocks.len()).collect();
+        order.sort_by_key(|idx| self.tree_out[*idx]);
+        order.into_iter().map(move |idx| self.blocks[idx])
+    }
+
+    /// Compute pre-order and post-order numbers of blocks in the tree.
+    fn number_tree(&mut self) {
+        let num_blocks = self.blocks.len();
+        self.tree_in = vec![0; num_blocks];
+        self.tree_out = vec![0; num_blocks];
+        self.tree_preorder = Vec::with_capacity(num_blocks);
+
+        if num_blocks == 0 {
+            return;
+        }
+
+        let (mut pre, mut post) = (0, 0);
+        let mut stack = vec![(0, 0)];
+
+        while let Some((idx, next_child)) = stack.pop() {
+            if next_child == 0 {
+                self.tree_in[idx] = pre;
+                self.tree_preorder.push(idx);
+                pre += 1;
+            }
+
+            match self.children[idx].get(next_child) {
+                Some(child) => {
+                    stack.push((idx, next_child + 1));
+                    stack.push((*child, 0));
+                }
+                None => {
+                    self.tree_out[idx] = post;
+                    post += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the dominators of a block, from the block up to the root.
+#[derive(Debug)]
+pub struct DominatorIter<'a, 'ctx> {
+    /// The dominator tree.
+    tree: &'a DominatorTree<'ctx>,
+
+    /// Index of the next block to visit.
+    current: Option<usize>,
+}
+
+impl<'a, 'ctx> Iterator for DominatorIter<'a, 'ctx> {
+    type Item = BasicBlock<'ctx>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.current?;
+        self.current = self.tree.idoms[idx];
+        Some(self.tree.blocks[idx])
+    }
+}
+
+/// Get the successors of a block, from the block operands of its terminator.
+fn get_successors(block: BasicBlock) -> Vec<BasicBlock> {
+    let mut successors = vec![];
+
+    if let Some(term) = block.get_terminator() {
+        for idx in 0..term.get_num_operands() {
+            if let Some(Right(succ)) = term.get_operand(idx) {
+                if !successors.contains(&succ) {
+                    successors.push(succ)
+                }
+            }
+        }
+    }
+
+    successors
+}
+
+/// Compute a depth-first spanning tree of the blocks reachable from the entry
+/// block of a function.
+///
+/// Return the blocks in the depth-first pre-order, and the index of the
+/// parent of each block in the spanning tree.
+fn compute_dfs_tree(
+    function: FunctionValue,
+) -> (Vec<BasicBlock>, Vec<Option<usize>>) {
+    let mut blocks = vec![];
+    let mut parents = vec![];
+    let mut visited: HashMap<BasicBlock, usize> = HashMap::new();
+
+    let entry = match function.get_first_basic_block() {
+        Some(entry) => entry,
+        None => return (blocks, parents),
+    };
+
+    let mut stack = vec![(entry, None)];
+    while let Some((block, parent)) = stack.pop() {
+        if visited.contains_key(&block) {
+            continue;
+        }
+
+        let idx = blocks.len();
+        visited.insert(block, idx);
+        blocks.push(block);
+        parents.push(parent);
+
+        for succ in get_successors(block).into_iter().rev() {
+            if !visited.contains_key(&succ) {
+                stack.push((succ, Some(idx)))
+            }
+        }
+    }
+
+    (blocks, parents)
+}
+
+/// Compute immediate dominators of blocks, indexed by their depth-first
+/// pre-order numbers, using the Lengauer-Tarjan algorithm.
+fn compute_idoms(
+    blocks: &[BasicBlock],
+    indices: &HashMap<BasicBlock, usize>,
+    parents: &[Option<usize>],
+) -> Vec<Option<usize>> {
+    let num_blocks = blocks.len();
+
+    let mut predecessors = vec![vec![]; num_blocks];
+    for (idx, block) in blocks.iter().enumerate() {
+        for succ in get_successors(*block) {
+            if let Some(succ) = indices.get(&succ) {
+                predecessors[*succ].push(idx)
+            }
+        }
+    }
+
+    let mut semi: Vec<usize> = (0..num_blocks).collect();
+    let mut label: Vec<usize> = (0..num_blocks).collect();
+    let mut ancestor: Vec<Option<usize>> = vec![None; num_blocks];
+    let mut idoms: Vec<Option<usize>> = vec![None; num_blocks];
+    let mut buckets: Vec<Vec<usize>> = vec![vec![]; num_blocks];
+
+    for w in (1..num_blocks).rev() {
+        for v in &predecessors[w] {
+            let u = eval(*v, &semi, &mut label, &mut ancestor);
+            if semi[u] < semi[w] {
+                semi[w] = semi[u]
+            }
+        }
+
+        buckets[semi[w]].push(w);
+        let parent = parents[w].unwrap();
+        ancestor[w] = Some(parent);
+
+        for v in std::mem::take(&mut buckets[parent]) {
+            let u = eval(v, &semi, &mut label, &mut ancestor);
+            idoms[v] = if semi[u] < semi[v] {
+                Some(u)
+            } else {
+                Some(parent)
+            };
+        }
+    }
+
+    for w in 1..num_blocks {
+        if idoms[w] != Some(semi[w]) {
+            idoms[w] = idoms[idoms[w].unwrap()]
+        }
+    }
+
+    idoms
+}
+
+/// Evaluate a block in the forest of the Lengauer-Tarjan algorithm: return
+/// the block with the minimal semi-dominator on the path from the root of its
+/// tree to the block, excluding the root.
+fn eval(
+    v: usize,
+    semi: &[usize],
+    label: &mut [usize],
+    ancestor: &mut [Option<usize>],
+) -> usize {
+    if ancestor[v].is_none() {
+        return v;
+    }
+
+    // Compress the path from `v` to the root of its tree, starting from the
+    // block nearest to the root.
+    let mut path = vec![];
+    let mut u = v;
+    while let Some(a) = ancestor[u] {
+        if ancestor[a].is_none() {
+            break;
+        }
+        path.push(u);
+        u = a;
+    }
+
+    for u in path.into_iter().rev() {
+        let a = ancestor[u].unwrap();
+        if semi[label[a]] < semi[label[u]] {
+            label[u] = label[a]
+        }
+        ancestor[u] = ancestor[a]
+    }
+
+    label[v]
+}
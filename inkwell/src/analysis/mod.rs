@@ -2,3 +2,4 @@
 
 // Export sub-modules
 pub mod alias;
+pub mod dominator;
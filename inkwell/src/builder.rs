@@ -2,6 +2,8 @@
 
 #[llvm_versions(3.9..=latest)]
 use llvm_sys::core::LLVMBuildAtomicCmpXchg;
+#[llvm_versions(10.0..=latest)]
+use llvm_sys::core::LLVMBuildFreeze;
 use llvm_sys::core::{
     LLVMAddCase, LLVMAddClause, LLVMAddDestination, LLVMBuildAShr,
     LLVMBuildAdd, LLVMBuildAddrSpaceCast, LLVMBuildAggregateRet,
@@ -2570,6 +2572,33 @@ impl<'ctx> Builder<'ctx> {
         unsafe { InstructionValue::new(val) }
     }
 
+    /// Builds a `fence` instruction, which orders memory operations with the
+    /// given atomic ordering. The fence synchronizes only with operations of
+    /// the same thread, e.g., signal handlers, if `num` is non-zero, and with
+    /// operations of all threads otherwise.
+    ///
+    /// The ordering must be `Acquire`, `Release`, `AcquireRelease` or
+    /// `SequentiallyConsistent`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use inkwell::context::Context;
+    /// use inkwell::AtomicOrdering;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("fence");
+    /// let builder = context.create_builder();
+    /// let void_type = context.void_type();
+    /// let fn_type = void_type.fn_type(&[], false);
+    /// let fn_value = module.add_function("fenced", fn_type, None);
+    /// let entry = context.append_basic_block(fn_value, "entry");
+    ///
+    /// builder.position_at_end(entry);
+    /// builder.build_fence(AtomicOrdering::SequentiallyConsistent, 0, "");
+    /// builder.build_return(None);
+    /// ```
+    // https://llvm.org/docs/LangRef.html#fence-instruction
     // REVIEW: Not sure if this should return InstructionValue or an actual value
     // TODO: Better name for num?
     pub fn build_fence(
@@ -2807,6 +2836,48 @@ impl<'ctx> Builder<'ctx> {
         unsafe { BasicValueEnum::new(value) }
     }
 
+    /// Builds a `freeze` instruction, which stops the propagation of `undef`
+    /// and `poison` values by replacing them with an arbitrary but fixed
+    /// value of the same type.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("freeze");
+    /// let builder = context.create_builder();
+    /// let i32_type = context.i32_type();
+    /// let fn_type = i32_type.fn_type(&[i32_type.into()], false);
+    /// let fn_value = module.add_function("frozen", fn_type, None);
+    /// let i32_param = fn_value.get_first_param().unwrap();
+    /// let entry = context.append_basic_block(fn_value, "entry");
+    ///
+    /// builder.position_at_end(entry);
+    /// let frozen = builder.build_freeze(i32_param, "frozen");
+    /// builder.build_return(Some(&frozen));
+    /// ```
+    // https://llvm.org/docs/LangRef.html#freeze-instruction
+    #[llvm_versions(10.0..=latest)]
+    pub fn build_freeze<V: BasicValue<'ctx>>(
+        &self,
+        value: V,
+        name: &str,
+    ) -> BasicValueEnum<'ctx> {
+        let c_string = to_c_str(name);
+
+        let value = unsafe {
+            LLVMBuildFreeze(
+                self.builder,
+                value.as_value_ref(),
+                c_string.as_ptr(),
+            )
+        };
+
+        unsafe { BasicValueEnum::new(value) }
+    }
+
     /// Builds an atomicrmw instruction. It allows you to atomically modify memory.
     ///
     /// # Example
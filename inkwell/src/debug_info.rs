@@ -132,8 +132,9 @@ use llvm_sys::debuginfo::{
     LLVMDIBuilderFinalize, LLVMDIBuilderInsertDbgValueBefore,
     LLVMDIBuilderInsertDeclareAtEnd, LLVMDIBuilderInsertDeclareBefore,
     LLVMDILocationGetColumn, LLVMDILocationGetLine, LLVMDILocationGetScope,
-    LLVMDISubprogramGetFlags, LLVMDITypeGetAlignInBits,
-    LLVMDITypeGetOffsetInBits, LLVMDITypeGetSizeInBits,
+    LLVMDISubprogramGetFlags, LLVMDISubprogramGetLine,
+    LLVMDITypeGetAlignInBits, LLVMDITypeGetOffsetInBits,
+    LLVMDITypeGetSizeInBits,
 };
 #[llvm_versions(8.0..=latest)]
 use llvm_sys::debuginfo::{
@@ -1058,6 +1059,11 @@ impl<'a> DISubprogram<'a> {
     pub fn get_flags(self) -> llvm_sys::debuginfo::LLVMDIFlags {
         return unsafe { LLVMDISubprogramGetFlags(self.metadata_ref) };
     }
+
+    /// Get the source line where the subprogram is declared.
+    pub fn get_line(self) -> u32 {
+        unsafe { LLVMDISubprogramGetLine(self.metadata_ref) }
+    }
 }
 
 /// Any kind of debug info type
@@ -1,7 +1,7 @@
 #[llvm_versions(9.0..=latest)]
 use llvm_sys::core::{
-    LLVMGetIntrinsicDeclaration, LLVMIntrinsicIsOverloaded,
-    LLVMLookupIntrinsicID,
+    LLVMGetIntrinsicDeclaration, LLVMIntrinsicGetName,
+    LLVMIntrinsicIsOverloaded, LLVMLookupIntrinsicID,
 };
 use llvm_sys::prelude::LLVMTypeRef;
 
@@ -9,7 +9,7 @@ use crate::module::Module;
 use crate::types::{AsTypeRef, BasicTypeEnum};
 use crate::values::FunctionValue;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct Intrinsic {
     id: u32,
 }
@@ -62,6 +62,32 @@ impl Intrinsic {
         return Some(unsafe { Intrinsic::new(id) });
     }
 
+    /// Get the raw LLVM intrinsic id
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    /// Get the base name of the intrinsic, without the suffix of overloaded
+    /// types
+    ///
+    /// # Example
+    /// ```no_run
+    /// use inkwell::intrinsics::Intrinsic;
+    ///
+    /// let sadd = Intrinsic::find("llvm.sadd.with.overflow").unwrap();
+    ///
+    /// assert_eq!(sadd.get_name(), "llvm.sadd.with.overflow");
+    /// ```
+    pub fn get_name(&self) -> &'static str {
+        let mut len = 0;
+        let ptr = unsafe { LLVMIntrinsicGetName(self.id, &mut len) };
+        let bytes =
+            unsafe { std::slice::from_raw_parts(ptr as *const u8, len) };
+
+        // Names of intrinsics are ASCII strings of the static table of LLVM
+        std::str::from_utf8(bytes).unwrap_or_default()
+    }
+
     /// Check if specified intrinsic is overloaded
     ///
     /// Overloaded intrinsics need some argument types to be specified to declare them
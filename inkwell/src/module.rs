@@ -40,7 +40,7 @@ use std::cell::{Cell, Ref, RefCell};
 use std::ffi::CStr;
 use std::fs::File;
 use std::marker::PhantomData;
-use std::mem::{forget, MaybeUninit};
+use std::mem::{forget, ManuallyDrop, MaybeUninit};
 use std::path::Path;
 use std::ptr;
 use std::rc::Rc;
@@ -1698,6 +1698,123 @@ impl Clone for Module<'_> {
     }
 }
 
+/// A `ModuleRef` allows borrowed, read-only access to a `Module` which is
+/// owned elsewhere, such as the parent module of a `FunctionValue`.
+///
+/// Unlike `Module`, the underlying module is not disposed when dropping this
+/// object. It does not deref to `Module`, so that methods transferring the
+/// ownership of the module, such as `create_execution_engine`, cannot be
+/// called on a borrowed module.
+#[derive(Debug)]
+pub struct ModuleRef<'ctx> {
+    module: ManuallyDrop<Module<'ctx>>,
+}
+
+impl<'ctx> ModuleRef<'ctx> {
+    pub(crate) unsafe fn new(module: LLVMModuleRef) -> Self {
+        ModuleRef {
+            module: ManuallyDrop::new(Module::new(module)),
+        }
+    }
+
+    /// Gets the `Context` of the module, see `Module::get_context`.
+    pub fn get_context(&self) -> ContextRef<'ctx> {
+        self.module.get_context()
+    }
+
+    /// Gets the name of the module, see `Module::get_name`.
+    #[llvm_versions(3.9..=latest)]
+    pub fn get_name(&self) -> &CStr {
+        self.module.get_name()
+    }
+
+    /// Gets the source file name of the module, see
+    /// `Module::get_source_file_name`.
+    #[llvm_versions(7.0..=latest)]
+    pub fn get_source_file_name(&self) -> &CStr {
+        self.module.get_source_file_name()
+    }
+
+    /// Gets the `TargetTriple` of the module, see `Module::get_triple`.
+    pub fn get_triple(&self) -> TargetTriple {
+        self.module.get_triple()
+    }
+
+    /// Gets the first `FunctionValue` of the module.
+    pub fn get_first_function(&self) -> Option<FunctionValue<'ctx>> {
+        self.module.get_first_function()
+    }
+
+    /// Gets the last `FunctionValue` of the module.
+    pub fn get_last_function(&self) -> Option<FunctionValue<'ctx>> {
+        self.module.get_last_function()
+    }
+
+    /// Gets a `FunctionValue` of the module by its name.
+    pub fn get_function(&self, name: &str) -> Option<FunctionValue<'ctx>> {
+        self.module.get_function(name)
+    }
+
+    /// Gets an iterator over the functions of the module.
+    pub fn get_functions(&self) -> FunctionIterator<'ctx> {
+        self.module.get_functions()
+    }
+
+    /// Gets the first `GlobalValue` of the module.
+    pub fn get_first_global(&self) -> Option<GlobalValue<'ctx>> {
+        self.module.get_first_global()
+    }
+
+    /// Gets the last `GlobalValue` of the module.
+    pub fn get_last_global(&self) -> Option<GlobalValue<'ctx>> {
+        self.module.get_last_global()
+    }
+
+    /// Gets a `GlobalValue` of the module by its name.
+    pub fn get_global(&self, name: &str) -> Option<GlobalValue<'ctx>> {
+        self.module.get_global(name)
+    }
+
+    /// Gets an iterator over the global values of the module.
+    pub fn get_globals(&self) -> GlobalIterator<'ctx> {
+        self.module.get_globals()
+    }
+
+    /// Gets a named `StructType` of the module.
+    pub fn get_struct_type(&self, name: &str) -> Option<StructType<'ctx>> {
+        self.module.get_struct_type(name)
+    }
+
+    /// Gets the metadata nodes of a named global metadata of the module.
+    pub fn get_global_metadata(&self, key: &str) -> Vec<MetadataValue<'ctx>> {
+        self.module.get_global_metadata(key)
+    }
+
+    /// Checks whether the module is valid, see `Module::verify`.
+    pub fn verify(&self) -> Result<(), LLVMString> {
+        self.module.verify()
+    }
+
+    /// Prints the module to a `String`.
+    pub fn print_to_string(&self) -> String {
+        self.module.print_to_string()
+    }
+}
+
+impl<'ctx> PartialEq<Module<'ctx>> for ModuleRef<'ctx> {
+    fn eq(&self, other: &Module<'ctx>) -> bool {
+        self.module.module.get() == other.module.get()
+    }
+}
+
+impl<'ctx> PartialEq for ModuleRef<'ctx> {
+    fn eq(&self, other: &ModuleRef<'ctx>) -> bool {
+        self.module.module.get() == other.module.module.get()
+    }
+}
+
+impl Eq for ModuleRef<'_> {}
+
 // Module owns the data layout string, so LLVMDisposeModule will deallocate it for us.
 // which is why DataLayout must be called with `new_borrowed`
 impl Drop for Module<'_> {
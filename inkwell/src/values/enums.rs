@@ -1,4 +1,6 @@
-use llvm_sys::core::{LLVMGetTypeKind, LLVMIsAInstruction, LLVMTypeOf};
+use llvm_sys::core::{
+    LLVMGetFirstUse, LLVMGetTypeKind, LLVMIsAInstruction, LLVMTypeOf,
+};
 use llvm_sys::prelude::LLVMValueRef;
 use llvm_sys::LLVMTypeKind;
 
@@ -6,8 +8,8 @@ use crate::support::LLVMString;
 use crate::types::{AnyTypeEnum, BasicTypeEnum};
 use crate::values::traits::AsValueRef;
 use crate::values::{
-    ArrayValue, FloatValue, FunctionValue, InstructionValue, IntValue,
-    MetadataValue, PointerValue, StructValue, VectorValue,
+    ArrayValue, BasicValueUse, FloatValue, FunctionValue, InstructionValue,
+    IntValue, MetadataValue, PointerValue, StructValue, VectorValue,
 };
 
 use std::convert::TryFrom;
@@ -214,6 +216,35 @@ impl<'ctx> AnyValueEnum<'ctx> {
             panic!("Found {:?} but expected the InstructionValue variant", self)
         }
     }
+
+    /// Get the `InstructionValue` of the current `AnyValueEnum`, if the
+    /// underlying value is an instruction.
+    ///
+    /// Unlike `into_instruction_value`, this also handles instructions that
+    /// produce a non-void value, such as an `IntValue` resulting from `add`.
+    pub fn into_instruction(self) -> Option<InstructionValue<'ctx>> {
+        if let AnyValueEnum::InstructionValue(v) = self {
+            return Some(v);
+        }
+
+        let value = self.as_value_ref();
+        if unsafe { LLVMIsAInstruction(value) }.is_null() {
+            return None;
+        }
+
+        unsafe { Some(InstructionValue::new(value)) }
+    }
+
+    /// Get first use of the current `AnyValueEnum`.
+    pub fn get_first_use(self) -> Option<BasicValueUse<'ctx>> {
+        let use_ = unsafe { LLVMGetFirstUse(self.as_value_ref()) };
+
+        if use_.is_null() {
+            return None;
+        }
+
+        unsafe { Some(BasicValueUse::new(use_)) }
+    }
 }
 
 impl<'ctx> BasicValueEnum<'ctx> {
@@ -14,9 +14,9 @@ use llvm_sys::core::{
     LLVMGetFirstUse, LLVMGetFunctionCallConv, LLVMGetFunctionParent, LLVMGetGC,
     LLVMGetIntrinsicID, LLVMGetLastBasicBlock, LLVMGetLastParam,
     LLVMGetLinkage, LLVMGetNextFunction, LLVMGetNextParam, LLVMGetParam,
-    LLVMGetParams, LLVMGetPreviousFunction, LLVMIsAFunction, LLVMIsConstant,
-    LLVMSetFunctionCallConv, LLVMSetGC, LLVMSetLinkage, LLVMSetParamAlignment,
-    LLVMSetSection,
+    LLVMGetParams, LLVMGetPreviousFunction, LLVMGetTypeContext,
+    LLVMIsAFunction, LLVMIsConstant, LLVMSetFunctionCallConv, LLVMSetGC,
+    LLVMSetLinkage, LLVMSetParamAlignment, LLVMSetSection, LLVMTypeOf,
 };
 #[llvm_versions(3.7..=latest)]
 use llvm_sys::core::{LLVMGetPersonalityFn, LLVMSetPersonalityFn};
@@ -33,12 +33,17 @@ use std::mem::forget;
 use crate::attributes::{Attribute, AttributeLoc};
 #[llvm_versions(7.0..=latest)]
 use crate::debug_info::DISubprogram;
-use crate::module::{Linkage, Module};
+#[llvm_versions(9.0..=latest)]
+use crate::intrinsics::Intrinsic;
+use crate::module::{Linkage, ModuleRef};
 use crate::support::to_c_str;
 use crate::types::{AnyType, FunctionType, PointerType};
 use crate::values::traits::{AnyValue, AsValueRef};
 use crate::values::BasicBlock;
-use crate::values::{BasicValueEnum, GlobalValue, Value};
+use crate::values::{
+    BasicValueEnum, GlobalValue, MetadataValue, Value,
+    FUNCTION_ENTRY_COUNT_PROFILE,
+};
 
 use super::{AnyValueEnum, BasicValueUse};
 
@@ -104,9 +109,12 @@ impl<'ctx> FunctionValue<'ctx> {
         }
     }
 
-    // FIXME: the call `Module::new(...)` will crash due to memory error.
-    pub fn get_parent(self) -> Module<'ctx> {
-        unsafe { Module::new(LLVMGetFunctionParent(self.as_value_ref())) }
+    /// Gets the parent module of the function.
+    ///
+    /// The returned `ModuleRef` borrows the module without taking ownership,
+    /// so dropping it does not dispose the module.
+    pub fn get_parent(self) -> ModuleRef<'ctx> {
+        unsafe { ModuleRef::new(LLVMGetFunctionParent(self.as_value_ref())) }
     }
 
     pub fn get_first_param(self) -> Option<BasicValueEnum<'ctx>> {
@@ -290,6 +298,37 @@ impl<'ctx> FunctionValue<'ctx> {
         unsafe { LLVMGetIntrinsicID(self.as_value_ref()) }
     }
 
+    /// Determines whether or not this function is an LLVM intrinsic, such as
+    /// `llvm.sadd.with.overflow.i32`.
+    pub fn is_intrinsic(self) -> bool {
+        self.get_intrinsic_id() != 0
+    }
+
+    /// Gets the intrinsic declared by this function, if it is an intrinsic.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use inkwell::context::Context;
+    /// use inkwell::intrinsics::Intrinsic;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_module");
+    /// let i32_type = context.i32_type();
+    /// let sadd = Intrinsic::find("llvm.sadd.with.overflow").unwrap();
+    /// let decl = sadd.get_declaration(&module, &[i32_type.into()]).unwrap();
+    ///
+    /// assert!(decl.is_intrinsic());
+    /// assert_eq!(decl.get_intrinsic(), Some(sadd));
+    /// ```
+    #[llvm_versions(9.0..=latest)]
+    pub fn get_intrinsic(self) -> Option<Intrinsic> {
+        match self.get_intrinsic_id() {
+            0 => None,
+            id => Some(unsafe { Intrinsic::new(id) }),
+        }
+    }
+
     pub fn get_call_conventions(self) -> u32 {
         unsafe { LLVMGetFunctionCallConv(self.as_value_ref()) }
     }
@@ -604,6 +643,39 @@ impl<'ctx> FunctionValue<'ctx> {
         }
     }
 
+    /// Get the entry count of the function from its `!prof` metadata.
+    ///
+    /// Return `None` if the function has no entry count.
+    #[llvm_versions(8.0..=latest)]
+    pub fn get_entry_count(self) -> Option<u64> {
+        let kind_id = unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(self.as_value_ref()));
+            MetadataValue::get_profile_kind_id(context)
+        };
+
+        self.as_global_value()
+            .get_metadata(kind_id)?
+            .get_profile_counts(FUNCTION_ENTRY_COUNT_PROFILE)?
+            .first()
+            .cloned()
+    }
+
+    /// Set the entry count of the function to its `!prof` metadata.
+    #[llvm_versions(8.0..=latest)]
+    pub fn set_entry_count(self, count: u64) {
+        unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(self.as_value_ref()));
+            let kind_id = MetadataValue::get_profile_kind_id(context);
+            let metadata = MetadataValue::new_profile(
+                context,
+                FUNCTION_ENTRY_COUNT_PROFILE,
+                &[count],
+                64,
+            );
+            self.as_global_value().set_metadata(metadata, kind_id)
+        }
+    }
+
     /// Set the section to which this function should belong
     pub fn set_section(self, section: &str) {
         let c_string = to_c_str(section);
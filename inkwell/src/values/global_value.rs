@@ -36,7 +36,7 @@ use std::fmt::{self, Display};
 
 #[llvm_versions(7.0..=latest)]
 use crate::comdat::Comdat;
-use crate::module::{Linkage, Module};
+use crate::module::{Linkage, ModuleRef};
 use crate::support::to_c_str;
 use crate::values::traits::AsValueRef;
 #[llvm_versions(8.0..=latest)]
@@ -72,12 +72,12 @@ impl<'ctx> GlobalValue<'ctx> {
         self.global_value.set_name(name)
     }
 
-    // FIXME: the call `Module::new(m)` will crash due to memory error.
-    pub fn get_parent(self) -> Module<'ctx> {
-        unsafe {
-            let m = LLVMGetGlobalParent(self.as_value_ref());
-            Module::new(m)
-        }
+    /// Gets the parent module of the global value.
+    ///
+    /// The returned `ModuleRef` borrows the module without taking ownership,
+    /// so dropping it does not dispose the module.
+    pub fn get_parent(self) -> ModuleRef<'ctx> {
+        unsafe { ModuleRef::new(LLVMGetGlobalParent(self.as_value_ref())) }
     }
 
     pub fn get_previous_global(self) -> Option<GlobalValue<'ctx>> {
@@ -298,6 +298,47 @@ impl<'ctx> GlobalValue<'ctx> {
         }
     }
 
+    /// Gets a metadata of the given type attached to the GlobalValue, if any.
+    #[llvm_versions(8.0..=latest)]
+    pub fn get_metadata(self, kind_id: u32) -> Option<MetadataValue<'ctx>> {
+        use llvm_sys::core::{
+            LLVMDisposeValueMetadataEntries, LLVMGetTypeContext,
+            LLVMGlobalCopyAllMetadata, LLVMMetadataAsValue, LLVMTypeOf,
+            LLVMValueMetadataEntriesGetKind,
+            LLVMValueMetadataEntriesGetMetadata,
+        };
+
+        let mut num_entries = 0;
+        let entries = unsafe {
+            LLVMGlobalCopyAllMetadata(self.as_value_ref(), &mut num_entries)
+        };
+
+        if entries.is_null() {
+            return None;
+        }
+
+        let mut metadata = None;
+
+        for index in 0..num_entries as u32 {
+            unsafe {
+                if LLVMValueMetadataEntriesGetKind(entries, index) == kind_id {
+                    let md =
+                        LLVMValueMetadataEntriesGetMetadata(entries, index);
+                    let context =
+                        LLVMGetTypeContext(LLVMTypeOf(self.as_value_ref()));
+                    metadata = Some(MetadataValue::new(LLVMMetadataAsValue(
+                        context, md,
+                    )));
+                    break;
+                }
+            }
+        }
+
+        unsafe { LLVMDisposeValueMetadataEntries(entries) };
+
+        metadata
+    }
+
     /// Gets a `Comdat` assigned to this `GlobalValue`, if any.
     #[llvm_versions(7.0..=latest)]
     pub fn get_comdat(self) -> Option<Comdat> {
@@ -4,36 +4,65 @@ use either::{
 };
 #[llvm_versions(3.9..=latest)]
 use llvm_sys::core::LLVMInstructionRemoveFromParent;
+use llvm_sys::core::{
+    LLVMCreateBuilderInContext, LLVMDisposeBuilder, LLVMDisposeMessage,
+    LLVMDisposeModule, LLVMGetAllocatedType, LLVMGetFirstBasicBlock,
+    LLVMGetFirstFunction, LLVMGetFirstInstruction,
+    LLVMInsertIntoBuilderWithName, LLVMPositionBuilderBefore,
+    LLVMPrintTypeToString, LLVMReplaceAllUsesWith,
+};
+use llvm_sys::core::{
+    LLVMDisposeValueMetadataEntries, LLVMGetMDKindIDInContext,
+    LLVMInstructionGetAllMetadataOtherThanDebugLoc, LLVMMetadataAsValue,
+    LLVMValueMetadataEntriesGetKind, LLVMValueMetadataEntriesGetMetadata,
+};
 use llvm_sys::core::{
     LLVMGetAlignment, LLVMGetDbgDeclareVar, LLVMGetFCmpPredicate,
     LLVMGetICmpPredicate, LLVMGetInstructionOpcode, LLVMGetInstructionParent,
     LLVMGetMetadata, LLVMGetNextInstruction, LLVMGetNumOperands,
-    LLVMGetOperand, LLVMGetOperandUse, LLVMGetPreviousInstruction,
-    LLVMGetSignednessInfo, LLVMGetVolatile, LLVMHasMetadata,
-    LLVMHasNoSignedWrap, LLVMHasNoUnsignedWrap, LLVMInstructionClone,
+    LLVMGetNumSuccessors, LLVMGetOperand, LLVMGetOperandUse,
+    LLVMGetPreviousInstruction, LLVMGetSignednessInfo, LLVMGetTypeContext,
+    LLVMGetVolatile, LLVMHasMetadata, LLVMHasNoSignedWrap,
+    LLVMHasNoUnsignedWrap, LLVMInstructionClone,
     LLVMInstructionEraseFromParent, LLVMIsAAllocaInst,
     LLVMIsAAtomicCmpXchgInst, LLVMIsAAtomicRMWInst, LLVMIsABasicBlock,
     LLVMIsABinaryOperator, LLVMIsABranchInst, LLVMIsACallBrInst,
     LLVMIsACallInst, LLVMIsACastInst, LLVMIsACmpInst, LLVMIsADbgDeclareInst,
-    LLVMIsAFCmpInst, LLVMIsAICmpInst, LLVMIsAIndirectBrInst, LLVMIsAInvokeInst,
-    LLVMIsALoadInst, LLVMIsAPHINode, LLVMIsAReturnInst, LLVMIsASExtInst,
-    LLVMIsAStoreInst, LLVMIsASwitchInst, LLVMIsATerminatorInst,
-    LLVMIsATruncInst, LLVMIsAUnaryOperator, LLVMIsAUnreachableInst,
-    LLVMIsAZExtInst, LLVMIsTailCall, LLVMSetAlignment, LLVMSetMetadata,
-    LLVMSetOperand, LLVMSetVolatile, LLVMValueAsBasicBlock,
+    LLVMIsAFCmpInst, LLVMIsAFenceInst, LLVMIsAFreezeInst,
+    LLVMIsAGetElementPtrInst, LLVMIsAICmpInst, LLVMIsAIndirectBrInst,
+    LLVMIsAInvokeInst, LLVMIsALoadInst, LLVMIsAPHINode, LLVMIsAReturnInst,
+    LLVMIsASExtInst, LLVMIsAStoreInst, LLVMIsASwitchInst,
+    LLVMIsATerminatorInst, LLVMIsATruncInst, LLVMIsAUnaryOperator,
+    LLVMIsAUnreachableInst, LLVMIsAVAArgInst, LLVMIsAZExtInst, LLVMIsTailCall,
+    LLVMSetAlignment, LLVMSetMetadata, LLVMSetOperand, LLVMSetVolatile,
+    LLVMTypeOf, LLVMValueAsBasicBlock,
 };
 #[llvm_versions(3.8..=latest)]
 use llvm_sys::core::{LLVMGetOrdering, LLVMSetOrdering};
+use llvm_sys::debuginfo::{
+    LLVMInstructionGetDebugLoc, LLVMInstructionSetDebugLoc,
+};
+use llvm_sys::ir_reader::LLVMParseIRInContext;
 #[llvm_versions(10.0..=latest)]
 use llvm_sys::{prelude::LLVMValueRef, LLVMOpcode};
+use llvm_sys::{
+    LLVMFastMathAll, LLVMFastMathAllowContract, LLVMFastMathAllowReassoc,
+    LLVMFastMathAllowReciprocal, LLVMFastMathApproxFunc, LLVMFastMathFlags,
+    LLVMFastMathNoInfs, LLVMFastMathNoNaNs, LLVMFastMathNoSignedZeros,
+    LLVMFastMathNone,
+};
 
-use std::{convert::TryInto, ffi::CStr, fmt, fmt::Display};
+use std::{convert::TryInto, ffi::CStr, fmt, fmt::Display, mem::forget, ptr};
 
+use crate::memory_buffer::MemoryBuffer;
+use crate::support::LLVMString;
+use crate::types::{AnyType, AnyTypeEnum, BasicTypeEnum};
 use crate::values::traits::AsValueRef;
+use crate::values::BasicBlock;
 use crate::values::{
     BasicValue, BasicValueEnum, BasicValueUse, MetadataValue, Value,
+    BRANCH_WEIGHTS_PROFILE,
 };
-use crate::{types::AnyTypeEnum, values::BasicBlock};
 use crate::{AtomicOrdering, FloatPredicate, IntPredicate};
 
 use super::{
@@ -126,6 +155,176 @@ pub enum InstructionOpcode {
     ZExt,
 }
 
+/// Keywords of the flags of instructions in the textual IR, which are printed
+/// right after the opcode, e.g., `add nsw` or `fcmp nnan olt`.
+const FLAG_KEYWORDS: [&str; 11] = [
+    "nuw", "nsw", "exact", "fast", "reassoc", "nnan", "ninf", "nsz", "arcp",
+    "contract", "afn",
+];
+
+/// Get the keyword of a floating point predicate in the textual IR.
+fn get_float_predicate_keyword(predicate: FloatPredicate) -> &'static str {
+    match predicate {
+        FloatPredicate::OEQ => "oeq",
+        FloatPredicate::OGE => "oge",
+        FloatPredicate::OGT => "ogt",
+        FloatPredicate::OLE => "ole",
+        FloatPredicate::OLT => "olt",
+        FloatPredicate::ONE => "one",
+        FloatPredicate::ORD => "ord",
+        FloatPredicate::PredicateFalse => "false",
+        FloatPredicate::PredicateTrue => "true",
+        FloatPredicate::UEQ => "ueq",
+        FloatPredicate::UGE => "uge",
+        FloatPredicate::UGT => "ugt",
+        FloatPredicate::ULE => "ule",
+        FloatPredicate::ULT => "ult",
+        FloatPredicate::UNE => "une",
+        FloatPredicate::UNO => "uno",
+    }
+}
+
+/// Fast-math flags of a floating point instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FastMathFlags(LLVMFastMathFlags);
+
+impl FastMathFlags {
+    /// Get the raw LLVM flag of a keyword of the textual IR, e.g., `nnan`.
+    fn get_keyword_flag(keyword: &str) -> Option<LLVMFastMathFlags> {
+        match keyword {
+            "fast" => Some(LLVMFastMathAll),
+            "reassoc" => Some(LLVMFastMathAllowReassoc),
+            "nnan" => Some(LLVMFastMathNoNaNs),
+            "ninf" => Some(LLVMFastMathNoInfs),
+            "nsz" => Some(LLVMFastMathNoSignedZeros),
+            "arcp" => Some(LLVMFastMathAllowReciprocal),
+            "contract" => Some(LLVMFastMathAllowContract),
+            "afn" => Some(LLVMFastMathApproxFunc),
+            _ => None,
+        }
+    }
+
+    /// Get the keywords of the flags in the textual IR, e.g., `nnan`.
+    fn get_keywords(self) -> Vec<&'static str> {
+        if self.is_fast() {
+            return vec!["fast"];
+        }
+
+        ["reassoc", "nnan", "ninf", "nsz", "arcp", "contract", "afn"]
+            .iter()
+            .copied()
+            .filter(|keyword| match FastMathFlags::get_keyword_flag(keyword) {
+                Some(flag) => self.0 & flag != 0,
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Create fast-math flags from a raw LLVM bitmask.
+    pub fn new(flags: LLVMFastMathFlags) -> Self {
+        FastMathFlags(flags)
+    }
+
+    /// Get the raw LLVM bitmask of the flags.
+    pub fn as_raw(self) -> LLVMFastMathFlags {
+        self.0
+    }
+
+    /// Check if no flag is set.
+    pub fn is_empty(self) -> bool {
+        self.0 == LLVMFastMathNone
+    }
+
+    /// Check if all flags are set, i.e., the `fast` flag.
+    pub fn is_fast(self) -> bool {
+        self.0 & LLVMFastMathAll == LLVMFastMathAll
+    }
+
+    /// Check if the `reassoc` flag is set.
+    pub fn allow_reassoc(self) -> bool {
+        self.0 & LLVMFastMathAllowReassoc != 0
+    }
+
+    /// Check if the `nnan` flag is set.
+    pub fn no_nans(self) -> bool {
+        self.0 & LLVMFastMathNoNaNs != 0
+    }
+
+    /// Check if the `ninf` flag is set.
+    pub fn no_infs(self) -> bool {
+        self.0 & LLVMFastMathNoInfs != 0
+    }
+
+    /// Check if the `nsz` flag is set.
+    pub fn no_signed_zeros(self) -> bool {
+        self.0 & LLVMFastMathNoSignedZeros != 0
+    }
+
+    /// Check if the `arcp` flag is set.
+    pub fn allow_reciprocal(self) -> bool {
+        self.0 & LLVMFastMathAllowReciprocal != 0
+    }
+
+    /// Check if the `contract` flag is set.
+    pub fn allow_contract(self) -> bool {
+        self.0 & LLVMFastMathAllowContract != 0
+    }
+
+    /// Check if the `afn` flag is set.
+    pub fn approx_func(self) -> bool {
+        self.0 & LLVMFastMathApproxFunc != 0
+    }
+
+    /// Create flags with all flags set, i.e., the `fast` flag.
+    pub fn fast() -> Self {
+        FastMathFlags(LLVMFastMathAll)
+    }
+
+    /// Set or clear the flags of a raw LLVM bitmask.
+    fn with_flag(self, flag: LLVMFastMathFlags, value: bool) -> Self {
+        if value {
+            FastMathFlags(self.0 | flag)
+        } else {
+            FastMathFlags(self.0 & !flag)
+        }
+    }
+
+    /// Set or clear the `reassoc` flag.
+    pub fn with_allow_reassoc(self, value: bool) -> Self {
+        self.with_flag(LLVMFastMathAllowReassoc, value)
+    }
+
+    /// Set or clear the `nnan` flag.
+    pub fn with_no_nans(self, value: bool) -> Self {
+        self.with_flag(LLVMFastMathNoNaNs, value)
+    }
+
+    /// Set or clear the `ninf` flag.
+    pub fn with_no_infs(self, value: bool) -> Self {
+        self.with_flag(LLVMFastMathNoInfs, value)
+    }
+
+    /// Set or clear the `nsz` flag.
+    pub fn with_no_signed_zeros(self, value: bool) -> Self {
+        self.with_flag(LLVMFastMathNoSignedZeros, value)
+    }
+
+    /// Set or clear the `arcp` flag.
+    pub fn with_allow_reciprocal(self, value: bool) -> Self {
+        self.with_flag(LLVMFastMathAllowReciprocal, value)
+    }
+
+    /// Set or clear the `contract` flag.
+    pub fn with_allow_contract(self, value: bool) -> Self {
+        self.with_flag(LLVMFastMathAllowContract, value)
+    }
+
+    /// Set or clear the `afn` flag.
+    pub fn with_approx_func(self, value: bool) -> Self {
+        self.with_flag(LLVMFastMathApproxFunc, value)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Copy, Hash)]
 pub struct InstructionValue<'ctx> {
     instruction_value: Value<'ctx>,
@@ -302,6 +501,19 @@ impl<'ctx> InstructionValue<'ctx> {
         Ok(unsafe { LLVMGetAlignment(self.as_value_ref()) })
     }
 
+    // SubTypes: Only apply to alloca instructions
+    /// Returns the type allocated by an alloca instruction.
+    pub fn get_allocated_type(
+        self,
+    ) -> Result<BasicTypeEnum<'ctx>, &'static str> {
+        if !self.is_a_alloca_inst() {
+            return Err("Value is not an alloca.");
+        }
+        Ok(unsafe {
+            BasicTypeEnum::new(LLVMGetAllocatedType(self.as_value_ref()))
+        })
+    }
+
     // SubTypes: Only apply to memory access and alloca instructions
     /// Sets alignment on a memory access instruction or alloca.
     pub fn set_alignment(self, alignment: u32) -> Result<(), &'static str> {
@@ -718,6 +930,125 @@ impl<'ctx> InstructionValue<'ctx> {
         Ok(())
     }
 
+    /// Get the metadata kind identifier of a kind name, e.g., `dbg` or a
+    /// custom kind, in the context of this `Instruction`.
+    fn get_kind_id(self, kind: &str) -> u32 {
+        unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(self.as_value_ref()));
+            LLVMGetMDKindIDInContext(
+                context,
+                kind.as_ptr() as *const ::libc::c_char,
+                kind.len() as u32,
+            )
+        }
+    }
+
+    /// Gets the `MetadataValue` associated with this `Instruction` at a
+    /// metadata kind name, e.g., `!verazt.instrumented` for the name
+    /// `verazt.instrumented`.
+    pub fn get_metadata_of_kind(
+        self,
+        kind: &str,
+    ) -> Option<MetadataValue<'ctx>> {
+        self.get_metadata(self.get_kind_id(kind))
+    }
+
+    /// Sets the `MetadataValue` associated with this `Instruction` at a
+    /// metadata kind name, registering the kind in the context if it is a new
+    /// custom kind.
+    pub fn set_metadata_of_kind(
+        self,
+        metadata: MetadataValue<'ctx>,
+        kind: &str,
+    ) -> Result<(), &'static str> {
+        self.set_metadata(metadata, self.get_kind_id(kind))
+    }
+
+    /// Removes the metadata associated with this `Instruction` at a specific
+    /// `kind_id`, if any.
+    pub fn remove_metadata(self, kind_id: u32) {
+        unsafe {
+            LLVMSetMetadata(
+                self.instruction_value.value,
+                kind_id,
+                std::ptr::null_mut(),
+            );
+        }
+    }
+
+    /// Gets all metadata associated with this `Instruction`, except its debug
+    /// location, with their kind identifiers.
+    pub fn get_all_metadata(self) -> Vec<(u32, MetadataValue<'ctx>)> {
+        let mut num_entries = 0;
+        unsafe {
+            let entries = LLVMInstructionGetAllMetadataOtherThanDebugLoc(
+                self.as_value_ref(),
+                &mut num_entries,
+            );
+            if entries.is_null() {
+                return vec![];
+            }
+
+            let context = LLVMGetTypeContext(LLVMTypeOf(self.as_value_ref()));
+            let metadata = (0..num_entries as u32)
+                .map(|index| {
+                    let kind_id =
+                        LLVMValueMetadataEntriesGetKind(entries, index);
+                    let md =
+                        LLVMValueMetadataEntriesGetMetadata(entries, index);
+                    let value = LLVMMetadataAsValue(context, md);
+                    (kind_id, MetadataValue::new(value))
+                })
+                .collect();
+            LLVMDisposeValueMetadataEntries(entries);
+            metadata
+        }
+    }
+
+    /// Get the branch weights of a terminator instruction from its `!prof`
+    /// metadata, one weight per successor.
+    ///
+    /// Return `None` if the instruction has no branch weights.
+    pub fn get_branch_weights(self) -> Option<Vec<u64>> {
+        let kind_id = unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(self.as_value_ref()));
+            MetadataValue::get_profile_kind_id(context)
+        };
+
+        self.get_metadata(kind_id)?
+            .get_profile_counts(BRANCH_WEIGHTS_PROFILE)
+    }
+
+    /// Set the branch weights of a terminator instruction to its `!prof`
+    /// metadata, one weight per successor.
+    pub fn set_branch_weights(
+        self,
+        weights: &[u32],
+    ) -> Result<(), &'static str> {
+        if !self.is_a_terminator_inst() {
+            return Err("Branch weights can only be set to a terminator.");
+        }
+
+        let num_successors =
+            unsafe { LLVMGetNumSuccessors(self.as_value_ref()) };
+        if weights.len() != num_successors as usize {
+            return Err("Number of branch weights and successors mismatch.");
+        }
+
+        let counts: Vec<u64> = weights.iter().map(|w| *w as u64).collect();
+        unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(self.as_value_ref()));
+            let kind_id = MetadataValue::get_profile_kind_id(context);
+            let metadata = MetadataValue::new_profile(
+                context,
+                BRANCH_WEIGHTS_PROFILE,
+                &counts,
+                32,
+            );
+            self.set_metadata(metadata, kind_id)
+        }
+    }
+
     /// Get the signedness of an instruction.
     pub fn get_signedness_info(self) -> Option<bool> {
         let value = unsafe { LLVMGetSignednessInfo(self.as_value_ref()) };
@@ -749,6 +1080,310 @@ impl<'ctx> InstructionValue<'ctx> {
         unsafe { LLVMHasNoSignedWrap(self.as_value_ref()) != 0 }
     }
 
+    /// Get the keywords of the flags of the current instruction, such as
+    /// `nsw`, `exact` or `nnan`, from its textual IR.
+    ///
+    /// The LLVM 14 C API has no accessors of the `exact` and fast-math flags,
+    /// but they are always printed right after the opcode.
+    fn get_flag_keywords(&self) -> Vec<String> {
+        let text = self.print_to_string();
+        let mut text = text.as_str();
+        if text.starts_with('%') {
+            // The name of the result may be quoted and contain spaces.
+            let name_end = match text.strip_prefix("%\"") {
+                Some(quoted) => quoted.find('"').map(|index| index + 3),
+                None => text.find(' '),
+            };
+            text = match name_end.and_then(|index| text.get(index..)) {
+                Some(rest) => rest.trim_start().trim_start_matches('='),
+                None => return vec![],
+            };
+        }
+
+        text.split_whitespace()
+            .skip_while(|word| matches!(*word, "tail" | "musttail" | "notail"))
+            .skip(1)
+            .take_while(|word| FLAG_KEYWORDS.contains(word))
+            .map(|word| word.to_string())
+            .collect()
+    }
+
+    /// Check if the current instruction is an overflowing binary operator,
+    /// i.e., an `add`, `sub`, `mul` or `shl`, which can have the `nuw` and
+    /// `nsw` flags.
+    fn can_have_wrap_flags(&self) -> bool {
+        matches!(
+            self.get_opcode(),
+            InstructionOpcode::Add
+                | InstructionOpcode::Sub
+                | InstructionOpcode::Mul
+                | InstructionOpcode::Shl
+        )
+    }
+
+    /// Set the NoUnsignedWrap (nuw) and NoSignedWrap (nsw) flags of the
+    /// current binary operator, see `replace_with_flags`.
+    fn set_wrap_flags(&mut self, nuw: bool, nsw: bool) -> bool {
+        if !self.can_have_wrap_flags() {
+            return false;
+        }
+        if self.has_no_unsigned_wrap() == nuw
+            && self.has_no_signed_wrap() == nsw
+        {
+            return true;
+        }
+
+        let mut flags = vec![];
+        if nuw {
+            flags.push("nuw");
+        }
+        if nsw {
+            flags.push("nsw");
+        }
+        self.replace_with_flags(&flags)
+    }
+
+    /// Set the NoUnsignedWrap (nuw) flag of the current binary operator.
+    ///
+    /// The instruction is replaced by an equivalent instruction with the new
+    /// flag, see `replace_with_flags`. Return `false`, without changing the
+    /// instruction, if it is not an `add`, `sub`, `mul` or `shl`, or if it is
+    /// not in a basic block.
+    pub fn set_no_unsigned_wrap(&mut self, value: bool) -> bool {
+        let nsw = self.has_no_signed_wrap();
+        self.set_wrap_flags(value, nsw)
+    }
+
+    /// Set the NoSignedWrap (nsw) flag of the current binary operator.
+    ///
+    /// The instruction is replaced by an equivalent instruction with the new
+    /// flag, see `replace_with_flags`. Return `false`, without changing the
+    /// instruction, if it is not an `add`, `sub`, `mul` or `shl`, or if it is
+    /// not in a basic block.
+    pub fn set_no_signed_wrap(&mut self, value: bool) -> bool {
+        let nuw = self.has_no_unsigned_wrap();
+        self.set_wrap_flags(nuw, value)
+    }
+
+    /// Check if the current instruction is a division or shift instruction,
+    /// which can have the `exact` flag.
+    fn can_be_exact(&self) -> bool {
+        matches!(
+            self.get_opcode(),
+            InstructionOpcode::UDiv
+                | InstructionOpcode::SDiv
+                | InstructionOpcode::LShr
+                | InstructionOpcode::AShr
+        )
+    }
+
+    /// Check if the current division or shift instruction has the `exact`
+    /// flag.
+    pub fn is_exact(&self) -> bool {
+        self.can_be_exact()
+            && self.get_flag_keywords().iter().any(|word| word == "exact")
+    }
+
+    /// Set the `exact` flag of the current division or shift instruction.
+    ///
+    /// The instruction is replaced by an equivalent instruction with the new
+    /// flag, see `replace_with_flags`. Return `false`, without changing the
+    /// instruction, if it is not a `udiv`, `sdiv`, `lshr` or `ashr`, or if it
+    /// is not in a basic block.
+    pub fn set_exact(&mut self, value: bool) -> bool {
+        if !self.can_be_exact() {
+            return false;
+        }
+        if self.is_exact() == value {
+            return true;
+        }
+        let flags: &[&str] = if value { &["exact"] } else { &[] };
+        self.replace_with_flags(flags)
+    }
+
+    /// Check if the current instruction is a floating point operation, which
+    /// can have fast-math flags.
+    ///
+    /// This follows `FPMathOperator::classof` of LLVM: `phi`, `select` and
+    /// `call` instructions are floating point operations when they produce
+    /// floating point values, possibly in vectors or nested arrays.
+    fn can_use_fast_math_flags(&self) -> bool {
+        match self.get_opcode() {
+            InstructionOpcode::FNeg
+            | InstructionOpcode::FAdd
+            | InstructionOpcode::FSub
+            | InstructionOpcode::FMul
+            | InstructionOpcode::FDiv
+            | InstructionOpcode::FRem
+            | InstructionOpcode::FCmp => true,
+            InstructionOpcode::Phi
+            | InstructionOpcode::Select
+            | InstructionOpcode::Call => {
+                let mut ty = self.get_type();
+                while let AnyTypeEnum::ArrayType(array_type) = ty {
+                    ty = array_type.get_element_type().as_any_type_enum();
+                }
+                match ty {
+                    AnyTypeEnum::FloatType(_) => true,
+                    AnyTypeEnum::VectorType(vector_type) => {
+                        vector_type.get_element_type().is_float_type()
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the fast-math flags of the current floating point instruction.
+    ///
+    /// Return `None` if the instruction cannot have fast-math flags.
+    pub fn get_fast_math_flags(&self) -> Option<FastMathFlags> {
+        if !self.can_use_fast_math_flags() {
+            return None;
+        }
+
+        let flags = self
+            .get_flag_keywords()
+            .iter()
+            .filter_map(|word| FastMathFlags::get_keyword_flag(word))
+            .fold(LLVMFastMathNone, |flags, flag| flags | flag);
+        Some(FastMathFlags::new(flags))
+    }
+
+    /// Set the fast-math flags of the current floating point instruction.
+    ///
+    /// The instruction is replaced by an equivalent instruction with the new
+    /// flags, see `replace_with_flags`. Return `false`, without changing the
+    /// instruction, if it is not a floating point binary operator, `fneg` or
+    /// `fcmp`, or if it is not in a basic block.
+    pub fn set_fast_math_flags(&mut self, flags: FastMathFlags) -> bool {
+        match self.get_fast_math_flags() {
+            Some(current) if current == flags => true,
+            Some(_) => self.replace_with_flags(&flags.get_keywords()),
+            None => false,
+        }
+    }
+
+    /// Replace the current instruction by an equivalent instruction with other
+    /// flags, e.g., `["nuw", "nsw"]`, keeping its name, metadata, debug
+    /// location and uses. The current value is updated to the new
+    /// instruction, and other copies of it must not be used anymore.
+    ///
+    /// The LLVM 14 C API cannot change the flags of an existing instruction,
+    /// so the new instruction is parsed from a textual IR template in the same
+    /// context, then its operands are replaced. Only binary operators, `fneg`
+    /// and `fcmp` are supported. Return `false`, without changing the
+    /// instruction, if it is not supported or not in a basic block.
+    fn replace_with_flags(&mut self, flags: &[&str]) -> bool {
+        let opcode = match self.get_opcode() {
+            InstructionOpcode::Add => "add",
+            InstructionOpcode::Sub => "sub",
+            InstructionOpcode::Mul => "mul",
+            InstructionOpcode::Shl => "shl",
+            InstructionOpcode::UDiv => "udiv",
+            InstructionOpcode::SDiv => "sdiv",
+            InstructionOpcode::LShr => "lshr",
+            InstructionOpcode::AShr => "ashr",
+            InstructionOpcode::FAdd => "fadd",
+            InstructionOpcode::FSub => "fsub",
+            InstructionOpcode::FMul => "fmul",
+            InstructionOpcode::FDiv => "fdiv",
+            InstructionOpcode::FRem => "frem",
+            InstructionOpcode::FNeg => "fneg",
+            InstructionOpcode::FCmp => "fcmp",
+            _ => return false,
+        };
+        if self.get_parent().is_none() {
+            return false;
+        }
+
+        let old_inst = self.as_value_ref();
+        let predicate = match self.get_fcmp_predicate() {
+            Some(predicate) => get_float_predicate_keyword(predicate),
+            None => "",
+        };
+        let operand_type = unsafe {
+            let operand = LLVMGetOperand(old_inst, 0);
+            LLVMString::new(LLVMPrintTypeToString(LLVMTypeOf(operand)))
+        };
+        let operands = match opcode {
+            "fneg" => "%a",
+            _ => "%a, %b",
+        };
+        let ir = format!(
+            "define void @inkwell.flags({ty} %a, {ty} %b) {{\n  \
+             %r = {} {} {} {ty} {}\n  ret void\n}}\n",
+            opcode,
+            flags.join(" "),
+            predicate,
+            operands,
+            ty = operand_type.to_string(),
+        );
+
+        unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(old_inst));
+            let buffer = MemoryBuffer::create_from_memory_range_copy(
+                ir.as_bytes(),
+                "flags",
+            );
+            let mut module = ptr::null_mut();
+            let mut err_str = ptr::null_mut();
+            let code = LLVMParseIRInContext(
+                context,
+                buffer.memory_buffer,
+                &mut module,
+                &mut err_str,
+            );
+            forget(buffer);
+            if code != 0 {
+                LLVMDisposeMessage(err_str);
+                return false;
+            }
+
+            let template = LLVMGetFirstFunction(module);
+            let new_inst =
+                LLVMGetFirstInstruction(LLVMGetFirstBasicBlock(template));
+            LLVMInstructionRemoveFromParent(new_inst);
+            for index in 0..LLVMGetNumOperands(old_inst) as u32 {
+                LLVMSetOperand(
+                    new_inst,
+                    index,
+                    LLVMGetOperand(old_inst, index),
+                );
+            }
+            LLVMDisposeModule(module);
+
+            let builder = LLVMCreateBuilderInContext(context);
+            LLVMPositionBuilderBefore(builder, old_inst);
+            LLVMInsertIntoBuilderWithName(
+                builder,
+                new_inst,
+                b"\0".as_ptr() as *const ::libc::c_char,
+            );
+            LLVMDisposeBuilder(builder);
+
+            LLVMInstructionSetDebugLoc(
+                new_inst,
+                LLVMInstructionGetDebugLoc(old_inst),
+            );
+            for (kind_id, metadata) in self.get_all_metadata() {
+                LLVMSetMetadata(new_inst, kind_id, metadata.as_value_ref());
+            }
+
+            let new_value = Value::new(new_inst);
+            let name = self.instruction_value.get_name().to_owned();
+            self.instruction_value.set_name("");
+            new_value.set_name(&name.to_string_lossy());
+
+            LLVMReplaceAllUsesWith(old_inst, new_inst);
+            LLVMInstructionEraseFromParent(old_inst);
+            self.instruction_value = new_value;
+        }
+
+        true
+    }
+
     /// Check if the current `InstructionValue` is an `alloca` instruction.
     pub fn is_a_alloca_inst(self) -> bool {
         !unsafe { LLVMIsAAllocaInst(self.as_value_ref()) }.is_null()
@@ -814,6 +1449,23 @@ impl<'ctx> InstructionValue<'ctx> {
         !unsafe { LLVMIsAFCmpInst(self.as_value_ref()) }.is_null()
     }
 
+    /// Check if the current `InstructionValue` is a `fence` instruction.
+    pub fn is_a_fence_inst(self) -> bool {
+        !unsafe { LLVMIsAFenceInst(self.as_value_ref()) }.is_null()
+    }
+
+    /// Check if the current `InstructionValue` is a `freeze` instruction.
+    #[llvm_versions(10.0..=latest)]
+    pub fn is_a_freeze_inst(self) -> bool {
+        !unsafe { LLVMIsAFreezeInst(self.as_value_ref()) }.is_null()
+    }
+
+    /// Check if the current `InstructionValue` is a `getelementptr`
+    /// instruction.
+    pub fn is_a_getelementptr_inst(self) -> bool {
+        !unsafe { LLVMIsAGetElementPtrInst(self.as_value_ref()) }.is_null()
+    }
+
     /// Check if the current `InstructionValue` is an `icmp` instruction.
     pub fn is_a_icmp_inst(self) -> bool {
         !unsafe { LLVMIsAICmpInst(self.as_value_ref()) }.is_null()
@@ -879,6 +1531,11 @@ impl<'ctx> InstructionValue<'ctx> {
         !unsafe { LLVMIsAUnreachableInst(self.as_value_ref()) }.is_null()
     }
 
+    /// Check if the current `InstructionValue` is a `va_arg` instruction.
+    pub fn is_a_vaarg_inst(self) -> bool {
+        !unsafe { LLVMIsAVAArgInst(self.as_value_ref()) }.is_null()
+    }
+
     /// Check if the current `InstructionValue` is a `zext` instruction.
     pub fn is_a_zext_inst(self) -> bool {
         !unsafe { LLVMIsAZExtInst(self.as_value_ref()) }.is_null()
@@ -0,0 +1,128 @@
+//! This module contain a typed wrapper of `getelementptr` instructions.
+
+use either::Either::Left;
+#[llvm_versions(14.0..=latest)]
+use llvm_sys::core::LLVMGetGEPSourceElementType;
+use llvm_sys::core::{LLVMIsInBounds, LLVMSetIsInBounds};
+use llvm_sys::prelude::LLVMValueRef;
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+#[llvm_versions(14.0..=latest)]
+use crate::types::BasicTypeEnum;
+use crate::values::{AnyValue, AsValueRef, BasicValueEnum, InstructionValue};
+
+/// Data structure representing a `getelementptr` (GEP) instruction.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct GEPInst<'ctx> {
+    /// Instruction value of the GEP.
+    gep_inst: InstructionValue<'ctx>,
+}
+
+impl<'ctx> GEPInst<'ctx> {
+    /// Constructor
+    ///
+    /// Panic if the instruction is not a `getelementptr` instruction.
+    pub fn new(inst: InstructionValue<'ctx>) -> Self {
+        assert!(inst.is_a_getelementptr_inst());
+        GEPInst { gep_inst: inst }
+    }
+
+    /// Get the underlying instruction value.
+    pub fn as_instruction_value(self) -> InstructionValue<'ctx> {
+        self.gep_inst
+    }
+
+    /// Get the pointer operand, i.e., the base address of the GEP.
+    ///
+    /// The operand is a pointer, or a vector of pointers for vector GEPs.
+    pub fn get_pointer_operand(self) -> BasicValueEnum<'ctx> {
+        match self.gep_inst.get_operand(0) {
+            Some(Left(value)) => value,
+            _ => panic!("Invalid GEP instruction: {}", self),
+        }
+    }
+
+    /// Get the number of index operands.
+    pub fn get_num_indices(self) -> u32 {
+        self.gep_inst.get_num_operands() - 1
+    }
+
+    /// Get the index operand at a position, starting from 0.
+    pub fn get_index(self, index: u32) -> Option<BasicValueEnum<'ctx>> {
+        if index >= self.get_num_indices() {
+            return None;
+        }
+
+        self.gep_inst.get_operand(index + 1)?.left()
+    }
+
+    /// Get all index operands.
+    pub fn get_indices(self) -> Vec<BasicValueEnum<'ctx>> {
+        (0..self.get_num_indices())
+            .filter_map(|idx| self.get_index(idx))
+            .collect()
+    }
+
+    /// Get the constant value of each index operand, or `None` for
+    /// non-constant indices.
+    pub fn get_constant_indices(self) -> Vec<Option<i64>> {
+        self.get_indices()
+            .into_iter()
+            .map(|idx| match idx {
+                BasicValueEnum::IntValue(int) => {
+                    int.get_sign_extended_constant()
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Check if all index operands are constants.
+    pub fn has_all_constant_indices(self) -> bool {
+        self.get_constant_indices().iter().all(|idx| idx.is_some())
+    }
+
+    /// Get the source element type, i.e., the type which the first index
+    /// steps over.
+    #[llvm_versions(14.0..=latest)]
+    pub fn get_source_element_type(self) -> BasicTypeEnum<'ctx> {
+        unsafe {
+            BasicTypeEnum::new(LLVMGetGEPSourceElementType(self.as_value_ref()))
+        }
+    }
+
+    /// Check if the GEP has the `inbounds` flag.
+    pub fn is_in_bounds(self) -> bool {
+        unsafe { LLVMIsInBounds(self.as_value_ref()) == 1 }
+    }
+
+    /// Set or clear the `inbounds` flag of the GEP.
+    pub fn set_in_bounds(self, in_bounds: bool) {
+        unsafe { LLVMSetIsInBounds(self.as_value_ref(), in_bounds as i32) }
+    }
+}
+
+impl AsValueRef for GEPInst<'_> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.gep_inst.as_value_ref()
+    }
+}
+
+impl Display for GEPInst<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.gep_inst.print_to_string())
+    }
+}
+
+impl<'ctx> TryFrom<InstructionValue<'ctx>> for GEPInst<'ctx> {
+    type Error = ();
+
+    fn try_from(inst: InstructionValue<'ctx>) -> Result<Self, Self::Error> {
+        if inst.is_a_getelementptr_inst() {
+            Ok(GEPInst::new(inst))
+        } else {
+            Err(())
+        }
+    }
+}
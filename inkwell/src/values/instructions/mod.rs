@@ -0,0 +1,7 @@
+//! This module contain typed wrappers of LLVM instructions.
+
+// Export sub-modules
+pub mod gep_inst;
+
+// Re-export sub-modules' data structures
+pub use gep_inst::GEPInst;
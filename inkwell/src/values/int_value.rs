@@ -511,7 +511,8 @@ impl<'ctx> IntValue<'ctx> {
         unsafe { Some(LLVMConstIntGetSExtValue(self.as_value_ref())) }
     }
 
-    /// Obtains a big integer constant from `IntValue`.
+    /// Obtains a big integer constant from `IntValue`, zero-extended to its
+    /// unsigned value.
     pub fn get_big_int_constant(self) -> Option<BigInt> {
         if !self.is_constant_int() {
             return None;
@@ -523,7 +524,7 @@ impl<'ctx> IntValue<'ctx> {
             let digits =
                 slice::from_raw_parts(words as *mut u32, (2 * size) as usize);
             let big_uint = BigUint::from_slice(digits);
-            let res = BigInt::from_biguint(Sign::NoSign, big_uint);
+            let res = BigInt::from_biguint(Sign::Plus, big_uint);
             Some(res)
         }
     }
@@ -1,8 +1,9 @@
 use llvm_sys::core::{
-    LLVMGetMDNodeNumOperands, LLVMGetMDNodeOperands, LLVMGetMDString,
-    LLVMIsAMDNode, LLVMIsAMDString,
+    LLVMConstInt, LLVMGetMDKindIDInContext, LLVMGetMDNodeNumOperands,
+    LLVMGetMDNodeOperands, LLVMGetMDString, LLVMIntTypeInContext,
+    LLVMIsAMDNode, LLVMIsAMDString, LLVMMDNodeInContext, LLVMMDStringInContext,
 };
-use llvm_sys::prelude::LLVMValueRef;
+use llvm_sys::prelude::{LLVMContextRef, LLVMValueRef};
 
 #[llvm_versions(7.0..=latest)]
 use llvm_sys::core::LLVMValueAsMetadata;
@@ -50,6 +51,16 @@ pub const FIRST_CUSTOM_METADATA_KIND_ID: u32 = 31;
 #[cfg(feature = "llvm14-0")]
 pub const FIRST_CUSTOM_METADATA_KIND_ID: u32 = 31;
 
+/// Name of the metadata kind of profile data, such as branch weights and
+/// function entry counts.
+pub const PROF_METADATA_KIND: &str = "prof";
+
+/// Name of the profile metadata storing branch weights of a terminator.
+pub const BRANCH_WEIGHTS_PROFILE: &str = "branch_weights";
+
+/// Name of the profile metadata storing the entry count of a function.
+pub const FUNCTION_ENTRY_COUNT_PROFILE: &str = "function_entry_count";
+
 #[derive(PartialEq, Eq, Clone, Copy, Hash)]
 pub struct MetadataValue<'ctx> {
     metadata_value: Value<'ctx>,
@@ -68,6 +79,72 @@ impl<'ctx> MetadataValue<'ctx> {
         }
     }
 
+    /// Create a profile metadata node, made of the profile name followed by
+    /// integer counts of `bit_width` bits, such as
+    /// `!{!"branch_weights", i32 3, i32 5}`.
+    pub(crate) unsafe fn new_profile(
+        context: LLVMContextRef,
+        name: &str,
+        counts: &[u64],
+        bit_width: u32,
+    ) -> Self {
+        let name = LLVMMDStringInContext(
+            context,
+            name.as_ptr() as *const ::libc::c_char,
+            name.len() as u32,
+        );
+        let int_type = LLVMIntTypeInContext(context, bit_width);
+
+        let mut values = vec![name];
+        for count in counts {
+            values.push(LLVMConstInt(int_type, *count, 0));
+        }
+
+        MetadataValue::new(LLVMMDNodeInContext(
+            context,
+            values.as_mut_ptr(),
+            values.len() as u32,
+        ))
+    }
+
+    /// Get the kind id of the `!prof` metadata in a context.
+    pub(crate) unsafe fn get_profile_kind_id(context: LLVMContextRef) -> u32 {
+        LLVMGetMDKindIDInContext(
+            context,
+            PROF_METADATA_KIND.as_ptr() as *const ::libc::c_char,
+            PROF_METADATA_KIND.len() as u32,
+        )
+    }
+
+    /// Get the counts of a profile metadata node whose name is `name`, such as
+    /// `[3, 5]` for the node `!{!"branch_weights", i32 3, i32 5}`.
+    ///
+    /// Return `None` if the current metadata is not a profile node of `name`.
+    pub fn get_profile_counts(self, name: &str) -> Option<Vec<u64>> {
+        let values = self.get_node_values();
+        let (profile_name, counts) = values.split_first()?;
+
+        let profile_name = match profile_name {
+            BasicMetadataValueEnum::MetadataValue(md) => {
+                md.get_string_value()?
+            }
+            _ => return None,
+        };
+        if profile_name.to_bytes() != name.as_bytes() {
+            return None;
+        }
+
+        counts
+            .iter()
+            .map(|count| match count {
+                BasicMetadataValueEnum::IntValue(count) => {
+                    count.get_zero_extended_constant()
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     #[llvm_versions(7.0..=latest)]
     pub(crate) fn as_metadata_ref(self) -> LLVMMetadataRef {
         unsafe { LLVMValueAsMetadata(self.as_value_ref()) }
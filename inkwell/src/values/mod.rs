@@ -14,6 +14,7 @@ mod fn_value;
 mod generic_value;
 mod global_value;
 mod instruction_value;
+pub mod instructions;
 mod int_value;
 mod metadata_value;
 mod phi_value;
@@ -38,11 +39,13 @@ pub use crate::values::global_value::GlobalValue;
 #[llvm_versions(7.0..=latest)]
 pub use crate::values::global_value::UnnamedAddress;
 pub use crate::values::instruction_value::{
-    InstructionOpcode, InstructionValue,
+    FastMathFlags, InstructionOpcode, InstructionValue,
 };
+pub use crate::values::instructions::GEPInst;
 pub use crate::values::int_value::IntValue;
 pub use crate::values::metadata_value::{
-    MetadataValue, FIRST_CUSTOM_METADATA_KIND_ID,
+    MetadataValue, BRANCH_WEIGHTS_PROFILE, FIRST_CUSTOM_METADATA_KIND_ID,
+    FUNCTION_ENTRY_COUNT_PROFILE, PROF_METADATA_KIND,
 };
 pub use crate::values::phi_value::PhiValue;
 pub use crate::values::ptr_value::PointerValue;
@@ -41,6 +41,7 @@ mod test_context;
     feature = "llvm6-0"
 )))]
 mod test_debug_info;
+mod test_dominator;
 mod test_execution_engine;
 mod test_instruction_conversion;
 mod test_instruction_values;
@@ -1381,6 +1381,34 @@ fn test_bitcast() {
     assert!(module.verify().is_ok());
 }
 
+#[llvm_versions(10.0..=latest)]
+#[test]
+fn test_freeze() {
+    use inkwell::values::{AnyValue, InstructionOpcode};
+
+    let context = Context::create();
+    let module = context.create_module("freeze");
+    let builder = context.create_builder();
+
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[i32_type.into()], false);
+    let fn_value = module.add_function("frozen", fn_type, None);
+    let i32_param = fn_value.get_first_param().unwrap();
+    let entry = context.append_basic_block(fn_value, "entry");
+
+    builder.position_at_end(entry);
+    let frozen = builder.build_freeze(i32_param, "frozen");
+    builder.build_return(Some(&frozen));
+
+    assert!(frozen.is_int_value());
+    let inst = frozen.as_instruction_value().unwrap();
+    assert_eq!(inst.get_opcode(), InstructionOpcode::Freeze);
+    assert!(inst.is_a_freeze_inst());
+    assert_eq!(inst.get_operand(0).unwrap().left().unwrap(), i32_param);
+    assert_eq!(inst.print_to_string(), "%frozen = freeze i32 %0");
+    assert!(module.verify().is_ok());
+}
+
 #[test]
 fn test_atomicrmw() {
     let context = Context::create();
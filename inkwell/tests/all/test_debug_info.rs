@@ -444,8 +444,8 @@ fn test_global_expressions() {
 
     gv.set_metadata(metadata, 0);
 
-    // TODO: Metadata set on the global values cannot be retrieved using the C api,
-    // therefore, it's currently not possible to test that the data was set without generating the IR
+    assert!(gv.get_metadata(0).is_some());
+    assert!(gv.get_metadata(1).is_none());
     assert!(
         gv.print_to_llvm_string().to_string().contains("!dbg"),
         "expected !dbg but generated gv was {}",
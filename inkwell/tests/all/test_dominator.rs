@@ -0,0 +1,94 @@
+use inkwell::analysis::dominator::DominatorTree;
+use inkwell::context::Context;
+
+#[test]
+fn test_dominator_tree() {
+    let context = Context::create();
+    let module = context.create_module("dominator");
+    let builder = context.create_builder();
+    let bool_type = context.bool_type();
+    let void_type = context.void_type();
+    let fn_type = void_type.fn_type(&[bool_type.into()], false);
+    let function = module.add_function("f", fn_type, None);
+    let cond = function.get_first_param().unwrap().into_int_value();
+
+    // entry -> then, else -> merge -> loop -> loop, exit
+    // dead -> merge, where dead is unreachable.
+    let entry = context.append_basic_block(function, "entry");
+    let then_block = context.append_basic_block(function, "then");
+    let else_block = context.append_basic_block(function, "else");
+    let merge = context.append_basic_block(function, "merge");
+    let loop_block = context.append_basic_block(function, "loop");
+    let exit = context.append_basic_block(function, "exit");
+    let dead = context.append_basic_block(function, "dead");
+
+    builder.position_at_end(entry);
+    builder.build_conditional_branch(cond, then_block, else_block);
+    builder.position_at_end(then_block);
+    builder.build_unconditional_branch(merge);
+    builder.position_at_end(else_block);
+    builder.build_unconditional_branch(merge);
+    builder.position_at_end(merge);
+    builder.build_unconditional_branch(loop_block);
+    builder.position_at_end(loop_block);
+    builder.build_conditional_branch(cond, loop_block, exit);
+    builder.position_at_end(exit);
+    builder.build_return(None);
+    builder.position_at_end(dead);
+    builder.build_unconditional_branch(merge);
+
+    let domtree = DominatorTree::new(function);
+
+    assert_eq!(domtree.get_root(), Some(entry));
+    assert!(domtree.is_reachable(exit));
+    assert!(!domtree.is_reachable(dead));
+
+    assert_eq!(domtree.immediate_dominator(entry), None);
+    assert_eq!(domtree.immediate_dominator(then_block), Some(entry));
+    assert_eq!(domtree.immediate_dominator(else_block), Some(entry));
+    assert_eq!(domtree.immediate_dominator(merge), Some(entry));
+    assert_eq!(domtree.immediate_dominator(loop_block), Some(merge));
+    assert_eq!(domtree.immediate_dominator(exit), Some(loop_block));
+    assert_eq!(domtree.immediate_dominator(dead), None);
+
+    assert!(domtree.dominates(entry, exit));
+    assert!(domtree.dominates(merge, merge));
+    assert!(domtree.dominates(loop_block, exit));
+    assert!(!domtree.dominates(then_block, merge));
+    assert!(!domtree.dominates(exit, loop_block));
+    assert!(domtree.dominates(entry, dead));
+    assert!(!domtree.dominates(dead, merge));
+    assert!(!domtree.strictly_dominates(merge, merge));
+    assert!(domtree.strictly_dominates(merge, exit));
+
+    assert_eq!(
+        domtree.nearest_common_dominator(then_block, else_block),
+        Some(entry)
+    );
+    assert_eq!(
+        domtree.nearest_common_dominator(exit, loop_block),
+        Some(loop_block)
+    );
+    assert_eq!(domtree.get_level(exit), Some(3));
+
+    let mut children = domtree.get_children(entry);
+    children.sort_by_key(|blk| blk.get_name().to_owned());
+    assert_eq!(children, vec![else_block, merge, then_block]);
+
+    let dominators: Vec<_> = domtree.dominators(exit).collect();
+    assert_eq!(dominators, vec![exit, loop_block, merge, entry]);
+
+    let preorder: Vec<_> = domtree.iter_preorder().collect();
+    assert_eq!(preorder.len(), 6);
+    assert_eq!(preorder[0], entry);
+    for blk in &preorder[1..] {
+        let idom = domtree.immediate_dominator(*blk).unwrap();
+        let idom_pos = preorder.iter().position(|b| *b == idom).unwrap();
+        let blk_pos = preorder.iter().position(|b| b == blk).unwrap();
+        assert!(idom_pos < blk_pos);
+    }
+
+    let postorder: Vec<_> = domtree.iter_postorder().collect();
+    assert_eq!(postorder.len(), 6);
+    assert_eq!(postorder[5], entry);
+}
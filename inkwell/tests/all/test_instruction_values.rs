@@ -1,6 +1,9 @@
 use inkwell::context::Context;
-use inkwell::types::AnyTypeEnum;
-use inkwell::values::{BasicValue, InstructionOpcode::*};
+use inkwell::memory_buffer::MemoryBuffer;
+use inkwell::types::{AnyTypeEnum, BasicTypeEnum};
+use inkwell::values::{
+    AnyValue, BasicValue, FastMathFlags, InstructionOpcode::*,
+};
 use inkwell::{
     AddressSpace, AtomicOrdering, AtomicRMWBinOp, FloatPredicate, IntPredicate,
 };
@@ -220,6 +223,35 @@ fn test_get_next_use() {
     assert!(module.verify().is_ok());
 }
 
+#[test]
+fn test_any_value_enum_users() {
+    let context = Context::create();
+    let module = context.create_module("ivs");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[i32_type.into()], false);
+    let function = module.add_function("add_one", fn_type, None);
+    let basic_block = context.append_basic_block(function, "entry");
+
+    builder.position_at_end(basic_block);
+
+    let arg1 = function.get_first_param().unwrap().into_int_value();
+    let one = i32_type.const_int(1, false);
+    let add = builder.build_int_add(arg1, one, "add");
+    let ret = builder.build_return(Some(&add));
+
+    // A non-void instruction is still recognized as an instruction.
+    let add_value = add.as_any_value_enum();
+    assert!(!add_value.is_instruction_value());
+    assert_eq!(add_value.into_instruction(), add.as_instruction_value());
+    assert_eq!(arg1.as_any_value_enum().into_instruction(), None);
+
+    let first_use = add_value.get_first_use().unwrap();
+    assert_eq!(first_use.get_user(), ret.as_any_value_enum());
+    assert!(first_use.get_next_use().is_none());
+    assert!(module.verify().is_ok());
+}
+
 #[test]
 fn test_instructions() {
     let context = Context::create();
@@ -302,6 +334,213 @@ fn test_instructions() {
     assert_eq!(instruction_clone, instruction_clone_copy);
 }
 
+#[test]
+fn test_allocated_type() {
+    let context = Context::create();
+    let module = context.create_module("testing");
+    let builder = context.create_builder();
+
+    let void_type = context.void_type();
+    let i32_type = context.i32_type();
+    let fn_type = void_type.fn_type(&[], false);
+
+    let function = module.add_function("alloca", fn_type, None);
+    let basic_block = context.append_basic_block(function, "entry");
+
+    builder.position_at_end(basic_block);
+
+    let alloca = builder.build_alloca(i32_type, "x");
+    let alloca_instruction = alloca.as_instruction_value().unwrap();
+    let return_instruction = builder.build_return(None);
+
+    assert_eq!(
+        alloca_instruction.get_allocated_type().unwrap(),
+        BasicTypeEnum::IntType(i32_type)
+    );
+    assert!(return_instruction.get_allocated_type().is_err());
+}
+
+#[test]
+fn test_exact_and_fast_math_flags() {
+    let context = Context::create();
+    let module = context.create_module("testing");
+    let builder = context.create_builder();
+
+    let i32_type = context.i32_type();
+    let f32_type = context.f32_type();
+    let fn_type = i32_type.fn_type(&[i32_type.into(), f32_type.into()], false);
+
+    let function = module.add_function("flags", fn_type, None);
+    let basic_block = context.append_basic_block(function, "entry");
+
+    builder.position_at_end(basic_block);
+
+    let arg1 = function.get_first_param().unwrap().into_int_value();
+    let arg2 = function.get_nth_param(1).unwrap().into_float_value();
+
+    let exact_div = builder.build_int_exact_signed_div(arg1, arg1, "exact");
+    let div = builder.build_int_signed_div(arg1, arg1, "div");
+    let f32_sum = builder.build_float_add(arg2, arg2, "f32_sum");
+    builder.build_return(Some(&div));
+
+    let exact_div = exact_div.as_instruction().unwrap();
+    let div = div.as_instruction().unwrap();
+    let f32_sum = f32_sum.as_instruction().unwrap();
+
+    assert!(exact_div.is_exact());
+    assert!(!div.is_exact());
+    assert!(!f32_sum.is_exact());
+
+    assert!(div.get_fast_math_flags().is_none());
+    let flags = f32_sum.get_fast_math_flags().unwrap();
+    assert!(flags.is_empty());
+    assert!(!flags.is_fast());
+    assert!(!flags.no_nans());
+}
+
+#[test]
+fn test_flags_of_parsed_instructions() {
+    let context = Context::create();
+    let ir = r#"
+        declare float @callee(float)
+
+        define float @flags(float %a, i32 %b) {
+          %"quoted sum" = fadd nnan nsz float %a, %a
+          %prod = fmul fast float %"quoted sum", %a
+          %cmp = fcmp ninf olt float %prod, %a
+          %call = tail call contract float @callee(float %prod)
+          %shr = lshr exact i32 %b, 1
+          %add = add nuw nsw i32 %b, 1
+          %sel = select i1 %cmp, float %call, float %a
+          ret float %sel
+        }
+    "#;
+    let buffer =
+        MemoryBuffer::create_from_memory_range_copy(ir.as_bytes(), "ir");
+    let module = context.create_module_from_ir(buffer).unwrap();
+    let function = module.get_function("flags").unwrap();
+    let insts = function.get_first_basic_block().unwrap().get_instructions();
+
+    let sum_flags = insts[0].get_fast_math_flags().unwrap();
+    assert!(sum_flags.no_nans());
+    assert!(sum_flags.no_signed_zeros());
+    assert!(!sum_flags.no_infs());
+
+    assert!(insts[1].get_fast_math_flags().unwrap().is_fast());
+
+    let cmp_flags = insts[2].get_fast_math_flags().unwrap();
+    assert!(cmp_flags.no_infs());
+    assert!(!cmp_flags.no_nans());
+
+    let call_flags = insts[3].get_fast_math_flags().unwrap();
+    assert!(call_flags.allow_contract());
+    assert!(!call_flags.allow_reassoc());
+
+    assert!(insts[4].is_exact());
+    assert!(insts[4].get_fast_math_flags().is_none());
+    assert!(!insts[5].is_exact());
+    assert!(insts[5].has_no_unsigned_wrap());
+
+    assert!(insts[6].get_fast_math_flags().unwrap().is_empty());
+}
+
+#[test]
+fn test_set_fast_math_flags() {
+    let context = Context::create();
+    let module = context.create_module("testing");
+    let builder = context.create_builder();
+
+    let f32_type = context.f32_type();
+    let fn_type = f32_type.fn_type(&[f32_type.into()], false);
+
+    let function = module.add_function("flags", fn_type, None);
+    let basic_block = context.append_basic_block(function, "entry");
+
+    builder.position_at_end(basic_block);
+
+    let arg = function.get_first_param().unwrap().into_float_value();
+    arg.set_name("arg");
+    let sum = builder.build_float_add(arg, arg, "sum");
+    let cmp = builder.build_float_compare(FloatPredicate::OLT, arg, sum, "cmp");
+    builder.build_return(Some(&sum));
+
+    let mut sum = sum.as_instruction().unwrap();
+    let mut cmp = cmp.as_instruction().unwrap();
+    let mut ret = basic_block.get_last_instruction().unwrap();
+
+    let flags = FastMathFlags::default()
+        .with_no_nans(true)
+        .with_no_infs(true)
+        .with_allow_contract(true);
+    assert!(sum.set_fast_math_flags(flags));
+    assert_eq!(sum.get_fast_math_flags(), Some(flags));
+    assert!(sum
+        .print_to_string()
+        .contains("%sum = fadd nnan ninf contract"));
+    assert!(cmp.print_to_string().contains("%arg, %sum"));
+    assert!(ret.print_to_string().contains("ret float %sum"));
+
+    let flags = flags.with_no_infs(false).with_no_signed_zeros(true);
+    assert!(cmp.set_fast_math_flags(flags));
+    let cmp_flags = cmp.get_fast_math_flags().unwrap();
+    assert!(cmp_flags.no_nans());
+    assert!(!cmp_flags.no_infs());
+    assert!(cmp_flags.no_signed_zeros());
+    assert_eq!(cmp.get_fcmp_predicate(), Some(FloatPredicate::OLT));
+
+    assert!(sum.set_fast_math_flags(FastMathFlags::fast()));
+    assert!(sum.get_fast_math_flags().unwrap().is_fast());
+    assert_eq!(basic_block.get_instructions().len(), 3);
+    assert!(module.verify().is_ok());
+
+    assert!(!ret.set_fast_math_flags(FastMathFlags::fast()));
+    assert!(ret.get_fast_math_flags().is_none());
+}
+
+#[test]
+fn test_set_wrap_and_exact_flags() {
+    let context = Context::create();
+    let module = context.create_module("testing");
+    let builder = context.create_builder();
+
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[i32_type.into()], false);
+
+    let function = module.add_function("flags", fn_type, None);
+    let basic_block = context.append_basic_block(function, "entry");
+
+    builder.position_at_end(basic_block);
+
+    let arg = function.get_first_param().unwrap().into_int_value();
+    let sum = builder.build_int_add(arg, arg, "sum");
+    let quot = builder.build_int_unsigned_div(sum, arg, "quot");
+    builder.build_return(Some(&quot));
+
+    let mut sum = sum.as_instruction().unwrap();
+    let mut quot = quot.as_instruction().unwrap();
+
+    assert!(sum.set_no_unsigned_wrap(true));
+    assert!(sum.set_no_signed_wrap(true));
+    assert!(sum.has_no_unsigned_wrap());
+    assert!(sum.has_no_signed_wrap());
+    assert!(sum.print_to_string().contains("%sum = add nuw nsw"));
+    assert!(quot.print_to_string().contains("udiv i32 %sum"));
+
+    assert!(sum.set_no_unsigned_wrap(false));
+    assert!(!sum.has_no_unsigned_wrap());
+    assert!(sum.has_no_signed_wrap());
+
+    assert!(quot.set_exact(true));
+    assert!(quot.is_exact());
+    assert!(quot.print_to_string().contains("%quot = udiv exact"));
+    assert_eq!(basic_block.get_instructions().len(), 3);
+    assert!(module.verify().is_ok());
+
+    assert!(!quot.set_no_signed_wrap(true));
+    assert!(!sum.set_exact(true));
+    assert!(!sum.is_exact());
+}
+
 #[llvm_versions(10.0..=latest)]
 #[test]
 fn test_volatile_atomicrmw_cmpxchg() {
@@ -596,3 +835,88 @@ fn test_metadata_kinds() {
         md_string.into(),
     ]);
 }
+
+#[test]
+fn test_custom_metadata() {
+    let context = Context::create();
+    let module = context.create_module("custom");
+    let builder = context.create_builder();
+    let void_type = context.void_type();
+    let fn_type = void_type.fn_type(&[], false);
+
+    let callee = module.add_function("callee", fn_type, None);
+    let function = module.add_function("caller", fn_type, None);
+    let entry = context.append_basic_block(function, "entry");
+
+    builder.position_at_end(entry);
+    let call = builder
+        .build_call(callee, &[], "")
+        .try_as_basic_value()
+        .right()
+        .unwrap();
+    builder.build_return(None);
+
+    assert!(call.get_metadata_of_kind("verazt.instrumented").is_none());
+    assert!(call.get_all_metadata().is_empty());
+
+    let marker = context.metadata_node(&[]);
+    assert!(call
+        .set_metadata_of_kind(marker, "verazt.instrumented")
+        .is_ok());
+    assert_eq!(
+        call.get_metadata_of_kind("verazt.instrumented"),
+        Some(marker)
+    );
+    assert!(call.print_to_string().contains("!verazt.instrumented"));
+
+    let kind_id = context.get_kind_id("verazt.instrumented");
+    assert_eq!(call.get_metadata(kind_id), Some(marker));
+    assert_eq!(call.get_all_metadata(), vec![(kind_id, marker)]);
+
+    let md_string = context.metadata_string("not a node");
+    assert!(call.set_metadata_of_kind(md_string, "verazt.note").is_err());
+
+    call.remove_metadata(kind_id);
+    assert!(call.get_metadata(kind_id).is_none());
+    assert!(call.get_all_metadata().is_empty());
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_profile_metadata() {
+    let context = Context::create();
+    let module = context.create_module("prof");
+    let builder = context.create_builder();
+    let void_type = context.void_type();
+    let bool_type = context.bool_type();
+    let fn_type = void_type.fn_type(&[bool_type.into()], false);
+
+    let function = module.add_function("branch", fn_type, None);
+    let entry = context.append_basic_block(function, "entry");
+    let then_block = context.append_basic_block(function, "then");
+    let else_block = context.append_basic_block(function, "else");
+
+    builder.position_at_end(entry);
+    let cond = function.get_first_param().unwrap().into_int_value();
+    let branch = builder.build_conditional_branch(cond, then_block, else_block);
+
+    builder.position_at_end(then_block);
+    let then_return = builder.build_return(None);
+
+    builder.position_at_end(else_block);
+    builder.build_return(None);
+
+    assert_eq!(branch.get_branch_weights(), None);
+    assert!(branch.set_branch_weights(&[3, 5]).is_ok());
+    assert_eq!(branch.get_branch_weights(), Some(vec![3, 5]));
+
+    assert!(branch.set_branch_weights(&[1]).is_err());
+    assert!(then_return.set_branch_weights(&[1]).is_err());
+    assert_eq!(branch.get_branch_weights(), Some(vec![3, 5]));
+
+    assert_eq!(function.get_entry_count(), None);
+    function.set_entry_count(42);
+    assert_eq!(function.get_entry_count(), Some(42));
+
+    assert!(module.verify().is_ok());
+}
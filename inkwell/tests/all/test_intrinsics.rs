@@ -53,3 +53,39 @@ fn test_get_decl_va_copy() {
 
     assert_eq!(decl.get_name().to_str().unwrap(), "llvm.va_copy.f32");
 }
+
+#[llvm_versions(9.0..=latest)]
+#[test]
+fn test_get_name() {
+    let sadd = Intrinsic::find("llvm.sadd.with.overflow").unwrap();
+
+    assert_eq!(sadd.get_name(), "llvm.sadd.with.overflow");
+    assert_eq!(Intrinsic::find(sadd.get_name()), Some(sadd));
+}
+
+#[llvm_versions(9.0..=latest)]
+#[test]
+fn test_function_get_intrinsic() {
+    let sadd = Intrinsic::find("llvm.sadd.with.overflow").unwrap();
+
+    let context = Context::create();
+    let module = context.create_module("my_module");
+
+    let decl = sadd
+        .get_declaration(&module, &[context.i32_type().into()])
+        .unwrap();
+
+    assert_eq!(
+        decl.get_name().to_str().unwrap(),
+        "llvm.sadd.with.overflow.i32"
+    );
+    assert!(decl.is_intrinsic());
+    assert_eq!(decl.get_intrinsic(), Some(sadd));
+    assert_eq!(decl.get_intrinsic_id(), sadd.get_id());
+
+    let fn_type = context.void_type().fn_type(&[], false);
+    let func = module.add_function("func", fn_type, None);
+
+    assert!(!func.is_intrinsic());
+    assert!(func.get_intrinsic().is_none());
+}
@@ -96,8 +96,12 @@ fn test_get_function() {
     assert_eq!(last_fn, function_1);
     assert_eq!(named_fn, function_1);
 
-    // FIXME: this assertion currently will make the unit test crashes
-    // assert_eq!(function_1.get_parent(), module);
+    assert_eq!(function_1.get_parent(), module);
+    assert_eq!(
+        function_1.get_parent().get_function("function_1"),
+        Some(function_1)
+    );
+    assert!(function_1.get_parent().verify().is_ok());
 
     let functions: Vec<_> = module.get_functions().collect();
     assert_eq!(functions, vec![function_1]);
@@ -133,8 +137,7 @@ fn test_write_and_load_memory_buffer() {
     let function = module.add_function("my_fn", function_type, None);
     let basic_block = context.append_basic_block(function, "entry");
 
-    // FIXME: this assertion currently will make the unit test crashes
-    // assert_eq!(function.get_parent(), module);
+    assert_eq!(function.get_parent(), module);
 
     builder.position_at_end(basic_block);
     builder.build_return(None);
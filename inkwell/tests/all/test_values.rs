@@ -948,8 +948,7 @@ fn test_globals() {
     assert!(module.get_global("my_global").is_none());
     assert_eq!(module.get_global("glob").unwrap(), global);
 
-    // FIXME: this assertion currently will make the unit test crashes
-    // assert_eq!(global.get_parent(), module);
+    assert_eq!(global.get_parent(), module);
 
     #[cfg(not(any(
         feature = "llvm3-6",
@@ -0,0 +1,27 @@
+//! Fuzz target of the bug annotation lexer and parser over arbitrary source
+//! text.
+//!
+//! Run with `cargo fuzz run annot_lexer` from the `llutil` directory.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use llutil::annotation::{nom_lexing, parse_bug_annotations};
+
+fuzz_target!(|data: &[u8]| {
+    let source = match std::str::from_utf8(data) {
+        Ok(source) => source,
+        Err(_) => return,
+    };
+
+    // Tokens are produced in the order of their positions.
+    if let Ok(tokens) = nom_lexing(source) {
+        for pair in tokens.windows(2) {
+            assert!(
+                (pair[0].line, pair[0].column) < (pair[1].line, pair[1].column)
+            );
+        }
+    }
+
+    let _ = parse_bug_annotations(source);
+});
@@ -0,0 +1,192 @@
+//! Module providing field-sensitive access paths of memory locations.
+//!
+//! An access path is a base value followed by a sequence of struct field
+//! indices and array subscripts, e.g., `account.lamports` or `buf[i].len`.
+//! Access paths are constructed from chains of `getelementptr` instructions,
+//! and are printed with source-level names from debug information, so that
+//! findings of analyses refer to fields instead of raw GEP offsets.
+
+use std::fmt::{self, Display};
+
+use inkwell::{
+    types::{BasicTypeEnum, StructType},
+    values::{AnyValue, BasicValueEnum, InstructionOpcode, PointerValue},
+};
+
+use crate::ir::{InstructionExt, PointerExt, SourceFields, SourceVariables};
+
+/// A step of an access path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessStep<'ctx> {
+    /// A field of a struct, by its index.
+    Field(StructType<'ctx>, u32),
+
+    /// An element of an array or a vector, or an offset of a pointer, by its
+    /// subscript.
+    Subscript(BasicValueEnum<'ctx>),
+}
+
+/// Data structure representing an access path, i.e., a base pointer
+/// followed by field and subscript steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessPath<'ctx> {
+    /// Base pointer of the path.
+    pub base: PointerValue<'ctx>,
+
+    /// Steps from the base pointer, in access order.
+    pub steps: Vec<AccessStep<'ctx>>,
+}
+
+/// Implement methods for `AccessPath`.
+impl<'ctx> AccessPath<'ctx> {
+    /// Constructor of an access path without steps.
+    pub fn new(base: PointerValue<'ctx>) -> Self {
+        AccessPath {
+            base,
+            steps: vec![],
+        }
+    }
+
+    /// Construct the access path of a pointer, by following its chain of
+    /// `getelementptr` and pointer `bitcast` instructions to its base.
+    pub fn from_pointer(ptr: PointerValue<'ctx>) -> Self {
+        let inst = match ptr.as_instruction() {
+            Some(inst) => inst,
+            None => return AccessPath::new(ptr),
+        };
+
+        if inst.get_opcode() == InstructionOpcode::BitCast {
+            return match inst.get_operand(0).and_then(|op| op.left()) {
+                Some(BasicValueEnum::PointerValue(src)) => {
+                    AccessPath::from_pointer(src)
+                }
+                _ => AccessPath::new(ptr),
+            };
+        }
+
+        let gep = match inst.try_into_gep_inst() {
+            Some(gep) => gep,
+            None => return AccessPath::new(ptr),
+        };
+
+        let mut path = AccessPath::from_pointer(gep.get_pointer_operand());
+        let mut indices = gep.get_indices().into_iter();
+
+        // The first index offsets the base pointer, and is omitted if zero.
+        if let Some(index) = indices.next() {
+            if get_constant_index(index) != Some(0) {
+                path.steps.push(AccessStep::Subscript(index))
+            }
+        }
+
+        let mut typ = gep.get_source_element_type();
+        for index in indices {
+            typ = match typ {
+                BasicTypeEnum::StructType(struct_type) => {
+                    let field = match get_constant_index(index) {
+                        Some(field) => field as u32,
+                        None => return AccessPath::new(ptr),
+                    };
+                    path.steps.push(AccessStep::Field(struct_type, field));
+                    match struct_type.get_field_type_at_index(field) {
+                        Some(field_type) => field_type,
+                        None => return AccessPath::new(ptr),
+                    }
+                }
+                BasicTypeEnum::ArrayType(array_type) => {
+                    path.steps.push(AccessStep::Subscript(index));
+                    array_type.get_element_type()
+                }
+                BasicTypeEnum::VectorType(vector_type) => {
+                    path.steps.push(AccessStep::Subscript(index));
+                    vector_type.get_element_type()
+                }
+                _ => return AccessPath::new(ptr),
+            }
+        }
+
+        path
+    }
+
+    /// Check if the access path has no steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Check if the access path is a prefix of another path, i.e., the
+    /// location of this path contains the location of the other path.
+    pub fn is_prefix_of(&self, other: &AccessPath<'ctx>) -> bool {
+        self.base == other.base && other.steps.starts_with(&self.steps)
+    }
+
+    /// Print the access path with source-level names of its base and fields,
+    /// e.g., `account.lamports`.
+    pub fn print_with(
+        &self,
+        variables: &SourceVariables,
+        fields: &SourceFields,
+    ) -> String {
+        let base = match variables.get_name(&self.base) {
+            Some(name) => name.to_string(),
+            None => self.base.get_name_or_default(),
+        };
+        self.print_steps(
+            base,
+            |struct_type, field| fields.get_name_or_default(struct_type, field),
+            |index| match get_constant_index(index) {
+                Some(index) => index.to_string(),
+                None => variables.get_name_or_default(&index),
+            },
+        )
+    }
+
+    /// Print the steps of the access path after a printed base.
+    fn print_steps<F, G>(
+        &self,
+        base: String,
+        print_field: F,
+        print_index: G,
+    ) -> String
+    where
+        F: Fn(StructType<'ctx>, u32) -> String,
+        G: Fn(BasicValueEnum<'ctx>) -> String,
+    {
+        let mut res = base;
+
+        for step in &self.steps {
+            match step {
+                AccessStep::Field(struct_type, field) => {
+                    res += &format!(".{}", print_field(*struct_type, *field))
+                }
+                AccessStep::Subscript(index) => {
+                    res += &format!("[{}]", print_index(*index))
+                }
+            }
+        }
+
+        res
+    }
+}
+
+/// Implement the trait `Display` for `AccessPath`.
+impl<'ctx> Display for AccessPath<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.print_steps(
+            self.base.get_name_or_default(),
+            |_, field| field.to_string(),
+            |index| match get_constant_index(index) {
+                Some(index) => index.to_string(),
+                None => index.print_to_string(),
+            },
+        );
+        write!(f, "{}", path)
+    }
+}
+
+/// Get the value of a constant integer index.
+fn get_constant_index(index: BasicValueEnum) -> Option<i64> {
+    match index {
+        BasicValueEnum::IntValue(int) => int.get_sign_extended_constant(),
+        _ => None,
+    }
+}
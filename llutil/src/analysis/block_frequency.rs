@@ -0,0 +1,281 @@
+//! Module implementing a static estimation of basic block frequencies.
+//!
+//! Branch probabilities are first estimated for each edge of the control flow
+//! graph, using the following heuristics in order, similar to those of LLVM's
+//! branch probability analysis:
+//!
+//! - Edge weights of an `EdgeProfile` or `!prof` branch weights, if
+//!   available for all successors of a block.
+//! - Edges leading to blocks ending with `unreachable` are almost never
+//!   taken.
+//! - Back edges of loops are taken much more often than loop exits.
+//! - Otherwise, all successors are equally likely.
+//!
+//! Block frequencies are then computed relatively to the entry block, whose
+//! frequency is 1, by iterating the flow equations in reverse post-order until
+//! they converge.
+
+use std::collections::{HashMap, HashSet};
+
+use inkwell::values::{BasicBlock, FunctionValue};
+
+use crate::{
+    analysis::{invariant::compute_back_edges, EdgeProfile},
+    ir::{basic_block::BasicBlockExt, compute_reverse_post_order},
+    normalize::unreachable::is_unreachable_block,
+};
+
+/// Weight of a back edge of a loop.
+const LOOP_TAKEN_WEIGHT: u64 = 124;
+
+/// Weight of a loop exit, when a block also has a back edge.
+const LOOP_NOT_TAKEN_WEIGHT: u64 = 4;
+
+/// Weight of an edge leading to an `unreachable` block.
+const UNREACHABLE_TAKEN_WEIGHT: u64 = 1;
+
+/// Weight of an edge leading to a block which is not `unreachable`, when the
+/// source block also has an edge to an `unreachable` block.
+const UNREACHABLE_NOT_TAKEN_WEIGHT: u64 = (1 << 20) - 1;
+
+/// Maximum number of iterations to solve the flow equations.
+const MAX_ITERATIONS: usize = 1000;
+
+/// Threshold of frequency changes under which the flow equations are
+/// considered converged.
+const CONVERGENCE_THRESHOLD: f64 = 1e-9;
+
+/// Data structure storing estimated frequencies of basic blocks of a function.
+#[derive(Debug, Clone)]
+pub struct BlockFrequency<'ctx> {
+    /// Function whose block frequencies are estimated.
+    pub function: FunctionValue<'ctx>,
+
+    /// Mapping from blocks to their frequencies, relative to the entry block.
+    frequencies: HashMap<BasicBlock<'ctx>, f64>,
+
+    /// Mapping from edges to their branch probabilities.
+    probabilities: HashMap<(BasicBlock<'ctx>, BasicBlock<'ctx>), f64>,
+}
+
+/// Implement methods for `BlockFrequency`.
+impl<'ctx> BlockFrequency<'ctx> {
+    /// Estimate block frequencies of a function.
+    pub fn compute(func: FunctionValue<'ctx>) -> Self {
+        Self::compute_with_profile(func, &EdgeProfile::new())
+    }
+
+    /// Estimate block frequencies of a function, using edge weights of a
+    /// profile when available.
+    pub fn compute_with_profile(
+        func: FunctionValue<'ctx>,
+        profile: &EdgeProfile<'ctx>,
+    ) -> Self {
+        let mut freq = BlockFrequency {
+            function: func,
+            frequencies: HashMap::new(),
+            probabilities: HashMap::new(),
+        };
+        freq.compute_probabilities(profile);
+        freq.compute_frequencies();
+        freq
+    }
+
+    /// Get the estimated frequency of a block, relative to the entry block.
+    ///
+    /// Blocks unreachable from the entry block have the frequency 0.
+    pub fn get_frequency(&self, blk: BasicBlock<'ctx>) -> f64 {
+        self.frequencies.get(&blk).cloned().unwrap_or(0.0)
+    }
+
+    /// Get the estimated probability that a block branches to a successor.
+    pub fn get_edge_probability(
+        &self,
+        src: BasicBlock<'ctx>,
+        dst: BasicBlock<'ctx>,
+    ) -> f64 {
+        self.probabilities.get(&(src, dst)).cloned().unwrap_or(0.0)
+    }
+
+    /// Get the estimated frequency of an edge, relative to the entry block.
+    pub fn get_edge_frequency(
+        &self,
+        src: BasicBlock<'ctx>,
+        dst: BasicBlock<'ctx>,
+    ) -> f64 {
+        self.get_frequency(src) * self.get_edge_probability(src, dst)
+    }
+
+    /// Get all blocks of the function, sorted by decreasing frequencies.
+    ///
+    /// Blocks with equal frequencies keep their order in the function.
+    pub fn get_blocks_by_frequency(&self) -> Vec<(BasicBlock<'ctx>, f64)> {
+        let mut blocks: Vec<(BasicBlock, f64)> = self
+            .function
+            .get_basic_blocks()
+            .into_iter()
+            .map(|blk| (blk, self.get_frequency(blk)))
+            .collect();
+        blocks.sort_by(|(_, f1), (_, f2)| f2.total_cmp(f1));
+        blocks
+    }
+
+    /// Estimate the cost of executing the function once, given the cost of
+    /// executing each block once.
+    pub fn estimate_cost<F>(&self, block_cost: F) -> f64
+    where
+        F: Fn(BasicBlock<'ctx>) -> f64,
+    {
+        self.function
+            .get_basic_blocks()
+            .into_iter()
+            .map(|blk| self.get_frequency(blk) * block_cost(blk))
+            .sum()
+    }
+
+    /// Estimate the number of instructions executed by calling the function
+    /// once.
+    pub fn estimate_instruction_count(&self) -> f64 {
+        self.estimate_cost(|blk| blk.get_instructions().len() as f64)
+    }
+
+    /// Estimate branch probabilities of all edges of the function.
+    fn compute_probabilities(&mut self, profile: &EdgeProfile<'ctx>) {
+        let back_edges = compute_back_edges(self.function);
+
+        for blk in self.function.get_basic_blocks() {
+            let succs = blk.get_successors();
+            if succs.is_empty() {
+                continue;
+            }
+
+            let weights = get_profile_weights(blk, &succs, profile)
+                .or_else(|| get_unreachable_weights(&succs))
+                .or_else(|| get_loop_weights(blk, &succs, &back_edges))
+                .unwrap_or_else(|| vec![1; succs.len()]);
+
+            let total: u64 = weights.iter().sum();
+            for (succ, weight) in succs.into_iter().zip(weights) {
+                let prob = match total {
+                    0 => 0.0,
+                    _ => weight as f64 / total as f64,
+                };
+                *self.probabilities.entry((blk, succ)).or_insert(0.0) += prob;
+            }
+        }
+    }
+
+    /// Compute block frequencies from branch probabilities.
+    fn compute_frequencies(&mut self) {
+        let blocks = compute_reverse_post_order(self.function);
+        let entry = match blocks.first() {
+            Some(blk) => *blk,
+            None => return,
+        };
+
+        let preds: HashMap<BasicBlock, Vec<BasicBlock>> = blocks
+            .iter()
+            .map(|blk| {
+                let mut visited = HashSet::new();
+                let mut preds = blk.get_predecessors();
+                preds.retain(|pred| visited.insert(*pred));
+                (*blk, preds)
+            })
+            .collect();
+
+        for _ in 0..MAX_ITERATIONS {
+            let mut max_change: f64 = 0.0;
+
+            for blk in &blocks {
+                let mut freq = if *blk == entry { 1.0 } else { 0.0 };
+                for pred in &preds[blk] {
+                    freq += self.get_edge_frequency(*pred, *blk);
+                }
+
+                let change = (freq - self.get_frequency(*blk)).abs();
+                max_change = max_change.max(change / freq.max(1.0));
+                self.frequencies.insert(*blk, freq);
+            }
+
+            if max_change < CONVERGENCE_THRESHOLD {
+                break;
+            }
+        }
+    }
+}
+
+/// Get weights of successors of a block from an edge profile or from the
+/// `!prof` branch weights of its terminator, if all of them are available.
+fn get_profile_weights<'ctx>(
+    blk: BasicBlock<'ctx>,
+    succs: &[BasicBlock<'ctx>],
+    profile: &EdgeProfile<'ctx>,
+) -> Option<Vec<u64>> {
+    let weights: Option<Vec<u64>> = succs
+        .iter()
+        .map(|succ| profile.get_weight(blk, *succ))
+        .collect();
+
+    weights.or_else(|| {
+        let weights = blk.get_terminator()?.get_branch_weights()?;
+        if weights.len() == succs.len() {
+            Some(weights)
+        } else {
+            None
+        }
+    })
+}
+
+/// Get weights of successors of a block when some but not all of them end
+/// with `unreachable`.
+fn get_unreachable_weights(succs: &[BasicBlock]) -> Option<Vec<u64>> {
+    let unreachables: Vec<bool> = succs
+        .iter()
+        .map(|succ| is_unreachable_block(*succ))
+        .collect();
+
+    if unreachables.iter().all(|b| *b) || unreachables.iter().all(|b| !*b) {
+        return None;
+    }
+
+    let weights = unreachables
+        .into_iter()
+        .map(|unreachable| {
+            if unreachable {
+                UNREACHABLE_TAKEN_WEIGHT
+            } else {
+                UNREACHABLE_NOT_TAKEN_WEIGHT
+            }
+        })
+        .collect();
+    Some(weights)
+}
+
+/// Get weights of successors of a block when some but not all of them are
+/// reached via back edges.
+fn get_loop_weights<'ctx>(
+    blk: BasicBlock<'ctx>,
+    succs: &[BasicBlock<'ctx>],
+    back_edges: &[(BasicBlock<'ctx>, BasicBlock<'ctx>)],
+) -> Option<Vec<u64>> {
+    let is_back_edges: Vec<bool> = succs
+        .iter()
+        .map(|succ| back_edges.contains(&(blk, *succ)))
+        .collect();
+
+    if is_back_edges.iter().all(|b| *b) || is_back_edges.iter().all(|b| !*b) {
+        return None;
+    }
+
+    let weights = is_back_edges
+        .into_iter()
+        .map(|is_back_edge| {
+            if is_back_edge {
+                LOOP_TAKEN_WEIGHT
+            } else {
+                LOOP_NOT_TAKEN_WEIGHT
+            }
+        })
+        .collect();
+    Some(weights)
+}
@@ -0,0 +1,393 @@
+//! Module providing the call graph of a module.
+//!
+//! The call graph has a node for each function of a module, including
+//! declarations, and an edge for each direct call site, i.e., each `call`,
+//! `invoke` or `callbr` instruction whose callee is a known function.
+//! Indirect calls are not resolved.
+//!
+//! Strongly connected components (SCCs) of the graph are computed by
+//! Tarjan's algorithm, whose output order is a reverse topological order of
+//! the SCCs, i.e., callees' SCCs come before callers' SCCs.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+};
+
+use inkwell::{
+    module::Module,
+    values::{FunctionValue, InstructionValue},
+};
+
+use crate::ir::{AnyCall, FunctionExt, InstructionExt};
+
+/// Data structure representing a direct call site, i.e., an edge of the call
+/// graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallSite<'ctx> {
+    /// The calling function.
+    pub caller: FunctionValue<'ctx>,
+
+    /// The called function.
+    pub callee: FunctionValue<'ctx>,
+
+    /// The call instruction.
+    pub instruction: InstructionValue<'ctx>,
+}
+
+/// Implement the trait `Display` for `CallSite`.
+impl<'ctx> Display for CallSite<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {}",
+            self.caller.get_name_or_default(),
+            self.callee.get_name_or_default()
+        )
+    }
+}
+
+/// Data structure representing the call graph of a module.
+#[derive(Debug, Clone)]
+pub struct CallGraph<'ctx> {
+    /// Functions of the module, in their module order.
+    functions: Vec<FunctionValue<'ctx>>,
+
+    /// Call sites of the module, in the order of their callers and their
+    /// positions in the callers.
+    call_sites: Vec<CallSite<'ctx>>,
+
+    /// Indices in `call_sites` of the call sites in each function.
+    call_sites_in: HashMap<FunctionValue<'ctx>, Vec<usize>>,
+
+    /// Indices in `call_sites` of the call sites calling each function.
+    call_sites_of: HashMap<FunctionValue<'ctx>, Vec<usize>>,
+
+    /// Distinct callees of each function, in the order of their first call
+    /// sites.
+    callees: HashMap<FunctionValue<'ctx>, Vec<FunctionValue<'ctx>>>,
+
+    /// Distinct callers of each function.
+    callers: HashMap<FunctionValue<'ctx>, Vec<FunctionValue<'ctx>>>,
+
+    /// SCCs of the graph, in reverse topological order.
+    sccs: Vec<Vec<FunctionValue<'ctx>>>,
+
+    /// Index of the SCC of each function in `sccs`.
+    scc_indices: HashMap<FunctionValue<'ctx>, usize>,
+}
+
+/// Implement methods for `CallGraph`.
+impl<'ctx> CallGraph<'ctx> {
+    /// Constructor, which computes the call graph of a module.
+    pub fn new(module: &Module<'ctx>) -> Self {
+        let functions: Vec<FunctionValue> = module.get_functions().collect();
+        let call_sites: Vec<CallSite> = functions
+            .iter()
+            .flat_map(|func| get_call_sites(*func))
+            .collect();
+
+        let mut callees: HashMap<FunctionValue, Vec<FunctionValue>> =
+            HashMap::new();
+        let mut callers: HashMap<FunctionValue, Vec<FunctionValue>> =
+            HashMap::new();
+        let mut call_sites_in: HashMap<FunctionValue, Vec<usize>> =
+            HashMap::new();
+        let mut call_sites_of: HashMap<FunctionValue, Vec<usize>> =
+            HashMap::new();
+        for (idx, call_site) in call_sites.iter().enumerate() {
+            call_sites_in.entry(call_site.caller).or_default().push(idx);
+            call_sites_of.entry(call_site.callee).or_default().push(idx);
+            let funcs = callees.entry(call_site.caller).or_default();
+            if !funcs.contains(&call_site.callee) {
+                funcs.push(call_site.callee)
+            }
+            let funcs = callers.entry(call_site.callee).or_default();
+            if !funcs.contains(&call_site.caller) {
+                funcs.push(call_site.caller)
+            }
+        }
+
+        let mut graph = CallGraph {
+            functions,
+            call_sites,
+            call_sites_in,
+            call_sites_of,
+            callees,
+            callers,
+            sccs: vec![],
+            scc_indices: HashMap::new(),
+        };
+        graph.compute_sccs();
+        graph
+    }
+
+    /// Get all functions of the graph, in their module order.
+    pub fn get_functions(&self) -> &[FunctionValue<'ctx>] {
+        &self.functions
+    }
+
+    /// Get all call sites of the graph.
+    pub fn get_call_sites(&self) -> &[CallSite<'ctx>] {
+        &self.call_sites
+    }
+
+    /// Get call sites in a function.
+    pub fn get_call_sites_in(
+        &self,
+        caller: FunctionValue<'ctx>,
+    ) -> Vec<&CallSite<'ctx>> {
+        self.get_indexed_call_sites(self.call_sites_in.get(&caller))
+    }
+
+    /// Get call sites calling a function.
+    pub fn get_call_sites_of(
+        &self,
+        callee: FunctionValue<'ctx>,
+    ) -> Vec<&CallSite<'ctx>> {
+        self.get_indexed_call_sites(self.call_sites_of.get(&callee))
+    }
+
+    /// Get the functions directly called by a function.
+    pub fn get_callees(
+        &self,
+        func: FunctionValue<'ctx>,
+    ) -> &[FunctionValue<'ctx>] {
+        self.callees
+            .get(&func)
+            .map(|funcs| funcs.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Get the functions directly calling a function.
+    pub fn get_callers(
+        &self,
+        func: FunctionValue<'ctx>,
+    ) -> &[FunctionValue<'ctx>] {
+        self.callers
+            .get(&func)
+            .map(|funcs| funcs.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Check if a function directly calls another function.
+    pub fn calls(
+        &self,
+        caller: FunctionValue<'ctx>,
+        callee: FunctionValue<'ctx>,
+    ) -> bool {
+        self.get_callees(caller).contains(&callee)
+    }
+
+    /// Get the SCCs of the graph, in reverse topological order, i.e.,
+    /// callees' SCCs come before callers' SCCs.
+    pub fn get_sccs(&self) -> &[Vec<FunctionValue<'ctx>>] {
+        &self.sccs
+    }
+
+    /// Iterate over the SCCs of the graph, in reverse topological order.
+    pub fn iter_sccs(
+        &self,
+    ) -> impl Iterator<Item = &[FunctionValue<'ctx>]> + '_ {
+        self.sccs.iter().map(|scc| scc.as_slice())
+    }
+
+    /// Get the SCC containing a function.
+    pub fn get_scc(
+        &self,
+        func: FunctionValue<'ctx>,
+    ) -> Option<&[FunctionValue<'ctx>]> {
+        let idx = self.scc_indices.get(&func)?;
+        Some(self.sccs[*idx].as_slice())
+    }
+
+    /// Check if a function is recursive, i.e., it belongs to an SCC of more
+    /// than one function, or it calls itself.
+    pub fn is_recursive(&self, func: FunctionValue<'ctx>) -> bool {
+        match self.get_scc(func) {
+            Some(scc) => scc.len() > 1 || self.calls(func, func),
+            None => false,
+        }
+    }
+
+    /// Get all functions in reverse topological order, i.e., callees come
+    /// before their callers, except within recursive SCCs.
+    pub fn get_reverse_topological_order(&self) -> Vec<FunctionValue<'ctx>> {
+        self.sccs.iter().flatten().copied().collect()
+    }
+
+    /// Get the call sites at some indices of `call_sites`.
+    fn get_indexed_call_sites(
+        &self,
+        indices: Option<&Vec<usize>>,
+    ) -> Vec<&CallSite<'ctx>> {
+        indices
+            .into_iter()
+            .flatten()
+            .map(|idx| &self.call_sites[*idx])
+            .collect()
+    }
+
+    /// Compute SCCs of the graph by Tarjan's algorithm.
+    fn compute_sccs(&mut self) {
+        let mut tarjan = Tarjan::default();
+
+        for func in &self.functions {
+            if !tarjan.indices.contains_key(func) {
+                tarjan.visit(*func, &self.callees);
+            }
+        }
+
+        self.scc_indices = tarjan
+            .sccs
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, scc)| scc.iter().map(move |func| (*func, idx)))
+            .collect();
+        self.sccs = tarjan.sccs;
+    }
+}
+
+/// Implement the trait `Display` for `CallGraph`.
+impl<'ctx> Display for CallGraph<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Call graph: {} functions, {} call sites",
+            self.functions.len(),
+            self.call_sites.len()
+        )?;
+        for func in &self.functions {
+            let callees = self.get_callees(*func);
+            if callees.is_empty() {
+                continue;
+            }
+            let names: Vec<String> =
+                callees.iter().map(|f| f.get_name_or_default()).collect();
+            write!(
+                f,
+                "\n  {} -> {}",
+                func.get_name_or_default(),
+                names.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Get direct call sites in a function.
+fn get_call_sites(func: FunctionValue) -> Vec<CallSite> {
+    let mut call_sites = vec![];
+
+    for blk in func.get_basic_blocks() {
+        for inst in blk.get_instructions() {
+            let callee = inst
+                .try_into_call_base()
+                .and_then(|call| call.get_called_function());
+            if let Some(callee) = callee {
+                call_sites.push(CallSite {
+                    caller: func,
+                    callee,
+                    instruction: inst,
+                })
+            }
+        }
+    }
+
+    call_sites
+}
+
+/// Data structure storing the states of Tarjan's SCC algorithm.
+#[derive(Default)]
+struct Tarjan<'ctx> {
+    /// Next visiting index.
+    index: usize,
+
+    /// Visiting indices of functions.
+    indices: HashMap<FunctionValue<'ctx>, usize>,
+
+    /// Lowest indices reachable from functions.
+    low_links: HashMap<FunctionValue<'ctx>, usize>,
+
+    /// Stack of visited functions whose SCCs are not determined yet.
+    stack: Vec<FunctionValue<'ctx>>,
+
+    /// Functions in the stack.
+    on_stack: HashSet<FunctionValue<'ctx>>,
+
+    /// Found SCCs.
+    sccs: Vec<Vec<FunctionValue<'ctx>>>,
+}
+
+/// Implement methods for `Tarjan`.
+impl<'ctx> Tarjan<'ctx> {
+    /// Visit a function and its callees.
+    ///
+    /// The traversal uses an explicit stack of frames, each holding a function
+    /// and the position of its next callee to visit, so that long call chains
+    /// do not overflow the native stack.
+    fn visit(
+        &mut self,
+        func: FunctionValue<'ctx>,
+        callees: &HashMap<FunctionValue<'ctx>, Vec<FunctionValue<'ctx>>>,
+    ) {
+        self.discover(func);
+        let mut frames = vec![(func, 0)];
+
+        while let Some((func, next_callee)) = frames.pop() {
+            let callee =
+                callees.get(&func).and_then(|funcs| funcs.get(next_callee));
+
+            match callee {
+                Some(callee) => {
+                    frames.push((func, next_callee + 1));
+                    if !self.indices.contains_key(callee) {
+                        self.discover(*callee);
+                        frames.push((*callee, 0));
+                    } else if self.on_stack.contains(callee) {
+                        let low_link =
+                            self.low_links[&func].min(self.indices[callee]);
+                        self.low_links.insert(func, low_link);
+                    }
+                }
+                None => {
+                    self.finish(func);
+
+                    // Propagate the low link to the caller.
+                    if let Some((caller, _)) = frames.last() {
+                        let low_link =
+                            self.low_links[caller].min(self.low_links[&func]);
+                        self.low_links.insert(*caller, low_link);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Assign the next visiting index to a function and push it to the stack.
+    fn discover(&mut self, func: FunctionValue<'ctx>) {
+        self.indices.insert(func, self.index);
+        self.low_links.insert(func, self.index);
+        self.index += 1;
+        self.stack.push(func);
+        self.on_stack.insert(func);
+    }
+
+    /// Pop the SCC of a function whose callees are all visited, if the
+    /// function is the root of its SCC.
+    fn finish(&mut self, func: FunctionValue<'ctx>) {
+        if self.low_links[&func] != self.indices[&func] {
+            return;
+        }
+
+        let mut scc = vec![];
+        while let Some(f) = self.stack.pop() {
+            self.on_stack.remove(&f);
+            scc.push(f);
+            if f == func {
+                break;
+            }
+        }
+        self.sccs.push(scc);
+    }
+}
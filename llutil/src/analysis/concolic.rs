@@ -0,0 +1,216 @@
+//! Module implementing a concolic mode on top of weakest preconditions.
+//!
+//! Starting from a concrete trace obtained by replay, new inputs are derived
+//! by negating one branch of the entry function at a time: the path prefix
+//! leading to the branch is kept, the branch is flipped to its other
+//! successor, and the solver is queried for arguments of the entry function
+//! that follow this new path. Blocks executed in callees are not constrained,
+//! so values returned by calls are free symbols.
+//!
+//! Derived inputs are written as binary files, where integer arguments of the
+//! entry function are laid out in order, each in little-endian over the
+//! smallest number of bytes holding its bit width.
+
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use inkwell::values::{BasicBlock, BasicValueEnum, FunctionValue};
+
+use crate::{
+    analysis::{check_time_budget, ReplayedTrace, SymExpr, WpGenerator},
+    ir::{basic_block::BasicBlockExt, AnyCondition, InstructionExt},
+    solver::{encoder::SmtEncoder, SatResult, Solver},
+};
+
+/// Data structure representing an input derived by negating a branch.
+#[derive(Debug, Clone)]
+pub struct ConcolicInput<'ctx> {
+    /// Index of the trace step whose branch is negated.
+    pub negated_step: usize,
+
+    /// Successor taken by the negated branch.
+    pub target: BasicBlock<'ctx>,
+
+    /// Integer arguments of the entry function and their values.
+    ///
+    /// Arguments which are not constrained by the solver have the value 0.
+    pub arguments: Vec<(BasicValueEnum<'ctx>, u128)>,
+}
+
+/// Implement methods for `ConcolicInput`.
+impl<'ctx> ConcolicInput<'ctx> {
+    /// Serialize the arguments into the byte layout read by fuzz harnesses.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+
+        for (arg, value) in &self.arguments {
+            let width = arg.into_int_value().get_type().get_bit_width();
+            let num_bytes = ((width + 7) / 8) as usize;
+            bytes.extend(value.to_le_bytes().iter().take(num_bytes));
+        }
+
+        bytes
+    }
+}
+
+/// Data structure deriving new inputs of a function from concrete traces.
+#[derive(Debug, Clone)]
+pub struct ConcolicExecutor<'ctx> {
+    /// Entry function of the traces.
+    function: FunctionValue<'ctx>,
+
+    /// Weakest precondition generator of the entry function.
+    wp: WpGenerator<'ctx>,
+}
+
+/// Implement methods for `ConcolicExecutor`.
+impl<'ctx> ConcolicExecutor<'ctx> {
+    /// Constructor
+    pub fn new(func: FunctionValue<'ctx>) -> Self {
+        ConcolicExecutor {
+            function: func,
+            wp: WpGenerator::new(func),
+        }
+    }
+
+    /// Derive new inputs from a trace of the entry function, by negating
+    /// each conditional branch of the trace once.
+    pub fn generate_inputs(
+        &self,
+        trace: &ReplayedTrace<'ctx>,
+        solver: &mut dyn Solver,
+    ) -> Vec<ConcolicInput<'ctx>> {
+        // Steps executed in the frame of the entry function.
+        let steps: Vec<(usize, BasicBlock)> = trace
+            .steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| {
+                step.call_stack.is_empty()
+                    && step.step.function == self.function
+            })
+            .map(|(idx, step)| (idx, step.step.block))
+            .collect();
+        let blocks: Vec<BasicBlock> =
+            steps.iter().map(|(_, blk)| *blk).collect();
+
+        let mut inputs = vec![];
+        let mut negated_branches = HashSet::new();
+
+        for (i, (step_idx, blk)) in steps.iter().enumerate() {
+            let next_blk = match blocks.get(i + 1) {
+                Some(next_blk) => *next_blk,
+                None => break,
+            };
+
+            let targets = get_alternative_targets(*blk, next_blk);
+            for target in targets {
+                if !negated_branches.insert((*blk, target)) {
+                    continue;
+                }
+                check_time_budget();
+
+                let mut path = blocks[..=i].to_vec();
+                path.push(target);
+
+                if let Some(arguments) = self.solve_path(&path, solver) {
+                    inputs.push(ConcolicInput {
+                        negated_step: *step_idx,
+                        target,
+                        arguments,
+                    })
+                }
+            }
+        }
+
+        inputs
+    }
+
+    /// Solve for arguments of the entry function following a path.
+    ///
+    /// Return `None` if the path is infeasible or cannot be encoded.
+    fn solve_path(
+        &self,
+        path: &[BasicBlock<'ctx>],
+        solver: &mut dyn Solver,
+    ) -> Option<Vec<(BasicValueEnum<'ctx>, u128)>> {
+        // The weakest precondition of `false` along a path is the negation
+        // of the conjunction of its branch conditions.
+        let wp = self.wp.compute_path_wp(path, SymExpr::Bool(false));
+        let path_cond = SymExpr::not(wp);
+
+        let mut encoder = SmtEncoder::new();
+        let constraint = encoder.encode_bool(&path_cond)?;
+
+        solver.push();
+        encoder.declare_symbols(solver);
+        solver.assert(&constraint);
+        let model = match solver.check_sat() {
+            SatResult::Sat => solver.get_model(),
+            _ => None,
+        };
+        solver.pop();
+
+        let model = model?;
+        let arguments = self
+            .function
+            .get_param_iter()
+            .filter(|param| param.is_int_value())
+            .map(|param| {
+                let value = encoder
+                    .get_symbol_name(&SymExpr::Var(param))
+                    .and_then(|name| model.get_bitvector_value(name))
+                    .unwrap_or(0);
+                (param, value)
+            })
+            .collect();
+
+        Some(arguments)
+    }
+}
+
+/// Write derived inputs to a directory, one binary file per input, and
+/// return the paths of the written files.
+pub fn write_concolic_inputs(
+    inputs: &[ConcolicInput],
+    dir: &Path,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    let mut paths = vec![];
+    for (idx, input) in inputs.iter().enumerate() {
+        let path = dir.join(format!("input_{}.bin", idx));
+        fs::write(&path, input.to_bytes())?;
+        paths.push(path)
+    }
+
+    Ok(paths)
+}
+
+/// Get successors of a block other than the executed successor, if the block
+/// ends with a conditional branch.
+fn get_alternative_targets<'ctx>(
+    blk: BasicBlock<'ctx>,
+    next_blk: BasicBlock<'ctx>,
+) -> Vec<BasicBlock<'ctx>> {
+    let mut targets: Vec<BasicBlock> = vec![];
+
+    let is_conditional_branch = blk
+        .get_terminator()
+        .and_then(|inst| inst.try_into_branch_inst())
+        .map_or(false, |branch| branch.has_condition());
+    if !is_conditional_branch {
+        return targets;
+    }
+
+    for succ in blk.get_successors() {
+        if succ != next_blk && !targets.contains(&succ) {
+            targets.push(succ)
+        }
+    }
+
+    targets
+}
@@ -0,0 +1,296 @@
+//! Module providing a driver running per-function analyses under a panic
+//! boundary.
+//!
+//! A panic while analyzing a function, e.g., due to a malformed function, is
+//! caught and recorded with the function name, and the remaining functions of
+//! the module are still analyzed.
+//!
+//! Each function can also be given a time budget. Analyses check the budget
+//! cooperatively by calling `check_time_budget` in their long-running loops,
+//! which unwinds out of the analysis once the budget is exhausted. The
+//! function is then recorded as timed out, and the remaining functions are
+//! still analyzed.
+//!
+//! Functions can also be scheduled by their complexity scores, so that cheap
+//! functions are analyzed first and expensive functions get larger budgets,
+//! which reports findings of large modules earlier.
+
+use inkwell::{module::Module, values::FunctionValue};
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt::{self, Display},
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::ir::{FunctionExt, ModuleExt};
+
+/// Data structure representing a function whose analysis panicked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionFailure {
+    /// Name of the function.
+    pub function: String,
+
+    /// Panic message.
+    pub message: String,
+}
+
+/// Data structure representing a function whose analysis exhausted its time
+/// budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionTimeout {
+    /// Name of the function.
+    pub function: String,
+
+    /// Time budget of the function.
+    pub budget: Duration,
+}
+
+/// Data structure representing results of a per-function analysis over a
+/// module.
+#[derive(Debug)]
+pub struct FunctionResults<'ctx, T> {
+    /// Results of functions successfully analyzed.
+    pub results: Vec<(FunctionValue<'ctx>, T)>,
+
+    /// Functions whose analysis panicked.
+    pub failures: Vec<FunctionFailure>,
+
+    /// Functions whose analysis exhausted its time budget.
+    pub timeouts: Vec<FunctionTimeout>,
+}
+
+/// Maximum factor by which the time budget of a complex function is scaled up
+/// by `run_per_function_by_complexity`.
+pub const MAX_BUDGET_FACTOR: f64 = 8.0;
+
+/// Unwinding payload raised when the time budget of a function is exhausted.
+struct TimeoutPayload;
+
+thread_local! {
+    /// Deadline of the function being analyzed by the driver, if any.
+    static DEADLINE: Cell<Option<Instant>> = Cell::new(None);
+}
+
+/// Implement methods for `FunctionResults`.
+impl<'ctx, T> FunctionResults<'ctx, T> {
+    /// Check if all functions were analyzed successfully, without failures
+    /// or timeouts.
+    pub fn is_successful(&self) -> bool {
+        self.failures.is_empty() && self.timeouts.is_empty()
+    }
+
+    /// Check if the analysis of a function exhausted its time budget.
+    pub fn is_timed_out(&self, func: FunctionValue<'ctx>) -> bool {
+        let name = func.get_name_or_default();
+        self.timeouts.iter().any(|timeout| timeout.function == name)
+    }
+
+    /// Get the result of a function, if it was analyzed successfully.
+    pub fn get(&self, func: FunctionValue<'ctx>) -> Option<&T> {
+        self.results
+            .iter()
+            .find(|(f, _)| *f == func)
+            .map(|(_, res)| res)
+    }
+
+    /// Print the section of failed functions of a report.
+    pub fn print_failures(&self) -> String {
+        let mut res = format!("Failed functions: {}", self.failures.len());
+
+        for failure in &self.failures {
+            res += &format!("\n  - {}: {}", failure.function, failure.message);
+        }
+
+        res
+    }
+
+    /// Print the section of timed-out functions of a report.
+    pub fn print_timeouts(&self) -> String {
+        let mut res = format!("Timed-out functions: {}", self.timeouts.len());
+
+        for timeout in &self.timeouts {
+            res += &format!("\n  - {}", timeout);
+        }
+
+        res
+    }
+}
+
+/// Implement the trait `Display` for `FunctionFailure`.
+impl Display for FunctionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.function, self.message)
+    }
+}
+
+/// Implement the trait `Display` for `FunctionTimeout`.
+impl Display for FunctionTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: timeout after {:?}", self.function, self.budget)
+    }
+}
+
+/// Run an analysis on each defined function of a module, in source order.
+///
+/// Panics of the analysis are caught and recorded as failures of the analyzed
+/// functions, instead of aborting the whole run.
+pub fn run_per_function<'ctx, T, F>(
+    module: &Module<'ctx>,
+    analyze: F,
+) -> FunctionResults<'ctx, T>
+where
+    F: FnMut(FunctionValue<'ctx>) -> T,
+{
+    run_per_function_with_budget(module, None, analyze)
+}
+
+/// Run an analysis on each defined function of a module, in source order,
+/// giving each function a time budget if `budget` is not `None`.
+///
+/// Functions whose analysis exhausts the budget are recorded as timed out,
+/// and panics are recorded as failures, instead of aborting the whole run.
+pub fn run_per_function_with_budget<'ctx, T, F>(
+    module: &Module<'ctx>,
+    budget: Option<Duration>,
+    analyze: F,
+) -> FunctionResults<'ctx, T>
+where
+    F: FnMut(FunctionValue<'ctx>) -> T,
+{
+    let funcs = module
+        .get_functions_sorted()
+        .into_iter()
+        .filter(|func| !func.is_only_declared())
+        .map(|func| (func, budget))
+        .collect();
+
+    run_scheduled_functions(funcs, analyze)
+}
+
+/// Run an analysis on each defined function of a module, from the cheapest
+/// to the most expensive function according to their complexity scores.
+///
+/// Cheap functions are analyzed first to report their findings early. If
+/// `budget` is not `None`, it is the time budget of a function of median
+/// complexity, and is scaled up to `MAX_BUDGET_FACTOR` times for more complex
+/// functions. Functions of equal scores are analyzed in source order.
+pub fn run_per_function_by_complexity<'ctx, T, F>(
+    module: &Module<'ctx>,
+    budget: Option<Duration>,
+    analyze: F,
+) -> FunctionResults<'ctx, T>
+where
+    F: FnMut(FunctionValue<'ctx>) -> T,
+{
+    let mut scored_funcs: Vec<(FunctionValue, u64)> = module
+        .get_functions_sorted()
+        .into_iter()
+        .filter(|func| !func.is_only_declared())
+        .map(|func| (func, func.complexity_score()))
+        .collect();
+    scored_funcs.sort_by_key(|(_, score)| *score);
+
+    let median_score = match scored_funcs.get(scored_funcs.len() / 2) {
+        Some((_, score)) => (*score).max(1),
+        None => 1,
+    };
+    let funcs = scored_funcs
+        .into_iter()
+        .map(|(func, score)| {
+            let budget = budget
+                .map(|budget| scale_time_budget(budget, score, median_score));
+            (func, budget)
+        })
+        .collect();
+
+    run_scheduled_functions(funcs, analyze)
+}
+
+/// Scale the time budget of a function by the ratio of its complexity score
+/// to a reference score, between 1 and `MAX_BUDGET_FACTOR`.
+pub fn scale_time_budget(
+    budget: Duration,
+    score: u64,
+    reference_score: u64,
+) -> Duration {
+    let factor = score as f64 / reference_score.max(1) as f64;
+    budget.mul_f64(factor.clamp(1.0, MAX_BUDGET_FACTOR))
+}
+
+/// Run an analysis on functions in the given order, each with its time
+/// budget, recording panics and timeouts of the analysis.
+fn run_scheduled_functions<'ctx, T, F>(
+    funcs: Vec<(FunctionValue<'ctx>, Option<Duration>)>,
+    mut analyze: F,
+) -> FunctionResults<'ctx, T>
+where
+    F: FnMut(FunctionValue<'ctx>) -> T,
+{
+    let mut results = vec![];
+    let mut failures = vec![];
+    let mut timeouts = vec![];
+
+    for (func, budget) in funcs {
+        let deadline = budget.map(|budget| Instant::now() + budget);
+        DEADLINE.with(|cell| cell.set(deadline));
+        let res = panic::catch_unwind(AssertUnwindSafe(|| analyze(func)));
+        DEADLINE.with(|cell| cell.set(None));
+
+        match res {
+            Ok(res) => results.push((func, res)),
+            Err(err) if err.is::<TimeoutPayload>() => {
+                let timeout = FunctionTimeout {
+                    function: func.get_name_or_default(),
+                    budget: budget.unwrap_or_default(),
+                };
+                log_warn!("Analysis timed out on function: {}", timeout);
+                timeouts.push(timeout)
+            }
+            Err(err) => {
+                let failure = FunctionFailure {
+                    function: func.get_name_or_default(),
+                    message: get_panic_message(&err),
+                };
+                log_warn!("Analysis failed on function: {}", failure);
+                failures.push(failure)
+            }
+        }
+    }
+
+    FunctionResults {
+        results,
+        failures,
+        timeouts,
+    }
+}
+
+/// Check the time budget of the function being analyzed by the driver, and
+/// unwind out of the analysis if the budget is exhausted.
+///
+/// Analyses call this function in their long-running loops. It has no effect
+/// outside of the drivers running analyses with time budgets.
+pub fn check_time_budget() {
+    let is_expired = DEADLINE.with(|cell| {
+        cell.get()
+            .map_or(false, |deadline| Instant::now() >= deadline)
+    });
+
+    // Resuming the unwinding does not invoke the panic hook, so timeouts are
+    // not printed as panics.
+    if is_expired {
+        panic::resume_unwind(Box::new(TimeoutPayload))
+    }
+}
+
+/// Get the message of a caught panic.
+pub(crate) fn get_panic_message(err: &Box<dyn Any + Send>) -> String {
+    if let Some(msg) = err.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = err.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "<unknown panic>".to_string()
+    }
+}
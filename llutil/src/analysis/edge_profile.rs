@@ -0,0 +1,152 @@
+//! Module providing edge profiles of control flow graphs.
+//!
+//! An edge profile attaches weights, such as execution counts from coverage
+//! instrumentation runs or `!prof` metadata, to the edges between basic
+//! blocks of a function. Path exploration can then prioritize hot paths by
+//! visiting successors in decreasing order of weights.
+//!
+//! Edge counts can be loaded from a text file, where each line consists of
+//! the source block name, the destination block name and the edge count,
+//! separated by whitespaces. Lines starting with `#` are ignored.
+
+use inkwell::values::{BasicBlock, FunctionValue};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use crate::ir::basic_block::BasicBlockExt;
+
+/// Data structure storing weights of edges between basic blocks.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeProfile<'ctx> {
+    /// Mapping from edges to their weights.
+    weights: HashMap<(BasicBlock<'ctx>, BasicBlock<'ctx>), u64>,
+}
+
+/// Implement methods for `EdgeProfile`.
+impl<'ctx> EdgeProfile<'ctx> {
+    /// Constructor of an empty profile.
+    pub fn new() -> Self {
+        EdgeProfile {
+            weights: HashMap::new(),
+        }
+    }
+
+    /// Set the weight of an edge, replacing any previous weight.
+    pub fn set_weight(
+        &mut self,
+        src: BasicBlock<'ctx>,
+        dst: BasicBlock<'ctx>,
+        weight: u64,
+    ) {
+        self.weights.insert((src, dst), weight);
+    }
+
+    /// Add a weight to an edge, e.g., to accumulate counts of several runs.
+    pub fn add_weight(
+        &mut self,
+        src: BasicBlock<'ctx>,
+        dst: BasicBlock<'ctx>,
+        weight: u64,
+    ) {
+        let entry = self.weights.entry((src, dst)).or_insert(0);
+        *entry = entry.saturating_add(weight);
+    }
+
+    /// Get the weight of an edge, if available.
+    pub fn get_weight(
+        &self,
+        src: BasicBlock<'ctx>,
+        dst: BasicBlock<'ctx>,
+    ) -> Option<u64> {
+        self.weights.get(&(src, dst)).cloned()
+    }
+
+    /// Get the number of weighted edges.
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Check if the profile has no weighted edge.
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Get successors of a block, sorted by decreasing edge weights.
+    ///
+    /// Successors without weights are placed last. Successors with equal
+    /// weights keep their original order.
+    pub fn get_successors_by_weight(
+        &self,
+        blk: BasicBlock<'ctx>,
+    ) -> Vec<(BasicBlock<'ctx>, Option<u64>)> {
+        let mut succs: Vec<(BasicBlock, Option<u64>)> = blk
+            .get_successors()
+            .into_iter()
+            .map(|succ| (succ, self.get_weight(blk, succ)))
+            .collect();
+        succs.dedup_by_key(|(succ, _)| *succ);
+
+        succs.sort_by(|(_, w1), (_, w2)| w2.cmp(w1));
+        succs
+    }
+
+    /// Load edge counts of a function from a file and add them to the
+    /// profile.
+    ///
+    /// Edges between blocks which do not exist in the function are ignored.
+    pub fn load_edge_counts(
+        &mut self,
+        func: FunctionValue<'ctx>,
+        path: &Path,
+    ) -> io::Result<()> {
+        let content = fs::read_to_string(path)?;
+        self.parse_edge_counts(func, &content)
+    }
+
+    /// Parse edge counts of a function and add them to the profile.
+    ///
+    /// Edges between blocks which do not exist in the function are ignored.
+    pub fn parse_edge_counts(
+        &mut self,
+        func: FunctionValue<'ctx>,
+        content: &str,
+    ) -> io::Result<()> {
+        let blocks: HashMap<String, BasicBlock> = func
+            .get_basic_blocks()
+            .into_iter()
+            .map(|blk| (blk.get_name_or_default(), blk))
+            .collect();
+
+        for (line_idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let count = fields.get(2).and_then(|c| c.parse::<u64>().ok());
+            let (src, dst, count) = match (fields.first(), fields.get(1), count)
+            {
+                (Some(src), Some(dst), Some(count)) if fields.len() == 3 => {
+                    (src, dst, count)
+                }
+                _ => {
+                    let msg =
+                        format!("Invalid edge count at line {}", line_idx + 1);
+                    return Err(io::Error::new(ErrorKind::InvalidData, msg));
+                }
+            };
+
+            if let (Some(src), Some(dst)) = (blocks.get(*src), blocks.get(*dst))
+            {
+                self.add_weight(*src, *dst, count)
+            }
+        }
+
+        Ok(())
+    }
+}
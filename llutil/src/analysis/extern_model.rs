@@ -0,0 +1,168 @@
+//! Module providing a registry of semantic models of external functions.
+//!
+//! External functions have no body to analyze, so analyses such as effects,
+//! taint or symbolic execution consult this registry to know how a call to an
+//! external function behaves. Models are keyed by glob patterns of function
+//! names, where `*` matches any sequence of characters and `?` matches any
+//! single character.
+
+use inkwell::values::FunctionValue;
+use regex::Regex;
+
+use crate::ir::{
+    builtin::{assertion_lib, c_lib, solang_ewasm_lib},
+    FunctionExt,
+};
+
+/// Semantic model of an external function.
+#[remain::sorted]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExternModel {
+    /// The function has no side effect and its result depends only on its
+    /// arguments.
+    PureStub,
+
+    /// The function returns a nondeterministic value.
+    ReturnsNondet,
+
+    /// The function reads Solidity contract storage.
+    StorageRead,
+
+    /// The function writes Solidity contract storage.
+    StorageWrite,
+
+    /// The function writes to the memory pointed to by its argument at the
+    /// given index.
+    WritesArg(u32),
+}
+
+/// Registry of semantic models of external functions.
+#[derive(Debug, Clone, Default)]
+pub struct ExternModelRegistry {
+    /// Registered glob patterns, their compiled regexes and their models.
+    entries: Vec<(String, Regex, Vec<ExternModel>)>,
+}
+
+/// Implement methods for `ExternModelRegistry`.
+impl ExternModelRegistry {
+    /// Constructor of an empty registry.
+    pub fn new() -> Self {
+        ExternModelRegistry { entries: vec![] }
+    }
+
+    /// Constructor of a registry containing models of known external
+    /// functions of C and Solang-generated code.
+    pub fn with_defaults() -> Self {
+        let mut registry = ExternModelRegistry::new();
+
+        // LLVM debug intrinsics
+        registry.register("llvm.dbg.*", &[ExternModel::PureStub]);
+
+        // Nondeterministic values
+        let nondet_glob = format!("{}*", assertion_lib::PREFIX_NONDET);
+        registry.register(&nondet_glob, &[ExternModel::ReturnsNondet]);
+        registry.register(c_lib::RAND, &[ExternModel::ReturnsNondet]);
+        registry.register(c_lib::TIME, &[ExternModel::ReturnsNondet]);
+
+        // Solidity environment
+        for func_name in [
+            solang_ewasm_lib::GETADDRESS,
+            solang_ewasm_lib::GETBLOCKCOINBASE,
+            solang_ewasm_lib::GETBLOCKDIFFICULTY,
+            solang_ewasm_lib::GETBLOCKGASLIMIT,
+            solang_ewasm_lib::GETBLOCKHASH,
+            solang_ewasm_lib::GETBLOCKNUMBER,
+            solang_ewasm_lib::GETBLOCKTIMESTAMP,
+            solang_ewasm_lib::GETCALLDATASIZE,
+            solang_ewasm_lib::GETCALLER,
+            solang_ewasm_lib::GETCALLVALUE,
+            solang_ewasm_lib::GETGASLEFT,
+            solang_ewasm_lib::GETTXGASPRICE,
+            solang_ewasm_lib::GETTXORIGIN,
+        ] {
+            registry.register(func_name, &[ExternModel::ReturnsNondet]);
+        }
+
+        // Solidity storage
+        registry.register(
+            solang_ewasm_lib::STORAGELOAD,
+            &[ExternModel::StorageRead, ExternModel::WritesArg(1)],
+        );
+        registry.register(
+            solang_ewasm_lib::STORAGESTORE,
+            &[ExternModel::StorageWrite],
+        );
+
+        registry
+    }
+
+    /// Register models of external functions whose names match a glob
+    /// pattern.
+    ///
+    /// Later registrations take precedence over earlier ones.
+    pub fn register(&mut self, glob: &str, models: &[ExternModel]) {
+        let regex = compile_glob(glob);
+        self.entries.retain(|(g, _, _)| g != glob);
+        self.entries
+            .push((glob.to_string(), regex, models.to_vec()))
+    }
+
+    /// Look up models of an external function by its name.
+    pub fn lookup(&self, func_name: &str) -> Option<&[ExternModel]> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(_, regex, _)| regex.is_match(func_name))
+            .map(|(_, _, models)| models.as_slice())
+    }
+
+    /// Look up models of an external function.
+    pub fn lookup_function(
+        &self,
+        func: FunctionValue,
+    ) -> Option<&[ExternModel]> {
+        self.lookup(&func.get_name_or_default())
+    }
+
+    /// Check if a function has a given model.
+    pub fn has_model(&self, func_name: &str, model: ExternModel) -> bool {
+        self.lookup(func_name)
+            .map_or(false, |models| models.contains(&model))
+    }
+
+    /// Get indices of arguments written by a function.
+    pub fn get_written_args(&self, func_name: &str) -> Vec<u32> {
+        self.lookup(func_name)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|model| match model {
+                ExternModel::WritesArg(idx) => Some(*idx),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the number of registered glob patterns.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the registry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Compile a glob pattern of function names into a regular expression.
+fn compile_glob(glob: &str) -> Regex {
+    let pattern = glob
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            _ => regex::escape(&c.to_string()),
+        })
+        .collect::<String>();
+
+    Regex::new(&format!("^{}$", pattern)).unwrap()
+}
@@ -0,0 +1,309 @@
+//! Module extracting the most likely execution paths of entry functions.
+//!
+//! A hot path is a sequence of basic blocks, each annotated with its function
+//! and the path condition under which it is entered. Paths are explored in
+//! decreasing order of probabilities, which are products of the branch
+//! probabilities estimated by `BlockFrequency`. When a block calls a defined
+//! function, the hot paths of the callee are spliced into the path of the
+//! caller right after the calling block.
+//!
+//! To keep paths finite, each block is visited at most `MAX_BLOCK_VISITS`
+//! times in a path of a function, recursive calls are not followed, and calls
+//! are followed up to the depth `MAX_CALL_DEPTH`.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use inkwell::values::{BasicBlock, FunctionValue};
+
+use crate::{
+    analysis::BlockFrequency,
+    ir::{
+        basic_block::BasicBlockExt, AnyCall, FunctionExt, InstructionExt,
+        PathCondition,
+    },
+};
+
+/// Maximum number of times a block is visited in a path of a function.
+const MAX_BLOCK_VISITS: usize = 2;
+
+/// Maximum depth of calls followed when extracting hot paths.
+const MAX_CALL_DEPTH: usize = 4;
+
+/// Maximum number of partial paths expanded when extracting the hot paths of
+/// a function.
+const MAX_EXPANSIONS: usize = 10000;
+
+/// Data structure representing a step of a hot path.
+#[derive(Debug, Clone)]
+pub struct PathStep<'ctx> {
+    /// Function of the block.
+    pub function: FunctionValue<'ctx>,
+
+    /// Block visited by the step.
+    pub block: BasicBlock<'ctx>,
+
+    /// Path condition under which the block is entered.
+    pub condition: PathCondition<'ctx>,
+}
+
+/// Implement the trait `Display` for `PathStep`.
+impl<'ctx> Display for PathStep<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            self.function.get_name_or_default(),
+            self.block.get_name_or_default()
+        )?;
+
+        match self.condition {
+            PathCondition::None => Ok(()),
+            _ => write!(f, " [{}]", self.condition),
+        }
+    }
+}
+
+/// Data structure representing a hot path and its estimated probability.
+#[derive(Debug, Clone)]
+pub struct HotPath<'ctx> {
+    /// Steps of the path, in the execution order.
+    pub steps: Vec<PathStep<'ctx>>,
+
+    /// Estimated probability that the path is executed.
+    pub probability: f64,
+}
+
+/// Implement the trait `Display` for `HotPath`.
+impl<'ctx> Display for HotPath<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hot path (probability: {:.6}):", self.probability)?;
+        for step in &self.steps {
+            write!(f, "\n  - {}", step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Extract the `k` most likely execution paths starting from an entry
+/// function, sorted by decreasing probabilities.
+pub fn extract_hot_paths<'ctx>(
+    entry: FunctionValue<'ctx>,
+    k: usize,
+) -> Vec<HotPath<'ctx>> {
+    let mut extractor = HotPathExtractor {
+        k,
+        frequencies: HashMap::new(),
+        local_paths: HashMap::new(),
+    };
+    extractor.extract(entry, &mut vec![])
+}
+
+/// Data structure storing the states of the hot path extraction.
+struct HotPathExtractor<'ctx> {
+    /// Number of hot paths to extract.
+    k: usize,
+
+    /// Block frequencies of visited functions.
+    frequencies: HashMap<FunctionValue<'ctx>, BlockFrequency<'ctx>>,
+
+    /// Hot paths of visited functions, without following calls.
+    local_paths: HashMap<FunctionValue<'ctx>, Vec<HotPath<'ctx>>>,
+}
+
+/// Implement methods for `HotPathExtractor`.
+impl<'ctx> HotPathExtractor<'ctx> {
+    /// Extract hot paths of a function, following calls to defined functions
+    /// which are not in the call stack.
+    fn extract(
+        &mut self,
+        func: FunctionValue<'ctx>,
+        call_stack: &mut Vec<FunctionValue<'ctx>>,
+    ) -> Vec<HotPath<'ctx>> {
+        call_stack.push(func);
+
+        let mut hot_paths = vec![];
+        for local_path in self.get_local_paths(func) {
+            let mut paths = vec![HotPath {
+                steps: vec![],
+                probability: local_path.probability,
+            }];
+
+            for step in local_path.steps {
+                let callees = get_called_functions(step.block);
+                for path in paths.iter_mut() {
+                    path.steps.push(step.clone())
+                }
+
+                for callee in callees {
+                    if call_stack.len() >= MAX_CALL_DEPTH
+                        || call_stack.contains(&callee)
+                    {
+                        continue;
+                    }
+
+                    let callee_paths = self.extract(callee, call_stack);
+                    if !callee_paths.is_empty() {
+                        paths = splice_paths(&paths, &callee_paths, self.k);
+                    }
+                }
+            }
+
+            hot_paths.extend(paths);
+        }
+
+        call_stack.pop();
+        sort_and_truncate(&mut hot_paths, self.k);
+        hot_paths
+    }
+
+    /// Get hot paths of a function from its entry block to its exit blocks,
+    /// without following calls.
+    fn get_local_paths(
+        &mut self,
+        func: FunctionValue<'ctx>,
+    ) -> Vec<HotPath<'ctx>> {
+        if let Some(paths) = self.local_paths.get(&func) {
+            return paths.clone();
+        }
+
+        let paths = self.compute_local_paths(func);
+        self.local_paths.insert(func, paths.clone());
+        paths
+    }
+
+    /// Compute hot paths of a function by a best-first search over its
+    /// control flow graph.
+    fn compute_local_paths(
+        &mut self,
+        func: FunctionValue<'ctx>,
+    ) -> Vec<HotPath<'ctx>> {
+        let entry = match func.get_first_basic_block() {
+            Some(blk) => blk,
+            None => return vec![],
+        };
+
+        let freq = self
+            .frequencies
+            .entry(func)
+            .or_insert_with(|| BlockFrequency::compute(func));
+
+        let mut paths = vec![];
+        let mut queue = vec![HotPath {
+            steps: vec![PathStep {
+                function: func,
+                block: entry,
+                condition: PathCondition::None,
+            }],
+            probability: 1.0,
+        }];
+
+        let mut num_expansions = 0;
+        while paths.len() < self.k && num_expansions < MAX_EXPANSIONS {
+            let path = match pop_most_likely_path(&mut queue) {
+                Some(path) => path,
+                None => break,
+            };
+            num_expansions += 1;
+
+            let blk = match path.steps.last() {
+                Some(step) => step.block,
+                None => continue,
+            };
+
+            let succs = blk.get_conditioned_successors();
+            if succs.is_empty() {
+                paths.push(path);
+                continue;
+            }
+
+            let mut visited_succs = vec![];
+            for succ in succs {
+                if visited_succs.contains(&succ.block) {
+                    continue;
+                }
+                visited_succs.push(succ.block);
+
+                let num_visits = path
+                    .steps
+                    .iter()
+                    .filter(|step| step.block == succ.block)
+                    .count();
+                if num_visits >= MAX_BLOCK_VISITS {
+                    continue;
+                }
+
+                let mut new_path = path.clone();
+                new_path.probability *=
+                    freq.get_edge_probability(blk, succ.block);
+                new_path.steps.push(PathStep {
+                    function: func,
+                    block: succ.block,
+                    condition: succ.condition,
+                });
+                queue.push(new_path);
+            }
+        }
+
+        paths
+    }
+}
+
+/// Get defined functions called by a block, in the order of calls.
+pub(crate) fn get_called_functions(blk: BasicBlock) -> Vec<FunctionValue> {
+    blk.get_instructions()
+        .into_iter()
+        .filter_map(|inst| inst.try_into_call_base())
+        .filter_map(|call| call.get_called_function())
+        .filter(|callee| !callee.is_only_declared())
+        .collect()
+}
+
+/// Append each callee's path to each caller's path, keeping the `k` most
+/// likely resulting paths.
+fn splice_paths<'ctx>(
+    paths: &[HotPath<'ctx>],
+    callee_paths: &[HotPath<'ctx>],
+    k: usize,
+) -> Vec<HotPath<'ctx>> {
+    let mut spliced_paths = vec![];
+
+    for path in paths {
+        for callee_path in callee_paths {
+            let mut steps = path.steps.clone();
+            steps.extend(callee_path.steps.iter().cloned());
+            spliced_paths.push(HotPath {
+                steps,
+                probability: path.probability * callee_path.probability,
+            })
+        }
+    }
+
+    sort_and_truncate(&mut spliced_paths, k);
+    spliced_paths
+}
+
+/// Remove and return the most likely path of a queue.
+///
+/// Among paths of the same probability, the earliest queued path is chosen.
+fn pop_most_likely_path<'ctx>(
+    queue: &mut Vec<HotPath<'ctx>>,
+) -> Option<HotPath<'ctx>> {
+    let mut best_idx: Option<usize> = None;
+    for (idx, path) in queue.iter().enumerate() {
+        match best_idx {
+            Some(best) if queue[best].probability >= path.probability => {}
+            _ => best_idx = Some(idx),
+        }
+    }
+
+    best_idx.map(|idx| queue.remove(idx))
+}
+
+/// Sort paths by decreasing probabilities and keep the `k` first paths.
+fn sort_and_truncate(paths: &mut Vec<HotPath>, k: usize) {
+    paths.sort_by(|p1, p2| p2.probability.total_cmp(&p1.probability));
+    paths.truncate(k)
+}
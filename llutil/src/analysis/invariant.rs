@@ -0,0 +1,201 @@
+//! Module handling loop invariant annotations.
+//!
+//! Loop invariants are written in source code as `/* invariant: expr */`
+//! annotations. They are attached to loop headers as block tags, so that
+//! verification utilities can assume them at the loop header and check them
+//! on the edges entering the header, i.e., the loop entries and back edges.
+
+use inkwell::values::{BasicBlock, FunctionValue};
+use regex::Regex;
+use std::collections::HashSet;
+
+use crate::ir::{basic_block::BasicBlockExt, InstructionExt, TagExt};
+
+/// Tag key storing loop invariants of loop headers.
+pub const INVARIANT_TAG: &str = "invariant";
+
+/// Separator of multiple invariants stored in a tag.
+const INVARIANT_SEPARATOR: &str = "\n";
+
+/// Data structure representing an invariant annotation in source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvariantAnnotation {
+    /// Source line of the annotation, starting from 1.
+    pub line: u32,
+
+    /// Invariant expression.
+    pub expr: String,
+}
+
+/// Data structure representing invariants of a loop and the program points
+/// where they are assumed and checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopInvariant<'ctx> {
+    /// Header of the loop, where invariants are assumed.
+    pub header: BasicBlock<'ctx>,
+
+    /// Invariant expressions.
+    pub exprs: Vec<String>,
+
+    /// Predecessors of the header outside of the loop.
+    pub entries: Vec<BasicBlock<'ctx>>,
+
+    /// Predecessors of the header inside the loop, via back edges.
+    pub latches: Vec<BasicBlock<'ctx>>,
+}
+
+/// Implement methods for `LoopInvariant`.
+impl<'ctx> LoopInvariant<'ctx> {
+    /// Get the edges on which invariants must be checked, i.e., the edges
+    /// from loop entries and latches to the loop header.
+    pub fn get_check_edges(&self) -> Vec<(BasicBlock<'ctx>, BasicBlock<'ctx>)> {
+        self.entries
+            .iter()
+            .chain(self.latches.iter())
+            .map(|blk| (*blk, self.header))
+            .collect()
+    }
+}
+
+/// Parse all invariant annotations of a source code.
+pub fn parse_invariant_annotations(source: &str) -> Vec<InvariantAnnotation> {
+    let regex = Regex::new(r"(?s)/\*\s*invariant:\s*(.*?)\s*\*/").unwrap();
+
+    regex
+        .captures_iter(source)
+        .map(|cap| {
+            let start = cap.get(0).map_or(0, |m| m.start());
+            let line = source[..start].matches('\n').count() as u32 + 1;
+            let expr = cap[1].split_whitespace().collect::<Vec<_>>().join(" ");
+            InvariantAnnotation { line, expr }
+        })
+        .collect()
+}
+
+/// Attach invariant annotations to loop headers of a function.
+///
+/// Each annotation is attached to the first loop whose header starts at or
+/// after the annotation's source line. Return the number of attached
+/// annotations.
+pub fn attach_loop_invariants(
+    func: FunctionValue,
+    annots: &[InvariantAnnotation],
+) -> usize {
+    let mut headers: Vec<(u32, BasicBlock)> = compute_back_edges(func)
+        .into_iter()
+        .map(|(_, header)| header)
+        .filter_map(|header| Some((get_block_line(header)?, header)))
+        .collect();
+    headers.sort_by_key(|(line, _)| *line);
+    headers.dedup();
+
+    let mut num_attached = 0;
+    for annot in annots {
+        let header = headers.iter().find(|(line, _)| *line >= annot.line);
+        if let Some((_, header)) = header {
+            let mut exprs = get_invariant_exprs(*header);
+            if !exprs.contains(&annot.expr) {
+                exprs.push(annot.expr.clone());
+            }
+            let value = exprs.join(INVARIANT_SEPARATOR);
+            if header.put_tag(INVARIANT_TAG, &value) {
+                num_attached += 1;
+            }
+        }
+    }
+
+    num_attached
+}
+
+/// Get invariants of all annotated loops of a function.
+pub fn get_loop_invariants(func: FunctionValue) -> Vec<LoopInvariant> {
+    let back_edges = compute_back_edges(func);
+    let mut invariants: Vec<LoopInvariant> = vec![];
+
+    for (_, header) in &back_edges {
+        if invariants.iter().any(|inv| inv.header == *header) {
+            continue;
+        }
+
+        let exprs = get_invariant_exprs(*header);
+        if exprs.is_empty() {
+            continue;
+        }
+
+        let (latches, entries) = header
+            .get_predecessors()
+            .into_iter()
+            .partition(|pred| back_edges.contains(&(*pred, *header)));
+
+        invariants.push(LoopInvariant {
+            header: *header,
+            exprs,
+            entries,
+            latches,
+        })
+    }
+
+    invariants
+}
+
+/// Get invariant expressions attached to a loop header.
+fn get_invariant_exprs(header: BasicBlock) -> Vec<String> {
+    match header.get_tag(INVARIANT_TAG) {
+        Some(value) => value
+            .split(INVARIANT_SEPARATOR)
+            .filter(|expr| !expr.is_empty())
+            .map(|expr| expr.to_string())
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Get the first source line of a block, if it has debug information.
+fn get_block_line(blk: BasicBlock) -> Option<u32> {
+    blk.get_instructions()
+        .into_iter()
+        .find_map(|inst| inst.get_source_location())
+        .map(|loc| loc.line)
+}
+
+/// Compute back edges of a function, found by a depth-first traversal from
+/// the entry block. Each back edge is a pair of a latch and a loop header.
+pub(crate) fn compute_back_edges(
+    func: FunctionValue,
+) -> Vec<(BasicBlock, BasicBlock)> {
+    let mut back_edges = vec![];
+
+    let entry = match func.get_first_basic_block() {
+        Some(blk) => blk,
+        None => return back_edges,
+    };
+
+    let mut visited = HashSet::new();
+    let mut on_stack = HashSet::new();
+    let mut stack = vec![(entry, entry.get_successors(), 0)];
+    visited.insert(entry);
+    on_stack.insert(entry);
+
+    while let Some((blk, succs, idx)) = stack.last_mut() {
+        let blk = *blk;
+        let succ = succs.get(*idx).copied();
+        *idx += 1;
+
+        match succ {
+            Some(succ) => {
+                if on_stack.contains(&succ) {
+                    back_edges.push((blk, succ))
+                } else if visited.insert(succ) {
+                    on_stack.insert(succ);
+                    stack.push((succ, succ.get_successors(), 0))
+                }
+            }
+            None => {
+                on_stack.remove(&blk);
+                stack.pop();
+            }
+        }
+    }
+
+    back_edges
+}
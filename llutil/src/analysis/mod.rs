@@ -0,0 +1,63 @@
+//! Module containing analysis frameworks for LLVM IR.
+
+// Export sub modules
+pub mod access_path;
+pub mod block_frequency;
+pub mod call_graph;
+pub mod concolic;
+pub mod driver;
+pub mod edge_profile;
+pub mod extern_model;
+pub mod hot_path;
+pub mod invariant;
+pub mod obligation;
+pub mod obligation_result;
+pub mod selector;
+pub mod source_variable;
+pub mod storage;
+pub mod summary;
+pub mod sym_expr;
+pub mod trace_replay;
+pub mod transaction;
+pub mod wp;
+
+// Re-export sub-modules' data structures
+pub use access_path::{AccessPath, AccessStep};
+pub use block_frequency::BlockFrequency;
+pub use call_graph::{CallGraph, CallSite};
+pub use concolic::{write_concolic_inputs, ConcolicExecutor, ConcolicInput};
+pub use driver::{
+    check_time_budget, run_per_function, run_per_function_by_complexity,
+    run_per_function_with_budget, scale_time_budget, FunctionFailure,
+    FunctionResults, FunctionTimeout, MAX_BUDGET_FACTOR,
+};
+pub use edge_profile::EdgeProfile;
+pub use extern_model::{ExternModel, ExternModelRegistry};
+pub use hot_path::{extract_hot_paths, HotPath, PathStep};
+pub use invariant::{InvariantAnnotation, LoopInvariant};
+pub use obligation::{
+    collect_function_obligations, collect_obligations, ObligationKind,
+    ObligationPass, ProofObligation,
+};
+pub use obligation_result::{
+    annotate_result, check_and_annotate_module, check_obligations,
+    get_annotated_result, report_annotated_results, ObligationResult,
+    ObligationStatus,
+};
+pub use selector::{
+    explore_public_functions, find_dispatched_functions, find_public_functions,
+    PublicFunction, SelectorResults,
+};
+pub use source_variable::{
+    SourceScope, SourceScopeKind, SourceVariable, SourceVariableMap,
+};
+pub use storage::{StorageAccess, StorageAccessKind, StorageKey, StorageModel};
+pub use summary::{FunctionSummary, SummaryDriver};
+pub use sym_expr::SymExpr;
+pub use trace_replay::{
+    load_trace, replay_trace, BlockNumbering, ReplayedTrace, TraceStep,
+};
+pub use transaction::{
+    SequenceFinding, Transaction, TransactionDriver, TransactionOptions,
+};
+pub use wp::WpGenerator;
@@ -0,0 +1,281 @@
+//! Module extracting proof obligations from assertion calls.
+//!
+//! Each call to an assertion function `__assert_*` or a refutation function
+//! `__refute_*` of the assertion library is turned into a proof obligation,
+//! consisting of the asserted condition, the source location of the call, the
+//! enclosing function and the path condition dominating the call. Checkers
+//! and solver backends consume these obligations instead of each collecting
+//! assertion calls by itself.
+//!
+//! The dominating path condition is the conjunction of the branch conditions
+//! leading to the chain of single-predecessor blocks ending at the block of
+//! the call. It under-approximates the conditions which hold at the call.
+
+use std::fmt::{self, Display};
+
+use inkwell::{
+    module::Module,
+    values::{BasicBlock, BasicValueEnum, FunctionValue, InstructionValue},
+    IntPredicate,
+};
+
+use crate::{
+    analysis::{wp::get_branch_condition, SymExpr},
+    ir::{
+        basic_block::BasicBlockExt,
+        builtin::{
+            assertion_lib, classify_verazt_function, VeraztFunctionKind,
+        },
+        AnyCall, FunctionExt, InstructionExt, SourceLocation,
+    },
+    pass::{AnalysisResults, Invalidation, Pass},
+    solver::{encoder::SmtEncoder, Solver, Validity},
+};
+
+/// Kind of a proof obligation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObligationKind {
+    /// The condition must hold.
+    Assert,
+
+    /// The condition must not hold.
+    Refute,
+}
+
+/// Data structure representing a proof obligation of an assertion call.
+#[derive(Debug, Clone)]
+pub struct ProofObligation<'ctx> {
+    /// Kind of the obligation.
+    pub kind: ObligationKind,
+
+    /// Name of the called assertion function.
+    pub assertion: String,
+
+    /// Assertion call.
+    pub call: InstructionValue<'ctx>,
+
+    /// Function enclosing the assertion call.
+    pub function: FunctionValue<'ctx>,
+
+    /// Asserted condition, if it can be expressed symbolically.
+    ///
+    /// Conditions of alias assertions are not expressible and are `None`.
+    pub condition: Option<SymExpr<'ctx>>,
+
+    /// Path condition dominating the assertion call.
+    pub path_condition: SymExpr<'ctx>,
+
+    /// Source location of the assertion call, if any.
+    pub location: Option<SourceLocation>,
+}
+
+/// Implement methods for `ProofObligation`.
+impl<'ctx> ProofObligation<'ctx> {
+    /// Get the formula which must be valid for the obligation to hold, i.e.,
+    /// the path condition implies the condition, or its negation for
+    /// refutations.
+    pub fn get_formula(&self) -> Option<SymExpr<'ctx>> {
+        let cond = match self.kind {
+            ObligationKind::Assert => self.condition.clone()?,
+            ObligationKind::Refute => SymExpr::not(self.condition.clone()?),
+        };
+        Some(SymExpr::implies(self.path_condition.clone(), cond))
+    }
+
+    /// Check if the obligation holds using a solver.
+    ///
+    /// Return `Validity::Unknown` if the obligation cannot be encoded or the
+    /// solver cannot decide.
+    pub fn check(&self, solver: &mut dyn Solver) -> Validity {
+        let mut encoder = SmtEncoder::new();
+        let formula =
+            match self.get_formula().and_then(|f| encoder.encode_bool(&f)) {
+                Some(formula) => formula,
+                None => return Validity::Unknown,
+            };
+
+        solver.push();
+        encoder.declare_symbols(solver);
+        let res = solver.check_valid(&formula);
+        solver.pop();
+        res
+    }
+}
+
+/// Implement the trait `Display` for `ProofObligation`.
+impl<'ctx> Display for ProofObligation<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in {}",
+            self.assertion,
+            self.function.get_name_or_default()
+        )?;
+
+        if let Some(loc) = &self.location {
+            write!(f, " at {}", loc)?;
+        }
+
+        if let Some(cond) = &self.condition {
+            write!(f, ": {}", cond)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Collect proof obligations of all defined functions of a module.
+pub fn collect_obligations<'ctx>(
+    module: &Module<'ctx>,
+) -> Vec<ProofObligation<'ctx>> {
+    module
+        .get_functions()
+        .filter(|func| !func.is_only_declared())
+        .flat_map(collect_function_obligations)
+        .collect()
+}
+
+/// Collect proof obligations of a function, in the order of its blocks.
+pub fn collect_function_obligations(
+    func: FunctionValue,
+) -> Vec<ProofObligation> {
+    let mut obligations = vec![];
+
+    for blk in func.get_basic_blocks() {
+        let mut path_condition = None;
+
+        for inst in blk.get_instructions() {
+            let call = match inst.try_into_call_inst() {
+                Some(call) => call,
+                None => continue,
+            };
+            let assertion = match call.get_called_function() {
+                Some(callee) => callee.get_name_or_default(),
+                None => continue,
+            };
+            let kind = match classify_verazt_function(&assertion) {
+                Some(VeraztFunctionKind::Assert) => ObligationKind::Assert,
+                Some(VeraztFunctionKind::Refute) => ObligationKind::Refute,
+                _ => continue,
+            };
+
+            let args = call.get_called_arguments();
+            let path_condition = path_condition
+                .get_or_insert_with(|| get_dominating_condition(blk))
+                .clone();
+
+            obligations.push(ProofObligation {
+                kind,
+                condition: get_assertion_condition(&assertion, &args),
+                assertion,
+                call: inst,
+                function: func,
+                path_condition,
+                location: inst.get_source_location(),
+            })
+        }
+    }
+
+    obligations
+}
+
+/// Get the condition asserted by an assertion function on its arguments.
+///
+/// Range assertions compare signed integers. Other assertions with a single
+/// integer argument assert that the argument is non-zero.
+fn get_assertion_condition<'ctx>(
+    assertion: &str,
+    args: &[BasicValueEnum<'ctx>],
+) -> Option<SymExpr<'ctx>> {
+    let arg = |i: usize| args.get(i).map(|arg| SymExpr::from_value(*arg));
+    let cmp = |pred: IntPredicate, lhs: SymExpr<'ctx>, rhs: SymExpr<'ctx>| {
+        SymExpr::Cmp(pred, Box::new(lhs), Box::new(rhs))
+    };
+
+    let assertion = match assertion.strip_prefix(assertion_lib::PREFIX_REFUTE) {
+        Some(suffix) => format!("{}{}", assertion_lib::PREFIX_ASSERT, suffix),
+        None => assertion.to_string(),
+    };
+
+    match assertion.as_str() {
+        assertion_lib::ASSERT_RANGE => Some(SymExpr::and(
+            cmp(IntPredicate::SGE, arg(0)?, arg(1)?),
+            cmp(IntPredicate::SLE, arg(0)?, arg(2)?),
+        )),
+        assertion_lib::ASSERT_LOWER_BOUND => {
+            Some(cmp(IntPredicate::SGE, arg(0)?, arg(1)?))
+        }
+        assertion_lib::ASSERT_UPPER_BOUND => {
+            Some(cmp(IntPredicate::SLE, arg(0)?, arg(1)?))
+        }
+        assertion_lib::ASSERT_NO_ALIAS
+        | assertion_lib::ASSERT_MUST_ALIAS
+        | assertion_lib::ASSERT_MAY_ALIAS => None,
+        _ => match args {
+            [BasicValueEnum::IntValue(v)] => {
+                let width = v.get_type().get_bit_width();
+                let value = SymExpr::from_value((*v).into());
+                if width == 1 {
+                    Some(value)
+                } else {
+                    let zero = SymExpr::Int(0, width);
+                    Some(cmp(IntPredicate::NE, value, zero))
+                }
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Get the path condition dominating a block, following the chain of blocks
+/// with a single predecessor.
+fn get_dominating_condition(blk: BasicBlock) -> SymExpr {
+    let mut cond = SymExpr::Bool(true);
+    let mut current_blk = blk;
+    let mut visited = vec![blk];
+
+    loop {
+        let predecessors = current_blk.get_predecessors();
+        let pred = match predecessors.as_slice() {
+            [pred] if !visited.contains(pred) => *pred,
+            _ => return cond,
+        };
+
+        if let Some(branch_cond) = get_branch_condition(pred, current_blk) {
+            cond = SymExpr::and(branch_cond, cond)
+        }
+
+        visited.push(pred);
+        current_blk = pred;
+    }
+}
+
+/// Pass collecting proof obligations of a module, to be scheduled by the
+/// `PassManager`.
+///
+/// Obligations refer to IR values, so the pass only keeps their textual
+/// descriptions. Use `collect_obligations` to get the obligations.
+#[derive(Debug, Default)]
+pub struct ObligationPass {
+    /// Descriptions of obligations collected by the last run.
+    pub obligations: Vec<String>,
+}
+
+/// Implement the trait `Pass` for `ObligationPass`.
+impl Pass for ObligationPass {
+    fn get_name(&self) -> String {
+        "collect-obligations".to_string()
+    }
+
+    fn invalidated_analyses(&self) -> Invalidation {
+        Invalidation::None
+    }
+
+    fn run(&mut self, module: &Module, _results: &AnalysisResults) {
+        self.obligations = collect_obligations(module)
+            .iter()
+            .map(|obligation| obligation.to_string())
+            .collect();
+        log_debug!("Collected {} proof obligations", self.obligations.len());
+    }
+}
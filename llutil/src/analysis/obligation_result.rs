@@ -0,0 +1,257 @@
+//! Module annotating assertion calls with results of checking their proof
+//! obligations, and reporting these results next to source lines.
+//!
+//! A result is stored in the IR as metadata of the assertion call, under the
+//! kind `RESULT_METADATA_KIND`. The metadata node contains the status of the
+//! obligation and, for violated obligations, a reference to the model
+//! witnessing the violation, e.g., `model_0`. Results thus survive when the
+//! module is written to a file, and can be reported later.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    fs,
+};
+
+use inkwell::{
+    module::Module,
+    values::{BasicMetadataValueEnum, InstructionValue},
+};
+
+use crate::{
+    analysis::{collect_obligations, ProofObligation},
+    ir::SourceLocation,
+    solver::{encoder::SmtEncoder, Model, SatResult, Solver},
+};
+
+/// Metadata kind storing results of assertion calls.
+pub const RESULT_METADATA_KIND: &str = "llutil.result";
+
+/// Prefix of references to models of violated obligations.
+pub const MODEL_REF_PREFIX: &str = "model_";
+
+/// Status of a checked proof obligation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObligationStatus {
+    /// The obligation holds.
+    Proved,
+
+    /// The obligation is violated.
+    Violated,
+
+    /// The obligation could not be decided.
+    Unknown,
+}
+
+/// Implement methods for `ObligationStatus`.
+impl ObligationStatus {
+    /// Parse a status from its textual form.
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "proved" => Some(ObligationStatus::Proved),
+            "violated" => Some(ObligationStatus::Violated),
+            "unknown" => Some(ObligationStatus::Unknown),
+            _ => None,
+        }
+    }
+}
+
+/// Implement the trait `Display` for `ObligationStatus`.
+impl Display for ObligationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObligationStatus::Proved => write!(f, "proved"),
+            ObligationStatus::Violated => write!(f, "violated"),
+            ObligationStatus::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Data structure representing the result of checking a proof obligation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObligationResult {
+    /// Status of the obligation.
+    pub status: ObligationStatus,
+
+    /// Reference to the model witnessing a violation, if any.
+    pub model_ref: Option<String>,
+}
+
+/// Implement the trait `Display` for `ObligationResult`.
+impl Display for ObligationResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let Some(model_ref) = &self.model_ref {
+            write!(f, " ({})", model_ref)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check proof obligations using a solver.
+///
+/// Return the result of each obligation, in the same order, and the models of
+/// violated obligations, keyed by the model references of their results.
+pub fn check_obligations(
+    obligations: &[ProofObligation],
+    solver: &mut dyn Solver,
+) -> (Vec<ObligationResult>, HashMap<String, Model>) {
+    let mut results = vec![];
+    let mut models = HashMap::new();
+
+    for obligation in obligations {
+        let (status, model) = check_obligation(obligation, solver);
+
+        let model_ref = model.map(|model| {
+            let model_ref = format!("{}{}", MODEL_REF_PREFIX, models.len());
+            models.insert(model_ref.clone(), model);
+            model_ref
+        });
+
+        results.push(ObligationResult { status, model_ref })
+    }
+
+    (results, models)
+}
+
+/// Annotate an assertion call with the result of its obligation.
+///
+/// Return `false` if the call cannot be annotated.
+pub fn annotate_result(
+    call: InstructionValue,
+    result: &ObligationResult,
+) -> bool {
+    let context = match call.get_parent() {
+        Some(blk) => blk.get_context(),
+        None => return false,
+    };
+    let kind_id = context.get_kind_id(RESULT_METADATA_KIND);
+
+    let mut values = vec![context.metadata_string(&result.status.to_string())];
+    if let Some(model_ref) = &result.model_ref {
+        values.push(context.metadata_string(model_ref))
+    }
+
+    let values: Vec<BasicMetadataValueEnum> =
+        values.into_iter().map(|v| v.into()).collect();
+    let md_node = context.metadata_node(&values);
+    call.set_metadata(md_node, kind_id).is_ok()
+}
+
+/// Get the result annotated to an assertion call, if any.
+pub fn get_annotated_result(
+    call: InstructionValue,
+) -> Option<ObligationResult> {
+    let context = call.get_parent()?.get_context();
+    let kind_id = context.get_kind_id(RESULT_METADATA_KIND);
+    let md_node = call.get_metadata(kind_id)?;
+
+    let strings: Vec<String> = md_node
+        .get_node_values()
+        .into_iter()
+        .filter(|value| value.is_metadata_value())
+        .filter_map(|value| {
+            let md_value = value.into_metadata_value();
+            let value = md_value.get_string_value()?;
+            value.to_str().ok().map(|v| v.to_owned())
+        })
+        .collect();
+
+    let status = ObligationStatus::parse(strings.first()?)?;
+    let model_ref = strings.get(1).cloned();
+    Some(ObligationResult { status, model_ref })
+}
+
+/// Check all proof obligations of a module and annotate their assertion calls
+/// with the results.
+///
+/// Return the checked obligations, their results and the models of violated
+/// obligations.
+pub fn check_and_annotate_module<'ctx>(
+    module: &Module<'ctx>,
+    solver: &mut dyn Solver,
+) -> (
+    Vec<(ProofObligation<'ctx>, ObligationResult)>,
+    HashMap<String, Model>,
+) {
+    let obligations = collect_obligations(module);
+    let (results, models) = check_obligations(&obligations, solver);
+
+    for (obligation, result) in obligations.iter().zip(results.iter()) {
+        if !annotate_result(obligation.call, result) {
+            log_warn!("Failed to annotate result of: {}", obligation);
+        }
+    }
+
+    (obligations.into_iter().zip(results).collect(), models)
+}
+
+/// Print a report of the results annotated to assertion calls of a module,
+/// showing each result next to the source line of its assertion.
+pub fn report_annotated_results(module: &Module) -> String {
+    let mut entries: Vec<(Option<SourceLocation>, String, ObligationResult)> =
+        vec![];
+
+    for obligation in collect_obligations(module) {
+        if let Some(result) = get_annotated_result(obligation.call) {
+            entries.push((
+                obligation.location.clone(),
+                obligation.to_string(),
+                result,
+            ))
+        }
+    }
+
+    let mut sources: HashMap<String, Option<Vec<String>>> = HashMap::new();
+    let mut report = format!("Assertion results: {}", entries.len());
+
+    for (loc, obligation, result) in entries {
+        report += &format!("\n  - [{}] {}", result, obligation);
+
+        let loc = match loc {
+            Some(loc) => loc,
+            None => continue,
+        };
+        let lines = sources.entry(loc.file.clone()).or_insert_with(|| {
+            fs::read_to_string(&loc.file)
+                .ok()
+                .map(|s| s.lines().map(|l| l.to_string()).collect())
+        });
+        let text = lines
+            .as_ref()
+            .and_then(|lines| lines.get((loc.line as usize).checked_sub(1)?));
+        if let Some(text) = text {
+            report += &format!("\n      {} | {}", loc.line, text.trim())
+        }
+    }
+
+    report
+}
+
+/// Check a proof obligation and return its status and, if it is violated,
+/// a model of the violation.
+fn check_obligation(
+    obligation: &ProofObligation,
+    solver: &mut dyn Solver,
+) -> (ObligationStatus, Option<Model>) {
+    let mut encoder = SmtEncoder::new();
+    let formula = match obligation
+        .get_formula()
+        .and_then(|formula| encoder.encode_bool(&formula))
+    {
+        Some(formula) => formula,
+        None => return (ObligationStatus::Unknown, None),
+    };
+
+    solver.push();
+    encoder.declare_symbols(solver);
+    solver.assert(&format!("(not {})", formula));
+    let res = match solver.check_sat() {
+        SatResult::Unsat => (ObligationStatus::Proved, None),
+        SatResult::Sat => (ObligationStatus::Violated, solver.get_model()),
+        SatResult::Unknown => (ObligationStatus::Unknown, None),
+    };
+    solver.pop();
+
+    res
+}
@@ -0,0 +1,275 @@
+//! Module enumerating public functions of Solidity contracts compiled to LLVM
+//! IR, and exploring each of them independently.
+//!
+//! Contracts compiled by Solang dispatch external calls in the function
+//! `solang_dispatch`, which reads the 4-byte function selector from the
+//! calldata and switches over the selectors of the public functions. Each case
+//! of a switch over a 32-bit value of the dispatcher is considered a public
+//! function: its code consists of the blocks reachable from the case block
+//! without going through other case blocks, and of the functions they call.
+//!
+//! Each public function is explored under the condition that the selector
+//! equals its case value. The remaining calldata is not constrained, i.e., it
+//! is modelled by free symbols.
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    panic::{self, AssertUnwindSafe},
+};
+
+use inkwell::{
+    module::Module,
+    values::{BasicBlock, BasicValueEnum, FunctionValue},
+    IntPredicate,
+};
+
+use crate::{
+    analysis::{
+        driver::get_panic_message, hot_path::get_called_functions,
+        FunctionFailure, SymExpr,
+    },
+    ir::{
+        basic_block::BasicBlockExt, builtin::solang_ewasm_lib, FunctionExt,
+        InstructionExt,
+    },
+};
+
+/// Bit width of function selectors.
+pub const SELECTOR_BIT_WIDTH: u32 = 32;
+
+/// Data structure representing a public function identified by its selector.
+#[derive(Debug, Clone)]
+pub struct PublicFunction<'ctx> {
+    /// Function selector.
+    pub selector: u32,
+
+    /// Dispatching function.
+    pub dispatcher: FunctionValue<'ctx>,
+
+    /// Value compared against the selector by the dispatcher.
+    pub selector_value: BasicValueEnum<'ctx>,
+
+    /// Block of the dispatcher executed for the selector.
+    pub case_block: BasicBlock<'ctx>,
+
+    /// Blocks of the dispatcher executed for the selector.
+    pub blocks: Vec<BasicBlock<'ctx>>,
+
+    /// Defined functions called by the blocks executed for the selector.
+    pub callees: Vec<FunctionValue<'ctx>>,
+}
+
+/// Implement methods for `PublicFunction`.
+impl<'ctx> PublicFunction<'ctx> {
+    /// Get the condition under which the dispatcher executes the function,
+    /// i.e., the selector value equals the selector.
+    pub fn get_selector_condition(&self) -> SymExpr<'ctx> {
+        let value = SymExpr::Var(self.selector_value);
+        let selector = SymExpr::Int(self.selector as u64, SELECTOR_BIT_WIDTH);
+        SymExpr::Cmp(IntPredicate::EQ, Box::new(value), Box::new(selector))
+    }
+}
+
+/// Implement the trait `Display` for `PublicFunction`.
+impl<'ctx> Display for PublicFunction<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:08x}", self.selector)?;
+
+        let names: Vec<String> = self
+            .callees
+            .iter()
+            .map(|callee| callee.get_name_or_default())
+            .collect();
+        if !names.is_empty() {
+            write!(f, " ({})", names.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Data structure representing results of exploring public functions.
+#[derive(Debug)]
+pub struct SelectorResults<'ctx, T> {
+    /// Public functions successfully explored and their findings.
+    pub results: Vec<(PublicFunction<'ctx>, T)>,
+
+    /// Public functions whose exploration panicked, identified by their
+    /// selectors.
+    pub failures: Vec<FunctionFailure>,
+}
+
+/// Implement methods for `SelectorResults`.
+impl<'ctx, T> SelectorResults<'ctx, T> {
+    /// Check if all public functions were explored successfully.
+    pub fn is_successful(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Get the findings of a selector, if its function was explored
+    /// successfully.
+    pub fn get(&self, selector: u32) -> Option<&T> {
+        self.results
+            .iter()
+            .find(|(func, _)| func.selector == selector)
+            .map(|(_, res)| res)
+    }
+}
+
+/// Implement methods for `SelectorResults` with printable findings.
+impl<'ctx, T: Display> SelectorResults<'ctx, T> {
+    /// Print a report of findings of each public function.
+    pub fn print_report(&self) -> String {
+        let mut res = format!("Public functions: {}", self.results.len());
+
+        for (func, findings) in &self.results {
+            res += &format!("\n  - {}: {}", func, findings);
+        }
+
+        if !self.failures.is_empty() {
+            res += &format!("\nFailed selectors: {}", self.failures.len());
+            for failure in &self.failures {
+                res += &format!("\n  - {}", failure);
+            }
+        }
+
+        res
+    }
+}
+
+/// Enumerate public functions dispatched by the Solang dispatcher of a
+/// module, in the order of their cases.
+pub fn find_public_functions<'ctx>(
+    module: &Module<'ctx>,
+) -> Vec<PublicFunction<'ctx>> {
+    match module.get_function(solang_ewasm_lib::SOLANG_DISPATCH) {
+        Some(dispatcher) if !dispatcher.is_only_declared() => {
+            find_dispatched_functions(dispatcher)
+        }
+        _ => vec![],
+    }
+}
+
+/// Enumerate public functions dispatched by a dispatching function, in the
+/// order of their cases.
+pub fn find_dispatched_functions(
+    dispatcher: FunctionValue,
+) -> Vec<PublicFunction> {
+    let mut cases = vec![];
+
+    for blk in dispatcher.get_basic_blocks() {
+        let switch = match blk
+            .get_terminator()
+            .and_then(|inst| inst.try_into_switch_inst())
+        {
+            Some(switch) => switch,
+            None => continue,
+        };
+
+        let selector_value = switch.get_condition();
+        let is_selector = selector_value.is_int_value()
+            && selector_value.into_int_value().get_type().get_bit_width()
+                == SELECTOR_BIT_WIDTH;
+        if !is_selector {
+            continue;
+        }
+
+        for i in 0..switch.get_num_cases() {
+            let (case, succ) = match switch.get_case_and_successor(i) {
+                Some(res) => res,
+                None => continue,
+            };
+            let selector = case
+                .into_int_value()
+                .get_zero_extended_constant()
+                .map(|n| n as u32);
+            if let Some(selector) = selector {
+                cases.push((selector, selector_value, succ))
+            }
+        }
+    }
+
+    let case_blocks: HashSet<BasicBlock> =
+        cases.iter().map(|(_, _, blk)| *blk).collect();
+
+    cases
+        .into_iter()
+        .map(|(selector, selector_value, case_block)| {
+            let blocks = get_case_blocks(case_block, &case_blocks);
+            let mut callees = vec![];
+            for blk in &blocks {
+                for callee in get_called_functions(*blk) {
+                    if !callees.contains(&callee) {
+                        callees.push(callee)
+                    }
+                }
+            }
+
+            PublicFunction {
+                selector,
+                dispatcher,
+                selector_value,
+                case_block,
+                blocks,
+                callees,
+            }
+        })
+        .collect()
+}
+
+/// Explore each public function of a module independently.
+///
+/// The exploration receives the public function and the condition under
+/// which it is dispatched. Panics of the exploration are caught and recorded
+/// as failures of the explored selectors, instead of aborting the whole run.
+pub fn explore_public_functions<'ctx, T, F>(
+    module: &Module<'ctx>,
+    mut explore: F,
+) -> SelectorResults<'ctx, T>
+where
+    F: FnMut(&PublicFunction<'ctx>, SymExpr<'ctx>) -> T,
+{
+    let mut results = vec![];
+    let mut failures = vec![];
+
+    for func in find_public_functions(module) {
+        let cond = func.get_selector_condition();
+
+        match panic::catch_unwind(AssertUnwindSafe(|| explore(&func, cond))) {
+            Ok(res) => results.push((func, res)),
+            Err(err) => {
+                let failure = FunctionFailure {
+                    function: func.to_string(),
+                    message: get_panic_message(&err),
+                };
+                log_warn!("Exploration failed on selector: {}", failure);
+                failures.push(failure)
+            }
+        }
+    }
+
+    SelectorResults { results, failures }
+}
+
+/// Get blocks reachable from a case block without going through other case
+/// blocks, in the order of their discovery.
+fn get_case_blocks<'ctx>(
+    case_block: BasicBlock<'ctx>,
+    case_blocks: &HashSet<BasicBlock<'ctx>>,
+) -> Vec<BasicBlock<'ctx>> {
+    let mut blocks = vec![case_block];
+    let mut visited = HashSet::from([case_block]);
+    let mut idx = 0;
+
+    while let Some(blk) = blocks.get(idx).cloned() {
+        idx += 1;
+        for succ in blk.get_successors() {
+            if !case_blocks.contains(&succ) && visited.insert(succ) {
+                blocks.push(succ)
+            }
+        }
+    }
+
+    blocks
+}
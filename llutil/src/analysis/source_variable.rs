@@ -0,0 +1,309 @@
+//! Module mapping SSA values and allocas to source-level variables over
+//! program points, using the debug intrinsics `llvm.dbg.declare`,
+//! `llvm.dbg.value` and `llvm.dbg.addr`.
+//!
+//! A `llvm.dbg.declare` binds the address of a variable, usually an alloca,
+//! for the whole function. A `llvm.dbg.value` or a `llvm.dbg.addr` binds a
+//! variable to a value from the intrinsic on, until the next intrinsic of the
+//! same variable. These bindings are propagated along the control flow graph
+//! by a forward dataflow analysis, where a binding holds at the entry of a
+//! block only if it holds at the exits of all its predecessors.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use inkwell::values::{
+    AnyValue, AsValueRef, BasicBlock, FunctionValue, InstructionValue,
+};
+use llvm_sys::{
+    core::LLVMValueAsMetadata,
+    debuginfo::{
+        LLVMDIVariableGetLine, LLVMDIVariableGetScope, LLVMGetMetadataKind,
+        LLVMMetadataKind,
+    },
+    prelude::LLVMValueRef,
+};
+use regex::Regex;
+
+use crate::ir::{
+    compute_reverse_post_order,
+    debug_info::{get_debug_intrinsic, get_variable_name, print_value_ref},
+    ControlFlowGraph, DebugIntrinsicKind,
+};
+
+/// Kinds of source-level scopes of variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SourceScopeKind {
+    /// Scope of a whole function.
+    Subprogram,
+
+    /// Scope of a block of statements inside a function.
+    LexicalBlock,
+
+    /// Any other scope.
+    Other,
+}
+
+/// Data structure representing the source-level scope of a variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceScope {
+    /// Identifier of the scope, unique within an LLVM context.
+    id: usize,
+
+    /// Kind of the scope.
+    pub kind: SourceScopeKind,
+}
+
+/// Data structure representing a source-level variable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceVariable {
+    /// Name of the variable.
+    pub name: String,
+
+    /// Line where the variable is declared, or 0 if unknown.
+    pub line: u32,
+
+    /// Position of the variable among function parameters, starting from 1,
+    /// if it is a parameter.
+    pub arg: Option<u32>,
+
+    /// Scope of the variable.
+    pub scope: SourceScope,
+}
+
+/// Implement methods for `SourceVariable`.
+impl SourceVariable {
+    /// Constructor of a `SourceVariable` from a `DILocalVariable` metadata
+    /// argument of a debug intrinsic.
+    fn from_metadata(variable: LLVMValueRef) -> Option<Self> {
+        let name = get_variable_name(variable)?;
+
+        let metadata = unsafe { LLVMValueAsMetadata(variable) };
+        let line = unsafe { LLVMDIVariableGetLine(metadata) };
+        let scope = unsafe { LLVMDIVariableGetScope(metadata) };
+        let kind = match unsafe { LLVMGetMetadataKind(scope) } {
+            LLVMMetadataKind::LLVMDISubprogramMetadataKind => {
+                SourceScopeKind::Subprogram
+            }
+            LLVMMetadataKind::LLVMDILexicalBlockMetadataKind
+            | LLVMMetadataKind::LLVMDILexicalBlockFileMetadataKind => {
+                SourceScopeKind::LexicalBlock
+            }
+            _ => SourceScopeKind::Other,
+        };
+
+        let text = print_value_ref(variable);
+        let regex = Regex::new(r"\barg: (\d+)").unwrap();
+        let arg = regex.captures(&text).and_then(|cap| cap[1].parse().ok());
+
+        Some(SourceVariable {
+            name,
+            line,
+            arg,
+            scope: SourceScope {
+                id: scope as usize,
+                kind,
+            },
+        })
+    }
+
+    /// Check if the variable is a function parameter.
+    pub fn is_parameter(&self) -> bool {
+        self.arg.is_some()
+    }
+}
+
+/// Implement the trait `Display` for `SourceVariable`.
+impl Display for SourceVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Bindings from source-level variables to the values they hold.
+type Bindings = HashMap<SourceVariable, LLVMValueRef>;
+
+/// Data structure mapping values of a function to source-level variables at
+/// each program point.
+#[derive(Debug, Clone)]
+pub struct SourceVariableMap<'ctx> {
+    /// Function whose values are mapped.
+    pub function: FunctionValue<'ctx>,
+
+    /// Variables whose addresses are declared for the whole function, by
+    /// `llvm.dbg.declare`, indexed by their addresses.
+    declared: HashMap<LLVMValueRef, SourceVariable>,
+
+    /// Variables described by each debug intrinsic of the function.
+    variables: HashMap<InstructionValue<'ctx>, SourceVariable>,
+
+    /// Bindings holding at the entry of each reachable block.
+    entry_bindings: HashMap<BasicBlock<'ctx>, Bindings>,
+}
+
+/// Implement methods for `SourceVariableMap`.
+impl<'ctx> SourceVariableMap<'ctx> {
+    /// Constructor, computing the bindings of source-level variables of a
+    /// function.
+    pub fn new(func: FunctionValue<'ctx>) -> Self {
+        let mut declared = HashMap::new();
+        let mut variables = HashMap::new();
+        let mut cache: HashMap<LLVMValueRef, Option<SourceVariable>> =
+            HashMap::new();
+
+        for blk in func.get_basic_blocks() {
+            for inst in blk.get_instructions() {
+                let (kind, value, variable) = match get_debug_intrinsic(inst) {
+                    Some(res) => res,
+                    None => continue,
+                };
+                let variable = match cache
+                    .entry(variable)
+                    .or_insert_with(|| SourceVariable::from_metadata(variable))
+                {
+                    Some(variable) => variable.clone(),
+                    None => continue,
+                };
+
+                if let (DebugIntrinsicKind::Declare, Some(value)) =
+                    (kind, value)
+                {
+                    declared.entry(value).or_insert_with(|| variable.clone());
+                }
+                variables.insert(inst, variable);
+            }
+        }
+
+        let mut map = SourceVariableMap {
+            function: func,
+            declared,
+            variables,
+            entry_bindings: HashMap::new(),
+        };
+        map.compute_entry_bindings();
+        map
+    }
+
+    /// Compute the bindings holding at the entry of each block, by iterating
+    /// over blocks in reverse post-order until a fixpoint is reached.
+    fn compute_entry_bindings(&mut self) {
+        let cfg = ControlFlowGraph::new(self.function);
+        let blocks = compute_reverse_post_order(self.function);
+        let entry_block = match cfg.get_entry_block() {
+            Some(blk) => blk,
+            None => return,
+        };
+
+        let mut exit_bindings: HashMap<BasicBlock, Bindings> = HashMap::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for blk in &blocks {
+                // Unvisited predecessors are ignored, so that bindings are
+                // propagated around loops.
+                let mut bindings: Option<Bindings> = None;
+                if *blk != entry_block {
+                    for pred in cfg.get_predecessors(*blk) {
+                        let pred_bindings = match exit_bindings.get(pred) {
+                            Some(pred_bindings) => pred_bindings,
+                            None => continue,
+                        };
+                        bindings = Some(match bindings {
+                            None => pred_bindings.clone(),
+                            Some(mut bindings) => {
+                                bindings.retain(|var, value| {
+                                    pred_bindings.get(var) == Some(value)
+                                });
+                                bindings
+                            }
+                        });
+                    }
+                }
+                let bindings = bindings.unwrap_or_default();
+
+                let mut exit = bindings.clone();
+                for inst in blk.get_instructions() {
+                    self.transfer(inst, &mut exit);
+                }
+
+                if self.entry_bindings.get(blk) != Some(&bindings) {
+                    self.entry_bindings.insert(*blk, bindings);
+                    changed = true;
+                }
+                if exit_bindings.get(blk) != Some(&exit) {
+                    exit_bindings.insert(*blk, exit);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    /// Update bindings after an instruction, which binds a variable if it is
+    /// a `llvm.dbg.value` or a `llvm.dbg.addr`.
+    fn transfer(&self, inst: InstructionValue<'ctx>, bindings: &mut Bindings) {
+        let variable = match self.variables.get(&inst) {
+            Some(variable) => variable,
+            None => return,
+        };
+
+        match get_debug_intrinsic(inst) {
+            Some((DebugIntrinsicKind::Declare, _, _)) | None => {}
+            Some((_, Some(value), _)) => {
+                bindings.insert(variable.clone(), value);
+            }
+            // The variable is not available anymore, e.g., when its value was
+            // optimized away.
+            Some((_, None, _)) => {
+                bindings.remove(variable);
+            }
+        }
+    }
+
+    /// Get the source-level variable held by a value, or whose address is the
+    /// value, right before an instruction.
+    ///
+    /// If several variables are bound to the value, the smallest variable by
+    /// name, line and scope is returned, so that the result is deterministic.
+    pub fn get_source_variable<V: AsValueRef>(
+        &self,
+        value: &V,
+        at_inst: InstructionValue<'ctx>,
+    ) -> Option<SourceVariable> {
+        let value = value.as_value_ref();
+        if let Some(variable) = self.declared.get(&value) {
+            return Some(variable.clone());
+        }
+
+        let blk = at_inst.get_parent()?;
+        let mut bindings = self.entry_bindings.get(&blk)?.clone();
+        for inst in blk.get_instructions() {
+            if inst == at_inst {
+                break;
+            }
+            self.transfer(inst, &mut bindings);
+        }
+
+        bindings
+            .into_iter()
+            .filter(|(_, bound_value)| *bound_value == value)
+            .map(|(variable, _)| variable)
+            .min()
+    }
+
+    /// Get the source-level variable name of a value right before an
+    /// instruction, or its textual representation in LLVM IR if it has no
+    /// source-level name.
+    pub fn get_name_or_default<V: AnyValue<'ctx>>(
+        &self,
+        value: &V,
+        at_inst: InstructionValue<'ctx>,
+    ) -> String {
+        match self.get_source_variable(value, at_inst) {
+            Some(variable) => variable.name,
+            None => value.print_to_string().to_string(),
+        }
+    }
+}
@@ -0,0 +1,414 @@
+//! Module providing an abstract model of smart contract storage.
+//!
+//! Contract state is accessed in two ways, depending on the target chain:
+//!
+//! - Solang contracts read and write storage through the external functions
+//!   `storageLoad(key, value)` and `storageStore(key, value)`, whose models
+//!   are looked up in the external model registry.
+//!
+//! - Solana programs read and write the data of their accounts, which is
+//!   mapped in memory and pointed to by the `data` field of the structure
+//!   `SolAccountInfo`.
+//!
+//! Both are modelled as a map from symbolic storage keys to symbolic values,
+//! which is updated along a path. A read of a key that was not written on the
+//! path returns a symbolic value denoting the initial content of the key,
+//! and later reads of the same key return the same value. Keys are compared
+//! syntactically, i.e., distinct key expressions are assumed not to alias.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use inkwell::{
+    types::AnyTypeEnum,
+    values::{
+        BasicBlock, BasicValueEnum, InstructionOpcode, InstructionValue,
+        PointerValue,
+    },
+};
+
+use crate::{
+    analysis::{ExternModel, ExternModelRegistry, SymExpr},
+    ir::{AnyCall, InstructionExt},
+};
+
+/// Name of the structure describing Solana accounts.
+pub const SOL_ACCOUNT_INFO: &str = "struct.SolAccountInfo";
+
+/// Index of the field `data` in the structure `SolAccountInfo`.
+pub const SOL_ACCOUNT_INFO_DATA_FIELD: u64 = 3;
+
+/// Key of a storage location.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StorageKey<'ctx> {
+    /// A slot of Solidity contract storage, identified by the value stored
+    /// into the key buffer before the access, or by the buffer itself if the
+    /// stored value is unknown.
+    Slot(SymExpr<'ctx>),
+
+    /// A location in the data of a Solana account, identified by the account
+    /// and the offset from the start of its data.
+    AccountData(SymExpr<'ctx>, SymExpr<'ctx>),
+}
+
+/// Kind of a storage access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageAccessKind {
+    /// The access reads storage.
+    Read,
+
+    /// The access writes storage.
+    Write,
+}
+
+/// Data structure representing a storage access of an instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageAccess<'ctx> {
+    /// Accessing instruction.
+    pub instruction: InstructionValue<'ctx>,
+
+    /// Kind of the access.
+    pub kind: StorageAccessKind,
+
+    /// Accessed key.
+    pub key: StorageKey<'ctx>,
+
+    /// Value read from or written to storage by the instruction.
+    pub value: SymExpr<'ctx>,
+}
+
+/// Data structure modelling contract storage along a path.
+///
+/// The model is cloned to fork the storage state when a path branches.
+#[derive(Debug, Clone)]
+pub struct StorageModel<'ctx> {
+    /// Models of external functions, used to identify storage accesses.
+    registry: ExternModelRegistry,
+
+    /// Current symbolic values of accessed keys.
+    values: HashMap<StorageKey<'ctx>, SymExpr<'ctx>>,
+
+    /// Storage accesses executed along the path.
+    accesses: Vec<StorageAccess<'ctx>>,
+}
+
+/// Implement methods for `StorageModel`.
+impl<'ctx> StorageModel<'ctx> {
+    /// Constructor, using the default models of external functions.
+    pub fn new() -> Self {
+        StorageModel::with_registry(ExternModelRegistry::with_defaults())
+    }
+
+    /// Constructor, using a registry of models of external functions.
+    pub fn with_registry(registry: ExternModelRegistry) -> Self {
+        StorageModel {
+            registry,
+            values: HashMap::new(),
+            accesses: vec![],
+        }
+    }
+
+    /// Get the storage access of an instruction, if any, without updating
+    /// the storage state.
+    ///
+    /// The value of a read is the value loaded by the instruction.
+    pub fn get_access(
+        &self,
+        inst: InstructionValue<'ctx>,
+    ) -> Option<StorageAccess<'ctx>> {
+        match inst.get_opcode() {
+            InstructionOpcode::Call => self.get_call_access(inst),
+            InstructionOpcode::Load => {
+                let load = inst.try_into_load_inst()?;
+                let key = get_account_data_key(load.get_pointer_operand())?;
+                let value = SymExpr::Var(inst.try_into_basic_value_enum()?);
+                Some(StorageAccess {
+                    instruction: inst,
+                    kind: StorageAccessKind::Read,
+                    key,
+                    value,
+                })
+            }
+            InstructionOpcode::Store => {
+                let store = inst.try_into_store_inst()?;
+                let key = get_account_data_key(store.get_pointer_operand())?;
+                let value = SymExpr::from_value(store.get_value_operand());
+                Some(StorageAccess {
+                    instruction: inst,
+                    kind: StorageAccessKind::Write,
+                    key,
+                    value,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Execute an instruction on the storage state and return its storage
+    /// access, if any.
+    ///
+    /// The value of a read is the current value of the accessed key.
+    pub fn execute(
+        &mut self,
+        inst: InstructionValue<'ctx>,
+    ) -> Option<StorageAccess<'ctx>> {
+        let mut access = self.get_access(inst)?;
+
+        match access.kind {
+            StorageAccessKind::Read => {
+                access.value = self.read(&access.key, access.value.clone())
+            }
+            StorageAccessKind::Write => {
+                self.write(access.key.clone(), access.value.clone())
+            }
+        }
+
+        self.accesses.push(access.clone());
+        Some(access)
+    }
+
+    /// Execute all instructions of a block on the storage state and return
+    /// their storage accesses.
+    pub fn execute_block(
+        &mut self,
+        blk: BasicBlock<'ctx>,
+    ) -> Vec<StorageAccess<'ctx>> {
+        blk.get_instructions()
+            .into_iter()
+            .filter_map(|inst| self.execute(inst))
+            .collect()
+    }
+
+    /// Execute a path of blocks on the storage state and return their
+    /// storage accesses.
+    pub fn execute_path(
+        &mut self,
+        path: &[BasicBlock<'ctx>],
+    ) -> Vec<StorageAccess<'ctx>> {
+        path.iter()
+            .flat_map(|blk| self.execute_block(*blk))
+            .collect()
+    }
+
+    /// Read the current value of a key.
+    ///
+    /// If the key was not accessed before, the initial value is used and
+    /// recorded as the value of the key.
+    pub fn read(
+        &mut self,
+        key: &StorageKey<'ctx>,
+        initial: SymExpr<'ctx>,
+    ) -> SymExpr<'ctx> {
+        self.values.entry(key.clone()).or_insert(initial).clone()
+    }
+
+    /// Write a value to a key.
+    pub fn write(&mut self, key: StorageKey<'ctx>, value: SymExpr<'ctx>) {
+        self.values.insert(key, value);
+    }
+
+    /// Get the current value of a key, if it was accessed.
+    pub fn get_value(&self, key: &StorageKey<'ctx>) -> Option<&SymExpr<'ctx>> {
+        self.values.get(key)
+    }
+
+    /// Get storage accesses executed along the path.
+    pub fn get_accesses(&self) -> &[StorageAccess<'ctx>] {
+        &self.accesses
+    }
+
+    /// Get keys written along the path.
+    pub fn get_written_keys(&self) -> Vec<&StorageKey<'ctx>> {
+        let mut keys: Vec<&StorageKey> = vec![];
+
+        for access in &self.accesses {
+            if access.kind == StorageAccessKind::Write
+                && !keys.contains(&&access.key)
+            {
+                keys.push(&access.key)
+            }
+        }
+
+        keys
+    }
+
+    /// Get the storage access of a call to a Solang storage function.
+    fn get_call_access(
+        &self,
+        inst: InstructionValue<'ctx>,
+    ) -> Option<StorageAccess<'ctx>> {
+        let call = inst.try_into_call_inst()?;
+        let callee = call.get_called_function()?;
+        let models = self.registry.lookup_function(callee)?;
+
+        let kind = if models.contains(&ExternModel::StorageRead) {
+            StorageAccessKind::Read
+        } else if models.contains(&ExternModel::StorageWrite) {
+            StorageAccessKind::Write
+        } else {
+            return None;
+        };
+
+        let args = call.get_called_arguments();
+        let key = get_buffer_content(inst, *args.first()?);
+
+        // The value buffer of a read is written by the call.
+        let value = match (kind, *args.get(1)?) {
+            (StorageAccessKind::Read, BasicValueEnum::PointerValue(ptr)) => {
+                SymExpr::Memory(ptr)
+            }
+            (_, value) => get_buffer_content(inst, value),
+        };
+
+        Some(StorageAccess {
+            instruction: inst,
+            kind,
+            key: StorageKey::Slot(key),
+            value,
+        })
+    }
+}
+
+/// Implement the trait `Default` for `StorageModel`.
+impl<'ctx> Default for StorageModel<'ctx> {
+    fn default() -> Self {
+        StorageModel::new()
+    }
+}
+
+/// Implement the trait `Display` for `StorageKey`.
+impl<'ctx> Display for StorageKey<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageKey::Slot(key) => write!(f, "storage[{}]", key),
+            StorageKey::AccountData(account, offset) => {
+                write!(f, "{}.data[{}]", account, offset)
+            }
+        }
+    }
+}
+
+/// Implement the trait `Display` for `StorageModel`.
+impl<'ctx> Display for StorageModel<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Storage:")?;
+
+        let mut entries: Vec<String> = self
+            .values
+            .iter()
+            .map(|(key, value)| format!("{} = {}", key, value))
+            .collect();
+        entries.sort();
+
+        for entry in entries {
+            write!(f, "\n  {}", entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the symbolic content of a buffer passed to a storage function.
+///
+/// The content is the value of the last store into the buffer preceding the
+/// call in its block. If there is no such store, or the buffer may be
+/// written by another call in between, the content is the buffer's memory.
+fn get_buffer_content<'ctx>(
+    call: InstructionValue<'ctx>,
+    value: BasicValueEnum<'ctx>,
+) -> SymExpr<'ctx> {
+    let ptr = match value {
+        BasicValueEnum::PointerValue(ptr) => ptr,
+        value => return SymExpr::from_value(value),
+    };
+
+    let buffer = strip_pointer_casts(ptr);
+    let mut prev_inst = call.get_previous_instruction();
+    while let Some(inst) = prev_inst {
+        if let Some(store) = inst.try_into_store_inst() {
+            if strip_pointer_casts(store.get_pointer_operand()) == buffer {
+                return SymExpr::from_value(store.get_value_operand());
+            }
+        } else if let Some(other_call) = inst.try_into_call_base() {
+            let is_buffer_passed =
+                other_call.get_called_arguments().iter().any(|arg| {
+                    arg.is_pointer_value()
+                        && strip_pointer_casts(arg.into_pointer_value())
+                            == buffer
+                });
+            if is_buffer_passed {
+                break;
+            }
+        }
+        prev_inst = inst.get_previous_instruction();
+    }
+
+    SymExpr::Memory(ptr)
+}
+
+/// Strip `bitcast` instructions from a pointer.
+fn strip_pointer_casts(ptr: PointerValue) -> PointerValue {
+    let mut ptr = ptr;
+    while let Some(inst) = ptr.as_instruction() {
+        if inst.get_opcode() != InstructionOpcode::BitCast {
+            break;
+        }
+        match inst.get_operand(0).and_then(|op| op.left()) {
+            Some(BasicValueEnum::PointerValue(base)) => ptr = base,
+            _ => break,
+        }
+    }
+    ptr
+}
+
+/// Get the account data key of a pointer, if it points into the data of a
+/// Solana account, i.e., it is of the form `gep(load(gep(info, 0, 3)), off)`
+/// where `info` points to a `SolAccountInfo` structure.
+fn get_account_data_key(ptr: PointerValue) -> Option<StorageKey> {
+    let (data_ptr, offset) = match ptr.as_instruction() {
+        Some(inst) if inst.get_opcode() == InstructionOpcode::GetElementPtr => {
+            let base = inst.get_operand(0)?.left()?;
+            let offset = match inst.get_num_operands() {
+                2 => SymExpr::from_value(inst.get_operand(1)?.left()?),
+                _ => return None,
+            };
+            (base.into_pointer_value(), offset)
+        }
+        _ => (ptr, SymExpr::Int(0, 64)),
+    };
+
+    // The data pointer is loaded from the `data` field of an account.
+    let load = data_ptr.as_instruction()?.try_into_load_inst()?;
+    let field_ptr = load.get_pointer_operand();
+    let field_gep = field_ptr.as_instruction()?;
+    if field_gep.get_opcode() != InstructionOpcode::GetElementPtr
+        || field_gep.get_num_operands() != 3
+    {
+        return None;
+    }
+
+    let info = field_gep.get_operand(0)?.left()?.into_pointer_value();
+    let field_idx = field_gep
+        .get_operand(2)?
+        .left()?
+        .into_int_value()
+        .get_zero_extended_constant()?;
+    if field_idx != SOL_ACCOUNT_INFO_DATA_FIELD || !is_account_info(info) {
+        return None;
+    }
+
+    let account = SymExpr::from_value(info.into());
+    Some(StorageKey::AccountData(account, offset))
+}
+
+/// Check if a pointer points to a `SolAccountInfo` structure.
+fn is_account_info(ptr: PointerValue) -> bool {
+    match ptr.get_type().get_element_type() {
+        AnyTypeEnum::StructType(typ) => typ.get_name().map_or(false, |name| {
+            name.to_bytes() == SOL_ACCOUNT_INFO.as_bytes()
+        }),
+        _ => false,
+    }
+}
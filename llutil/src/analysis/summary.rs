@@ -0,0 +1,151 @@
+//! Module providing a framework for summary-based interprocedural analyses.
+//!
+//! A summary-based analysis computes a summary for each function, which
+//! abstracts the function's behavior and is merged into its callers at call
+//! sites. Summaries are computed bottom-up over strongly connected components
+//! (SCCs) of the call graph, so that callees are summarized before callers.
+//! Functions in a recursive SCC are iterated until their summaries reach a
+//! fixpoint.
+
+use std::collections::HashMap;
+
+use inkwell::{module::Module, values::FunctionValue};
+
+use crate::{
+    ir::{CallBase, FunctionExt},
+    progress::{NoProgress, ProgressSink, ProgressTracker},
+};
+
+use super::call_graph::CallGraph;
+
+/// Trait of a function summary, computed by a summary-based analysis.
+pub trait FunctionSummary<'ctx>: Sized + Clone + PartialEq {
+    /// Get the initial summary of a function.
+    ///
+    /// This summary is used for functions without bodies, and as the starting
+    /// point of the fixpoint iteration of recursive functions.
+    fn initial(func: FunctionValue<'ctx>) -> Self;
+
+    /// Compute the summary of a function, given the current summaries of
+    /// other functions.
+    ///
+    /// The computation is expected to call `merge_at_call_site` for each call
+    /// site whose callee has a summary in `summaries`.
+    fn compute(
+        func: FunctionValue<'ctx>,
+        summaries: &HashMap<FunctionValue<'ctx>, Self>,
+    ) -> Self;
+
+    /// Merge the summary of a callee into the current summary at a call site.
+    fn merge_at_call_site(&mut self, callee: &Self, call_site: CallBase<'ctx>);
+}
+
+/// Data structure driving the computation of function summaries over the call
+/// graph of a module.
+#[derive(Debug, Clone)]
+pub struct SummaryDriver {
+    /// Maximum number of iterations to compute summaries of a recursive SCC.
+    pub max_iterations: usize,
+}
+
+/// Implement methods for `SummaryDriver`.
+impl SummaryDriver {
+    /// Constructor
+    pub fn new(max_iterations: usize) -> Self {
+        SummaryDriver { max_iterations }
+    }
+
+    /// Compute summaries of all functions of a module.
+    pub fn compute<'ctx, S: FunctionSummary<'ctx>>(
+        &self,
+        module: &Module<'ctx>,
+    ) -> HashMap<FunctionValue<'ctx>, S> {
+        self.compute_with_progress(module, &NoProgress)
+    }
+
+    /// Compute summaries of all functions of a module, reporting the progress
+    /// over SCCs of the call graph to a sink.
+    pub fn compute_with_progress<'ctx, S: FunctionSummary<'ctx>>(
+        &self,
+        module: &Module<'ctx>,
+        progress: &dyn ProgressSink,
+    ) -> HashMap<FunctionValue<'ctx>, S> {
+        let mut summaries = HashMap::new();
+
+        for func in module.get_functions() {
+            if func.is_only_declared() {
+                summaries.insert(func, S::initial(func));
+            }
+        }
+
+        let call_graph = CallGraph::new(module);
+        let sccs: Vec<&[FunctionValue]> = call_graph
+            .iter_sccs()
+            .filter(|scc| !scc[0].is_only_declared())
+            .collect();
+        let mut tracker = ProgressTracker::new(progress, "summary", sccs.len());
+
+        for scc in sccs {
+            tracker.start_item(&scc[0].get_name_or_default());
+            let recursive = call_graph.is_recursive(scc[0]);
+            self.compute_scc(scc, recursive, &mut summaries);
+            tracker.finish_item();
+        }
+
+        tracker.finish();
+        summaries
+    }
+
+    /// Compute summaries of functions in an SCC, iterating until a fixpoint
+    /// is reached if the SCC is recursive.
+    fn compute_scc<'ctx, S: FunctionSummary<'ctx>>(
+        &self,
+        scc: &[FunctionValue<'ctx>],
+        recursive: bool,
+        summaries: &mut HashMap<FunctionValue<'ctx>, S>,
+    ) {
+        if !recursive {
+            let func = scc[0];
+            let summary = S::compute(func, summaries);
+            summaries.insert(func, summary);
+            return;
+        }
+
+        for func in scc {
+            summaries.insert(*func, S::initial(*func));
+        }
+
+        for _ in 0..self.max_iterations {
+            let mut changed = false;
+
+            for func in scc {
+                let summary = S::compute(*func, summaries);
+                if summaries.get(func) != Some(&summary) {
+                    summaries.insert(*func, summary);
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                return;
+            }
+        }
+
+        log_debug!(
+            "Summaries of recursive functions do not converge after {} \
+             iterations: {}",
+            self.max_iterations,
+            scc.iter()
+                .map(|f| f.get_name_or_default())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
+}
+
+/// Implement the trait `Default` for `SummaryDriver`.
+impl Default for SummaryDriver {
+    fn default() -> Self {
+        SummaryDriver::new(100)
+    }
+}
@@ -0,0 +1,184 @@
+//! Module modelling symbolic expressions over LLVM values.
+
+use inkwell::{
+    values::{AnyValue, BasicValueEnum, InstructionOpcode, PointerValue},
+    IntPredicate,
+};
+use std::fmt::{self, Display};
+
+use crate::ir::{BasicValueExt, BinaryPredicate, PointerExt};
+
+/// Data structure modelling a symbolic expression over LLVM values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SymExpr<'ctx> {
+    /// Boolean constant.
+    Bool(bool),
+
+    /// Integer constant, consisting of its zero-extended value and its bit
+    /// width.
+    Int(u64, u32),
+
+    /// An SSA value.
+    Var(BasicValueEnum<'ctx>),
+
+    /// The content of a memory location pointed to by a pointer.
+    Memory(PointerValue<'ctx>),
+
+    /// Binary operation, identified by its LLVM opcode.
+    Binary(InstructionOpcode, Box<SymExpr<'ctx>>, Box<SymExpr<'ctx>>),
+
+    /// Integer comparison.
+    Cmp(IntPredicate, Box<SymExpr<'ctx>>, Box<SymExpr<'ctx>>),
+
+    /// Cast operation, consisting of its LLVM opcode, the casted expression
+    /// and the destination bit width.
+    Cast(InstructionOpcode, Box<SymExpr<'ctx>>, u32),
+
+    /// If-then-else expression.
+    Ite(Box<SymExpr<'ctx>>, Box<SymExpr<'ctx>>, Box<SymExpr<'ctx>>),
+
+    /// Logical negation.
+    Not(Box<SymExpr<'ctx>>),
+
+    /// Logical conjunction.
+    And(Box<SymExpr<'ctx>>, Box<SymExpr<'ctx>>),
+
+    /// Logical disjunction.
+    Or(Box<SymExpr<'ctx>>, Box<SymExpr<'ctx>>),
+
+    /// Logical implication.
+    Implies(Box<SymExpr<'ctx>>, Box<SymExpr<'ctx>>),
+}
+
+/// Implement methods for `SymExpr`.
+impl<'ctx> SymExpr<'ctx> {
+    /// Create a symbolic expression from an LLVM value.
+    ///
+    /// Integer constants of at most 64 bits are converted to `SymExpr::Int`,
+    /// other values, including wider integer constants, are converted to
+    /// `SymExpr::Var`.
+    pub fn from_value(value: BasicValueEnum<'ctx>) -> Self {
+        if let BasicValueEnum::IntValue(v) = value {
+            if let Some(n) = v.get_zero_extended_constant() {
+                let width = v.get_type().get_bit_width();
+                return match width {
+                    1 => SymExpr::Bool(n != 0),
+                    _ => SymExpr::Int(n, width),
+                };
+            }
+        }
+
+        SymExpr::Var(value)
+    }
+
+    /// Create a negation of an expression.
+    pub fn not(expr: SymExpr<'ctx>) -> Self {
+        match expr {
+            SymExpr::Bool(b) => SymExpr::Bool(!b),
+            SymExpr::Not(e) => *e,
+            _ => SymExpr::Not(Box::new(expr)),
+        }
+    }
+
+    /// Create a conjunction of two expressions.
+    pub fn and(lhs: SymExpr<'ctx>, rhs: SymExpr<'ctx>) -> Self {
+        match (lhs, rhs) {
+            (SymExpr::Bool(true), e) | (e, SymExpr::Bool(true)) => e,
+            (SymExpr::Bool(false), _) | (_, SymExpr::Bool(false)) => {
+                SymExpr::Bool(false)
+            }
+            (lhs, rhs) => SymExpr::And(Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    /// Create an implication of two expressions.
+    pub fn implies(lhs: SymExpr<'ctx>, rhs: SymExpr<'ctx>) -> Self {
+        match (lhs, rhs) {
+            (SymExpr::Bool(true), e) => e,
+            (SymExpr::Bool(false), _) | (_, SymExpr::Bool(true)) => {
+                SymExpr::Bool(true)
+            }
+            (lhs, rhs) => SymExpr::Implies(Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    /// Substitute all occurrences of a sub-expression by another expression.
+    pub fn substitute(&self, from: &SymExpr<'ctx>, to: &SymExpr<'ctx>) -> Self {
+        if self == from {
+            return to.clone();
+        }
+
+        let subst = |e: &SymExpr<'ctx>| Box::new(e.substitute(from, to));
+
+        match self {
+            SymExpr::Bool(_)
+            | SymExpr::Int(..)
+            | SymExpr::Var(_)
+            | SymExpr::Memory(_) => self.clone(),
+            SymExpr::Binary(op, l, r) => {
+                SymExpr::Binary(*op, subst(l), subst(r))
+            }
+            SymExpr::Cmp(pred, l, r) => SymExpr::Cmp(*pred, subst(l), subst(r)),
+            SymExpr::Cast(op, e, w) => SymExpr::Cast(*op, subst(e), *w),
+            SymExpr::Ite(c, t, e) => SymExpr::Ite(subst(c), subst(t), subst(e)),
+            SymExpr::Not(e) => SymExpr::Not(subst(e)),
+            SymExpr::And(l, r) => SymExpr::And(subst(l), subst(r)),
+            SymExpr::Or(l, r) => SymExpr::Or(subst(l), subst(r)),
+            SymExpr::Implies(l, r) => SymExpr::Implies(subst(l), subst(r)),
+        }
+    }
+
+    /// Check if the expression contains a sub-expression.
+    pub fn contains(&self, sub: &SymExpr<'ctx>) -> bool {
+        if self == sub {
+            return true;
+        }
+
+        match self {
+            SymExpr::Bool(_)
+            | SymExpr::Int(..)
+            | SymExpr::Var(_)
+            | SymExpr::Memory(_) => false,
+            SymExpr::Cast(_, e, _) | SymExpr::Not(e) => e.contains(sub),
+            SymExpr::Binary(_, l, r)
+            | SymExpr::Cmp(_, l, r)
+            | SymExpr::And(l, r)
+            | SymExpr::Or(l, r)
+            | SymExpr::Implies(l, r) => l.contains(sub) || r.contains(sub),
+            SymExpr::Ite(c, t, e) => {
+                c.contains(sub) || t.contains(sub) || e.contains(sub)
+            }
+        }
+    }
+}
+
+/// Implement the trait `Display` for `SymExpr`.
+impl<'ctx> Display for SymExpr<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymExpr::Bool(b) => write!(f, "{}", b),
+            SymExpr::Int(n, w) => write!(f, "{}:i{}", n, w),
+            SymExpr::Var(v) => match v.get_name_or_default() {
+                name if name.is_empty() => write!(f, "{}", v.print_to_string()),
+                name => write!(f, "%{}", name),
+            },
+            SymExpr::Memory(p) => match p.get_name_or_default() {
+                name if name.is_empty() => {
+                    write!(f, "*({})", p.print_to_string())
+                }
+                name => write!(f, "*%{}", name),
+            },
+            SymExpr::Binary(op, l, r) => write!(f, "({:?} {} {})", op, l, r),
+            SymExpr::Cmp(pred, l, r) => {
+                let pred = BinaryPredicate::IntPred(*pred);
+                write!(f, "({} {} {})", l, pred, r)
+            }
+            SymExpr::Cast(op, e, w) => write!(f, "({:?} {} to i{})", op, e, w),
+            SymExpr::Ite(c, t, e) => write!(f, "({} ? {} : {})", c, t, e),
+            SymExpr::Not(e) => write!(f, "!{}", e),
+            SymExpr::And(l, r) => write!(f, "({} && {})", l, r),
+            SymExpr::Or(l, r) => write!(f, "({} || {})", l, r),
+            SymExpr::Implies(l, r) => write!(f, "({} => {})", l, r),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Module replaying execution traces recorded by tracing instrumentation.
+//!
+//! A trace log lists the IDs of executed basic blocks, one ID per line, in the
+//! execution order. Empty lines and lines starting with `#` are ignored. Block
+//! IDs are assigned by `BlockNumbering`: blocks of defined functions are
+//! numbered from 0, following the order of functions in the module and the
+//! order of blocks in each function.
+//!
+//! Replaying a trace reconstructs the executed path through the module and its
+//! call stack, and validates each transition against the control flow graph:
+//! the next block is either a successor of the current block, the entry block
+//! of a function called by the current block, or, after the current block
+//! returns, a block reachable the same way from the calling block.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use inkwell::{
+    module::Module,
+    values::{BasicBlock, FunctionValue, InstructionOpcode},
+};
+
+use crate::{
+    analysis::{hot_path::get_called_functions, PathStep},
+    ir::{basic_block::BasicBlockExt, FunctionExt, PathCondition},
+};
+
+/// Data structure assigning IDs to basic blocks of a module.
+#[derive(Debug, Clone)]
+pub struct BlockNumbering<'ctx> {
+    /// Blocks and their functions, indexed by block IDs.
+    blocks: Vec<(FunctionValue<'ctx>, BasicBlock<'ctx>)>,
+
+    /// Mapping from blocks to their IDs.
+    ids: HashMap<BasicBlock<'ctx>, usize>,
+}
+
+/// Implement methods for `BlockNumbering`.
+impl<'ctx> BlockNumbering<'ctx> {
+    /// Constructor, which numbers blocks of all defined functions of a module.
+    pub fn new(module: &Module<'ctx>) -> Self {
+        let mut blocks = vec![];
+        let mut ids = HashMap::new();
+
+        for func in module.get_functions() {
+            for blk in func.get_basic_blocks() {
+                ids.insert(blk, blocks.len());
+                blocks.push((func, blk));
+            }
+        }
+
+        BlockNumbering { blocks, ids }
+    }
+
+    /// Get the block of an ID and its function.
+    pub fn get_block(
+        &self,
+        id: usize,
+    ) -> Option<(FunctionValue<'ctx>, BasicBlock<'ctx>)> {
+        self.blocks.get(id).cloned()
+    }
+
+    /// Get the ID of a block.
+    pub fn get_id(&self, blk: BasicBlock<'ctx>) -> Option<usize> {
+        self.ids.get(&blk).cloned()
+    }
+
+    /// Get the number of numbered blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Check if no block is numbered.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// Data structure representing a step of a replayed trace.
+#[derive(Debug, Clone)]
+pub struct TraceStep<'ctx> {
+    /// Executed block, its function and the path condition under which it is
+    /// entered.
+    pub step: PathStep<'ctx>,
+
+    /// Calling blocks of the active call frames, from the outermost frame.
+    pub call_stack: Vec<BasicBlock<'ctx>>,
+}
+
+/// Data structure representing an executed path reconstructed from a trace.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayedTrace<'ctx> {
+    /// Steps of the trace, in the execution order.
+    pub steps: Vec<TraceStep<'ctx>>,
+}
+
+/// Implement methods for `ReplayedTrace`.
+impl<'ctx> ReplayedTrace<'ctx> {
+    /// Get the executed path, without call stacks.
+    pub fn get_path_steps(&self) -> Vec<PathStep<'ctx>> {
+        self.steps.iter().map(|step| step.step.clone()).collect()
+    }
+}
+
+/// Implement the trait `Display` for `ReplayedTrace`.
+impl<'ctx> Display for ReplayedTrace<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Replayed trace:")?;
+        for step in &self.steps {
+            let indent = "  ".repeat(step.call_stack.len() + 1);
+            write!(f, "\n{}- {}", indent, step.step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Load a trace log from a file and replay it on a module.
+pub fn load_trace<'ctx>(
+    module: &Module<'ctx>,
+    path: &Path,
+) -> io::Result<ReplayedTrace<'ctx>> {
+    let content = fs::read_to_string(path)?;
+    replay_trace(module, &content)
+}
+
+/// Replay a trace log on a module.
+pub fn replay_trace<'ctx>(
+    module: &Module<'ctx>,
+    content: &str,
+) -> io::Result<ReplayedTrace<'ctx>> {
+    let numbering = BlockNumbering::new(module);
+    let mut trace = ReplayedTrace::default();
+
+    // Current block of each active call frame, from the outermost frame.
+    let mut frames: Vec<BasicBlock> = vec![];
+
+    for (line_idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (func, blk) = match line
+            .parse::<usize>()
+            .ok()
+            .and_then(|id| numbering.get_block(id))
+        {
+            Some(res) => res,
+            None => {
+                let msg = format!(
+                    "Invalid block ID at line {}: {}",
+                    line_idx + 1,
+                    line
+                );
+                return Err(io::Error::new(ErrorKind::InvalidData, msg));
+            }
+        };
+
+        let condition = match replay_transition(&mut frames, func, blk) {
+            Some(condition) => condition,
+            None => {
+                let msg = format!(
+                    "Invalid transition to block {} of function {} at line {}",
+                    blk.get_name_or_default(),
+                    func.get_name_or_default(),
+                    line_idx + 1
+                );
+                return Err(io::Error::new(ErrorKind::InvalidData, msg));
+            }
+        };
+
+        let call_stack = frames[..frames.len() - 1].to_vec();
+        trace.steps.push(TraceStep {
+            step: PathStep {
+                function: func,
+                block: blk,
+                condition,
+            },
+            call_stack,
+        })
+    }
+
+    Ok(trace)
+}
+
+/// Update call frames by a transition to a block and return the path
+/// condition under which the block is entered.
+///
+/// Return `None` if the transition is not valid.
+fn replay_transition<'ctx>(
+    frames: &mut Vec<BasicBlock<'ctx>>,
+    func: FunctionValue<'ctx>,
+    blk: BasicBlock<'ctx>,
+) -> Option<PathCondition<'ctx>> {
+    // The first block of a trace must be a function entry.
+    let mut current_blk = match frames.last() {
+        Some(current_blk) => *current_blk,
+        None => {
+            if func.get_first_basic_block() != Some(blk) {
+                return None;
+            }
+            frames.push(blk);
+            return Some(PathCondition::None);
+        }
+    };
+
+    loop {
+        // Enter a function called by the current block.
+        if func.get_first_basic_block() == Some(blk)
+            && get_called_functions(current_blk).contains(&func)
+        {
+            frames.push(blk);
+            return Some(PathCondition::None);
+        }
+
+        // Jump to a successor of the current block.
+        let succ = current_blk
+            .get_conditioned_successors()
+            .into_iter()
+            .find(|succ| succ.block == blk);
+        if let Some(succ) = succ {
+            if let Some(frame) = frames.last_mut() {
+                *frame = blk;
+            }
+            return Some(succ.condition);
+        }
+
+        // Return to the caller and retry from the calling block.
+        if !is_returning_block(current_blk) || frames.len() < 2 {
+            return None;
+        }
+        frames.pop();
+        current_blk = *frames.last()?;
+    }
+}
+
+/// Check if a block returns to its caller.
+fn is_returning_block(blk: BasicBlock) -> bool {
+    blk.get_terminator()
+        .map_or(false, |inst| inst.get_opcode() == InstructionOpcode::Return)
+}
@@ -0,0 +1,274 @@
+//! Module providing a driver composing executions of multiple entry functions
+//! over a shared contract storage.
+//!
+//! Many contract vulnerabilities only manifest across transactions, e.g., a
+//! withdrawal after a deposit. The driver enumerates bounded sequences of
+//! entry functions, executes one path of each entry function in order on a
+//! `StorageModel` shared by the whole sequence, and checks the resulting
+//! storage state after each complete sequence.
+//!
+//! Paths of entry functions are provided by the caller, or by default are the
+//! hot paths of the entry functions.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use inkwell::values::{BasicBlock, FunctionValue};
+
+use crate::{
+    analysis::{extract_hot_paths, StorageModel},
+    ir::FunctionExt,
+};
+
+/// Default maximum number of transactions of a sequence.
+pub const DEFAULT_MAX_SEQUENCE_LENGTH: usize = 2;
+
+/// Default maximum number of explored sequences.
+pub const DEFAULT_MAX_SEQUENCES: usize = 1000;
+
+/// Default number of paths explored for each transaction.
+pub const DEFAULT_PATHS_PER_TRANSACTION: usize = 4;
+
+/// Options of the multi-transaction analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionOptions {
+    /// Maximum number of transactions of a sequence.
+    pub max_length: usize,
+
+    /// Maximum number of explored sequences of entry functions.
+    pub max_sequences: usize,
+
+    /// Maximum number of paths explored for each transaction.
+    pub paths_per_transaction: usize,
+
+    /// Whether an entry function may be executed several times in a
+    /// sequence.
+    pub allow_repeats: bool,
+
+    /// Whether an entry function may be executed twice in a row.
+    ///
+    /// This option is only relevant if repeats are allowed.
+    pub allow_consecutive_repeats: bool,
+}
+
+/// Implement the trait `Default` for `TransactionOptions`.
+impl Default for TransactionOptions {
+    fn default() -> Self {
+        TransactionOptions {
+            max_length: DEFAULT_MAX_SEQUENCE_LENGTH,
+            max_sequences: DEFAULT_MAX_SEQUENCES,
+            paths_per_transaction: DEFAULT_PATHS_PER_TRANSACTION,
+            allow_repeats: true,
+            allow_consecutive_repeats: true,
+        }
+    }
+}
+
+/// Data structure representing an executed transaction.
+#[derive(Debug, Clone)]
+pub struct Transaction<'ctx> {
+    /// Entry function of the transaction.
+    pub entry: FunctionValue<'ctx>,
+
+    /// Executed path of the transaction.
+    pub path: Vec<BasicBlock<'ctx>>,
+}
+
+/// Data structure representing a finding of the multi-transaction analysis.
+#[derive(Debug, Clone)]
+pub struct SequenceFinding<'ctx, T> {
+    /// Executed transactions, in order.
+    pub transactions: Vec<Transaction<'ctx>>,
+
+    /// Finding reported on the storage state after the transactions.
+    pub finding: T,
+}
+
+/// Implement the trait `Display` for `SequenceFinding`.
+impl<'ctx, T: Display> Display for SequenceFinding<'ctx, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = self
+            .transactions
+            .iter()
+            .map(|tx| tx.entry.get_name_or_default())
+            .collect();
+        write!(f, "{}: {}", entries.join(" -> "), self.finding)
+    }
+}
+
+/// Data structure driving the analysis of sequences of transactions.
+#[derive(Debug, Clone)]
+pub struct TransactionDriver<'ctx> {
+    /// Entry functions which can start a transaction.
+    entries: Vec<FunctionValue<'ctx>>,
+
+    /// Options of the analysis.
+    options: TransactionOptions,
+}
+
+/// Implement methods for `TransactionDriver`.
+impl<'ctx> TransactionDriver<'ctx> {
+    /// Constructor
+    pub fn new(
+        entries: Vec<FunctionValue<'ctx>>,
+        options: TransactionOptions,
+    ) -> Self {
+        TransactionDriver { entries, options }
+    }
+
+    /// Get the options of the analysis.
+    pub fn get_options(&self) -> &TransactionOptions {
+        &self.options
+    }
+
+    /// Enumerate sequences of entry functions, in increasing order of length,
+    /// up to the maximum number of sequences.
+    pub fn enumerate_sequences(&self) -> Vec<Vec<FunctionValue<'ctx>>> {
+        let mut sequences: Vec<Vec<FunctionValue>> = vec![];
+        let mut frontier: Vec<Vec<FunctionValue>> = vec![vec![]];
+
+        for _ in 0..self.options.max_length {
+            let mut next_frontier = vec![];
+
+            for seq in &frontier {
+                for entry in &self.entries {
+                    if !self.can_append(seq, *entry) {
+                        continue;
+                    }
+                    if sequences.len() >= self.options.max_sequences {
+                        return sequences;
+                    }
+
+                    let mut next_seq = seq.clone();
+                    next_seq.push(*entry);
+                    sequences.push(next_seq.clone());
+                    next_frontier.push(next_seq)
+                }
+            }
+
+            frontier = next_frontier;
+        }
+
+        sequences
+    }
+
+    /// Run the analysis, executing hot paths of entry functions, and return
+    /// findings reported by a check of storage states.
+    pub fn run<T, C>(&self, check: C) -> Vec<SequenceFinding<'ctx, T>>
+    where
+        C: FnMut(&[Transaction<'ctx>], &StorageModel<'ctx>) -> Option<T>,
+    {
+        let k = self.options.paths_per_transaction;
+        let mut hot_paths: HashMap<FunctionValue, Vec<Vec<BasicBlock>>> =
+            HashMap::new();
+        let get_paths = |entry: FunctionValue<'ctx>| {
+            hot_paths
+                .entry(entry)
+                .or_insert_with(|| {
+                    extract_hot_paths(entry, k)
+                        .into_iter()
+                        .map(|path| {
+                            path.steps.iter().map(|step| step.block).collect()
+                        })
+                        .collect()
+                })
+                .clone()
+        };
+        self.run_with_paths(get_paths, check)
+    }
+
+    /// Run the analysis, executing paths of entry functions provided by a
+    /// function, and return findings reported by a check of storage states.
+    ///
+    /// The check is called after each complete sequence of transactions.
+    pub fn run_with_paths<T, P, C>(
+        &self,
+        mut get_paths: P,
+        mut check: C,
+    ) -> Vec<SequenceFinding<'ctx, T>>
+    where
+        P: FnMut(FunctionValue<'ctx>) -> Vec<Vec<BasicBlock<'ctx>>>,
+        C: FnMut(&[Transaction<'ctx>], &StorageModel<'ctx>) -> Option<T>,
+    {
+        let mut findings = vec![];
+
+        for seq in self.enumerate_sequences() {
+            let paths: Vec<Vec<Vec<BasicBlock>>> = seq
+                .iter()
+                .map(|entry| {
+                    let mut paths = get_paths(*entry);
+                    paths.truncate(self.options.paths_per_transaction);
+                    paths
+                })
+                .collect();
+
+            let mut transactions = vec![];
+            explore_sequence(
+                &seq,
+                &paths,
+                StorageModel::new(),
+                &mut transactions,
+                &mut |transactions, storage| {
+                    if let Some(finding) = check(transactions, storage) {
+                        log_debug!("Found stateful finding in sequence");
+                        findings.push(SequenceFinding {
+                            transactions: transactions.to_vec(),
+                            finding,
+                        })
+                    }
+                },
+            )
+        }
+
+        findings
+    }
+
+    /// Check if an entry function can be appended to a sequence.
+    fn can_append(
+        &self,
+        seq: &[FunctionValue<'ctx>],
+        entry: FunctionValue<'ctx>,
+    ) -> bool {
+        if !seq.contains(&entry) {
+            return true;
+        }
+        if !self.options.allow_repeats {
+            return false;
+        }
+        self.options.allow_consecutive_repeats || seq.last() != Some(&entry)
+    }
+}
+
+/// Execute every combination of paths of the remaining transactions of a
+/// sequence on a storage state, and visit each complete combination.
+fn explore_sequence<'ctx, V>(
+    seq: &[FunctionValue<'ctx>],
+    paths: &[Vec<Vec<BasicBlock<'ctx>>>],
+    storage: StorageModel<'ctx>,
+    transactions: &mut Vec<Transaction<'ctx>>,
+    visit: &mut V,
+) where
+    V: FnMut(&[Transaction<'ctx>], &StorageModel<'ctx>),
+{
+    let idx = transactions.len();
+    let (entry, entry_paths) = match (seq.get(idx), paths.get(idx)) {
+        (Some(entry), Some(entry_paths)) => (*entry, entry_paths),
+        _ => {
+            visit(transactions, &storage);
+            return;
+        }
+    };
+
+    for path in entry_paths {
+        let mut next_storage = storage.clone();
+        next_storage.execute_path(path);
+        transactions.push(Transaction {
+            entry,
+            path: path.clone(),
+        });
+        explore_sequence(seq, paths, next_storage, transactions, visit);
+        transactions.pop();
+    }
+}
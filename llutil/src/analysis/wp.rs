@@ -0,0 +1,234 @@
+//! Module computing weakest preconditions of symbolic postconditions across
+//! straight-line code.
+//!
+//! The weakest precondition of an instruction is obtained by substituting the
+//! value it defines (or the memory it writes) in the postcondition by its
+//! symbolic definition. Memory is only tracked for non-escaping `alloca`
+//! locations, i.e., those used only as pointer operands of loads and stores.
+//! Instructions that are not modelled leave their values unconstrained.
+
+use inkwell::values::{
+    BasicBlock, BasicValueEnum, FunctionValue, InstructionOpcode,
+    InstructionValue, PointerValue,
+};
+use std::collections::HashSet;
+
+use crate::ir::{AnyCondition, AnyTerminator, ArithKind, InstructionExt};
+
+use super::{driver::check_time_budget, sym_expr::SymExpr};
+
+/// Data structure computing weakest preconditions within a function.
+#[derive(Debug, Clone)]
+pub struct WpGenerator<'ctx> {
+    /// Non-escaping `alloca` locations of the function.
+    non_escaping_allocas: HashSet<PointerValue<'ctx>>,
+}
+
+/// Implement methods for `WpGenerator`.
+impl<'ctx> WpGenerator<'ctx> {
+    /// Constructor, collecting non-escaping `alloca` locations of a function.
+    pub fn new(func: FunctionValue<'ctx>) -> Self {
+        let mut non_escaping_allocas = HashSet::new();
+
+        for blk in func.get_basic_blocks() {
+            for inst in blk.get_instructions() {
+                if inst.get_opcode() != InstructionOpcode::Alloca {
+                    continue;
+                }
+                if let Some(ptr) = inst.try_into_pointer_value() {
+                    if !is_escaping_alloca(inst, ptr) {
+                        non_escaping_allocas.insert(ptr);
+                    }
+                }
+            }
+        }
+
+        WpGenerator {
+            non_escaping_allocas,
+        }
+    }
+
+    /// Check if a pointer is a non-escaping `alloca` location.
+    pub fn is_non_escaping_alloca(&self, ptr: PointerValue<'ctx>) -> bool {
+        self.non_escaping_allocas.contains(&ptr)
+    }
+
+    /// Compute the weakest precondition of a postcondition across an
+    /// instruction.
+    pub fn compute_instruction_wp(
+        &self,
+        inst: InstructionValue<'ctx>,
+        post: SymExpr<'ctx>,
+    ) -> SymExpr<'ctx> {
+        if inst.get_opcode() == InstructionOpcode::Store {
+            let ptr =
+                get_value_operand(inst, 1).map(|v| v.into_pointer_value());
+            let value = get_value_operand(inst, 0);
+            return match (ptr, value) {
+                (Some(ptr), Some(value))
+                    if self.is_non_escaping_alloca(ptr) =>
+                {
+                    let mem = SymExpr::Memory(ptr);
+                    post.substitute(&mem, &SymExpr::from_value(value))
+                }
+                _ => post,
+            };
+        }
+
+        let var = match inst.try_into_basic_value_enum() {
+            Some(value) => SymExpr::Var(value),
+            None => return post,
+        };
+
+        if !post.contains(&var) {
+            return post;
+        }
+
+        match self.get_definition(inst) {
+            Some(def) => post.substitute(&var, &def),
+            None => post,
+        }
+    }
+
+    /// Compute the weakest precondition of a postcondition across all
+    /// non-terminator instructions of a block.
+    pub fn compute_block_wp(
+        &self,
+        blk: BasicBlock<'ctx>,
+        post: SymExpr<'ctx>,
+    ) -> SymExpr<'ctx> {
+        let terminator = blk.get_terminator();
+
+        blk.get_instructions()
+            .into_iter()
+            .rev()
+            .filter(|inst| Some(*inst) != terminator)
+            .fold(post, |post, inst| self.compute_instruction_wp(inst, post))
+    }
+
+    /// Compute the weakest precondition of a postcondition along a path of
+    /// blocks.
+    ///
+    /// The branch condition leading from each block to its successor on the
+    /// path is assumed, i.e., it becomes the premise of an implication.
+    pub fn compute_path_wp(
+        &self,
+        path: &[BasicBlock<'ctx>],
+        post: SymExpr<'ctx>,
+    ) -> SymExpr<'ctx> {
+        let mut wp = post;
+
+        for (i, blk) in path.iter().enumerate().rev() {
+            check_time_budget();
+            if let Some(next_blk) = path.get(i + 1) {
+                if let Some(cond) = get_branch_condition(*blk, *next_blk) {
+                    wp = SymExpr::implies(cond, wp)
+                }
+            }
+            wp = self.compute_block_wp(*blk, wp)
+        }
+
+        wp
+    }
+
+    /// Get the symbolic definition of a value-producing instruction, if it is
+    /// modelled.
+    fn get_definition(
+        &self,
+        inst: InstructionValue<'ctx>,
+    ) -> Option<SymExpr<'ctx>> {
+        let opr = |i: u32| get_value_operand(inst, i).map(SymExpr::from_value);
+
+        match inst.get_opcode() {
+            op if ArithKind::from_opcode(op)
+                .map_or(false, |kind| kind.is_integer()) =>
+            {
+                Some(SymExpr::Binary(op, Box::new(opr(0)?), Box::new(opr(1)?)))
+            }
+            InstructionOpcode::ICmp => {
+                let pred = inst.get_icmp_predicate()?;
+                Some(SymExpr::Cmp(pred, Box::new(opr(0)?), Box::new(opr(1)?)))
+            }
+            InstructionOpcode::ZExt
+            | InstructionOpcode::SExt
+            | InstructionOpcode::Trunc => {
+                let width = inst.get_type().into_int_type().get_bit_width();
+                Some(SymExpr::Cast(inst.get_opcode(), Box::new(opr(0)?), width))
+            }
+            InstructionOpcode::Select => Some(SymExpr::Ite(
+                Box::new(opr(0)?),
+                Box::new(opr(1)?),
+                Box::new(opr(2)?),
+            )),
+            InstructionOpcode::Freeze => opr(0),
+            InstructionOpcode::Load => {
+                let ptr = get_value_operand(inst, 0)?.into_pointer_value();
+                match self.is_non_escaping_alloca(ptr) {
+                    true => Some(SymExpr::Memory(ptr)),
+                    false => None,
+                }
+            }
+            // Results of calls, including `__nondet_*` built-in functions,
+            // are fresh symbols.
+            _ => None,
+        }
+    }
+}
+
+/// Get a value operand of an instruction.
+fn get_value_operand(
+    inst: InstructionValue,
+    index: u32,
+) -> Option<BasicValueEnum> {
+    inst.get_operand(index).and_then(|opr| opr.left())
+}
+
+/// Check if an `alloca` location may escape, i.e., it is used other than as
+/// the pointer operand of a load or a store.
+fn is_escaping_alloca(alloca: InstructionValue, ptr: PointerValue) -> bool {
+    let mut use_ = alloca.get_first_use();
+
+    while let Some(value_use) = use_ {
+        let is_memory_access = match value_use.get_user().into_instruction() {
+            Some(inst) => match inst.get_opcode() {
+                InstructionOpcode::Load => true,
+                InstructionOpcode::Store => {
+                    get_value_operand(inst, 0)
+                        != Some(BasicValueEnum::PointerValue(ptr))
+                }
+                _ => false,
+            },
+            None => false,
+        };
+
+        if !is_memory_access {
+            return true;
+        }
+
+        use_ = value_use.get_next_use()
+    }
+
+    false
+}
+
+/// Get the symbolic condition under which a block branches to a successor,
+/// if the block ends with a conditional branch.
+pub(crate) fn get_branch_condition<'ctx>(
+    blk: BasicBlock<'ctx>,
+    next_blk: BasicBlock<'ctx>,
+) -> Option<SymExpr<'ctx>> {
+    let branch = blk.get_terminator()?.try_into_branch_inst()?;
+    if !branch.has_condition() {
+        return None;
+    }
+
+    let cond = SymExpr::from_value(branch.get_condition());
+    let then_blk = branch.get_successor(0)?;
+    let else_blk = branch.get_successor(1)?;
+
+    match (then_blk == next_blk, else_blk == next_blk) {
+        (true, false) => Some(cond),
+        (false, true) => Some(SymExpr::not(cond)),
+        _ => None,
+    }
+}
@@ -0,0 +1,201 @@
+//! Module lexing source code into the tokens relevant to annotations.
+//!
+//! Annotations are written in comments of C-family source code. The lexer
+//! extracts block comments and line comments with their positions, and skips
+//! string and character literals so that comment delimiters inside literals
+//! are not mistaken for comments. Other characters are skipped.
+//!
+//! Rust source code is lexed by `nom_lexing_rust`, which also skips lifetimes
+//! and loop labels, e.g., `'a` or `'outer`, so that their quotes are not
+//! mistaken for the start of character literals.
+//!
+//! The lexer consumes one token per iteration of a loop, without recursion,
+//! so that its stack usage does not grow with the size of the source code.
+//! Malformed source code, e.g., an unterminated block comment, is reported as
+//! a `LexError` instead of a panic.
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{escaped, is_not, tag, take_until, take_while1},
+    character::complete::{anychar, char, not_line_ending},
+    combinator::{cut, map, not, opt, value},
+    sequence::{preceded, terminated},
+    IResult,
+};
+use nom_locate::LocatedSpan;
+
+/// Input span of the lexer, tracking line and column positions.
+pub type Span<'a> = LocatedSpan<&'a str>;
+
+/// Kinds of tokens of the lexer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// A block comment `/* ... */`.
+    BlockComment,
+
+    /// A line comment `// ...`.
+    LineComment,
+}
+
+/// Data structure representing a token of the lexer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Kind of the token.
+    pub kind: TokenKind,
+
+    /// Content of the token, without comment delimiters.
+    pub text: String,
+
+    /// Line of the start of the token, starting from 1.
+    pub line: u32,
+
+    /// Column of the start of the token, counted in bytes and starting from
+    /// 1, consistently with columns of debug locations.
+    pub column: u32,
+}
+
+/// Data structure representing an error of the lexer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    /// Line of the start of the malformed token, starting from 1.
+    pub line: u32,
+
+    /// Column of the start of the malformed token, starting from 1.
+    pub column: u32,
+
+    /// Description of the error.
+    pub message: String,
+}
+
+/// Implement the trait `Display` for `LexError`.
+impl Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}:{}", self.message, self.line, self.column)
+    }
+}
+
+/// Implement the trait `Error` for `LexError`.
+impl Error for LexError {}
+
+/// Lex a source code into comment tokens, in the order of their positions.
+pub fn nom_lexing(source: &str) -> Result<Vec<Token>, LexError> {
+    lex_tokens(source, lex_token)
+}
+
+/// Lex a Rust source code into comment tokens, in the order of their
+/// positions.
+pub fn nom_lexing_rust(source: &str) -> Result<Vec<Token>, LexError> {
+    lex_tokens(source, lex_rust_token)
+}
+
+/// Lex a source code into comment tokens, using a lexer of single tokens.
+fn lex_tokens(
+    source: &str,
+    lexer: fn(Span) -> IResult<Span, Option<(TokenKind, &str)>>,
+) -> Result<Vec<Token>, LexError> {
+    let mut input = Span::new(source);
+    let mut tokens = vec![];
+
+    while !input.fragment().is_empty() {
+        let (line, column) = (input.location_line(), get_column(&input));
+
+        match lexer(input) {
+            Ok((rest, Some((kind, text)))) => {
+                tokens.push(Token {
+                    kind,
+                    text: text.to_string(),
+                    line,
+                    column,
+                });
+                input = rest
+            }
+            Ok((rest, None)) => input = rest,
+            Err(_) => {
+                let message = if input.fragment().starts_with("/*") {
+                    "Unterminated block comment"
+                } else {
+                    "Invalid token"
+                };
+                return Err(LexError {
+                    line,
+                    column,
+                    message: message.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Lex a token at the start of an input. Return the comment kind and content
+/// if the token is a comment.
+fn lex_token(input: Span) -> IResult<Span, Option<(TokenKind, &str)>> {
+    alt((
+        map(lex_block_comment, |text| {
+            Some((TokenKind::BlockComment, *text.fragment()))
+        }),
+        map(lex_line_comment, |text| {
+            Some((TokenKind::LineComment, *text.fragment()))
+        }),
+        value(None, lex_literal('"')),
+        value(None, lex_literal('\'')),
+        value(None, anychar),
+    ))(input)
+}
+
+/// Lex a token of Rust source code at the start of an input. Return the
+/// comment kind and content if the token is a comment.
+fn lex_rust_token(input: Span) -> IResult<Span, Option<(TokenKind, &str)>> {
+    alt((value(None, lex_lifetime), lex_token))(input)
+}
+
+/// Lex a Rust lifetime or loop label, i.e., a quote followed by an identifier
+/// which is not closed by another quote.
+fn lex_lifetime(input: Span) -> IResult<Span, Span> {
+    let ident = take_while1(|c: char| c.is_alphanumeric() || c == '_');
+    preceded(char('\''), terminated(ident, not(char('\''))))(input)
+}
+
+/// Lex a block comment and return its content.
+///
+/// Fail without backtracking if the comment is not terminated.
+fn lex_block_comment(input: Span) -> IResult<Span, Span> {
+    preceded(tag("/*"), cut(terminated(take_until("*/"), tag("*/"))))(input)
+}
+
+/// Lex a line comment and return its content.
+fn lex_line_comment(input: Span) -> IResult<Span, Span> {
+    preceded(tag("//"), not_line_ending)(input)
+}
+
+/// Lex a string or character literal delimited by a quote character.
+fn lex_literal(quote: char) -> impl FnMut(Span) -> IResult<Span, Option<Span>> {
+    move |input| {
+        let forbidden = match quote {
+            '"' => "\\\"\n",
+            _ => "\\'\n",
+        };
+        preceded(
+            char(quote),
+            terminated(
+                opt(escaped(is_not(forbidden), '\\', anychar)),
+                char(quote),
+            ),
+        )(input)
+    }
+}
+
+/// Get the column of the start of an input, counted in bytes and starting
+/// from 1.
+///
+/// Columns of debug locations emitted by `clang` count bytes, so counting
+/// UTF-8 characters would shift annotations following non-ASCII text.
+fn get_column(input: &Span) -> u32 {
+    input.get_column() as u32
+}
@@ -0,0 +1,276 @@
+//! Module matching bug annotations of a source file with instructions of a
+//! module, using their debug locations.
+//!
+//! The column of a debug location often differs from the position of the
+//! annotation comment, e.g., `clang` locates a binary operation at its
+//! operator, while the comment is written before the expression. Matching is
+//! therefore configurable by a `MatchMode`. Annotations without a column,
+//! e.g., from oracle files, are always matched by their lines.
+//!
+//! A source file can be compiled into several modules, e.g., Solang emits a
+//! module for each contract of a Solidity file. Annotations of such a file
+//! are matched against instructions of all its modules.
+//!
+//! Annotations with expression snippets are further matched against the
+//! source-level expressions reconstructed from instructions, to disambiguate
+//! several operations of the same line.
+
+use std::{
+    fmt::{self, Display},
+    path::Path,
+};
+
+use inkwell::{module::Module, values::InstructionValue};
+
+use crate::ir::{InstructionExt, ModuleExt, SourceLocation, SourceVariables};
+
+use super::{
+    bug_annot::{normalize_expression, BugAnnotation},
+    source_expr::reconstruct_expression,
+};
+
+/// Modes of matching annotations with instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Match instructions at the exact line and column of an annotation.
+    Exact,
+
+    /// Match all instructions at the line of an annotation.
+    LineOnly,
+
+    /// Match instructions at the line of an annotation, whose columns differ
+    /// from the column of the annotation by at most the given tolerance.
+    ColumnTolerance(u32),
+
+    /// Match the instructions at the line of an annotation whose columns are
+    /// the nearest to the column of the annotation.
+    NearestOnLine,
+}
+
+/// Implement the trait `Default` for `MatchMode`.
+impl Default for MatchMode {
+    fn default() -> Self {
+        MatchMode::NearestOnLine
+    }
+}
+
+/// Data structure representing an instruction located in a source file.
+#[derive(Debug, Clone)]
+pub struct LocatedInstruction<'ctx> {
+    /// The instruction.
+    pub instruction: InstructionValue<'ctx>,
+
+    /// Source location of the instruction.
+    pub location: SourceLocation,
+
+    /// Source-level expression reconstructed from the instruction, if any.
+    pub expression: Option<String>,
+}
+
+/// Data structure representing an annotation and its matched instructions.
+#[derive(Debug, Clone)]
+pub struct AnnotationMatch<'ctx> {
+    /// The annotation.
+    pub annotation: BugAnnotation,
+
+    /// Instructions matched by the annotation, in the order of the module.
+    pub instructions: Vec<InstructionValue<'ctx>>,
+}
+
+/// Data structure representing results of matching annotations.
+#[derive(Debug, Clone, Default)]
+pub struct MatchResults<'ctx> {
+    /// Annotations which matched at least one instruction.
+    pub matched: Vec<AnnotationMatch<'ctx>>,
+
+    /// Annotations which matched no instruction.
+    pub unmatched: Vec<BugAnnotation>,
+}
+
+/// Implement methods for `MatchResults`.
+impl<'ctx> MatchResults<'ctx> {
+    /// Check if all annotations matched some instructions.
+    pub fn is_complete(&self) -> bool {
+        self.unmatched.is_empty()
+    }
+
+    /// Log a warning for each annotation which matched no instruction.
+    pub fn report_unmatched(&self) {
+        for annot in &self.unmatched {
+            log_warn!("Annotation matched no instruction: {}", annot);
+        }
+    }
+}
+
+/// Implement the trait `Display` for `MatchResults`.
+impl<'ctx> Display for MatchResults<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Matched annotations: {}, unmatched annotations: {}",
+            self.matched.len(),
+            self.unmatched.len()
+        )?;
+        for annot in &self.unmatched {
+            write!(f, "\n  - unmatched: {}", annot)?;
+        }
+        Ok(())
+    }
+}
+
+/// Match annotations of a source file with instructions of a module.
+pub fn match_annotations<'ctx>(
+    annotations: &[BugAnnotation],
+    source_file: &Path,
+    module: &Module<'ctx>,
+    mode: MatchMode,
+) -> MatchResults<'ctx> {
+    let modules = std::slice::from_ref(module);
+    match_annotations_in_modules(annotations, source_file, modules, mode)
+}
+
+/// Match annotations of a source file with instructions of all modules
+/// compiled from it, e.g., modules of contracts of a Solidity file.
+pub fn match_annotations_in_modules<'ctx>(
+    annotations: &[BugAnnotation],
+    source_file: &Path,
+    modules: &[Module<'ctx>],
+    mode: MatchMode,
+) -> MatchResults<'ctx> {
+    let located_insts: Vec<LocatedInstruction> = modules
+        .iter()
+        .flat_map(|module| get_located_instructions(module, source_file))
+        .collect();
+    let mut results = MatchResults::default();
+
+    for annot in annotations {
+        let instructions = match_annotation(annot, &located_insts, mode);
+        if instructions.is_empty() {
+            results.unmatched.push(annot.clone());
+        } else {
+            results.matched.push(AnnotationMatch {
+                annotation: annot.clone(),
+                instructions,
+            })
+        }
+    }
+
+    results
+}
+
+/// Match an annotation with located instructions.
+///
+/// If the annotation has an expression snippet, only instructions whose
+/// reconstructed expressions equal the snippet are matched. When the mode
+/// selects none of them, all of them are matched.
+pub fn match_annotation<'ctx>(
+    annot: &BugAnnotation,
+    located_insts: &[LocatedInstruction<'ctx>],
+    mode: MatchMode,
+) -> Vec<InstructionValue<'ctx>> {
+    let on_line: Vec<&LocatedInstruction> = located_insts
+        .iter()
+        .filter(|inst| inst.location.line == annot.line)
+        .collect();
+
+    let expr = match &annot.expression {
+        Some(expr) => normalize_expression(expr),
+        None => return match_by_mode(annot, &on_line, mode),
+    };
+
+    let with_expr: Vec<&LocatedInstruction> = on_line
+        .into_iter()
+        .filter(|inst| {
+            let inst_expr =
+                inst.expression.as_deref().map(normalize_expression);
+            inst_expr.as_ref() == Some(&expr)
+        })
+        .collect();
+
+    let candidates = match_by_mode(annot, &with_expr, mode);
+    if !candidates.is_empty() {
+        return candidates;
+    }
+
+    match_by_mode(annot, &with_expr, MatchMode::LineOnly)
+}
+
+/// Match an annotation with located instructions of its line by a mode.
+fn match_by_mode<'ctx>(
+    annot: &BugAnnotation,
+    on_line: &[&LocatedInstruction<'ctx>],
+    mode: MatchMode,
+) -> Vec<InstructionValue<'ctx>> {
+    let on_line = on_line
+        .iter()
+        .map(|inst| (inst.instruction, &inst.location));
+
+    // Annotations without columns are matched by their lines.
+    let mode = match annot.column {
+        0 => MatchMode::LineOnly,
+        _ => mode,
+    };
+
+    let distance = |loc: &SourceLocation| annot.column.abs_diff(loc.column);
+
+    match mode {
+        MatchMode::Exact => on_line
+            .filter(|(_, loc)| loc.column == annot.column)
+            .map(|(inst, _)| inst)
+            .collect(),
+        MatchMode::LineOnly => on_line.map(|(inst, _)| inst).collect(),
+        MatchMode::ColumnTolerance(tolerance) => on_line
+            .filter(|(_, loc)| distance(loc) <= tolerance)
+            .map(|(inst, _)| inst)
+            .collect(),
+        MatchMode::NearestOnLine => {
+            let on_line: Vec<_> = on_line.collect();
+            let min_distance =
+                on_line.iter().map(|(_, loc)| distance(loc)).min();
+            on_line
+                .into_iter()
+                .filter(|(_, loc)| Some(distance(loc)) == min_distance)
+                .map(|(inst, _)| inst)
+                .collect()
+        }
+    }
+}
+
+/// Get instructions of a module located in a source file, with their
+/// locations and reconstructed expressions.
+pub fn get_located_instructions<'ctx>(
+    module: &Module<'ctx>,
+    source_file: &Path,
+) -> Vec<LocatedInstruction<'ctx>> {
+    let mut located_insts = vec![];
+
+    for func in module.get_functions_sorted() {
+        let source_vars = SourceVariables::new(func);
+        for inst in func
+            .get_basic_blocks()
+            .iter()
+            .flat_map(|blk| blk.get_instructions())
+        {
+            let location = match inst.get_source_location() {
+                Some(loc) => loc,
+                None => continue,
+            };
+            if !is_same_file(Path::new(&location.file), source_file) {
+                continue;
+            }
+            located_insts.push(LocatedInstruction {
+                instruction: inst,
+                location,
+                expression: reconstruct_expression(inst, &source_vars),
+            })
+        }
+    }
+
+    located_insts
+}
+
+/// Check if two paths refer to the same source file, where one path may be
+/// relative to an unknown directory.
+pub(crate) fn is_same_file(path1: &Path, path2: &Path) -> bool {
+    path1.ends_with(path2) || path2.ends_with(path1)
+}
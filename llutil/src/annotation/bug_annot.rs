@@ -0,0 +1,330 @@
+//! Module parsing bug annotations of source code.
+//!
+//! A bug annotation marks the source location of an expected bug, and is
+//! written as a comment `/* bug: <bug_type> */` or `// bug: <bug_type>`, e.g.,
+//! `/* bug: integer_overflow */`. The location of an annotation is the
+//! position of its comment.
+//!
+//! An annotation can end with an expression snippet, e.g.,
+//! `/* bug: integer_overflow: a+b */`, identifying which of several
+//! operations of the same line is meant. A comment can contain several
+//! annotations separated by `;`, e.g.,
+//! `/* bug: integer_overflow: a+b; bug: division_by_zero: c/d */`.
+//!
+//! Annotations are also extracted from documentation comments, e.g.,
+//! `/** bug: integer_overflow */` or `/// bug: integer_overflow`, which are
+//! common in Solidity code using NatSpec.
+//!
+//! Annotations of C/C++ files can be extracted after preprocessing, or from
+//! code containing `#line` directives. Their lines are then mapped by line
+//! markers to the original lines, which agree with debug locations emitted
+//! by `clang`.
+//!
+//! Annotations of Rust files are also written as attributes, and are located
+//! at the lines they annotate, as described in `rust_annot`.
+//!
+//! Bug annotations can also be listed in oracle files, one entry per line in
+//! the format `<line>[:<column>] bug: <bug_type>[: <expression>]`, e.g.,
+//! `12:5 bug: integer_overflow`. Empty lines and lines starting with `#` are
+//! ignored.
+
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    path::Path,
+};
+
+use nom::{
+    bytes::complete::{is_not, tag, take_while1},
+    character::complete::{char, multispace0, multispace1, u32 as parse_u32},
+    combinator::{all_consuming, opt},
+    sequence::{preceded, terminated, tuple},
+    IResult,
+};
+
+use crate::{file::FileType, tool::clang};
+
+use super::{
+    annot_lexer::{nom_lexing, LexError},
+    annot_matcher::is_same_file,
+    line_marker::LineMap,
+    rust_annot::load_rust_bug_annotations,
+};
+
+/// Keyword starting bug annotations.
+pub const BUG_KEYWORD: &str = "bug";
+
+/// Separator of annotations in a comment.
+pub const ANNOTATION_SEPARATOR: char = ';';
+
+/// Leading characters of comment bodies of documentation comments, such as
+/// `/** ... */`, `/*! ... */` and `/// ...`.
+const DOC_COMMENT_MARKERS: &[char] = &['*', '/', '!'];
+
+/// Options of extracting bug annotations of C/C++ source files.
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationOptions {
+    /// Extract annotations from the code preprocessed by `clang`.
+    pub preprocess: bool,
+
+    /// Include directories used by the preprocessor.
+    pub include_dirs: Vec<String>,
+}
+
+/// Data structure representing a bug annotation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BugAnnotation {
+    /// Type of the bug, e.g., `integer_overflow`.
+    pub bug_type: String,
+
+    /// Line of the annotation, starting from 1.
+    pub line: u32,
+
+    /// Column of the annotation, starting from 1, or 0 if unknown.
+    pub column: u32,
+
+    /// Expression snippet of the annotated operation, if any.
+    pub expression: Option<String>,
+}
+
+/// Implement methods for `BugAnnotation`.
+impl BugAnnotation {
+    /// Constructor
+    pub fn new(bug_type: &str, line: u32, column: u32) -> Self {
+        BugAnnotation {
+            bug_type: bug_type.to_string(),
+            line,
+            column,
+            expression: None,
+        }
+    }
+
+    /// Set the expression snippet of the annotation.
+    pub fn with_expression(mut self, expression: Option<&str>) -> Self {
+        self.expression = expression.map(|expr| expr.to_string());
+        self
+    }
+
+    /// Check if the annotation matches another annotation, i.e., they have
+    /// the same bug type and line, the same column unless a column is
+    /// unknown, and the same expression unless an expression is unknown.
+    pub fn matches(&self, other: &BugAnnotation) -> bool {
+        let same_expression = match (&self.expression, &other.expression) {
+            (Some(expr1), Some(expr2)) => {
+                normalize_expression(expr1) == normalize_expression(expr2)
+            }
+            _ => true,
+        };
+
+        self.bug_type == other.bug_type
+            && self.line == other.line
+            && (self.column == 0
+                || other.column == 0
+                || self.column == other.column)
+            && same_expression
+    }
+}
+
+/// Implement the trait `Display` for `BugAnnotation`.
+impl Display for BugAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            0 => write!(f, "{} {}: {}", self.line, BUG_KEYWORD, self.bug_type)?,
+            _ => write!(
+                f,
+                "{}:{} {}: {}",
+                self.line, self.column, BUG_KEYWORD, self.bug_type
+            )?,
+        }
+        if let Some(expr) = &self.expression {
+            write!(f, ": {}", expr)?;
+        }
+        Ok(())
+    }
+}
+
+/// Normalize an expression snippet for comparisons, by removing whitespaces
+/// and parentheses.
+pub fn normalize_expression(expr: &str) -> String {
+    expr.chars()
+        .filter(|c| !c.is_whitespace() && *c != '(' && *c != ')')
+        .collect()
+}
+
+/// Parse the body of a comment, and return the bug types and expression
+/// snippets of its annotations.
+///
+/// Return an empty list if the body contains no bug annotation.
+pub fn parse_annotation_body(body: &str) -> Vec<(String, Option<String>)> {
+    body.split(ANNOTATION_SEPARATOR)
+        .map(|part| part.trim_start_matches(DOC_COMMENT_MARKERS))
+        .filter_map(|part| parse_bug_body(part).ok())
+        .map(|(_, (bug_type, expr))| {
+            (bug_type.to_string(), expr.map(|expr| expr.to_string()))
+        })
+        .collect()
+}
+
+/// Parse all bug annotations of a source code.
+pub fn parse_bug_annotations(
+    source: &str,
+) -> Result<Vec<BugAnnotation>, LexError> {
+    let annots = nom_lexing(source)?
+        .into_iter()
+        .flat_map(|token| {
+            parse_annotation_body(&token.text).into_iter().map(
+                move |(bug_type, expression)| BugAnnotation {
+                    bug_type,
+                    line: token.line,
+                    column: token.column,
+                    expression,
+                },
+            )
+        })
+        .collect();
+    Ok(annots)
+}
+
+/// Load all bug annotations of a source file.
+pub fn load_bug_annotations(path: &Path) -> io::Result<Vec<BugAnnotation>> {
+    let source = fs::read_to_string(path)?;
+    parse_bug_annotations(&source)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Parse bug annotations of a source file from its preprocessed code, or
+/// from code containing `#line` directives.
+///
+/// Lines of annotations are mapped to their original lines by line markers,
+/// and only annotations originating from `source_file` are returned.
+pub fn parse_preprocessed_bug_annotations(
+    source: &str,
+    source_file: &Path,
+) -> Result<Vec<BugAnnotation>, LexError> {
+    let line_map = LineMap::new(source, &source_file.to_string_lossy());
+
+    let annots = parse_bug_annotations(source)?
+        .into_iter()
+        .filter_map(|mut annot| {
+            let (file, line) = line_map.resolve(annot.line)?;
+            if !is_same_file(Path::new(file), source_file) {
+                return None;
+            }
+            annot.line = line;
+            Some(annot)
+        })
+        .collect();
+    Ok(annots)
+}
+
+/// Load all bug annotations of a C/C++ source file, mapping their lines by
+/// line markers of the code.
+pub fn load_bug_annotations_with(
+    path: &Path,
+    options: &AnnotationOptions,
+) -> io::Result<Vec<BugAnnotation>> {
+    let source = if options.preprocess {
+        let include_dirs: Vec<&str> = options
+            .include_dirs
+            .iter()
+            .map(|dir| dir.as_str())
+            .collect();
+        clang::preprocess(&path.to_string_lossy(), &include_dirs)?
+    } else {
+        fs::read_to_string(path)?
+    };
+
+    parse_preprocessed_bug_annotations(&source, path)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Load all bug annotations of a source file, using the extraction method
+/// of its language.
+///
+/// Lines of annotations of C/C++ files are mapped by their `#line`
+/// directives, annotations of Rust files are located at the lines they
+/// annotate, while other files, such as Solidity files, are lexed as they
+/// are.
+pub fn load_source_bug_annotations(
+    path: &Path,
+) -> io::Result<Vec<BugAnnotation>> {
+    match FileType::new(&path.to_string_lossy()) {
+        FileType::CCpp => {
+            load_bug_annotations_with(path, &AnnotationOptions::default())
+        }
+        FileType::Rust => load_rust_bug_annotations(path),
+        _ => load_bug_annotations(path),
+    }
+}
+
+/// Parse an entry of an oracle file, in the format
+/// `<line>[:<column>] bug: <bug_type>[: <expression>]`.
+///
+/// Return `None` if the entry is invalid.
+pub fn parse_oracle_entry(entry: &str) -> Option<BugAnnotation> {
+    let (_, (line, column, (bug_type, expr))) = all_consuming(terminated(
+        tuple((
+            preceded(multispace0, parse_u32),
+            opt(preceded(char(':'), parse_u32)),
+            preceded(multispace1, parse_bug_body),
+        )),
+        multispace0,
+    ))(entry)
+    .ok()?;
+
+    let annot = BugAnnotation::new(bug_type, line, column.unwrap_or(0));
+    Some(annot.with_expression(expr))
+}
+
+/// Parse the content of an oracle file.
+pub fn parse_oracle(content: &str) -> io::Result<Vec<BugAnnotation>> {
+    let mut annots = vec![];
+
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match parse_oracle_entry(line) {
+            Some(annot) => annots.push(annot),
+            None => {
+                let msg = format!("Invalid oracle entry at line {}", idx + 1);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+        }
+    }
+
+    Ok(annots)
+}
+
+/// Load the entries of an oracle file.
+pub fn load_oracle(path: &Path) -> io::Result<Vec<BugAnnotation>> {
+    let content = fs::read_to_string(path)?;
+    parse_oracle(&content)
+}
+
+/// Parse a single annotation, i.e., its bug type and its optional expression
+/// snippet, which extends until the next annotation separator.
+fn parse_bug_body(input: &str) -> IResult<&str, (&str, Option<&str>)> {
+    let (input, bug_type) = parse_bug_type(input)?;
+    let (input, expr) =
+        opt(preceded(tuple((multispace0, char(':'))), is_not(";")))(input)?;
+
+    let expr = expr.map(|expr| expr.trim()).filter(|expr| !expr.is_empty());
+    Ok((input, (bug_type, expr)))
+}
+
+/// Parse the bug type of an annotation body.
+fn parse_bug_type(input: &str) -> IResult<&str, &str> {
+    preceded(
+        tuple((
+            multispace0,
+            tag(BUG_KEYWORD),
+            multispace0,
+            char(':'),
+            multispace0,
+        )),
+        take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_'),
+    )(input)
+}
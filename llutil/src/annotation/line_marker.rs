@@ -0,0 +1,111 @@
+//! Module mapping lines of preprocessed C/C++ source code back to their
+//! original files and lines, using line markers.
+//!
+//! The preprocessor emits line markers `# <line> "<file>" <flags>...`, and
+//! source code can contain directives `#line <line> ["<file>"]`. Both state
+//! that the next line is the line `<line>` of the file `<file>`. Debug
+//! locations emitted by `clang` follow these markers, so annotations of
+//! preprocessed code must be mapped accordingly.
+
+use regex::Regex;
+
+/// Data structure representing a line marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMarker {
+    /// Line of the marker in the preprocessed code, starting from 1.
+    pub marker_line: u32,
+
+    /// Original line of the line following the marker.
+    pub line: u32,
+
+    /// Original file of the line following the marker, if specified.
+    pub file: Option<String>,
+}
+
+/// Data structure mapping lines of preprocessed code to original locations.
+#[derive(Debug, Clone)]
+pub struct LineMap {
+    /// File of the preprocessed code, used before the first marker naming
+    /// a file.
+    default_file: String,
+
+    /// Line markers of the preprocessed code, in their order.
+    markers: Vec<LineMarker>,
+}
+
+/// Implement methods for `LineMap`.
+impl LineMap {
+    /// Constructor, collecting line markers of preprocessed code.
+    pub fn new(source: &str, default_file: &str) -> Self {
+        let markers = source
+            .lines()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                let (line, file) = parse_line_marker(line)?;
+                Some(LineMarker {
+                    marker_line: idx as u32 + 1,
+                    line,
+                    file,
+                })
+            })
+            .collect();
+
+        LineMap {
+            default_file: default_file.to_string(),
+            markers,
+        }
+    }
+
+    /// Get the line markers.
+    pub fn get_markers(&self) -> &[LineMarker] {
+        &self.markers
+    }
+
+    /// Map a line of the preprocessed code to its original file and line.
+    ///
+    /// Return `None` if the line is a line marker.
+    pub fn resolve(&self, line: u32) -> Option<(&str, u32)> {
+        let mut file = self.default_file.as_str();
+        let mut last_marker = None;
+
+        for marker in &self.markers {
+            if marker.marker_line > line {
+                break;
+            }
+            if let Some(marker_file) = &marker.file {
+                file = marker_file
+            }
+            last_marker = Some(marker);
+        }
+
+        match last_marker {
+            Some(marker) if marker.marker_line == line => None,
+            Some(marker) => {
+                Some((file, marker.line + (line - marker.marker_line - 1)))
+            }
+            None => Some((file, line)),
+        }
+    }
+}
+
+/// Parse a line marker `# <line> ["<file>"] <flags>...` or a directive
+/// `#line <line> ["<file>"]`, and return its line and file.
+///
+/// Return `None` if the text is not a line marker.
+pub fn parse_line_marker(text: &str) -> Option<(u32, Option<String>)> {
+    // Skip the regular expression for most lines.
+    if !text.trim_start().starts_with('#') {
+        return None;
+    }
+
+    let regex =
+        Regex::new(r#"^\s*#\s*(?:line\s+)?(\d+)(?:\s+"((?:[^"\\]|\\.)*)")?"#)
+            .unwrap();
+    let captures = regex.captures(text)?;
+    let line = captures.get(1)?.as_str().parse().ok()?;
+    let file = captures
+        .get(2)
+        .map(|file| file.as_str().replace("\\\\", "\\"));
+
+    Some((line, file))
+}
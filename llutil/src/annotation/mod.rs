@@ -0,0 +1,30 @@
+//! Module handling annotations written in comments of source code.
+
+// Export sub modules
+pub mod annot_lexer;
+pub mod annot_matcher;
+pub mod bug_annot;
+pub mod line_marker;
+pub mod rust_annot;
+pub mod source_expr;
+
+// Re-export sub-modules' data structures
+pub use annot_lexer::{
+    nom_lexing, nom_lexing_rust, LexError, Token, TokenKind,
+};
+pub use annot_matcher::{
+    get_located_instructions, match_annotation, match_annotations,
+    match_annotations_in_modules, AnnotationMatch, LocatedInstruction,
+    MatchMode, MatchResults,
+};
+pub use bug_annot::{
+    load_bug_annotations, load_bug_annotations_with, load_oracle,
+    load_source_bug_annotations, normalize_expression, parse_annotation_body,
+    parse_bug_annotations, parse_oracle, parse_oracle_entry,
+    parse_preprocessed_bug_annotations, AnnotationOptions, BugAnnotation,
+};
+pub use line_marker::{parse_line_marker, LineMap, LineMarker};
+pub use rust_annot::{
+    load_rust_bug_annotations, parse_bug_attribute, parse_rust_bug_annotations,
+};
+pub use source_expr::reconstruct_expression;
@@ -0,0 +1,141 @@
+//! Module parsing bug annotations of Rust source code.
+//!
+//! Annotations of Rust code are written in line comments, e.g.,
+//! `// bug: integer_overflow`, or as attributes of the annotated item or
+//! statement, e.g., `#[verazt::bug(integer_overflow)]` or
+//! `#[verazt::bug(integer_overflow, "a + b")]`. The attributes require the
+//! tool to be registered in the crate by `#![register_tool(verazt)]`.
+//!
+//! Line comments end a line, so their columns do not point to the annotated
+//! operations, unlike the positions of block comments of C code. Annotations
+//! of Rust code therefore have no column, and are matched with the debug
+//! locations emitted by `rustc` by their lines and expression snippets.
+//!
+//! A comment at the end of a line annotates that line. A comment or an
+//! attribute on its own line annotates the next line containing code.
+
+use std::{fs, io, path::Path};
+
+use nom::{
+    bytes::complete::{is_not, tag, take_while1},
+    character::complete::{char, multispace0},
+    combinator::opt,
+    sequence::{delimited, preceded, terminated, tuple},
+    IResult,
+};
+
+use super::{
+    annot_lexer::{nom_lexing_rust, LexError},
+    bug_annot::{parse_annotation_body, BugAnnotation},
+};
+
+/// Path of the attribute of bug annotations.
+pub const BUG_ATTRIBUTE: &str = "verazt::bug";
+
+/// Parse all bug annotations of a Rust source code, from its line comments
+/// and its `#[verazt::bug(...)]` attributes.
+pub fn parse_rust_bug_annotations(
+    source: &str,
+) -> Result<Vec<BugAnnotation>, LexError> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut annots = vec![];
+
+    for token in nom_lexing_rust(source)? {
+        let line = if is_own_line(&lines, token.line, token.column) {
+            find_next_code_line(&lines, token.line)
+        } else {
+            Some(token.line)
+        };
+        let line = match line {
+            Some(line) => line,
+            None => continue,
+        };
+
+        for (bug_type, expr) in parse_annotation_body(&token.text) {
+            let annot = BugAnnotation::new(&bug_type, line, 0);
+            annots.push(annot.with_expression(expr.as_deref()))
+        }
+    }
+
+    for (idx, text) in lines.iter().enumerate() {
+        let (bug_type, expr) = match parse_bug_attribute(text.trim()) {
+            Some(attr) => attr,
+            None => continue,
+        };
+        if let Some(line) = find_next_code_line(&lines, idx as u32 + 1) {
+            let annot = BugAnnotation::new(&bug_type, line, 0);
+            annots.push(annot.with_expression(expr.as_deref()))
+        }
+    }
+
+    annots.sort_by_key(|annot| annot.line);
+    Ok(annots)
+}
+
+/// Load all bug annotations of a Rust source file.
+pub fn load_rust_bug_annotations(
+    path: &Path,
+) -> io::Result<Vec<BugAnnotation>> {
+    let source = fs::read_to_string(path)?;
+    parse_rust_bug_annotations(&source)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Parse an attribute `#[verazt::bug(<bug_type>[, "<expression>"])]`, and
+/// return its bug type and expression snippet.
+///
+/// Return `None` if the input is not a bug annotation attribute.
+pub fn parse_bug_attribute(input: &str) -> Option<(String, Option<String>)> {
+    let (_, (bug_type, expr)) = parse_attribute(input).ok()?;
+    let expr = expr.map(|expr| expr.trim()).filter(|expr| !expr.is_empty());
+    Some((bug_type.to_string(), expr.map(|expr| expr.to_string())))
+}
+
+/// Parse the bug type and the optional expression snippet of an attribute.
+fn parse_attribute(input: &str) -> IResult<&str, (&str, Option<&str>)> {
+    let bug_type = take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_');
+    let expr = preceded(
+        tuple((multispace0, char(','), multispace0)),
+        delimited(char('"'), is_not("\""), char('"')),
+    );
+
+    delimited(
+        tuple((tag("#["), multispace0, tag(BUG_ATTRIBUTE), multispace0)),
+        delimited(
+            terminated(char('('), multispace0),
+            tuple((bug_type, opt(expr))),
+            preceded(multispace0, char(')')),
+        ),
+        preceded(multispace0, char(']')),
+    )(input)
+}
+
+/// Check if a line, starting from 1, contains only whitespaces before a
+/// column, counted in bytes and starting from 1.
+fn is_own_line(lines: &[&str], line: u32, column: u32) -> bool {
+    let text = match lines.get(line as usize - 1) {
+        Some(text) => text,
+        None => return false,
+    };
+
+    match text.get(..column as usize - 1) {
+        Some(prefix) => prefix.trim().is_empty(),
+        None => false,
+    }
+}
+
+/// Find the first line after a line, starting from 1, which contains code,
+/// i.e., which is not empty, a line comment, or an attribute.
+fn find_next_code_line(lines: &[&str], line: u32) -> Option<u32> {
+    lines
+        .iter()
+        .enumerate()
+        .skip(line as usize)
+        .find(|(_, text)| {
+            let text = text.trim();
+            !text.is_empty()
+                && !text.starts_with("//")
+                && !text.starts_with("#[")
+        })
+        .map(|(idx, _)| idx as u32 + 1)
+}
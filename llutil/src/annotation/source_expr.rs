@@ -0,0 +1,152 @@
+//! Module reconstructing source-level expressions of instructions, used to
+//! match expression-targeted bug annotations.
+//!
+//! An expression is rebuilt from the operands of an instruction, using the
+//! source-level variable names given by debug intrinsics. Casts are
+//! transparent, and loads of named variables are printed as the variables,
+//! so that `a + b` in C is reconstructed from `add (load a), (load b)`.
+
+use either::Either::Left;
+use inkwell::{
+    values::{BasicValue, BasicValueEnum, InstructionOpcode, InstructionValue},
+    IntPredicate,
+};
+
+use crate::ir::{AnyCall, InstructionExt, SourceVariables};
+
+/// Maximal depth of operands visited when reconstructing an expression.
+pub const MAX_EXPRESSION_DEPTH: usize = 8;
+
+/// Reconstruct the source-level expression computed by an instruction.
+///
+/// Return `None` if the instruction is not an expression, or if it has
+/// operands which cannot be reconstructed.
+pub fn reconstruct_expression(
+    inst: InstructionValue,
+    source_vars: &SourceVariables,
+) -> Option<String> {
+    reconstruct_instruction(inst, source_vars, MAX_EXPRESSION_DEPTH)
+}
+
+/// Reconstruct the expression of an instruction, visiting its operands up to
+/// a given depth.
+fn reconstruct_instruction(
+    inst: InstructionValue,
+    source_vars: &SourceVariables,
+    depth: usize,
+) -> Option<String> {
+    if depth == 0 {
+        return None;
+    }
+
+    let opcode = inst.get_opcode();
+
+    if let Some(op) = get_binary_operator(opcode) {
+        let lhs = reconstruct_operand(inst, 0, source_vars, depth)?;
+        let rhs = reconstruct_operand(inst, 1, source_vars, depth)?;
+        return Some(format!("{} {} {}", lhs, op, rhs));
+    }
+
+    match opcode {
+        InstructionOpcode::ICmp => {
+            let op = get_comparison_operator(inst.get_icmp_predicate()?);
+            let lhs = reconstruct_operand(inst, 0, source_vars, depth)?;
+            let rhs = reconstruct_operand(inst, 1, source_vars, depth)?;
+            Some(format!("{} {} {}", lhs, op, rhs))
+        }
+        InstructionOpcode::Trunc
+        | InstructionOpcode::ZExt
+        | InstructionOpcode::SExt
+        | InstructionOpcode::SIToFP
+        | InstructionOpcode::UIToFP
+        | InstructionOpcode::FPToSI
+        | InstructionOpcode::FPToUI
+        | InstructionOpcode::FPExt
+        | InstructionOpcode::FPTrunc
+        | InstructionOpcode::BitCast
+        | InstructionOpcode::Freeze
+        | InstructionOpcode::Load => {
+            reconstruct_operand(inst, 0, source_vars, depth)
+        }
+        InstructionOpcode::Call => {
+            let call = inst.try_into_call_base()?;
+            let callee = call.get_called_function()?;
+            let num_args = inst.get_num_operands().checked_sub(1)?;
+            let args = (0..num_args)
+                .map(|idx| reconstruct_operand(inst, idx, source_vars, depth))
+                .collect::<Option<Vec<String>>>()?;
+            Some(format!(
+                "{}({})",
+                callee.get_name().to_string_lossy(),
+                args.join(", ")
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Reconstruct the expression of an operand of an instruction.
+fn reconstruct_operand(
+    inst: InstructionValue,
+    index: u32,
+    source_vars: &SourceVariables,
+    depth: usize,
+) -> Option<String> {
+    match inst.get_operand(index)? {
+        Left(value) => reconstruct_value(value, source_vars, depth - 1),
+        _ => None,
+    }
+}
+
+/// Reconstruct the expression of a value.
+fn reconstruct_value(
+    value: BasicValueEnum,
+    source_vars: &SourceVariables,
+    depth: usize,
+) -> Option<String> {
+    if let Some(name) = source_vars.get_name(&value) {
+        return Some(name.to_string());
+    }
+
+    if let BasicValueEnum::IntValue(int) = value {
+        if let Some(i) = int.get_sign_extended_constant() {
+            return Some(i.to_string());
+        }
+    }
+
+    let inst = value.as_instruction_value()?;
+    reconstruct_instruction(inst, source_vars, depth)
+}
+
+/// Get the source-level operator of a binary operation.
+fn get_binary_operator(opcode: InstructionOpcode) -> Option<&'static str> {
+    match opcode {
+        InstructionOpcode::Add | InstructionOpcode::FAdd => Some("+"),
+        InstructionOpcode::Sub | InstructionOpcode::FSub => Some("-"),
+        InstructionOpcode::Mul | InstructionOpcode::FMul => Some("*"),
+        InstructionOpcode::UDiv
+        | InstructionOpcode::SDiv
+        | InstructionOpcode::FDiv => Some("/"),
+        InstructionOpcode::URem
+        | InstructionOpcode::SRem
+        | InstructionOpcode::FRem => Some("%"),
+        InstructionOpcode::Shl => Some("<<"),
+        InstructionOpcode::LShr | InstructionOpcode::AShr => Some(">>"),
+        InstructionOpcode::And => Some("&"),
+        InstructionOpcode::Or => Some("|"),
+        InstructionOpcode::Xor => Some("^"),
+        _ => None,
+    }
+}
+
+/// Get the source-level operator of an integer comparison.
+fn get_comparison_operator(pred: IntPredicate) -> &'static str {
+    match pred {
+        IntPredicate::EQ => "==",
+        IntPredicate::NE => "!=",
+        IntPredicate::ULT | IntPredicate::SLT => "<",
+        IntPredicate::ULE | IntPredicate::SLE => "<=",
+        IntPredicate::UGT | IntPredicate::SGT => ">",
+        IntPredicate::UGE | IntPredicate::SGE => ">=",
+    }
+}
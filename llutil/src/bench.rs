@@ -0,0 +1,551 @@
+//! Module providing a runner of benchmark suites.
+//!
+//! A benchmark suite is a directory whose entries are benchmarks: C/C++,
+//! Solidity, Rust or LLVM IR files, and Solana projects, i.e., directories
+//! containing a `Cargo.toml` manifest. Each benchmark is compiled with the
+//! frontend of its language, loaded and normalized, then analyzed by a
+//! user-provided function returning the findings of the benchmark.
+//!
+//! The expected findings of a benchmark can be listed in a file next to it,
+//! named after the benchmark with the extension `.expected`, e.g.,
+//! `overflow.c.expected`, with one finding per line. Empty lines and lines
+//! starting with `#` are ignored.
+//!
+//! The expected bug locations of a benchmark can be listed in an oracle file
+//! next to it, with the extension `.oracle`, in the format of the bug
+//! annotation language, e.g., `12:5 bug: integer_overflow`. Findings of
+//! benchmarks with oracles are then parsed in the same format and scored by
+//! precision and recall.
+
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    ops::AddAssign,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use inkwell::{context::Context, module::Module};
+
+use crate::{
+    analysis::driver::get_panic_message,
+    annotation::{load_oracle, parse_oracle_entry, BugAnnotation},
+    file::{self, ext, FileType},
+    normalize::block_order,
+    progress::{NoProgress, ProgressSink, ProgressTracker},
+    tool::{self, clang, rustc, solana, solang},
+    workspace::load_module_file,
+};
+
+/// File extension of files listing expected findings of benchmarks.
+pub const EXPECTED_FILE_EXT: &str = "expected";
+
+/// File extension of oracle files listing expected bugs of benchmarks.
+pub const ORACLE_FILE_EXT: &str = "oracle";
+
+/// Data structure representing a benchmark.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Benchmark {
+    /// Name of the benchmark, i.e., its file name.
+    pub name: String,
+
+    /// Path of the benchmark file or project.
+    pub path: PathBuf,
+
+    /// Type of the benchmark file.
+    pub file_type: FileType,
+
+    /// Expected findings of the benchmark, if listed.
+    pub expected: Option<Vec<String>>,
+
+    /// Expected bugs of the benchmark, if an oracle file exists.
+    pub oracle: Option<Vec<BugAnnotation>>,
+}
+
+/// Implement methods for `Benchmark`.
+impl Benchmark {
+    /// Constructor, which loads expected findings and oracle listed next to
+    /// the benchmark, if any.
+    ///
+    /// Return `None` if the path is not a supported benchmark.
+    pub fn new(path: &Path) -> io::Result<Option<Self>> {
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.to_string(),
+            None => return Ok(None),
+        };
+
+        let file_type = FileType::new(&path.to_string_lossy());
+        if !is_supported_benchmark(path, &file_type) {
+            return Ok(None);
+        }
+
+        let expected_file =
+            path.with_file_name(format!("{}.{}", name, EXPECTED_FILE_EXT));
+        let expected = match fs::read_to_string(&expected_file) {
+            Ok(content) => Some(parse_expected_findings(&content)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+
+        let oracle_file =
+            path.with_file_name(format!("{}.{}", name, ORACLE_FILE_EXT));
+        let oracle = match load_oracle(&oracle_file) {
+            Ok(oracle) => Some(oracle),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+
+        Ok(Some(Benchmark {
+            name,
+            path: path.to_path_buf(),
+            file_type,
+            expected,
+            oracle,
+        }))
+    }
+
+    /// Compile the benchmark with the frontend of its language, and return
+    /// the output LLVM bitcode or IR files.
+    pub fn compile(&self) -> Vec<String> {
+        let path = self.path.to_string_lossy();
+
+        match self.file_type {
+            FileType::CCpp => clang::compile(&path, &[], &[], &[]),
+            FileType::Solidity => solang::compile(&path, &[]),
+            FileType::Rust => rustc::compile(&path, &[]),
+            FileType::Folder => solana::compile(&path, &[]),
+            FileType::ELFObject => {
+                match file::get_bitcode_of_object(&self.path) {
+                    Ok(Some(bitcode)) => {
+                        vec![bitcode.to_string_lossy().to_string()]
+                    }
+                    Ok(None) => panic!("No bitcode found of: {}", path),
+                    Err(err) => panic!("Failed to extract bitcode: {}", err),
+                }
+            }
+            _ => vec![path.to_string()],
+        }
+    }
+}
+
+/// Data structure representing the score of findings against an oracle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Score {
+    /// Number of findings matching expected bugs.
+    pub true_positives: usize,
+
+    /// Number of findings not matching any expected bug.
+    pub false_positives: usize,
+
+    /// Number of expected bugs not matched by any finding.
+    pub false_negatives: usize,
+}
+
+/// Implement methods for `Score`.
+impl Score {
+    /// Get the precision, i.e., the ratio of findings matching expected bugs.
+    ///
+    /// Return `None` if there are no findings.
+    pub fn precision(&self) -> Option<f64> {
+        let total = self.true_positives + self.false_positives;
+        (total > 0).then(|| self.true_positives as f64 / total as f64)
+    }
+
+    /// Get the recall, i.e., the ratio of expected bugs matched by findings.
+    ///
+    /// Return `None` if there are no expected bugs.
+    pub fn recall(&self) -> Option<f64> {
+        let total = self.true_positives + self.false_negatives;
+        (total > 0).then(|| self.true_positives as f64 / total as f64)
+    }
+}
+
+/// Implement the trait `AddAssign` for `Score`.
+impl AddAssign for Score {
+    fn add_assign(&mut self, other: Self) {
+        self.true_positives += other.true_positives;
+        self.false_positives += other.false_positives;
+        self.false_negatives += other.false_negatives;
+    }
+}
+
+/// Implement the trait `Display` for `Score`.
+impl Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ratio = |r: Option<f64>| match r {
+            Some(r) => format!("{:.3}", r),
+            None => "n/a".to_string(),
+        };
+
+        write!(
+            f,
+            "TP: {}, FP: {}, FN: {}, precision: {}, recall: {}",
+            self.true_positives,
+            self.false_positives,
+            self.false_negatives,
+            ratio(self.precision()),
+            ratio(self.recall())
+        )
+    }
+}
+
+/// Data structure representing the result of running a benchmark.
+#[derive(Debug, Clone)]
+pub struct BenchmarkResult {
+    /// The benchmark.
+    pub benchmark: Benchmark,
+
+    /// Time spent to compile, load and normalize the benchmark.
+    pub compile_time: Duration,
+
+    /// Time spent to analyze the benchmark.
+    pub analysis_time: Duration,
+
+    /// Findings reported by the analysis.
+    pub findings: Vec<String>,
+
+    /// Error message, if the benchmark could not be compiled or analyzed.
+    pub error: Option<String>,
+}
+
+/// Implement methods for `BenchmarkResult`.
+impl BenchmarkResult {
+    /// Get expected findings which are not reported.
+    pub fn get_missed_findings(&self) -> Vec<&str> {
+        match &self.benchmark.expected {
+            Some(expected) => expected
+                .iter()
+                .filter(|finding| !self.findings.contains(finding))
+                .map(|finding| finding.as_str())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Get reported findings which are not expected.
+    pub fn get_unexpected_findings(&self) -> Vec<&str> {
+        match &self.benchmark.expected {
+            Some(expected) => self
+                .findings
+                .iter()
+                .filter(|finding| !expected.contains(finding))
+                .map(|finding| finding.as_str())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Score the findings against the oracle of the benchmark.
+    ///
+    /// Each expected bug is matched by at most one finding. Findings not in
+    /// the oracle format are counted as false positives.
+    ///
+    /// Return `None` if the benchmark has no oracle.
+    pub fn get_score(&self) -> Option<Score> {
+        let oracle = self.benchmark.oracle.as_ref()?;
+        let mut matched = vec![false; oracle.len()];
+        let mut score = Score::default();
+
+        for finding in &self.findings {
+            let idx = parse_oracle_entry(finding).and_then(|finding| {
+                (0..oracle.len()).find(|idx| {
+                    !matched[*idx] && oracle[*idx].matches(&finding)
+                })
+            });
+
+            match idx {
+                Some(idx) => {
+                    matched[idx] = true;
+                    score.true_positives += 1
+                }
+                None => score.false_positives += 1,
+            }
+        }
+
+        score.false_negatives = matched.iter().filter(|m| !**m).count();
+        Some(score)
+    }
+
+    /// Check if the benchmark succeeded and reported exactly the expected
+    /// findings.
+    ///
+    /// Return `None` if no findings are expected.
+    pub fn is_passed(&self) -> Option<bool> {
+        self.benchmark.expected.as_ref()?;
+        Some(
+            self.error.is_none()
+                && self.get_missed_findings().is_empty()
+                && self.get_unexpected_findings().is_empty(),
+        )
+    }
+}
+
+/// Implement the trait `Display` for `BenchmarkResult`.
+impl Display for BenchmarkResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match (&self.error, self.is_passed()) {
+            (Some(_), _) => "ERROR",
+            (None, Some(true)) => "PASS",
+            (None, Some(false)) => "FAIL",
+            (None, None) => "DONE",
+        };
+
+        write!(
+            f,
+            "[{}] {}: {} findings, compile: {:.3}s, analysis: {:.3}s",
+            status,
+            self.benchmark.name,
+            self.findings.len(),
+            self.compile_time.as_secs_f64(),
+            self.analysis_time.as_secs_f64()
+        )?;
+
+        if let Some(err) = &self.error {
+            write!(f, "\n    error: {}", err)?;
+        }
+        if let Some(score) = self.get_score() {
+            write!(f, "\n    score: {}", score)?;
+        }
+        for finding in self.get_missed_findings() {
+            write!(f, "\n    missed: {}", finding)?;
+        }
+        for finding in self.get_unexpected_findings() {
+            write!(f, "\n    unexpected: {}", finding)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Data structure representing results of running a benchmark suite.
+#[derive(Debug, Clone, Default)]
+pub struct BenchmarkReport {
+    /// Results of benchmarks, in the order of their names.
+    pub results: Vec<BenchmarkResult>,
+}
+
+/// Implement methods for `BenchmarkReport`.
+impl BenchmarkReport {
+    /// Get the number of benchmarks with expected findings which passed.
+    pub fn get_num_passed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|res| res.is_passed() == Some(true))
+            .count()
+    }
+
+    /// Get the number of benchmarks with expected findings which failed.
+    pub fn get_num_failed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|res| res.is_passed() == Some(false))
+            .count()
+    }
+
+    /// Get the aggregated score of all benchmarks with oracles.
+    ///
+    /// Return `None` if no benchmark has an oracle.
+    pub fn get_score(&self) -> Option<Score> {
+        let mut scores = self.results.iter().filter_map(|res| res.get_score());
+        let mut total = scores.next()?;
+        for score in scores {
+            total += score
+        }
+        Some(total)
+    }
+
+    /// Get the total time spent on all benchmarks.
+    pub fn get_total_time(&self) -> Duration {
+        self.results
+            .iter()
+            .map(|res| res.compile_time + res.analysis_time)
+            .sum()
+    }
+}
+
+/// Implement the trait `Display` for `BenchmarkReport`.
+impl Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Benchmarks: {}, passed: {}, failed: {}, time: {:.3}s",
+            self.results.len(),
+            self.get_num_passed(),
+            self.get_num_failed(),
+            self.get_total_time().as_secs_f64()
+        )?;
+
+        if let Some(score) = self.get_score() {
+            write!(f, "\nScore: {}", score)?;
+        }
+
+        for res in &self.results {
+            write!(f, "\n  {}", res)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Data structure representing a suite of benchmarks.
+#[derive(Debug, Clone, Default)]
+pub struct BenchmarkSuite {
+    /// Benchmarks of the suite, in the order of their names.
+    pub benchmarks: Vec<Benchmark>,
+}
+
+/// Implement methods for `BenchmarkSuite`.
+impl BenchmarkSuite {
+    /// Load benchmarks from the entries of a directory.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir)? {
+            paths.push(entry?.path())
+        }
+        paths.sort();
+
+        let mut benchmarks = vec![];
+        for path in paths {
+            if let Some(bench) = Benchmark::new(&path)? {
+                benchmarks.push(bench)
+            }
+        }
+
+        Ok(BenchmarkSuite { benchmarks })
+    }
+
+    /// Run an analysis on all benchmarks of the suite.
+    ///
+    /// The analysis receives each compiled and normalized benchmark, and
+    /// returns its findings.
+    pub fn run<F>(&self, analyze: F) -> BenchmarkReport
+    where
+        F: FnMut(&Benchmark, &Module) -> Vec<String>,
+    {
+        self.run_with_progress(analyze, &NoProgress)
+    }
+
+    /// Run an analysis on all benchmarks of the suite, reporting the progress
+    /// to a sink.
+    ///
+    /// Panics while compiling or analyzing a benchmark are caught and
+    /// recorded as errors of the benchmark.
+    pub fn run_with_progress<F>(
+        &self,
+        mut analyze: F,
+        progress: &dyn ProgressSink,
+    ) -> BenchmarkReport
+    where
+        F: FnMut(&Benchmark, &Module) -> Vec<String>,
+    {
+        let mut tracker =
+            ProgressTracker::new(progress, "benchmark", self.benchmarks.len());
+        let mut report = BenchmarkReport::default();
+
+        for bench in &self.benchmarks {
+            tracker.start_item(&bench.name);
+            report.results.push(run_benchmark(bench, &mut analyze));
+            tracker.finish_item();
+        }
+
+        tracker.finish();
+        report
+    }
+}
+
+/// Compile, load, normalize and analyze a benchmark.
+fn run_benchmark<F>(bench: &Benchmark, analyze: &mut F) -> BenchmarkResult
+where
+    F: FnMut(&Benchmark, &Module) -> Vec<String>,
+{
+    let mut result = BenchmarkResult {
+        benchmark: bench.clone(),
+        compile_time: Duration::ZERO,
+        analysis_time: Duration::ZERO,
+        findings: vec![],
+        error: None,
+    };
+
+    let context = Context::create();
+    let start = Instant::now();
+    let module = panic::catch_unwind(AssertUnwindSafe(|| {
+        load_module(&context, &bench.compile())
+    }));
+    result.compile_time = start.elapsed();
+
+    let module = match module {
+        Ok(Ok(module)) => module,
+        Ok(Err(err)) => {
+            result.error = Some(err);
+            return result;
+        }
+        Err(err) => {
+            result.error = Some(get_panic_message(&err));
+            return result;
+        }
+    };
+
+    let start = Instant::now();
+    match panic::catch_unwind(AssertUnwindSafe(|| analyze(bench, &module))) {
+        Ok(findings) => result.findings = findings,
+        Err(err) => result.error = Some(get_panic_message(&err)),
+    }
+    result.analysis_time = start.elapsed();
+
+    if let Some(err) = &result.error {
+        log_warn!("Benchmark {} failed: {}", bench.name, err);
+    }
+
+    result
+}
+
+/// Load LLVM bitcode or IR files into a single module and normalize it.
+fn load_module<'ctx>(
+    context: &'ctx Context,
+    files: &[String],
+) -> Result<Module<'ctx>, String> {
+    let mut module: Option<Module> = None;
+
+    for file in files {
+        let other = load_module_file(context, Path::new(file))?;
+
+        match &module {
+            Some(module) => module
+                .link_in_module(other)
+                .map_err(|err| err.to_string())?,
+            None => module = Some(other),
+        }
+    }
+
+    let module = module.ok_or_else(|| "No compiled output".to_string())?;
+    block_order::normalize_module(&module);
+    Ok(module)
+}
+
+/// Check if a path with a file type is a supported benchmark.
+fn is_supported_benchmark(path: &Path, file_type: &FileType) -> bool {
+    match file_type {
+        FileType::CCpp => !matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some(ext::H | ext::HPP | ext::HXX)
+        ),
+        FileType::Folder => path.join(tool::CARGO_TOML).is_file(),
+        FileType::Solidity
+        | FileType::Rust
+        | FileType::ELFObject
+        | FileType::LLVMBC
+        | FileType::LLVMIR => true,
+        _ => false,
+    }
+}
+
+/// Parse expected findings, one finding per line.
+fn parse_expected_findings(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect()
+}
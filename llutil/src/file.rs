@@ -1,5 +1,15 @@
 //! Module handling code unit
+//!
+//! Compiled artifacts, e.g., `.so` files of Solana programs, are ELF objects.
+//! Their LLVM bitcode is extracted from their `.llvmbc` sections when it is
+//! embedded, or is located among the `.bc` files emitted next to them.
 use rutil::system;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::tool::OUTPUT_DIR;
 
 /// Module containing extensions of supported code files.
 pub mod ext {
@@ -30,9 +40,15 @@ pub mod ext {
     /// File extension of LLVM textual IR.
     pub const LL: &str = "ll";
 
+    /// File extension of ELF object files.
+    pub const O: &str = "o";
+
     /// File extension of Rust files.
     pub const RS: &str = "rs";
 
+    /// File extension of ELF shared objects, e.g., Solana programs.
+    pub const SO: &str = "so";
+
     /// File extension of Solidity smart contracts.
     pub const SOL: &str = "sol";
 
@@ -53,6 +69,9 @@ pub enum FileType {
     /// C/C++ and other C-family code files.
     CCpp,
 
+    /// ELF object files, e.g., deployed Solana programs.
+    ELFObject,
+
     /// EMV bytecode files.
     EVMBC,
 
@@ -92,6 +111,7 @@ impl FileType {
             Some(ext::LL) => FileType::LLVMIR,
             Some(ext::EVM) => FileType::EVMBC,
             Some(ext::YUL) => FileType::YulIR,
+            Some(ext::O | ext::SO) => FileType::ELFObject,
             _ => {
                 // check whether the file is a folder
                 match system::is_folder(file_name){
@@ -116,4 +136,248 @@ impl FileType {
     pub fn is_rust_code(&self) -> bool {
         matches!(self, FileType::Rust)
     }
+
+    /// Check if the current file is an ELF object file.
+    pub fn is_elf_object(&self) -> bool {
+        matches!(self, FileType::ELFObject)
+    }
+}
+
+/// Magic number starting ELF files.
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+/// Name of the ELF section containing embedded LLVM bitcode.
+pub const LLVM_BITCODE_SECTION: &str = ".llvmbc";
+
+/// Type of ELF sections occupying no space in the file, e.g., `.bss`.
+const SHT_NOBITS: u32 = 8;
+
+/// Name of the directory containing build outputs of Cargo.
+const CARGO_TARGET_DIR: &str = "target";
+
+/// Reader of the header fields of an ELF file.
+struct ElfReader<'a> {
+    /// Content of the ELF file.
+    data: &'a [u8],
+
+    /// Whether the file uses the 64-bit format.
+    is_64_bit: bool,
+
+    /// Whether the file is encoded in little endian.
+    is_little_endian: bool,
+}
+
+/// Implement methods for `ElfReader`.
+impl<'a> ElfReader<'a> {
+    /// Constructor, which checks the identification bytes of the file.
+    fn new(data: &'a [u8]) -> io::Result<Self> {
+        if !data.starts_with(ELF_MAGIC) || data.len() < 6 {
+            return Err(invalid_elf("missing ELF magic number"));
+        }
+
+        let is_64_bit = match data[4] {
+            1 => false,
+            2 => true,
+            _ => return Err(invalid_elf("unknown ELF class")),
+        };
+        let is_little_endian = match data[5] {
+            1 => true,
+            2 => false,
+            _ => return Err(invalid_elf("unknown ELF data encoding")),
+        };
+
+        Ok(ElfReader {
+            data,
+            is_64_bit,
+            is_little_endian,
+        })
+    }
+
+    /// Read an unsigned integer of `size` bytes at an offset.
+    fn read_uint(&self, offset: usize, size: usize) -> io::Result<u64> {
+        let bytes = offset
+            .checked_add(size)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| invalid_elf("truncated file"))?;
+
+        let value = match self.is_little_endian {
+            true => bytes.iter().rev().fold(0, |v, b| (v << 8) | *b as u64),
+            false => bytes.iter().fold(0, |v, b| (v << 8) | *b as u64),
+        };
+        Ok(value)
+    }
+
+    /// Read an address-sized unsigned integer at an offset.
+    fn read_addr(&self, offset: usize) -> io::Result<u64> {
+        match self.is_64_bit {
+            true => self.read_uint(offset, 8),
+            false => self.read_uint(offset, 4),
+        }
+    }
+
+    /// Get the content of a section by its name.
+    fn find_section(&self, name: &str) -> io::Result<Option<&'a [u8]>> {
+        let (shoff, shentsize, shnum, shstrndx) = match self.is_64_bit {
+            true => (
+                self.read_addr(0x28)?,
+                self.read_uint(0x3A, 2)?,
+                self.read_uint(0x3C, 2)?,
+                self.read_uint(0x3E, 2)?,
+            ),
+            false => (
+                self.read_addr(0x20)?,
+                self.read_uint(0x2E, 2)?,
+                self.read_uint(0x30, 2)?,
+                self.read_uint(0x32, 2)?,
+            ),
+        };
+
+        let min_shentsize = match self.is_64_bit {
+            true => 0x28,
+            false => 0x18,
+        };
+        let table_end = shoff.checked_add(shnum * shentsize);
+        if shentsize < min_shentsize
+            || table_end.map_or(true, |end| end > self.data.len() as u64)
+        {
+            return Err(invalid_elf("invalid section header table"));
+        }
+
+        let headers: Vec<usize> = (0..shnum)
+            .map(|idx| (shoff + idx * shentsize) as usize)
+            .collect();
+        let names = match headers.get(shstrndx as usize) {
+            Some(header) => self.get_section_content(*header)?,
+            None => return Ok(None),
+        };
+
+        for header in headers {
+            let name_offset = self.read_uint(header, 4)? as usize;
+            let section_name = names
+                .get(name_offset..)
+                .and_then(|names| names.split(|b| *b == 0).next())
+                .ok_or_else(|| invalid_elf("invalid section name"))?;
+            if section_name == name.as_bytes() {
+                return self.get_section_content(header).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Get the content of a section by the offset of its header.
+    fn get_section_content(&self, header: usize) -> io::Result<&'a [u8]> {
+        if self.read_uint(header + 4, 4)? as u32 == SHT_NOBITS {
+            return Ok(&[]);
+        }
+
+        let (offset, size) = match self.is_64_bit {
+            true => (
+                self.read_addr(header + 0x18)?,
+                self.read_addr(header + 0x20)?,
+            ),
+            false => (
+                self.read_addr(header + 0x10)?,
+                self.read_addr(header + 0x14)?,
+            ),
+        };
+        offset
+            .checked_add(size)
+            .and_then(|end| self.data.get(offset as usize..end as usize))
+            .ok_or_else(|| invalid_elf("section out of file bounds"))
+    }
+}
+
+/// Create an error of an invalid ELF file.
+fn invalid_elf(msg: &str) -> io::Error {
+    let msg = format!("Invalid ELF file: {}", msg);
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Extract the LLVM bitcode embedded in the `.llvmbc` section of an ELF
+/// object file.
+///
+/// Return `None` if the file embeds no bitcode.
+pub fn extract_embedded_bitcode(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    let data = fs::read(path)?;
+    let section = ElfReader::new(&data)?.find_section(LLVM_BITCODE_SECTION)?;
+    Ok(section
+        .filter(|content| !content.is_empty())
+        .map(|content| content.to_vec()))
+}
+
+/// Find the `.bc` file emitted next to an ELF object file by its build.
+///
+/// The bitcode file has the same stem as the object file, possibly followed
+/// by a hash as in the `deps` directory of Cargo, e.g., `program-1a2b.bc` of
+/// `program.so`. It is searched in the directory of the object file, then in
+/// the enclosing Cargo `target` directory. The most recently modified file is
+/// returned when several files are found.
+pub fn find_sibling_bitcode(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?;
+    let parent_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let sibling = parent_dir.join(format!("{}.{}", stem, ext::BC));
+    if sibling.is_file() {
+        return Some(sibling);
+    }
+
+    let target_dir = path
+        .ancestors()
+        .find(|dir| dir.file_name() == Some(CARGO_TARGET_DIR.as_ref()))?;
+    let mut candidates = vec![];
+    collect_bitcode_files(target_dir, stem, &mut candidates);
+
+    candidates
+        .into_iter()
+        .max_by_key(|file| fs::metadata(file).and_then(|md| md.modified()).ok())
+}
+
+/// Collect recursively the bitcode files of a directory, whose names are a
+/// stem possibly followed by a hash.
+fn collect_bitcode_files(dir: &Path, stem: &str, files: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for path in entries.filter_map(|entry| entry.ok()).map(|e| e.path()) {
+        if path.is_dir() {
+            collect_bitcode_files(&path, stem, files);
+            continue;
+        }
+        if path.extension().and_then(|ext| ext.to_str()) != Some(ext::BC) {
+            continue;
+        }
+        let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let is_matched = match file_stem.strip_prefix(stem) {
+            Some(suffix) => suffix.is_empty() || suffix.starts_with('-'),
+            None => false,
+        };
+        if is_matched {
+            files.push(path)
+        }
+    }
+}
+
+/// Get the LLVM bitcode file of an ELF object file.
+///
+/// Embedded bitcode is extracted to the output directory next to the object
+/// file, otherwise the bitcode file emitted next to it by its build is
+/// returned. Return `None` if no bitcode is found.
+pub fn get_bitcode_of_object(path: &Path) -> io::Result<Option<PathBuf>> {
+    let bitcode = match extract_embedded_bitcode(path)? {
+        Some(bitcode) => bitcode,
+        None => return Ok(find_sibling_bitcode(path)),
+    };
+
+    let filename = path.file_name().unwrap_or_default();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let parent_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let output_dir = parent_dir.join(OUTPUT_DIR).join(filename);
+    fs::create_dir_all(&output_dir)?;
+
+    let output_file = output_dir.join(format!("{}.{}", stem, ext::BC));
+    fs::write(&output_file, bitcode)?;
+    Ok(Some(output_file))
 }
@@ -0,0 +1,140 @@
+//! Module instrumenting bug annotations of a source file into LLVM IR.
+//!
+//! Each instruction matched by a bug annotation is preceded by a call to the
+//! function `__assert_bug_<bug_type>`, which is marked as instrumentation.
+//! Instrumenting a module again refreshes its bug assertions instead of
+//! duplicating them.
+
+use inkwell::{
+    context::Context,
+    module::Module,
+    values::{FunctionValue, InstructionOpcode, InstructionValue},
+};
+
+use crate::{
+    annotation::MatchResults,
+    ir::{builtin::assertion_lib, AnyCall, FunctionExt, InstructionExt},
+};
+
+use super::marker::{is_instrumented_instruction, mark_instrumented};
+
+/// Get the name of the assertion function of a bug type.
+pub fn get_bug_assertion_name(bug_type: &str) -> String {
+    format!("{}{}", assertion_lib::PREFIX_ASSERT_BUG, bug_type)
+}
+
+/// Instrument matched bug annotations into a module, by inserting a call to
+/// the assertion function of each annotation before each of its matched
+/// instructions.
+///
+/// Bug assertions already in the module, e.g., from a previous
+/// instrumentation, are removed first. Return the number of inserted calls.
+pub fn instrument_bug_annotations<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    results: &MatchResults<'ctx>,
+) -> usize {
+    let num_removed = remove_bug_assertions(module);
+    if num_removed > 0 {
+        log_debug!("Refresh instrumentation: removed {} calls", num_removed);
+    }
+
+    let builder = context.create_builder();
+    let mut num_inserted = 0;
+
+    for annot_match in &results.matched {
+        let bug_type = &annot_match.annotation.bug_type;
+        let assert_func =
+            get_or_insert_bug_assertion(context, module, bug_type);
+
+        for inst in &annot_match.instructions {
+            if is_preceded_by_assertion(*inst, assert_func) {
+                continue;
+            }
+
+            builder.position_before(inst);
+            let call = builder.build_call(assert_func, &[], "");
+            if let Some(call_inst) = call.try_as_basic_value().right() {
+                mark_instrumented(context, call_inst);
+                num_inserted += 1;
+            }
+        }
+    }
+
+    num_inserted
+}
+
+/// Remove all bug assertions from a module, including unmarked calls to
+/// assertion functions of bug types, and their unused declarations.
+///
+/// Return the number of removed calls.
+pub fn remove_bug_assertions(module: &Module) -> usize {
+    let calls: Vec<InstructionValue> = module
+        .get_functions()
+        .flat_map(|func| func.get_basic_blocks())
+        .flat_map(|blk| blk.get_instructions())
+        .filter(|inst| get_bug_assertion(*inst).is_some())
+        .collect();
+    let num_calls = calls.len();
+
+    for call in calls {
+        call.erase_from_basic_block()
+    }
+
+    let unused_funcs: Vec<FunctionValue> = module
+        .get_functions()
+        .filter(|func| is_bug_assertion_function(*func))
+        .filter(|func| func.get_first_use().is_none())
+        .collect();
+    for func in unused_funcs {
+        unsafe { func.delete() }
+    }
+
+    num_calls
+}
+
+/// Check if a function is the assertion function of a bug type.
+fn is_bug_assertion_function(func: FunctionValue) -> bool {
+    func.get_name_or_default()
+        .starts_with(assertion_lib::PREFIX_ASSERT_BUG)
+}
+
+/// Get the called assertion function if an instruction is a bug assertion.
+fn get_bug_assertion(inst: InstructionValue) -> Option<FunctionValue> {
+    if inst.get_opcode() != InstructionOpcode::Call {
+        return None;
+    }
+    let callee = inst.try_into_call_base()?.get_called_function()?;
+    is_bug_assertion_function(callee).then_some(callee)
+}
+
+/// Check if an instruction is immediately preceded by an instrumented call
+/// to an assertion function.
+fn is_preceded_by_assertion(
+    inst: InstructionValue,
+    assert_func: FunctionValue,
+) -> bool {
+    match inst.get_previous_instruction() {
+        Some(prev_inst) => {
+            is_instrumented_instruction(prev_inst)
+                && get_bug_assertion(prev_inst) == Some(assert_func)
+        }
+        None => false,
+    }
+}
+
+/// Get or declare the assertion function of a bug type.
+fn get_or_insert_bug_assertion<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    bug_type: &str,
+) -> FunctionValue<'ctx> {
+    let func_name = get_bug_assertion_name(bug_type);
+    match module.get_function(&func_name) {
+        Some(func) => func,
+        None => {
+            let fn_type = context.void_type().fn_type(&[], false);
+            module.add_function(&func_name, fn_type, None)
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Module providing an API to inject ghost variables into LLVM IR.
+//!
+//! Ghost variables are global or local variables used only by specifications,
+//! such as the sum of all transfers of a contract. They do not affect the
+//! semantics of the program: they are only read and written by ghost
+//! instructions. Ghost globals and ghost instructions are marked by the
+//! metadata kind `GHOST_METADATA_KIND`.
+
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    module::{Linkage, Module},
+    types::BasicType,
+    values::{
+        BasicValue, BasicValueEnum, FunctionValue, GlobalValue,
+        InstructionValue, MetadataValue, PointerValue,
+    },
+};
+
+/// Metadata kind marking ghost globals and ghost instructions.
+pub const GHOST_METADATA_KIND: &str = "llutil.ghost";
+
+/// Builder declaring and updating ghost variables of a module.
+pub struct GhostBuilder<'a, 'ctx> {
+    /// Context of the instrumented module.
+    context: &'ctx Context,
+
+    /// Module being instrumented.
+    module: &'a Module<'ctx>,
+
+    /// Builder creating ghost instructions.
+    builder: Builder<'ctx>,
+}
+
+/// Implement methods for `GhostBuilder`.
+impl<'a, 'ctx> GhostBuilder<'a, 'ctx> {
+    /// Constructor
+    pub fn new(context: &'ctx Context, module: &'a Module<'ctx>) -> Self {
+        let builder = context.create_builder();
+        GhostBuilder {
+            context,
+            module,
+            builder,
+        }
+    }
+
+    /// Declare a ghost global variable, initialized by `init` or by zero.
+    pub fn declare_global<T: BasicType<'ctx>>(
+        &self,
+        name: &str,
+        ty: T,
+        init: Option<BasicValueEnum<'ctx>>,
+    ) -> GlobalValue<'ctx> {
+        let ty = ty.as_basic_type_enum();
+        let global = self.module.add_global(ty, None, name);
+        let init = init.unwrap_or_else(|| ty.const_zero());
+
+        global.set_initializer(&init);
+        global.set_linkage(Linkage::Internal);
+        global.set_metadata(self.create_ghost_metadata(), self.get_kind_id());
+
+        global
+    }
+
+    /// Declare a ghost local variable of a function, allocated at the
+    /// beginning of its entry block.
+    ///
+    /// Return `None` if the function has no body.
+    pub fn declare_local<T: BasicType<'ctx>>(
+        &self,
+        func: FunctionValue<'ctx>,
+        name: &str,
+        ty: T,
+    ) -> Option<PointerValue<'ctx>> {
+        let ty = ty.as_basic_type_enum();
+        let entry = func.get_first_basic_block()?;
+
+        match entry.get_first_instruction() {
+            Some(inst) => self.builder.position_before(&inst),
+            None => self.builder.position_at_end(entry),
+        }
+
+        let ptr = self.builder.build_alloca(ty, name);
+        let init = ty.const_zero();
+        let store = self.builder.build_store(ptr, init);
+
+        if let Some(alloca) = ptr.as_instruction_value() {
+            self.mark_ghost_instruction(alloca)
+        }
+        self.mark_ghost_instruction(store);
+
+        Some(ptr)
+    }
+
+    /// Update a ghost variable right before an instruction.
+    ///
+    /// The function `update` receives the builder, positioned at the update
+    /// point, and the current value of the ghost variable, and returns its
+    /// new value. All instructions created by the update are marked as ghost.
+    pub fn update_before<F>(
+        &self,
+        point: InstructionValue<'ctx>,
+        ghost: PointerValue<'ctx>,
+        update: F,
+    ) -> InstructionValue<'ctx>
+    where
+        F: FnOnce(&Builder<'ctx>, BasicValueEnum<'ctx>) -> BasicValueEnum<'ctx>,
+    {
+        self.builder.position_before(&point);
+        self.build_update(ghost, update)
+    }
+
+    /// Update a ghost variable right after an instruction.
+    ///
+    /// See `update_before` for the semantics of `update`.
+    pub fn update_after<F>(
+        &self,
+        point: InstructionValue<'ctx>,
+        ghost: PointerValue<'ctx>,
+        update: F,
+    ) -> InstructionValue<'ctx>
+    where
+        F: FnOnce(&Builder<'ctx>, BasicValueEnum<'ctx>) -> BasicValueEnum<'ctx>,
+    {
+        match (point.get_next_instruction(), point.get_parent()) {
+            (Some(next_inst), _) => self.builder.position_before(&next_inst),
+            (None, Some(blk)) => self.builder.position_at_end(blk),
+            (None, None) => panic!("Update point is not in a block: {}", point),
+        }
+        self.build_update(ghost, update)
+    }
+
+    /// Build instructions loading, updating and storing a ghost variable at
+    /// the current position of the builder. Return the ghost store.
+    fn build_update<F>(
+        &self,
+        ghost: PointerValue<'ctx>,
+        update: F,
+    ) -> InstructionValue<'ctx>
+    where
+        F: FnOnce(&Builder<'ctx>, BasicValueEnum<'ctx>) -> BasicValueEnum<'ctx>,
+    {
+        let old_value = self.builder.build_load(ghost, "ghost.old");
+        let new_value = update(&self.builder, old_value);
+        let store = self.builder.build_store(ghost, new_value);
+
+        // Mark all instructions from the load to the store as ghost.
+        let mut inst = old_value.as_instruction_value();
+        while let Some(ghost_inst) = inst {
+            self.mark_ghost_instruction(ghost_inst);
+            if ghost_inst == store {
+                break;
+            }
+            inst = ghost_inst.get_next_instruction();
+        }
+
+        store
+    }
+
+    /// Get the metadata kind identifier of ghost markers.
+    fn get_kind_id(&self) -> u32 {
+        self.context.get_kind_id(GHOST_METADATA_KIND)
+    }
+
+    /// Create the metadata marking ghost values.
+    fn create_ghost_metadata(&self) -> MetadataValue<'ctx> {
+        self.context.metadata_node(&[])
+    }
+
+    /// Mark an instruction as ghost.
+    fn mark_ghost_instruction(&self, inst: InstructionValue<'ctx>) {
+        let _ =
+            inst.set_metadata(self.create_ghost_metadata(), self.get_kind_id());
+    }
+}
+
+/// Check if an instruction is a ghost instruction.
+pub fn is_ghost_instruction(inst: InstructionValue) -> bool {
+    let context = match inst.get_parent() {
+        Some(blk) => blk.get_context(),
+        None => return false,
+    };
+    let kind_id = context.get_kind_id(GHOST_METADATA_KIND);
+    inst.get_metadata(kind_id).is_some()
+}
+
+/// Check if a global variable is a ghost global.
+pub fn is_ghost_global(global: GlobalValue) -> bool {
+    let context = global.as_pointer_value().get_type().get_context();
+    let kind_id = context.get_kind_id(GHOST_METADATA_KIND);
+    global.get_metadata(kind_id).is_some()
+}
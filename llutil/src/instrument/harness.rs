@@ -0,0 +1,214 @@
+//! Module generating drivers which execute a single function on inputs read
+//! from a byte buffer.
+//!
+//! A driver has the signature `i32 (i8*, i64)`, similar to fuzzing entry
+//! points: it receives the input bytes and their size, initializes arguments
+//! of the target function from the bytes, calls the target function and
+//! stores its result to a global variable. Drivers are added to the module of
+//! the target function, so that the module can be JIT-executed or compiled.
+//!
+//! Integer arguments are read in order, each over the smallest number of bytes
+//! holding its bit width, in the byte order of the target machine. This is
+//! the layout of inputs written by the concolic mode. Pointer arguments to
+//! integers point to buffers allocated by the driver, holding one integer read
+//! from the input in the same way. Other arguments are initialized to zero,
+//! and target functions with pointer parameters to other types are rejected.
+//! The driver returns -1 without calling the target function if the input is
+//! too short, and 0 otherwise.
+//!
+//! The result global variables have external linkage, so that they can be
+//! looked up by name after executing drivers.
+
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    module::{Linkage, Module},
+    types::{AnyTypeEnum, BasicTypeEnum, IntType},
+    values::{
+        BasicMetadataValueEnum, FunctionValue, GlobalValue, IntValue,
+        PointerValue,
+    },
+    AddressSpace, IntPredicate,
+};
+
+use crate::ir::FunctionExt;
+
+/// Name prefix of generated drivers.
+pub const HARNESS_DRIVER_PREFIX: &str = "__llutil_harness_";
+
+/// Name prefix of global variables capturing results of target functions.
+pub const HARNESS_RESULT_PREFIX: &str = "__llutil_harness_result_";
+
+/// Data structure representing a generated driver of a target function.
+#[derive(Debug, Clone, Copy)]
+pub struct Harness<'ctx> {
+    /// Function executed by the driver.
+    pub target: FunctionValue<'ctx>,
+
+    /// Driver function.
+    pub driver: FunctionValue<'ctx>,
+
+    /// Global variable with external linkage capturing the result of the
+    /// target function, if it returns a value.
+    pub result: Option<GlobalValue<'ctx>>,
+
+    /// Number of input bytes read by the driver.
+    pub input_size: u64,
+}
+
+/// Generate a driver executing a target function of a module.
+///
+/// Return `None` if the target function is only declared, is variadic or has
+/// pointer parameters to non-integer types.
+pub fn generate_harness<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    target: FunctionValue<'ctx>,
+) -> Option<Harness<'ctx>> {
+    if target.is_only_declared() || target.get_type().is_var_arg() {
+        return None;
+    }
+
+    let has_unsupported_pointer = target.get_param_iter().any(|param| {
+        let typ = param.get_type();
+        typ.is_pointer_type() && get_input_int_type(typ).is_none()
+    });
+    if has_unsupported_pointer {
+        return None;
+    }
+
+    let builder = context.create_builder();
+    let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+
+    let target_name = target.get_name_or_default();
+    let driver_type =
+        i32_type.fn_type(&[i8_ptr_type.into(), i64_type.into()], false);
+    let driver = module.add_function(
+        &format!("{}{}", HARNESS_DRIVER_PREFIX, target_name),
+        driver_type,
+        None,
+    );
+
+    let entry_blk = context.append_basic_block(driver, "entry");
+    let call_blk = context.append_basic_block(driver, "call");
+    let reject_blk = context.append_basic_block(driver, "reject");
+
+    let data = driver.get_nth_param(0)?.into_pointer_value();
+    let size = driver.get_nth_param(1)?.into_int_value();
+
+    let input_size: u64 = target
+        .get_param_iter()
+        .filter_map(|param| get_input_int_type(param.get_type()))
+        .map(get_byte_size)
+        .sum();
+
+    // Reject inputs which are too short.
+    builder.position_at_end(entry_blk);
+    let min_size = i64_type.const_int(input_size, false);
+    let is_long_enough =
+        builder.build_int_compare(IntPredicate::UGE, size, min_size, "");
+    builder.build_conditional_branch(is_long_enough, call_blk, reject_blk);
+
+    builder.position_at_end(reject_blk);
+    builder.build_return(Some(&i32_type.const_int(-1i64 as u64, true)));
+
+    // Initialize arguments and call the target function.
+    builder.position_at_end(call_blk);
+    let mut args: Vec<BasicMetadataValueEnum> = vec![];
+    let mut offset = 0;
+
+    for param in target.get_param_iter() {
+        let typ = param.get_type();
+        let arg = match get_input_int_type(typ) {
+            Some(int_type) => {
+                let value =
+                    build_input_load(context, &builder, data, offset, int_type);
+                offset += get_byte_size(int_type);
+
+                if typ.is_pointer_type() {
+                    let buffer = builder.build_alloca(int_type, "");
+                    builder.build_store(buffer, value);
+                    buffer.into()
+                } else {
+                    value.into()
+                }
+            }
+            None => typ.const_zero(),
+        };
+        args.push(arg.into())
+    }
+
+    let call = builder.build_call(target, &args, "");
+
+    let result = call.try_as_basic_value().left().map(|value| {
+        let global = module.add_global(
+            value.get_type(),
+            None,
+            &format!("{}{}", HARNESS_RESULT_PREFIX, target_name),
+        );
+        global.set_initializer(&value.get_type().const_zero());
+        global.set_linkage(Linkage::External);
+        builder.build_store(global.as_pointer_value(), value);
+        global
+    });
+
+    builder.build_return(Some(&i32_type.const_zero()));
+
+    Some(Harness {
+        target,
+        driver,
+        result,
+        input_size,
+    })
+}
+
+/// Load an integer of a type from the input bytes at an offset.
+fn build_input_load<'ctx>(
+    context: &'ctx Context,
+    builder: &Builder<'ctx>,
+    data: PointerValue<'ctx>,
+    offset: u64,
+    int_type: IntType<'ctx>,
+) -> IntValue<'ctx> {
+    let num_bytes = get_byte_size(int_type);
+    let mem_type = context.custom_width_int_type(8 * num_bytes as u32);
+    let idx = context.i64_type().const_int(offset, false);
+    let ptr = unsafe { builder.build_gep(data, &[idx], "") };
+    let ptr = builder.build_pointer_cast(
+        ptr,
+        mem_type.ptr_type(AddressSpace::Generic),
+        "",
+    );
+    let value = builder.build_load(ptr, "").into_int_value();
+    if let Some(load) = value.as_instruction() {
+        let _ = load.set_alignment(1);
+    }
+
+    if mem_type == int_type {
+        value
+    } else {
+        builder.build_int_truncate(value, int_type, "")
+    }
+}
+
+/// Get the integer type read from the input bytes for a parameter type, which
+/// is either an integer type or a pointer type to an integer type.
+fn get_input_int_type(typ: BasicTypeEnum) -> Option<IntType> {
+    match typ {
+        BasicTypeEnum::IntType(int_type) => Some(int_type),
+        BasicTypeEnum::PointerType(ptr_type) => {
+            match ptr_type.get_element_type() {
+                AnyTypeEnum::IntType(int_type) => Some(int_type),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get the number of input bytes holding an integer type.
+fn get_byte_size(int_type: IntType) -> u64 {
+    ((int_type.get_bit_width() + 7) / 8) as u64
+}
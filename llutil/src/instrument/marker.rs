@@ -0,0 +1,72 @@
+//! Module marking instructions inserted by instrumentation passes.
+//!
+//! Instructions inserted by instrumentation are marked by the metadata kind
+//! `INSTRUMENTATION_METADATA_KIND`, so that instrumented modules can be
+//! detected and their instrumentation removed.
+
+use inkwell::{context::Context, module::Module, values::InstructionValue};
+
+use crate::ir::{builtin::is_verazt_library_function, FunctionExt};
+
+/// Metadata kind marking instructions inserted by instrumentation.
+pub const INSTRUMENTATION_METADATA_KIND: &str = "llutil.instrumentation";
+
+/// Mark an instruction as inserted by instrumentation.
+pub fn mark_instrumented<'ctx>(
+    context: &'ctx Context,
+    inst: InstructionValue<'ctx>,
+) {
+    let marker = context.metadata_node(&[]);
+    let _ = inst.set_metadata_of_kind(marker, INSTRUMENTATION_METADATA_KIND);
+}
+
+/// Check if an instruction is inserted by instrumentation.
+pub fn is_instrumented_instruction(inst: InstructionValue) -> bool {
+    inst.get_metadata_of_kind(INSTRUMENTATION_METADATA_KIND)
+        .is_some()
+}
+
+/// Get all instructions of a module inserted by instrumentation.
+pub fn get_instrumented_instructions<'ctx>(
+    module: &Module<'ctx>,
+) -> Vec<InstructionValue<'ctx>> {
+    module
+        .get_functions()
+        .flat_map(|func| func.get_basic_blocks())
+        .flat_map(|blk| blk.get_instructions())
+        .filter(|inst| is_instrumented_instruction(*inst))
+        .collect()
+}
+
+/// Check if a module contains instructions inserted by instrumentation.
+pub fn is_instrumented(module: &Module) -> bool {
+    !get_instrumented_instructions(module).is_empty()
+}
+
+/// Remove all instructions inserted by instrumentation from a module, then
+/// remove declarations of Verazt library functions which are no longer used.
+///
+/// Return the number of removed instructions.
+pub fn remove_instrumentation(module: &Module) -> usize {
+    let insts = get_instrumented_instructions(module);
+    let num_insts = insts.len();
+
+    // Remove users before the instructions they use.
+    for inst in insts.into_iter().rev() {
+        inst.erase_from_basic_block()
+    }
+
+    let unused_funcs: Vec<_> = module
+        .get_functions()
+        .filter(|func| {
+            func.is_only_declared()
+                && func.get_first_use().is_none()
+                && is_verazt_library_function(&func.get_name_or_default())
+        })
+        .collect();
+    for func in unused_funcs {
+        unsafe { func.delete() }
+    }
+
+    num_insts
+}
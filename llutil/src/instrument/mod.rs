@@ -0,0 +1,25 @@
+//! Module containing utilities to instrument LLVM IR.
+
+// Export sub modules
+pub mod bug_assert;
+pub mod ghost;
+pub mod harness;
+pub mod marker;
+pub mod nondet;
+pub mod solana_input;
+
+// Re-export sub-modules' data structures
+pub use bug_assert::{
+    get_bug_assertion_name, instrument_bug_annotations, remove_bug_assertions,
+};
+pub use ghost::{is_ghost_global, is_ghost_instruction, GhostBuilder};
+pub use harness::{generate_harness, Harness};
+pub use marker::{
+    get_instrumented_instructions, is_instrumented,
+    is_instrumented_instruction, mark_instrumented, remove_instrumentation,
+};
+pub use nondet::replace_calls_with_nondet;
+pub use solana_input::{
+    get_solana_entrypoint, SolanaAccount, SolanaAccountEntry,
+    SolanaFieldLayout, SolanaInput, SolanaInputField,
+};
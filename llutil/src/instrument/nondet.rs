@@ -0,0 +1,133 @@
+//! Module replacing calls to environment functions by calls to built-in
+//! functions returning nondeterministic values, such as `__nondet_i32`.
+//!
+//! This standardizes the modelling of the environment: results of calls to
+//! `__nondet_*` functions are treated as fresh symbols by analyses.
+
+use inkwell::{
+    context::Context,
+    module::Module,
+    types::{BasicType, BasicTypeEnum},
+    values::{BasicValue, FunctionValue, InstructionValue},
+    AddressSpace,
+};
+
+use crate::ir::{builtin::assertion_lib, AnyCall, FunctionExt, InstructionExt};
+
+/// Replace all calls to the functions `func_names` in a module by calls to
+/// nondeterministic built-in functions of the same return type.
+///
+/// Calls returning `void` or non-integer, non-pointer values are kept
+/// unchanged. Return the number of replaced calls.
+pub fn replace_calls_with_nondet<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    func_names: &[&str],
+) -> usize {
+    let mut calls = vec![];
+
+    for func in module.get_functions() {
+        for blk in func.get_basic_blocks() {
+            for inst in blk.get_instructions() {
+                let callee = inst
+                    .try_into_call_inst()
+                    .and_then(|call| call.get_called_function());
+                if let Some(callee) = callee {
+                    let callee_name = callee.get_name_or_default();
+                    if func_names.contains(&callee_name.as_str()) {
+                        calls.push(inst)
+                    }
+                }
+            }
+        }
+    }
+
+    calls
+        .into_iter()
+        .filter(|call| replace_call_with_nondet(context, module, *call))
+        .count()
+}
+
+/// Replace a call instruction by a call to a nondeterministic built-in
+/// function. Return `true` if the call is replaced.
+fn replace_call_with_nondet<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    call: InstructionValue<'ctx>,
+) -> bool {
+    let ret_type = match BasicTypeEnum::try_from(call.get_type()) {
+        Ok(ty) => ty,
+        Err(_) => return false,
+    };
+
+    let nondet_func =
+        match get_or_insert_nondet_function(context, module, ret_type) {
+            Some(func) => func,
+            None => return false,
+        };
+
+    let builder = context.create_builder();
+    builder.position_before(&call);
+
+    let nondet_value = match builder
+        .build_call(nondet_func, &[], "nondet")
+        .try_as_basic_value()
+        .left()
+    {
+        Some(value) => value,
+        None => return false,
+    };
+
+    let nondet_value = match nondet_value.get_type() == ret_type {
+        true => nondet_value,
+        false => builder.build_bitcast(nondet_value, ret_type, "nondet.cast"),
+    };
+
+    match nondet_value.as_instruction_value() {
+        Some(nondet_inst) => {
+            call.replace_all_uses_with(&nondet_inst);
+            call.erase_from_basic_block();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Get or declare the nondeterministic built-in function returning a type.
+///
+/// Integers of bit width `N` are returned by `__nondet_iN`, and pointers are
+/// returned by `__nondet_ptr` as `i8*`.
+fn get_or_insert_nondet_function<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    ty: BasicTypeEnum<'ctx>,
+) -> Option<FunctionValue<'ctx>> {
+    let (func_name, ret_type) = match ty {
+        BasicTypeEnum::IntType(int_type) => {
+            let width = int_type.get_bit_width();
+            let func_name = match width {
+                32 => assertion_lib::NONDET_I32.to_string(),
+                64 => assertion_lib::NONDET_I64.to_string(),
+                256 => assertion_lib::NONDET_I256.to_string(),
+                _ => format!("{}i{}", assertion_lib::PREFIX_NONDET, width),
+            };
+            (func_name, int_type.as_basic_type_enum())
+        }
+        BasicTypeEnum::PointerType(_) => {
+            let ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+            let func_name = assertion_lib::NONDET_PTR.to_string();
+            (func_name, ptr_type.as_basic_type_enum())
+        }
+        _ => return None,
+    };
+
+    let func = match module.get_function(&func_name) {
+        Some(func) => func,
+        None => {
+            let func_type = ret_type.fn_type(&[], false);
+            module.add_function(&func_name, func_type, None)
+        }
+    };
+
+    Some(func)
+}
@@ -0,0 +1,397 @@
+//! Module modelling the serialized input of Solana program entrypoints.
+//!
+//! The `entrypoint` function of a Solana program receives a single buffer,
+//! serialized by the BPF loader, which contains the accounts passed to the
+//! program, the instruction data and the program ID:
+//!
+//! - The number of accounts, as a `u64`.
+//! - For each account, a duplicate marker byte, which is `0xff` for a new
+//!   account, or the index of the duplicated account followed by 7 padding
+//!   bytes. A new account consists of its signer, writable and executable
+//!   flags, 4 padding bytes, its key, its owner, its lamports, its data length
+//!   and data, a reserved space of `MAX_PERMITTED_DATA_INCREASE` bytes for
+//!   reallocation, padding to an 8-byte alignment, and its rent epoch.
+//! - The instruction data length, as a `u64`, and the instruction data.
+//! - The program ID.
+//!
+//! All integers are little-endian. The layout of an input describes the byte
+//! range of each field, so that analyses can treat the contents of accounts
+//! and instruction data as symbolic while keeping the structure concrete.
+
+use std::fmt::{self, Display};
+
+use inkwell::{module::Module, values::FunctionValue};
+
+use crate::ir::builtin::solang_ewasm_lib;
+
+/// Size of public keys, such as account keys and program IDs.
+pub const PUBKEY_SIZE: usize = 32;
+
+/// Size of the space reserved after account data for reallocation.
+pub const MAX_PERMITTED_DATA_INCREASE: usize = 10240;
+
+/// Marker of a non-duplicate account.
+pub const NON_DUP_MARKER: u8 = 0xff;
+
+/// Data structure representing an account passed to a Solana program.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SolanaAccount {
+    /// Public key of the account.
+    pub key: [u8; PUBKEY_SIZE],
+
+    /// Program owning the account.
+    pub owner: [u8; PUBKEY_SIZE],
+
+    /// Balance of the account.
+    pub lamports: u64,
+
+    /// Data of the account.
+    pub data: Vec<u8>,
+
+    /// Whether the account signed the transaction.
+    pub is_signer: bool,
+
+    /// Whether the account is writable.
+    pub is_writable: bool,
+
+    /// Whether the account contains a program.
+    pub executable: bool,
+
+    /// Epoch at which the account next owes rent.
+    pub rent_epoch: u64,
+}
+
+/// Data structure representing an account entry of a serialized input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolanaAccountEntry {
+    /// A new account.
+    Account(SolanaAccount),
+
+    /// A duplicate of the account at an index.
+    Duplicate(u8),
+}
+
+/// Data structure representing the input of a Solana program entrypoint.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SolanaInput {
+    /// Accounts passed to the program.
+    pub accounts: Vec<SolanaAccountEntry>,
+
+    /// Instruction data.
+    pub instruction_data: Vec<u8>,
+
+    /// ID of the invoked program.
+    pub program_id: [u8; PUBKEY_SIZE],
+}
+
+/// Fields of a serialized Solana input.
+///
+/// Account fields carry the index of their account entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SolanaInputField {
+    /// Number of accounts.
+    NumAccounts,
+
+    /// Duplicate marker of an account entry.
+    DupMarker(usize),
+
+    /// Signer flag of an account.
+    IsSigner(usize),
+
+    /// Writable flag of an account.
+    IsWritable(usize),
+
+    /// Executable flag of an account.
+    Executable(usize),
+
+    /// Key of an account.
+    Key(usize),
+
+    /// Owner of an account.
+    Owner(usize),
+
+    /// Lamports of an account.
+    Lamports(usize),
+
+    /// Data length of an account.
+    DataLen(usize),
+
+    /// Data of an account.
+    Data(usize),
+
+    /// Rent epoch of an account.
+    RentEpoch(usize),
+
+    /// Instruction data length.
+    InstructionDataLen,
+
+    /// Instruction data.
+    InstructionData,
+
+    /// Program ID.
+    ProgramId,
+}
+
+/// Implement methods for `SolanaInputField`.
+impl SolanaInputField {
+    /// Check if the field is a content of the input, which can be treated as
+    /// symbolic, rather than a part of its structure, such as lengths, flags,
+    /// keys and duplicate markers.
+    pub fn is_symbolic(&self) -> bool {
+        matches!(
+            self,
+            SolanaInputField::Lamports(_)
+                | SolanaInputField::Data(_)
+                | SolanaInputField::InstructionData
+        )
+    }
+}
+
+/// Implement the trait `Display` for `SolanaInputField`.
+impl Display for SolanaInputField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolanaInputField::NumAccounts => write!(f, "num_accounts"),
+            SolanaInputField::DupMarker(i) => write!(f, "account_{}.dup", i),
+            SolanaInputField::IsSigner(i) => {
+                write!(f, "account_{}.is_signer", i)
+            }
+            SolanaInputField::IsWritable(i) => {
+                write!(f, "account_{}.is_writable", i)
+            }
+            SolanaInputField::Executable(i) => {
+                write!(f, "account_{}.executable", i)
+            }
+            SolanaInputField::Key(i) => write!(f, "account_{}.key", i),
+            SolanaInputField::Owner(i) => write!(f, "account_{}.owner", i),
+            SolanaInputField::Lamports(i) => {
+                write!(f, "account_{}.lamports", i)
+            }
+            SolanaInputField::DataLen(i) => write!(f, "account_{}.data_len", i),
+            SolanaInputField::Data(i) => write!(f, "account_{}.data", i),
+            SolanaInputField::RentEpoch(i) => {
+                write!(f, "account_{}.rent_epoch", i)
+            }
+            SolanaInputField::InstructionDataLen => {
+                write!(f, "instruction_data_len")
+            }
+            SolanaInputField::InstructionData => write!(f, "instruction_data"),
+            SolanaInputField::ProgramId => write!(f, "program_id"),
+        }
+    }
+}
+
+/// Data structure representing the byte range of a field in a serialized
+/// input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolanaFieldLayout {
+    /// The field.
+    pub field: SolanaInputField,
+
+    /// Offset of the field from the start of the input.
+    pub offset: usize,
+
+    /// Size of the field in bytes.
+    pub size: usize,
+}
+
+/// Implement methods for `SolanaInput`.
+impl SolanaInput {
+    /// Constructor
+    pub fn new(
+        accounts: Vec<SolanaAccountEntry>,
+        instruction_data: Vec<u8>,
+        program_id: [u8; PUBKEY_SIZE],
+    ) -> Self {
+        SolanaInput {
+            accounts,
+            instruction_data,
+            program_id,
+        }
+    }
+
+    /// Serialize the input into the buffer passed to `entrypoint`.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        self.write_fields(|_, bytes| buf.extend_from_slice(bytes));
+        buf
+    }
+
+    /// Get the layout of all fields of the serialized input, in the order of
+    /// their offsets. Padding bytes are not part of any field.
+    pub fn get_layout(&self) -> Vec<SolanaFieldLayout> {
+        let mut layout = vec![];
+        let mut offset = 0;
+        self.write_fields(|field, bytes| {
+            if let Some(field) = field {
+                layout.push(SolanaFieldLayout {
+                    field,
+                    offset,
+                    size: bytes.len(),
+                });
+            }
+            offset += bytes.len();
+        });
+        layout
+    }
+
+    /// Deserialize an input from the buffer passed to `entrypoint`.
+    ///
+    /// Return `None` if the buffer is not a well-formed input.
+    pub fn deserialize(buf: &[u8]) -> Option<Self> {
+        let mut reader = Reader { buf, offset: 0 };
+        let mut accounts = vec![];
+
+        let num_accounts = reader.read_u64()?;
+        for _ in 0..num_accounts {
+            let dup_marker = reader.read_bytes(1)?[0];
+            if dup_marker != NON_DUP_MARKER {
+                reader.read_bytes(7)?;
+                accounts.push(SolanaAccountEntry::Duplicate(dup_marker));
+                continue;
+            }
+
+            let flags = reader.read_bytes(3)?;
+            let (is_signer, is_writable, executable) =
+                (flags[0] != 0, flags[1] != 0, flags[2] != 0);
+            reader.read_bytes(4)?;
+            let key = reader.read_pubkey()?;
+            let owner = reader.read_pubkey()?;
+            let lamports = reader.read_u64()?;
+            let data_len = reader.read_u64()? as usize;
+            let data = reader.read_bytes(data_len)?.to_vec();
+            reader.read_bytes(MAX_PERMITTED_DATA_INCREASE)?;
+            reader.read_bytes(get_alignment_padding(data_len))?;
+            let rent_epoch = reader.read_u64()?;
+
+            accounts.push(SolanaAccountEntry::Account(SolanaAccount {
+                key,
+                owner,
+                lamports,
+                data,
+                is_signer,
+                is_writable,
+                executable,
+                rent_epoch,
+            }))
+        }
+
+        let data_len = reader.read_u64()? as usize;
+        let instruction_data = reader.read_bytes(data_len)?.to_vec();
+        let program_id = reader.read_pubkey()?;
+
+        Some(SolanaInput::new(accounts, instruction_data, program_id))
+    }
+
+    /// Write fields of the serialized input in order, including padding
+    /// bytes, which are written with the field `None`.
+    fn write_fields<F>(&self, mut write: F)
+    where
+        F: FnMut(Option<SolanaInputField>, &[u8]),
+    {
+        let num_accounts = self.accounts.len() as u64;
+        write(
+            Some(SolanaInputField::NumAccounts),
+            &num_accounts.to_le_bytes(),
+        );
+
+        for (i, entry) in self.accounts.iter().enumerate() {
+            let account = match entry {
+                SolanaAccountEntry::Duplicate(idx) => {
+                    write(Some(SolanaInputField::DupMarker(i)), &[*idx]);
+                    write(None, &[0; 7]);
+                    continue;
+                }
+                SolanaAccountEntry::Account(account) => account,
+            };
+
+            write(Some(SolanaInputField::DupMarker(i)), &[NON_DUP_MARKER]);
+            write(
+                Some(SolanaInputField::IsSigner(i)),
+                &[account.is_signer as u8],
+            );
+            write(
+                Some(SolanaInputField::IsWritable(i)),
+                &[account.is_writable as u8],
+            );
+            write(
+                Some(SolanaInputField::Executable(i)),
+                &[account.executable as u8],
+            );
+            write(None, &[0; 4]);
+            write(Some(SolanaInputField::Key(i)), &account.key);
+            write(Some(SolanaInputField::Owner(i)), &account.owner);
+            write(
+                Some(SolanaInputField::Lamports(i)),
+                &account.lamports.to_le_bytes(),
+            );
+            let data_len = account.data.len();
+            write(
+                Some(SolanaInputField::DataLen(i)),
+                &(data_len as u64).to_le_bytes(),
+            );
+            write(Some(SolanaInputField::Data(i)), &account.data);
+            write(None, &vec![0; MAX_PERMITTED_DATA_INCREASE]);
+            write(None, &vec![0; get_alignment_padding(data_len)]);
+            write(
+                Some(SolanaInputField::RentEpoch(i)),
+                &account.rent_epoch.to_le_bytes(),
+            );
+        }
+
+        let data_len = self.instruction_data.len() as u64;
+        write(
+            Some(SolanaInputField::InstructionDataLen),
+            &data_len.to_le_bytes(),
+        );
+        write(
+            Some(SolanaInputField::InstructionData),
+            &self.instruction_data,
+        );
+        write(Some(SolanaInputField::ProgramId), &self.program_id);
+    }
+}
+
+/// Get the `entrypoint` function of a Solana program module, if any.
+pub fn get_solana_entrypoint<'ctx>(
+    module: &Module<'ctx>,
+) -> Option<FunctionValue<'ctx>> {
+    module
+        .get_function(solang_ewasm_lib::ENTRYPOINT)
+        .filter(|func| func.count_params() == 1)
+}
+
+/// Get the number of padding bytes aligning account data to 8 bytes.
+fn get_alignment_padding(data_len: usize) -> usize {
+    (8 - data_len % 8) % 8
+}
+
+/// Data structure reading a serialized input.
+struct Reader<'a> {
+    /// Serialized input.
+    buf: &'a [u8],
+
+    /// Offset of the next byte to read.
+    offset: usize,
+}
+
+/// Implement methods for `Reader`.
+impl<'a> Reader<'a> {
+    /// Read a number of bytes.
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.buf.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    /// Read a little-endian `u64`.
+    fn read_u64(&mut self) -> Option<u64> {
+        let bytes = self.read_bytes(8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Read a public key.
+    fn read_pubkey(&mut self) -> Option<[u8; PUBKEY_SIZE]> {
+        self.read_bytes(PUBKEY_SIZE)?.try_into().ok()
+    }
+}
@@ -1,20 +1,50 @@
 //! Module provide additional utilities to handle LLVM `AnyValueEnum`.
 
-use inkwell::values::AnyValueEnum;
+use inkwell::values::{AnyValue, AnyValueEnum, InstructionValue};
 
 use super::{
+    use_iter::{self, UseIter, UserIter},
     ArrayExt, FloatExt, FunctionExt, InstructionExt, IntExt, MetadataExt,
     PointerExt, StructExt, VectorExt,
 };
 
 /// Trait providing additional functions to handle `AnyValueEnum`.
-pub trait AnyValueExt {
+pub trait AnyValueExt<'ctx> {
     /// Get name of the `AnyValueEnum` or return a default name.
     fn get_name_or_default(&self) -> String;
+
+    /// Get all users of the current `AnyValueEnum`, in LLVM's use-list order.
+    ///
+    /// The use-list order is not stable across runs, use `get_users_sorted`
+    /// if a deterministic order is needed.
+    fn get_users(&self) -> Vec<AnyValueEnum<'ctx>>;
+
+    /// Get all users of the current `AnyValueEnum` in a deterministic order.
+    ///
+    /// Instruction users are sorted by their positions, i.e., the name of
+    /// their parent function, then the index of their parent block, then their
+    /// index in the block. Other users, such as constant expressions, follow
+    /// and are sorted by their textual representations.
+    fn get_users_sorted(&self) -> Vec<AnyValueEnum<'ctx>>;
+
+    /// Iterate lazily over the uses of the current `AnyValueEnum`, in LLVM's
+    /// use-list order.
+    fn uses(&self) -> UseIter<'ctx>;
+
+    /// Iterate lazily over the users of the current `AnyValueEnum`, in LLVM's
+    /// use-list order, one item for each use.
+    fn users(&self) -> UserIter<'ctx>;
+
+    /// Check if the current `AnyValueEnum` has exactly one use.
+    fn has_single_use(&self) -> bool;
+
+    /// Get the unique user of the current `AnyValueEnum`, which may use it
+    /// several times.
+    fn get_unique_user(&self) -> Option<AnyValueEnum<'ctx>>;
 }
 
 /// Implement the trait `AnyValueExt` for `AnyValueEnum`.
-impl<'ctx> AnyValueExt for AnyValueEnum<'ctx> {
+impl<'ctx> AnyValueExt<'ctx> for AnyValueEnum<'ctx> {
     fn get_name_or_default(&self) -> String {
         match self {
             AnyValueEnum::ArrayValue(v) => v.get_name_or_default(),
@@ -28,4 +58,75 @@ impl<'ctx> AnyValueExt for AnyValueEnum<'ctx> {
             AnyValueEnum::MetadataValue(v) => v.get_name_or_default(),
         }
     }
+
+    fn get_users(&self) -> Vec<AnyValueEnum<'ctx>> {
+        self.users().collect()
+    }
+
+    fn get_users_sorted(&self) -> Vec<AnyValueEnum<'ctx>> {
+        let mut users = self
+            .get_users()
+            .into_iter()
+            .map(|user| (get_user_position(user), user))
+            .collect::<Vec<_>>();
+
+        users.sort_by(|(pos1, _), (pos2, _)| pos1.cmp(pos2));
+        users.into_iter().map(|(_, user)| user).collect()
+    }
+
+    fn uses(&self) -> UseIter<'ctx> {
+        UseIter::new(self.get_first_use())
+    }
+
+    fn users(&self) -> UserIter<'ctx> {
+        self.uses().users()
+    }
+
+    fn has_single_use(&self) -> bool {
+        use_iter::has_single_use(self.uses())
+    }
+
+    fn get_unique_user(&self) -> Option<AnyValueEnum<'ctx>> {
+        use_iter::get_unique_user(self.users())
+    }
+}
+
+/// Data structure representing the position of a user, used to sort users.
+///
+/// Instruction positions are ordered before other positions.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum UserPosition {
+    /// Position of an instruction: the parent function name, the parent block
+    /// index and the instruction index in the block.
+    Instruction(String, usize, usize),
+
+    /// Position of a non-instruction user, represented by its text.
+    Other(String),
+}
+
+/// Compute the position of a user.
+fn get_user_position(user: AnyValueEnum) -> UserPosition {
+    match user.into_instruction() {
+        Some(inst) => {
+            let (func_name, blk_idx, inst_idx) = get_instruction_position(inst);
+            UserPosition::Instruction(func_name, blk_idx, inst_idx)
+        }
+        None => UserPosition::Other(user.print_to_string()),
+    }
+}
+
+/// Compute the position of an instruction, including its parent function name,
+/// its parent block index and its index in the block.
+fn get_instruction_position(inst: InstructionValue) -> (String, usize, usize) {
+    let func = inst.get_parent().and_then(|blk| blk.get_parent());
+
+    match func {
+        Some(func) => match func.get_instruction_position(inst, None) {
+            Some((blk_idx, inst_idx)) => {
+                (func.get_name_or_default(), blk_idx, inst_idx)
+            }
+            None => (func.get_name_or_default(), 0, inst.get_index_in_block()),
+        },
+        None => (String::new(), 0, inst.get_index_in_block()),
+    }
 }
@@ -1,6 +1,7 @@
 //! Module provide additional utilities to handle LLVM `BasicBlock`.
 
 use super::{
+    pretty_print::{print_ellipsis, PrettyPrintOptions},
     AnyTerminator, InstructionExt, PhiNode, PredecessorBlock, SuccessorBlock,
     TerminatorInst,
 };
@@ -15,9 +16,20 @@ pub trait BasicBlockExt<'ctx> {
     /// Get name of the `BasicBlock` or return a default name.
     fn get_name_or_default(&self) -> String;
 
+    /// Print the header of the `BasicBlock`, containing its name and the names
+    /// of its predecessors and successors.
+    fn print_header(&self) -> String;
+
     /// Print the `BasicBlock` to string in a pretty format.
+    ///
+    /// The output starts with a header containing the block name and the names
+    /// of its predecessors and successors, followed by indented instructions.
     fn print_pretty(&self) -> String;
 
+    /// Print the `BasicBlock` to string in a pretty format, truncating its
+    /// instructions according to `options`.
+    fn print_pretty_with(&self, options: &PrettyPrintOptions) -> String;
+
     /// Get all the Phi instructions of the current `BasicBlock`.
     ///
     /// By LLVM IR formatl, all Phi instructions must be located at the top of
@@ -34,6 +46,18 @@ pub trait BasicBlockExt<'ctx> {
     /// A successor block is the block that the current block jumps to.
     fn get_successors(&self) -> Vec<BasicBlock<'ctx>>;
 
+    /// Count predecessor blocks of the current `BasicBlock`.
+    ///
+    /// This is cheaper than `get_predecessors` since it does not collect the
+    /// predecessor blocks.
+    fn count_predecessors(&self) -> usize;
+
+    /// Count successor blocks of the current `BasicBlock`.
+    ///
+    /// This is cheaper than `get_successors` since it does not collect the
+    /// successor blocks.
+    fn count_successors(&self) -> usize;
+
     /// Get predecessor blocks of the current `BasicBlock` and their path
     /// conditions.
     fn get_conditioned_predecessors(self) -> Vec<PredecessorBlock<'ctx>>;
@@ -51,11 +75,31 @@ impl<'ctx> BasicBlockExt<'ctx> for BasicBlock<'ctx> {
         }
     }
 
+    fn print_header(&self) -> String {
+        let mut predecessors = self.get_predecessors();
+        predecessors.dedup();
+
+        format!(
+            "{}:  ; preds = {}, succs = {}",
+            self.get_name_or_default(),
+            predecessors.print_block_names(),
+            self.get_successors().print_block_names()
+        )
+    }
+
     fn print_pretty(&self) -> String {
-        let mut res = self.get_name_or_default() + ":";
+        self.print_pretty_with(&PrettyPrintOptions::default())
+    }
+
+    fn print_pretty_with(&self, options: &PrettyPrintOptions) -> String {
+        let mut res = self.print_header();
+
+        let insts = self.get_instructions();
+        let num_insts = insts.len();
+        let max_insts = options.max_insts_per_block.unwrap_or(num_insts);
 
         // Print each instruction of the block
-        for inst in self.get_instructions() {
+        for inst in insts.into_iter().take(max_insts) {
             res += "\n";
             let sinst = format!("{inst}");
 
@@ -66,6 +110,12 @@ impl<'ctx> BasicBlockExt<'ctx> for BasicBlock<'ctx> {
             }
         }
 
+        if num_insts > max_insts {
+            res += "\n";
+            res +=
+                &print_ellipsis(num_insts - max_insts, "instruction").indent(2);
+        }
+
         res
     }
 
@@ -116,6 +166,32 @@ impl<'ctx> BasicBlockExt<'ctx> for BasicBlock<'ctx> {
         vec![]
     }
 
+    fn count_predecessors(&self) -> usize {
+        let mut count = 0;
+        let mut use_ = self.get_first_use();
+
+        while let Some(value_use) = use_ {
+            let user = value_use.get_user();
+            if user.is_instruction_value()
+                && user.into_instruction_value().get_parent().is_some()
+            {
+                count += 1;
+            }
+            use_ = value_use.get_next_use()
+        }
+
+        count
+    }
+
+    fn count_successors(&self) -> usize {
+        if let Some(inst) = self.get_terminator() {
+            if let Some(term_inst) = inst.try_into_terminator_inst() {
+                return term_inst.get_num_successors() as usize;
+            }
+        }
+        0
+    }
+
     fn get_conditioned_predecessors(self) -> Vec<PredecessorBlock<'ctx>> {
         let mut predecessors = vec![];
         let mut self_use = self.get_first_use();
@@ -0,0 +1,159 @@
+//! Module providing traversal orders of basic blocks of a function.
+//!
+//! All traversals start from the entry block of a function and visit only
+//! the blocks reachable from it. Successors are visited in the order of
+//! their appearances in the terminator instruction, so that every order is
+//! deterministic.
+
+use std::collections::{HashSet, VecDeque};
+
+use inkwell::values::{BasicBlock, FunctionValue};
+
+use super::basic_block::BasicBlockExt;
+
+/// Iterator over basic blocks of a function in depth-first pre-order.
+#[derive(Debug, Clone)]
+pub struct PreOrderIter<'ctx> {
+    /// Blocks to be visited, the next one on the top.
+    stack: Vec<BasicBlock<'ctx>>,
+
+    /// Blocks already visited.
+    visited: HashSet<BasicBlock<'ctx>>,
+}
+
+/// Implement methods for `PreOrderIter`.
+impl<'ctx> PreOrderIter<'ctx> {
+    /// Constructor
+    pub fn new(func: FunctionValue<'ctx>) -> Self {
+        PreOrderIter {
+            stack: func.get_first_basic_block().into_iter().collect(),
+            visited: HashSet::new(),
+        }
+    }
+}
+
+/// Implement the trait `Iterator` for `PreOrderIter`.
+impl<'ctx> Iterator for PreOrderIter<'ctx> {
+    type Item = BasicBlock<'ctx>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(blk) = self.stack.pop() {
+            if !self.visited.insert(blk) {
+                continue;
+            }
+
+            // Push successors in reverse order to visit the first one first.
+            for succ in blk.get_successors().into_iter().rev() {
+                if !self.visited.contains(&succ) {
+                    self.stack.push(succ)
+                }
+            }
+
+            return Some(blk);
+        }
+
+        None
+    }
+}
+
+/// Iterator over basic blocks of a function in breadth-first order.
+#[derive(Debug, Clone)]
+pub struct BreadthFirstIter<'ctx> {
+    /// Blocks to be visited, the next one at the front.
+    queue: VecDeque<BasicBlock<'ctx>>,
+
+    /// Blocks already queued.
+    queued: HashSet<BasicBlock<'ctx>>,
+}
+
+/// Implement methods for `BreadthFirstIter`.
+impl<'ctx> BreadthFirstIter<'ctx> {
+    /// Constructor
+    pub fn new(func: FunctionValue<'ctx>) -> Self {
+        let queue: VecDeque<BasicBlock> =
+            func.get_first_basic_block().into_iter().collect();
+        let queued = queue.iter().cloned().collect();
+        BreadthFirstIter { queue, queued }
+    }
+}
+
+/// Implement the trait `Iterator` for `BreadthFirstIter`.
+impl<'ctx> Iterator for BreadthFirstIter<'ctx> {
+    type Item = BasicBlock<'ctx>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let blk = self.queue.pop_front()?;
+
+        for succ in blk.get_successors() {
+            if self.queued.insert(succ) {
+                self.queue.push_back(succ)
+            }
+        }
+
+        Some(blk)
+    }
+}
+
+/// Iterate over basic blocks of a function in depth-first pre-order.
+pub fn iter_pre_order(func: FunctionValue) -> PreOrderIter {
+    PreOrderIter::new(func)
+}
+
+/// Iterate over basic blocks of a function in breadth-first order.
+pub fn iter_breadth_first(func: FunctionValue) -> BreadthFirstIter {
+    BreadthFirstIter::new(func)
+}
+
+/// Iterate over basic blocks of a function in depth-first post-order.
+pub fn iter_post_order(func: FunctionValue) -> std::vec::IntoIter<BasicBlock> {
+    compute_post_order(func).into_iter()
+}
+
+/// Iterate over basic blocks of a function in reverse post-order.
+pub fn iter_reverse_post_order(
+    func: FunctionValue,
+) -> std::vec::IntoIter<BasicBlock> {
+    compute_reverse_post_order(func).into_iter()
+}
+
+/// Compute the depth-first post-order of basic blocks reachable from the
+/// entry block of a function.
+pub fn compute_post_order<'ctx>(
+    func: FunctionValue<'ctx>,
+) -> Vec<BasicBlock<'ctx>> {
+    let entry_blk = match func.get_first_basic_block() {
+        Some(blk) => blk,
+        None => return vec![],
+    };
+
+    let mut post_order = vec![];
+    let mut visited = HashSet::new();
+    visited.insert(entry_blk);
+
+    // Each stack item is a block and the index of its next successor to visit.
+    let mut stack = vec![(entry_blk, entry_blk.get_successors(), 0)];
+
+    while let Some((blk, successors, idx)) = stack.last_mut() {
+        if let Some(succ) = successors.get(*idx).cloned() {
+            *idx += 1;
+            if visited.insert(succ) {
+                stack.push((succ, succ.get_successors(), 0));
+            }
+        } else {
+            post_order.push(*blk);
+            stack.pop();
+        }
+    }
+
+    post_order
+}
+
+/// Compute the reverse post-order of basic blocks reachable from the entry
+/// block of a function.
+pub fn compute_reverse_post_order<'ctx>(
+    func: FunctionValue<'ctx>,
+) -> Vec<BasicBlock<'ctx>> {
+    let mut order = compute_post_order(func);
+    order.reverse();
+    order
+}
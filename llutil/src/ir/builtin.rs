@@ -1,4 +1,98 @@
 //! Module implementing a list of library functions
+//!
+//! Names of built-in functions are stored in tables embedded from
+//! `data/builtins.toml`. These tables can be extended at runtime via
+//! `Builtins::load_overrides`, so that newly supported library functions do
+//! not require a new release of this crate.
+
+use serde::Deserialize;
+use std::{collections::BTreeSet, fs, io, path::Path, sync::RwLock};
+
+/// Embedded tables of built-in functions.
+const BUILTINS_TABLE: &str = include_str!("../../data/builtins.toml");
+
+/// Built-in function tables in use, initialized from `BUILTINS_TABLE` on
+/// first access.
+static BUILTINS: RwLock<Option<Builtins>> = RwLock::new(None);
+
+/// Data structure representing tables of built-in function names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Builtins {
+    /// LLVM intrinsic functions.
+    pub llvm_intrinsics: BTreeSet<String>,
+
+    /// Main functions of C programs.
+    pub c_main_functions: BTreeSet<String>,
+
+    /// Library functions of the C language.
+    pub c_lib_functions: BTreeSet<String>,
+
+    /// Name prefixes of assertion checking functions.
+    pub assertion_prefixes: BTreeSet<String>,
+
+    /// Library functions of Solidity generated by the Solang compiler to
+    /// EWASM target.
+    pub solang_ewasm_lib_functions: BTreeSet<String>,
+
+    /// Entry functions of Solidity programs compiled by Solang.
+    pub solang_main_functions: BTreeSet<String>,
+}
+
+/// Implement methods for `Builtins`.
+impl Builtins {
+    /// Parse tables of built-in functions from a TOML string.
+    pub fn parse(content: &str) -> io::Result<Self> {
+        toml::from_str(content)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Get the tables of built-in functions embedded in this crate.
+    pub fn embedded() -> Self {
+        match Builtins::parse(BUILTINS_TABLE) {
+            Ok(builtins) => builtins,
+            Err(err) => panic!("Invalid embedded builtin tables: {}", err),
+        }
+    }
+
+    /// Extend the current tables by entries of other tables.
+    pub fn extend(&mut self, other: Builtins) {
+        self.llvm_intrinsics.extend(other.llvm_intrinsics);
+        self.c_main_functions.extend(other.c_main_functions);
+        self.c_lib_functions.extend(other.c_lib_functions);
+        self.assertion_prefixes.extend(other.assertion_prefixes);
+        self.solang_ewasm_lib_functions
+            .extend(other.solang_ewasm_lib_functions);
+        self.solang_main_functions
+            .extend(other.solang_main_functions);
+    }
+
+    /// Load a TOML file of the same format as the embedded tables, and add its
+    /// entries to the built-in function tables in use.
+    pub fn load_overrides<P: AsRef<Path>>(path: P) -> io::Result<()> {
+        let overrides = Builtins::parse(&fs::read_to_string(path)?)?;
+        let mut builtins = BUILTINS.write().unwrap();
+        builtins
+            .get_or_insert_with(Builtins::embedded)
+            .extend(overrides);
+        Ok(())
+    }
+
+    /// Reset the built-in function tables in use to the embedded tables.
+    pub fn reset() {
+        *BUILTINS.write().unwrap() = Some(Builtins::embedded());
+    }
+
+    /// Apply a function on the built-in function tables in use.
+    pub fn with<T, F: FnOnce(&Builtins) -> T>(f: F) -> T {
+        if let Some(builtins) = BUILTINS.read().unwrap().as_ref() {
+            return f(builtins);
+        }
+
+        let mut builtins = BUILTINS.write().unwrap();
+        f(builtins.get_or_insert_with(Builtins::embedded))
+    }
+}
 
 /// Module containing built-in names of LLVM functions.
 #[allow(missing_docs)]
@@ -7,6 +101,9 @@ pub mod llvm_lib {
     pub const LLVM_DBG_ADDR: &str = "llvm.dbg.addr";
     pub const LLVM_DBG_DECLARE: &str = "llvm.dbg.declare";
     pub const LLVM_DBG_VALUE: &str = "llvm.dbg.value";
+    pub const LLVM_MEMCPY: &str = "llvm.memcpy";
+    pub const LLVM_MEMMOVE: &str = "llvm.memmove";
+    pub const LLVM_MEMSET: &str = "llvm.memset";
 }
 
 /// Module containing built-in library function names of the C language.
@@ -33,21 +130,6 @@ pub mod cmain {
     pub const MAIN: &str = "main";
 }
 
-/// List of all considered C library functions.
-pub const C_LIB_FUNCS: &[&str] = &[
-    c_lib::ISOC99_FSCANF,
-    c_lib::ISOC99_SSCANF,
-    c_lib::ISWXDIGIT,
-    c_lib::ISOC99_SWSCANF,
-    c_lib::PRINTF,
-    c_lib::PUTS,
-    c_lib::RAND,
-    c_lib::SRAND,
-    c_lib::TIME,
-    c_lib::WPRINTF,
-    c_lib::CTYPE_B_LOC,
-];
-
 /// Module containing built-in names of assertion functions.
 #[allow(missing_docs)]
 #[allow(clippy::missing_docs_in_private_items)]
@@ -68,10 +150,59 @@ pub mod assertion_lib {
     pub const REFUTE_MAY_ALIAS: &str = "__refute_may_alias";
     pub const REFUTE_NO_ALIAS: &str = "__refute_no_alias";
 
+    // Nondeterministic values
+    pub const NONDET_I32: &str = "__nondet_i32";
+    pub const NONDET_I64: &str = "__nondet_i64";
+    pub const NONDET_I256: &str = "__nondet_i256";
+    pub const NONDET_PTR: &str = "__nondet_ptr";
+
+    // Bug annotations
+    pub const PREFIX_ASSERT_BUG: &str = "__assert_bug_";
+
     // General prefixes
     pub const PREFIX_ASSERT: &str = "__assert_";
     pub const PREFIX_REFUTE: &str = "__refute_";
     pub const PREFIX_ASSUME: &str = "__assume_";
+    pub const PREFIX_NONDET: &str = "__nondet_";
+    pub const PREFIX_COVERAGE: &str = "__coverage_";
+}
+
+/// Kinds of runtime library functions of Verazt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VeraztFunctionKind {
+    /// Assertion functions, prefixed by `__assert_`.
+    Assert,
+
+    /// Assumption functions, prefixed by `__assume_`.
+    Assume,
+
+    /// Coverage instrumentation functions, prefixed by `__coverage_`.
+    Coverage,
+
+    /// Functions returning nondeterministic values, prefixed by `__nondet_`.
+    Nondet,
+
+    /// Refutation functions, prefixed by `__refute_`.
+    Refute,
+}
+
+/// Catalogue of name prefixes of Verazt runtime library functions.
+pub const VERAZT_LIB_PREFIXES: &[(&str, VeraztFunctionKind)] = &[
+    (assertion_lib::PREFIX_ASSERT, VeraztFunctionKind::Assert),
+    (assertion_lib::PREFIX_ASSUME, VeraztFunctionKind::Assume),
+    (assertion_lib::PREFIX_COVERAGE, VeraztFunctionKind::Coverage),
+    (assertion_lib::PREFIX_NONDET, VeraztFunctionKind::Nondet),
+    (assertion_lib::PREFIX_REFUTE, VeraztFunctionKind::Refute),
+];
+
+/// Classify a Verazt runtime library function by its name.
+///
+/// Return `None` if the function is not a Verazt runtime library function.
+pub fn classify_verazt_function(func_name: &str) -> Option<VeraztFunctionKind> {
+    VERAZT_LIB_PREFIXES
+        .iter()
+        .find(|(prefix, _)| func_name.starts_with(prefix))
+        .map(|(_, kind)| *kind)
 }
 
 /// Module containing built-in names of Solidity library functions generated by
@@ -174,136 +305,54 @@ pub mod solang_ewasm_lib {
     pub const VECTOR_NEW: &str = "vector_new";
 }
 
-/// List of all considered Solidity library functions generated by the Solang
-/// compiler to EWASM target.
-pub const SOLANG_WASM_LIB_FUNCS: &[&str] = &[
-    solang_ewasm_lib::ACCOUNT_DATA_ALLOC,
-    solang_ewasm_lib::ACCOUNT_DATA_FREE,
-    solang_ewasm_lib::ACCOUNT_DATA_LEN,
-    solang_ewasm_lib::ACCOUNT_DATA_REALLOC,
-    solang_ewasm_lib::ADDRESS_EQUAL,
-    solang_ewasm_lib::ADDRESS_HASH,
-    solang_ewasm_lib::ASHLTI3,
-    solang_ewasm_lib::ASHRTI3,
-    solang_ewasm_lib::BE32TOLEN,
-    solang_ewasm_lib::BENTOLEN,
-    solang_ewasm_lib::BITS,
-    solang_ewasm_lib::BITS128,
-    solang_ewasm_lib::BITS256,
-    solang_ewasm_lib::BITS512,
-    solang_ewasm_lib::BZERO8,
-    solang_ewasm_lib::CALL,
-    solang_ewasm_lib::CALLDATACOPY,
-    solang_ewasm_lib::CALLDELEGATE,
-    solang_ewasm_lib::CALLSTATIC,
-    solang_ewasm_lib::CODECOPY,
-    solang_ewasm_lib::CONCAT,
-    solang_ewasm_lib::CREATE,
-    solang_ewasm_lib::CREATE_CONTRACT,
-    solang_ewasm_lib::ENTRYPOINT,
-    solang_ewasm_lib::EXTERNAL_CALL,
-    solang_ewasm_lib::FINISH,
-    solang_ewasm_lib::FREE,
-    solang_ewasm_lib::GETADDRESS,
-    solang_ewasm_lib::GETBLOCKCOINBASE,
-    solang_ewasm_lib::GETBLOCKDIFFICULTY,
-    solang_ewasm_lib::GETBLOCKGASLIMIT,
-    solang_ewasm_lib::GETBLOCKHASH,
-    solang_ewasm_lib::GETBLOCKNUMBER,
-    solang_ewasm_lib::GETBLOCKTIMESTAMP,
-    solang_ewasm_lib::GETCALLDATASIZE,
-    solang_ewasm_lib::GETCALLER,
-    solang_ewasm_lib::GETCALLVALUE,
-    solang_ewasm_lib::GETCODESIZE,
-    solang_ewasm_lib::GETEXTERNALBALANCE,
-    solang_ewasm_lib::GETEXTERNALCODESIZE,
-    solang_ewasm_lib::GETGASLEFT,
-    solang_ewasm_lib::GETRETURNDATASIZE,
-    solang_ewasm_lib::GETTXGASPRICE,
-    solang_ewasm_lib::GETTXORIGIN,
-    solang_ewasm_lib::HEX_ENCODE,
-    solang_ewasm_lib::HEX_ENCODE_REV,
-    solang_ewasm_lib::INIT_HEAP,
-    solang_ewasm_lib::LENTOBE32,
-    solang_ewasm_lib::LENTOBEN,
-    solang_ewasm_lib::LLVM_ASSUME,
-    solang_ewasm_lib::LLVM_UADD_WITH_OVERFLOW_I64,
-    solang_ewasm_lib::LLVM_WASM_MEMORY_SIZE_I32,
-    solang_ewasm_lib::LOG,
-    solang_ewasm_lib::LSHRTI3,
-    solang_ewasm_lib::MALLOC,
-    solang_ewasm_lib::MEMCMP,
-    solang_ewasm_lib::MEMCMP_ORD,
-    solang_ewasm_lib::MEMCPY,
-    solang_ewasm_lib::MEMCPY8,
-    solang_ewasm_lib::MEMSET,
-    solang_ewasm_lib::MEMSET8,
-    solang_ewasm_lib::MUL32,
-    solang_ewasm_lib::PRINTMEM,
-    solang_ewasm_lib::REALLOC,
-    solang_ewasm_lib::RETURNDATACOPY,
-    solang_ewasm_lib::REVERT,
-    solang_ewasm_lib::RIPEMD160,
-    solang_ewasm_lib::RIPEMD160_COMPRESS,
-    solang_ewasm_lib::SDIVMOD128,
-    solang_ewasm_lib::SDIVMOD256,
-    solang_ewasm_lib::SDIVMOD512,
-    solang_ewasm_lib::SELFDESTRUC,
-    solang_ewasm_lib::SHL128,
-    solang_ewasm_lib::SHR128,
-    solang_ewasm_lib::SIGNATURE_VERIFY,
-    solang_ewasm_lib::SOLANG_DISPATCH,
-    solang_ewasm_lib::SOLPUBKEY_SAME,
-    solang_ewasm_lib::SOL_ACCOUNT_LAMPORT,
-    solang_ewasm_lib::SOL_CLOCK,
-    solang_ewasm_lib::SOL_TRANSFER,
-    solang_ewasm_lib::SOL_TRY_TRANSFER,
-    solang_ewasm_lib::STORAGELOAD,
-    solang_ewasm_lib::STORAGESTORE,
-    solang_ewasm_lib::UDIVMOD128,
-    solang_ewasm_lib::UDIVMOD256,
-    solang_ewasm_lib::UDIVMOD512,
-    solang_ewasm_lib::UINT128DEC,
-    solang_ewasm_lib::UINT256DEC,
-    solang_ewasm_lib::UINT2BIN,
-    solang_ewasm_lib::UINT2DEC,
-    solang_ewasm_lib::UINT2HEX,
-    solang_ewasm_lib::VECTOR_HASH,
-    solang_ewasm_lib::VECTOR_NEW,
-];
-
 /// Check whether a function is a library function of C code.
 pub fn is_c_library_function(func_name: &str) -> bool {
-    C_LIB_FUNCS.contains(&func_name)
+    Builtins::with(|builtins| builtins.c_lib_functions.contains(func_name))
 }
 
 /// Check whether a function is a main function of C code.
 pub fn is_c_main_function(func_name: &str) -> bool {
-    func_name.eq(cmain::MAIN)
+    Builtins::with(|builtins| builtins.c_main_functions.contains(func_name))
 }
 
 /// Check whether a function is a Solang-generated library function of Solidity.
 pub fn is_solidity_library_function(func_name: &str) -> bool {
-    SOLANG_WASM_LIB_FUNCS.contains(&func_name)
+    Builtins::with(|builtins| {
+        builtins.solang_ewasm_lib_functions.contains(func_name)
+    })
 }
 
 /// Check whether a function is a entry function of Solidity.
 pub fn is_solang_main_function(func_name: &str) -> bool {
-    func_name.eq(cmain::MAIN)
+    Builtins::with(|builtins| {
+        builtins.solang_main_functions.contains(func_name)
+    })
 }
 
 /// Check whether a function is an LLVM intrinsic function.
 pub fn is_llvm_intrinsic_function(func_name: &str) -> bool {
-    func_name.eq(llvm_lib::LLVM_DBG_ADDR)
-        || func_name.eq(llvm_lib::LLVM_DBG_DECLARE)
-        || func_name.eq(llvm_lib::LLVM_DBG_VALUE)
+    Builtins::with(|builtins| builtins.llvm_intrinsics.contains(func_name))
 }
 
 /// Check whether a function is a built-in function of Verazt.
 pub fn is_assertion_checking_function(func_name: &str) -> bool {
-    func_name.starts_with(assertion_lib::PREFIX_ASSERT)
-        || func_name.starts_with(assertion_lib::PREFIX_REFUTE)
-        || func_name.starts_with(assertion_lib::PREFIX_ASSUME)
+    Builtins::with(|builtins| {
+        builtins
+            .assertion_prefixes
+            .iter()
+            .any(|prefix| func_name.starts_with(prefix.as_str()))
+    })
+}
+
+/// Check whether a function is a runtime library function of Verazt.
+pub fn is_verazt_library_function(func_name: &str) -> bool {
+    classify_verazt_function(func_name).is_some()
+}
+
+/// Check whether a function is a built-in function returning a
+/// nondeterministic value, e.g., `__nondet_i32`.
+pub fn is_nondet_function(func_name: &str) -> bool {
+    classify_verazt_function(func_name) == Some(VeraztFunctionKind::Nondet)
 }
 
 /// Check whether a function is `__assert_range`
@@ -0,0 +1,392 @@
+//! Module providing a precomputed control flow graph of a function.
+//!
+//! Querying predecessors of a `BasicBlock` walks its use list on every call.
+//! The `ControlFlowGraph` computes the edges of a function once and caches
+//! the predecessor and successor maps, so that repeated queries are answered
+//! by lookups. The graph must be invalidated, then recomputed or updated,
+//! whenever terminators of the function are modified.
+//!
+//! The graph can be exported to the GraphViz DOT format for debugging, where
+//! each node shows a block name and its terminator, and each edge shows its
+//! path condition.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use inkwell::values::{AnyValue, BasicBlock, BasicValueEnum, FunctionValue};
+
+use super::{basic_block::BasicBlockExt, PathCondition};
+
+/// Data structure representing an edge of a control flow graph.
+#[derive(Clone, Debug)]
+pub struct CfgEdge<'ctx> {
+    /// Source block of the edge.
+    pub source: BasicBlock<'ctx>,
+
+    /// Target block of the edge.
+    pub target: BasicBlock<'ctx>,
+
+    /// Path condition leading from the source to the target block.
+    pub condition: PathCondition<'ctx>,
+}
+
+/// Implement the trait `Display` for `CfgEdge`.
+impl<'ctx> Display for CfgEdge<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {} [{}]",
+            self.source.get_name_or_default(),
+            self.target.get_name_or_default(),
+            self.condition
+        )
+    }
+}
+
+/// Data structure representing the control flow graph of a function, with
+/// cached predecessor and successor maps.
+#[derive(Clone, Debug)]
+pub struct ControlFlowGraph<'ctx> {
+    /// The function of the graph.
+    function: FunctionValue<'ctx>,
+
+    /// Blocks of the function, in their layout order.
+    blocks: Vec<BasicBlock<'ctx>>,
+
+    /// Edges of the graph, in the order of their source blocks and
+    /// successor indices.
+    edges: Vec<CfgEdge<'ctx>>,
+
+    /// Successor blocks of each block.
+    successors: HashMap<BasicBlock<'ctx>, Vec<BasicBlock<'ctx>>>,
+
+    /// Predecessor blocks of each block.
+    predecessors: HashMap<BasicBlock<'ctx>, Vec<BasicBlock<'ctx>>>,
+
+    /// Indices of outgoing edges of each block.
+    outgoing_edges: HashMap<BasicBlock<'ctx>, Vec<usize>>,
+
+    /// Indices of incoming edges of each block.
+    incoming_edges: HashMap<BasicBlock<'ctx>, Vec<usize>>,
+
+    /// Whether the graph is consistent with the function.
+    valid: bool,
+}
+
+/// Implement methods for `ControlFlowGraph`.
+impl<'ctx> ControlFlowGraph<'ctx> {
+    /// Constructor, which computes the graph of a function.
+    pub fn new(function: FunctionValue<'ctx>) -> Self {
+        let mut cfg = ControlFlowGraph {
+            function,
+            blocks: vec![],
+            edges: vec![],
+            successors: HashMap::new(),
+            predecessors: HashMap::new(),
+            outgoing_edges: HashMap::new(),
+            incoming_edges: HashMap::new(),
+            valid: false,
+        };
+        cfg.recompute();
+        cfg
+    }
+
+    /// Get the function of the graph.
+    pub fn get_function(&self) -> FunctionValue<'ctx> {
+        self.function
+    }
+
+    /// Get blocks of the graph, in their layout order.
+    pub fn get_blocks(&self) -> &[BasicBlock<'ctx>] {
+        &self.blocks
+    }
+
+    /// Get the entry block of the graph, if any.
+    pub fn get_entry_block(&self) -> Option<BasicBlock<'ctx>> {
+        self.blocks.first().copied()
+    }
+
+    /// Get successors of a block.
+    pub fn get_successors(&self, blk: BasicBlock<'ctx>) -> &[BasicBlock<'ctx>] {
+        self.successors
+            .get(&blk)
+            .map(|blks| blks.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Get predecessors of a block.
+    pub fn get_predecessors(
+        &self,
+        blk: BasicBlock<'ctx>,
+    ) -> &[BasicBlock<'ctx>] {
+        self.predecessors
+            .get(&blk)
+            .map(|blks| blks.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Count successors of a block.
+    pub fn count_successors(&self, blk: BasicBlock<'ctx>) -> usize {
+        self.get_successors(blk).len()
+    }
+
+    /// Count predecessors of a block.
+    pub fn count_predecessors(&self, blk: BasicBlock<'ctx>) -> usize {
+        self.get_predecessors(blk).len()
+    }
+
+    /// Get outgoing edges of a block, with their path conditions.
+    pub fn get_conditioned_successors(
+        &self,
+        blk: BasicBlock<'ctx>,
+    ) -> Vec<&CfgEdge<'ctx>> {
+        self.get_edges_by_indices(self.outgoing_edges.get(&blk))
+    }
+
+    /// Get incoming edges of a block, with their path conditions.
+    pub fn get_conditioned_predecessors(
+        &self,
+        blk: BasicBlock<'ctx>,
+    ) -> Vec<&CfgEdge<'ctx>> {
+        self.get_edges_by_indices(self.incoming_edges.get(&blk))
+    }
+
+    /// Check if there is an edge from a block to another block.
+    pub fn has_edge(
+        &self,
+        source: BasicBlock<'ctx>,
+        target: BasicBlock<'ctx>,
+    ) -> bool {
+        self.get_successors(source).contains(&target)
+    }
+
+    /// Iterate over all edges of the graph.
+    pub fn edges(&self) -> std::slice::Iter<'_, CfgEdge<'ctx>> {
+        self.edges.iter()
+    }
+
+    /// Get the number of edges of the graph.
+    pub fn count_edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Check if the graph is consistent with its function, i.e., it was not
+    /// invalidated since its last computation.
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// Mark the graph as inconsistent with its function, e.g., after
+    /// terminators of the function are modified.
+    pub fn invalidate(&mut self) {
+        self.valid = false
+    }
+
+    /// Recompute the graph if it was invalidated.
+    ///
+    /// Return `true` if the graph is recomputed.
+    pub fn ensure_valid(&mut self) -> bool {
+        if self.valid {
+            return false;
+        }
+        self.recompute();
+        true
+    }
+
+    /// Recompute the whole graph from its function.
+    pub fn recompute(&mut self) {
+        self.blocks = self.function.get_basic_blocks();
+        self.edges = self
+            .blocks
+            .iter()
+            .flat_map(|blk| compute_outgoing_edges(*blk))
+            .collect();
+        self.rebuild_maps();
+        self.valid = true
+    }
+
+    /// Update outgoing edges of a block after its terminator is modified,
+    /// without recomputing edges of other blocks.
+    ///
+    /// Use `recompute` instead if blocks were added to or removed from the
+    /// function.
+    pub fn update_block(&mut self, blk: BasicBlock<'ctx>) {
+        let mut edges: Vec<CfgEdge> = self
+            .edges
+            .drain(..)
+            .filter(|edge| edge.source != blk)
+            .collect();
+        edges.extend(compute_outgoing_edges(blk));
+
+        // Keep edges in the order of their source blocks.
+        let positions: HashMap<BasicBlock, usize> = self
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(idx, blk)| (*blk, idx))
+            .collect();
+        edges.sort_by_key(|edge| positions.get(&edge.source).copied());
+
+        self.edges = edges;
+        self.rebuild_maps();
+        self.valid = true
+    }
+
+    /// Export the graph to the GraphViz DOT format.
+    pub fn to_dot(&self) -> String {
+        let positions: HashMap<BasicBlock, usize> = self
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(idx, blk)| (*blk, idx))
+            .collect();
+
+        let mut lines = vec![
+            format!(
+                "digraph \"{}\" {{",
+                escape_dot(&self.function.get_name().to_string_lossy())
+            ),
+            "  node [shape=box, fontname=\"monospace\"];".to_string(),
+        ];
+
+        for (idx, blk) in self.blocks.iter().enumerate() {
+            let name = match blk.get_name().to_str() {
+                Ok(name) if !name.is_empty() => name.to_string(),
+                _ => format!("<block {}>", idx),
+            };
+            let label = match blk.get_terminator() {
+                Some(term) => format!(
+                    "{}:\\l{}\\l",
+                    escape_dot(&name),
+                    escape_dot(term.print_to_string().to_string().trim())
+                ),
+                None => format!("{}:\\l", escape_dot(&name)),
+            };
+            lines.push(format!("  bb{} [label=\"{}\"];", idx, label));
+        }
+
+        for edge in &self.edges {
+            let (source, target) = match (
+                positions.get(&edge.source),
+                positions.get(&edge.target),
+            ) {
+                (Some(source), Some(target)) => (source, target),
+                _ => continue,
+            };
+            match print_dot_condition(&edge.condition) {
+                Some(cond) => lines.push(format!(
+                    "  bb{} -> bb{} [label=\"{}\"];",
+                    source,
+                    target,
+                    escape_dot(&cond)
+                )),
+                None => lines.push(format!("  bb{} -> bb{};", source, target)),
+            }
+        }
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Rebuild the predecessor and successor maps from the edges.
+    fn rebuild_maps(&mut self) {
+        self.successors.clear();
+        self.predecessors.clear();
+        self.outgoing_edges.clear();
+        self.incoming_edges.clear();
+
+        for (idx, edge) in self.edges.iter().enumerate() {
+            self.successors
+                .entry(edge.source)
+                .or_default()
+                .push(edge.target);
+            self.predecessors
+                .entry(edge.target)
+                .or_default()
+                .push(edge.source);
+            self.outgoing_edges
+                .entry(edge.source)
+                .or_default()
+                .push(idx);
+            self.incoming_edges
+                .entry(edge.target)
+                .or_default()
+                .push(idx);
+        }
+    }
+
+    /// Get edges by their indices.
+    fn get_edges_by_indices(
+        &self,
+        indices: Option<&Vec<usize>>,
+    ) -> Vec<&CfgEdge<'ctx>> {
+        match indices {
+            Some(indices) => {
+                indices.iter().map(|idx| &self.edges[*idx]).collect()
+            }
+            None => vec![],
+        }
+    }
+}
+
+/// Implement the trait `Display` for `ControlFlowGraph`.
+impl<'ctx> Display for ControlFlowGraph<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CFG of {}: {} blocks, {} edges",
+            self.function.get_name().to_string_lossy(),
+            self.blocks.len(),
+            self.edges.len()
+        )?;
+        for edge in &self.edges {
+            write!(f, "\n  {}", edge)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute outgoing edges of a block from its terminator.
+fn compute_outgoing_edges(blk: BasicBlock) -> Vec<CfgEdge> {
+    blk.get_conditioned_successors()
+        .into_iter()
+        .map(|sblk| CfgEdge {
+            source: blk,
+            target: sblk.block,
+            condition: sblk.condition,
+        })
+        .collect()
+}
+
+/// Print a path condition as a DOT edge label.
+///
+/// Return `None` if the edge is unconditional.
+fn print_dot_condition(condition: &PathCondition) -> Option<String> {
+    match condition {
+        PathCondition::None => None,
+        PathCondition::Boolean(v, true) => Some(print_value_ref(v)),
+        PathCondition::Boolean(v, false) => {
+            Some(format!("!{}", print_value_ref(v)))
+        }
+        PathCondition::Value(v, u) => {
+            Some(format!("{}={}", print_value_ref(v), print_value_ref(u)))
+        }
+    }
+}
+
+/// Print a short reference of a value, i.e., the name of an instruction
+/// result, or the textual representation of a constant.
+fn print_value_ref(value: &BasicValueEnum) -> String {
+    let text = value.print_to_string().to_string();
+    match text.split_once(" = ") {
+        Some((name, _)) => name.trim().to_string(),
+        None => text.trim().to_string(),
+    }
+}
+
+/// Escape a string to be used in a quoted DOT label.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
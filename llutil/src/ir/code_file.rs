@@ -1,6 +1,11 @@
-use std::fmt::{self, Display};
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    path::Path,
+};
 
 use inkwell::values::FunctionValue;
+use regex::Regex;
 
 use crate::file::FileType;
 
@@ -23,6 +28,43 @@ pub struct CodeFile {
 
     /// Names of entry functions of the current file.
     pub entry_point: EntryPoint,
+
+    /// Artifacts from which the current file is derived, from the raw input
+    /// file to the current file, by pipeline stages.
+    pub provenance: Vec<ProvenanceStep>,
+}
+
+/// Suffix of the metadata file persisting the provenance of a code file.
+pub const META_FILE_SUFFIX: &str = ".meta.json";
+
+/// Stages of the pipeline producing code files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PipelineStage {
+    /// Input file, as given by the user or compiled from source code.
+    Raw,
+
+    /// File linked from several input files.
+    Linked,
+
+    /// File optimized by LLVM passes.
+    Optimized,
+
+    /// File normalized before analysis.
+    Normalized,
+
+    /// File instrumented with assertions or other runtime checks.
+    Instrumented,
+}
+
+/// Data structure representing a step of the provenance of a code file, i.e.,
+/// an artifact produced by a pipeline stage.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProvenanceStep {
+    /// Stage producing the artifact.
+    pub stage: PipelineStage,
+
+    /// File name of the artifact.
+    pub file_name: String,
 }
 
 /// Data structure representing entry points of a program.
@@ -48,7 +90,124 @@ impl CodeFile {
             source_file_name: Some(file_name.to_owned()),
             source_file_type: Some(FileType::new(file_name)),
             entry_point,
+            provenance: vec![ProvenanceStep {
+                stage: PipelineStage::Raw,
+                file_name: file_name.to_owned(),
+            }],
+        }
+    }
+
+    /// Derive the code file of an artifact produced from the current file by
+    /// a pipeline stage, extending the provenance of the current file.
+    pub fn derive_from_file(
+        &self,
+        file_name: &str,
+        stage: PipelineStage,
+    ) -> Self {
+        let mut provenance = self.provenance.clone();
+        provenance.push(ProvenanceStep {
+            stage,
+            file_name: file_name.to_owned(),
+        });
+
+        CodeFile {
+            file_name: file_name.to_owned(),
+            file_type: FileType::new(file_name),
+            provenance,
+            ..(self.clone())
+        }
+    }
+
+    /// Get the stage producing the current file.
+    pub fn get_stage(&self) -> PipelineStage {
+        match self.provenance.last() {
+            Some(step) => step.stage,
+            None => PipelineStage::Raw,
+        }
+    }
+
+    /// Get the file name of the latest artifact produced by a stage in the
+    /// provenance of the current file, if any.
+    pub fn get_artifact(&self, stage: PipelineStage) -> Option<&str> {
+        self.provenance
+            .iter()
+            .rev()
+            .find(|step| step.stage == stage)
+            .map(|step| step.file_name.as_str())
+    }
+
+    /// Get the path of the metadata file of the current file.
+    pub fn get_meta_file_name(&self) -> String {
+        format!("{}{}", self.file_name, META_FILE_SUFFIX)
+    }
+
+    /// Save the provenance of the current file to its metadata file.
+    pub fn save_metadata(&self) -> io::Result<()> {
+        let steps: Vec<String> = self
+            .provenance
+            .iter()
+            .map(|step| {
+                format!(
+                    "    {{\"stage\": \"{}\", \"file\": \"{}\"}}",
+                    step.stage,
+                    escape_json(&step.file_name)
+                )
+            })
+            .collect();
+        let source_file = match &self.source_file_name {
+            Some(file_name) => format!("\"{}\"", escape_json(file_name)),
+            None => "null".to_string(),
+        };
+
+        let content = format!(
+            "{{\n  \"file\": \"{}\",\n  \"source_file\": {},\n  \
+             \"provenance\": [\n{}\n  ]\n}}\n",
+            escape_json(&self.file_name),
+            source_file,
+            steps.join(",\n")
+        );
+        fs::write(self.get_meta_file_name(), content)
+    }
+
+    /// Load the provenance of the current file from its metadata file, if it
+    /// exists, e.g., when the current file was produced by a previous run.
+    ///
+    /// Return `true` if the provenance is loaded.
+    pub fn load_metadata(&mut self) -> io::Result<bool> {
+        let meta_file = self.get_meta_file_name();
+        if !Path::new(&meta_file).is_file() {
+            return Ok(false);
+        }
+
+        let content = fs::read_to_string(&meta_file)?;
+        let regex =
+            Regex::new(r#"\{"stage": "(\w+)", "file": "((?:[^"\\]|\\.)*)"\}"#)
+                .unwrap();
+        let mut provenance = vec![];
+        for cap in regex.captures_iter(&content) {
+            let stage = match PipelineStage::from_name(&cap[1]) {
+                Some(stage) => stage,
+                None => {
+                    let msg = format!("Invalid pipeline stage: {}", &cap[1]);
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        msg,
+                    ));
+                }
+            };
+            provenance.push(ProvenanceStep {
+                stage,
+                file_name: unescape_json(&cap[2]),
+            });
+        }
+
+        if provenance.is_empty() {
+            let msg = format!("No provenance found in: {}", meta_file);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
         }
+
+        self.provenance = provenance;
+        Ok(true)
     }
 
     /// Obtain the source file information of the other file.
@@ -78,6 +237,7 @@ impl CodeFile {
     /// Check if a function is a library function of the current code file.
     pub fn check_library_function(&self, func: &FunctionValue) -> bool {
         self.check_assertion_function(func)
+            || self.check_verazt_library(func)
             || self.check_c_cpp_library(func)
             || self.check_solidity_library(func)
             || self.check_solang_generated_library(func)
@@ -104,6 +264,11 @@ impl CodeFile {
         func.is_assertion_checking_function()
     }
 
+    /// Check if a function is a Verazt runtime library function.
+    pub fn check_verazt_library(&self, func: &FunctionValue) -> bool {
+        func.is_verazt_library_function()
+    }
+
     /// Check if a function is a C/C++ main function of the current program.
     pub fn check_c_cpp_main_function(&self, func: &FunctionValue) -> bool {
         self.is_from_c_cpp() && func.is_c_cpp_main_function()
@@ -121,3 +286,61 @@ impl Display for CodeFile {
         write!(f, "{}", self.file_name)
     }
 }
+
+/// Implement methods for `PipelineStage`.
+impl PipelineStage {
+    /// Get the name of the stage.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            PipelineStage::Raw => "raw",
+            PipelineStage::Linked => "linked",
+            PipelineStage::Optimized => "optimized",
+            PipelineStage::Normalized => "normalized",
+            PipelineStage::Instrumented => "instrumented",
+        }
+    }
+
+    /// Get a stage by its name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "raw" => Some(PipelineStage::Raw),
+            "linked" => Some(PipelineStage::Linked),
+            "optimized" => Some(PipelineStage::Optimized),
+            "normalized" => Some(PipelineStage::Normalized),
+            "instrumented" => Some(PipelineStage::Instrumented),
+            _ => None,
+        }
+    }
+}
+
+/// Implement trait `Display` for `PipelineStage`.
+impl Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_name())
+    }
+}
+
+/// Implement trait `Display` for `ProvenanceStep`.
+impl Display for ProvenanceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.file_name, self.stage)
+    }
+}
+
+/// Escape a string to write it in a JSON string literal.
+fn escape_json(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Unescape a string read from a JSON string literal.
+fn unescape_json(text: &str) -> String {
+    let mut res = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => res.extend(chars.next()),
+            _ => res.push(c),
+        }
+    }
+    res
+}
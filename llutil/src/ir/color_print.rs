@@ -0,0 +1,182 @@
+//! Module providing an ANSI-colored printer of LLVM IR for terminals.
+//!
+//! Opcodes, values, types, constants, metadata and block labels are printed
+//! in distinct colors, and a given instruction can be highlighted. Colors can
+//! be disabled, e.g., when the output is not a terminal.
+
+use inkwell::values::{AnyValue, BasicBlock, FunctionValue, InstructionValue};
+use regex::{Captures, Regex};
+
+use super::{basic_block::BasicBlockExt, FunctionExt};
+
+/// Module containing ANSI escape codes used by the colored printer.
+#[allow(missing_docs)]
+#[allow(clippy::missing_docs_in_private_items)]
+pub mod ansi {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const REVERSE: &str = "\x1b[7m";
+    pub const RED: &str = "\x1b[31m";
+    pub const GREEN: &str = "\x1b[32m";
+    pub const YELLOW: &str = "\x1b[33m";
+    pub const BLUE: &str = "\x1b[34m";
+    pub const MAGENTA: &str = "\x1b[35m";
+    pub const CYAN: &str = "\x1b[36m";
+    pub const GRAY: &str = "\x1b[90m";
+}
+
+/// Marker printed before a highlighted instruction.
+const HIGHLIGHT_MARKER: &str = "=> ";
+
+/// Printer of LLVM IR with ANSI colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorPrinter<'ctx> {
+    /// Whether colors are enabled.
+    pub enabled: bool,
+
+    /// Instruction to be highlighted, if any.
+    pub highlight: Option<InstructionValue<'ctx>>,
+}
+
+/// Implement methods for `ColorPrinter`.
+impl<'ctx> ColorPrinter<'ctx> {
+    /// Constructor
+    pub fn new(enabled: bool) -> Self {
+        ColorPrinter {
+            enabled,
+            highlight: None,
+        }
+    }
+
+    /// Set the instruction to be highlighted.
+    pub fn with_highlight(mut self, inst: InstructionValue<'ctx>) -> Self {
+        self.highlight = Some(inst);
+        self
+    }
+
+    /// Print an instruction, without indentation.
+    pub fn print_instruction(&self, inst: InstructionValue<'ctx>) -> String {
+        let text = inst.print_to_string().to_string();
+        let text = text.trim();
+
+        if Some(inst) == self.highlight {
+            match self.enabled {
+                true => format!(
+                    "{}{}{}{}",
+                    ansi::BOLD,
+                    ansi::REVERSE,
+                    text,
+                    ansi::RESET
+                ),
+                false => text.to_string(),
+            }
+        } else if self.enabled {
+            colorize_instruction(text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Print the label line of a block.
+    pub fn print_block_label(&self, blk: BasicBlock<'ctx>) -> String {
+        let label = blk.get_name_or_default() + ":";
+        self.paint(&label, ansi::MAGENTA)
+    }
+
+    /// Print a block with its label and indented instructions.
+    pub fn print_block(&self, blk: BasicBlock<'ctx>) -> String {
+        let insts = blk.get_instructions();
+        let mut lines = vec![self.print_block_label(blk)];
+        lines.extend(insts.into_iter().map(|inst| self.print_line(inst)));
+        lines.join("\n")
+    }
+
+    /// Print a function with its header and all blocks.
+    pub fn print_function(&self, func: FunctionValue<'ctx>) -> String {
+        let header = self.paint(&func.print_header(), ansi::BOLD);
+        let blocks = func
+            .get_basic_blocks()
+            .into_iter()
+            .map(|blk| self.print_block(blk))
+            .collect::<Vec<String>>();
+
+        match blocks.is_empty() {
+            true => header,
+            false => format!("{}\n{}", header, blocks.join("\n\n")),
+        }
+    }
+
+    /// Print an instruction as a line of a block, marking it if it is
+    /// highlighted.
+    pub fn print_line(&self, inst: InstructionValue<'ctx>) -> String {
+        let prefix = match Some(inst) == self.highlight {
+            true => self.paint(HIGHLIGHT_MARKER, ansi::RED),
+            false => " ".repeat(HIGHLIGHT_MARKER.len()),
+        };
+        format!("{}{}", prefix, self.print_instruction(inst))
+    }
+
+    /// Paint a text by a color, if colors are enabled.
+    fn paint(&self, text: &str, color: &str) -> String {
+        match self.enabled {
+            true => format!("{}{}{}", color, text, ansi::RESET),
+            false => text.to_string(),
+        }
+    }
+}
+
+/// Implement the trait `Default` for `ColorPrinter`.
+impl<'ctx> Default for ColorPrinter<'ctx> {
+    fn default() -> Self {
+        ColorPrinter::new(true)
+    }
+}
+
+/// Colorize the textual representation of an instruction.
+fn colorize_instruction(text: &str) -> String {
+    let opcode_regex = Regex::new(
+        r#"^((?:(?:%[-\w.$]+|%"[^"]*")\s*=\s*)?)([a-z][a-z0-9_.]*)(.*)$"#,
+    )
+    .unwrap();
+
+    match opcode_regex.captures(text) {
+        Some(cap) => format!(
+            "{}{}{}{}{}",
+            colorize_tokens(&cap[1]),
+            ansi::YELLOW,
+            &cap[2],
+            ansi::RESET,
+            colorize_tokens(&cap[3])
+        ),
+        None => colorize_tokens(text),
+    }
+}
+
+/// Colorize values, types, constants and metadata in a text.
+fn colorize_tokens(text: &str) -> String {
+    let token_regex = Regex::new(concat!(
+        r#"(?P<global>@[-\w.$]+|@"[^"]*")"#,
+        r#"|(?P<local>%[-\w.$]+|%"[^"]*")"#,
+        r#"|(?P<type>\b(?:i\d+|void|half|float|double|fp128|ptr|label)\b\**)"#,
+        r#"|(?P<metadata>![-\w.]+)"#,
+        r#"|(?P<number>\b-?\d+\b)"#,
+    ))
+    .unwrap();
+
+    token_regex
+        .replace_all(text, |cap: &Captures| {
+            let color = if cap.name("global").is_some() {
+                ansi::BLUE
+            } else if cap.name("local").is_some() {
+                ansi::CYAN
+            } else if cap.name("type").is_some() {
+                ansi::GREEN
+            } else if cap.name("metadata").is_some() {
+                ansi::GRAY
+            } else {
+                ansi::MAGENTA
+            };
+            format!("{}{}{}", color, &cap[0], ansi::RESET)
+        })
+        .to_string()
+}
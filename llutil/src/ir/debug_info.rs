@@ -0,0 +1,667 @@
+//! Module provide utilities to map LLVM IR to source code via debug
+//! information.
+
+use inkwell::{
+    module::Module,
+    targets::TargetData,
+    types::{AnyType, StructType},
+    values::{AnyValue, AsValueRef, FunctionValue, InstructionValue},
+};
+use llvm_sys::core::{
+    LLVMGetDebugLocColumn, LLVMGetDebugLocDirectory, LLVMGetDebugLocFilename,
+    LLVMGetDebugLocLine, LLVMGetMDNodeNumOperands, LLVMGetMDNodeOperands,
+    LLVMGetOperand, LLVMGetTypeContext, LLVMIsAMDNode, LLVMIsUndef,
+    LLVMMetadataAsValue, LLVMTypeOf, LLVMValueAsMetadata,
+};
+use llvm_sys::debuginfo::{
+    LLVMDIFileGetDirectory, LLVMDIFileGetFilename, LLVMDILocationGetColumn,
+    LLVMDILocationGetInlinedAt, LLVMDILocationGetLine, LLVMDILocationGetScope,
+    LLVMDIScopeGetFile, LLVMDISubprogramGetLine, LLVMGetMetadataKind,
+    LLVMGetSubprogram, LLVMInstructionGetDebugLoc, LLVMMetadataKind,
+};
+use llvm_sys::prelude::{LLVMContextRef, LLVMMetadataRef, LLVMValueRef};
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::CStr,
+    fmt::{self, Display},
+    os::raw::c_char,
+    path::Path,
+    ptr,
+};
+
+use super::{
+    builtin::llvm_lib, struct_layout::strip_numeric_suffix, AnyCall,
+    InstructionExt, ModuleExt,
+};
+
+/// Prefixes added by Clang to the names of structs, classes and unions.
+const CLANG_STRUCT_PREFIXES: [&str; 3] = ["struct.", "class.", "union."];
+
+/// Data structure representing a location in source code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceLocation {
+    /// Path of the source file, including its directory if known.
+    pub file: String,
+
+    /// Line number, starting from 1.
+    pub line: u32,
+
+    /// Column number, starting from 1, or 0 if unknown.
+    pub column: u32,
+}
+
+/// Implement methods for `SourceLocation`.
+impl SourceLocation {
+    /// Constructor
+    pub fn new(file: &str, line: u32, column: u32) -> Self {
+        SourceLocation {
+            file: file.to_string(),
+            line,
+            column,
+        }
+    }
+
+    /// Get the debug location attached to an instruction, if any.
+    pub fn from_instruction(inst: InstructionValue) -> Option<Self> {
+        let inst_ref = inst.as_value_ref();
+        let line = unsafe { LLVMGetDebugLocLine(inst_ref) };
+        if line == 0 {
+            return None;
+        }
+
+        let column = unsafe { LLVMGetDebugLocColumn(inst_ref) };
+        let file = get_debug_loc_string(inst_ref, LLVMGetDebugLocFilename)?;
+        let dir = get_debug_loc_string(inst_ref, LLVMGetDebugLocDirectory);
+
+        Some(SourceLocation::new(
+            &join_source_path(dir, file),
+            line,
+            column,
+        ))
+    }
+
+    /// Get the location of the declaration of a function from its
+    /// `DISubprogram`, if any. The column of the location is 0.
+    pub fn from_function(func: FunctionValue) -> Option<Self> {
+        let subprogram = unsafe { LLVMGetSubprogram(func.as_value_ref()) };
+        if subprogram.is_null() {
+            return None;
+        }
+
+        let line = unsafe { LLVMDISubprogramGetLine(subprogram) };
+        let file_md = unsafe { LLVMDIScopeGetFile(subprogram) };
+        if file_md.is_null() {
+            return None;
+        }
+        let file = get_debug_loc_string(file_md, LLVMDIFileGetFilename)?;
+        let dir = get_debug_loc_string(file_md, LLVMDIFileGetDirectory);
+
+        Some(SourceLocation::new(&join_source_path(dir, file), line, 0))
+    }
+
+    /// Constructor of a `SourceLocation` from a `DILocation` metadata.
+    fn from_di_location(loc: LLVMMetadataRef) -> Option<Self> {
+        let line = unsafe { LLVMDILocationGetLine(loc) };
+        if line == 0 {
+            return None;
+        }
+
+        let column = unsafe { LLVMDILocationGetColumn(loc) };
+        let scope = unsafe { LLVMDILocationGetScope(loc) };
+        let file_md = unsafe { LLVMDIScopeGetFile(scope) };
+        if file_md.is_null() {
+            return None;
+        }
+        let file = get_debug_loc_string(file_md, LLVMDIFileGetFilename)?;
+        let dir = get_debug_loc_string(file_md, LLVMDIFileGetDirectory);
+
+        Some(SourceLocation::new(
+            &join_source_path(dir, file),
+            line,
+            column,
+        ))
+    }
+}
+
+/// Join the directory and the file name of a debug location, unless the
+/// file name is already an absolute path.
+fn join_source_path(dir: Option<String>, file: String) -> String {
+    match dir {
+        Some(dir) if !dir.is_empty() && Path::new(&file).is_relative() => {
+            Path::new(&dir).join(&file).to_string_lossy().to_string()
+        }
+        _ => file,
+    }
+}
+
+/// Implement the trait `Display` for `SourceLocation`.
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.column {
+            0 => write!(f, "{}:{}", self.file, self.line),
+            _ => write!(f, "{}:{}:{}", self.file, self.line, self.column),
+        }
+    }
+}
+
+/// Read a string of the debug location of an instruction, or of a `DIFile`
+/// metadata.
+fn get_debug_loc_string<T>(
+    arg: T,
+    getter: unsafe extern "C" fn(T, *mut u32) -> *const c_char,
+) -> Option<String> {
+    let mut len = 0;
+    let ptr = unsafe { getter(arg, &mut len) };
+    if ptr.is_null() {
+        return None;
+    }
+
+    let bytes =
+        unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
+    Some(String::from_utf8_lossy(bytes).to_string())
+}
+
+/// Data structure representing a frame of the inlining stack of an
+/// instruction, i.e., a function and a location in its source code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InlineFrame {
+    /// Source-level name of the function of the frame, if known.
+    pub function: Option<String>,
+
+    /// Location in the function: the location of the instruction for the
+    /// innermost frame, or the call site of the inlined callee otherwise.
+    pub location: SourceLocation,
+}
+
+/// Implement the trait `Display` for `InlineFrame`.
+impl Display for InlineFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.function {
+            Some(func) => write!(f, "{} at {}", func, self.location),
+            None => write!(f, "<unknown> at {}", self.location),
+        }
+    }
+}
+
+/// Get the inlining stack of an instruction from the `inlinedAt` chain of its
+/// debug location, from the innermost inlined callee to the function
+/// containing the instruction.
+///
+/// An instruction which was not inlined has a single frame, and an
+/// instruction without debug location has no frame.
+pub fn get_inline_stack(inst: InstructionValue) -> Vec<InlineFrame> {
+    let inst_ref = inst.as_value_ref();
+    let context = unsafe { LLVMGetTypeContext(LLVMTypeOf(inst_ref)) };
+
+    let mut frames = vec![];
+    let mut loc = unsafe { LLVMInstructionGetDebugLoc(inst_ref) };
+    while !loc.is_null() {
+        let location = match SourceLocation::from_di_location(loc) {
+            Some(location) => location,
+            None => break,
+        };
+        let scope = unsafe { LLVMDILocationGetScope(loc) };
+        let function = get_scope_function_name(context, scope);
+        frames.push(InlineFrame { function, location });
+
+        loc = unsafe { LLVMDILocationGetInlinedAt(loc) };
+    }
+
+    frames
+}
+
+/// Check if an instruction was inlined from another function, according to
+/// its debug location.
+pub fn is_inlined(inst: InstructionValue) -> bool {
+    let loc = unsafe { LLVMInstructionGetDebugLoc(inst.as_value_ref()) };
+    !loc.is_null() && !unsafe { LLVMDILocationGetInlinedAt(loc) }.is_null()
+}
+
+/// Get the source-level name of the function enclosing a local scope, by
+/// walking up the parents of lexical blocks to their subprogram.
+fn get_scope_function_name(
+    context: LLVMContextRef,
+    scope: LLVMMetadataRef,
+) -> Option<String> {
+    let mut scope = scope;
+    while !scope.is_null() {
+        let scope_value = unsafe { LLVMMetadataAsValue(context, scope) };
+        match unsafe { LLVMGetMetadataKind(scope) } {
+            LLVMMetadataKind::LLVMDISubprogramMetadataKind => {
+                return get_variable_name(scope_value)
+            }
+            LLVMMetadataKind::LLVMDILexicalBlockMetadataKind
+            | LLVMMetadataKind::LLVMDILexicalBlockFileMetadataKind => {
+                // The parent scope is the second operand of a lexical block.
+                let num_operands =
+                    unsafe { LLVMGetMDNodeNumOperands(scope_value) };
+                if num_operands < 2 {
+                    return None;
+                }
+                let mut operands = vec![ptr::null_mut(); num_operands as usize];
+                unsafe {
+                    LLVMGetMDNodeOperands(scope_value, operands.as_mut_ptr())
+                };
+                scope = match operands[1] {
+                    parent if parent.is_null() => return None,
+                    parent => unsafe { LLVMValueAsMetadata(parent) },
+                };
+            }
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Data structure mapping LLVM values of a function to their source-level
+/// variable names, using the debug intrinsics `llvm.dbg.declare`,
+/// `llvm.dbg.value` and `llvm.dbg.addr`.
+#[derive(Debug, Clone, Default)]
+pub struct SourceVariables {
+    /// Source-level variable names of values.
+    names: HashMap<LLVMValueRef, String>,
+}
+
+/// Implement methods for `SourceVariables`.
+impl SourceVariables {
+    /// Constructor, collecting source-level variables of a function.
+    pub fn new(func: FunctionValue) -> Self {
+        let mut names = HashMap::new();
+
+        for blk in func.get_basic_blocks() {
+            for inst in blk.get_instructions() {
+                if let Some((value, name)) = get_debug_variable(inst) {
+                    names.entry(value).or_insert(name);
+                }
+            }
+        }
+
+        SourceVariables { names }
+    }
+
+    /// Get the source-level variable name of a value, if any.
+    pub fn get_name<V: AsValueRef>(&self, value: &V) -> Option<&str> {
+        self.names.get(&value.as_value_ref()).map(|v| v.as_str())
+    }
+
+    /// Get the source-level variable name of a value, or its textual
+    /// representation in LLVM IR if it has no source-level name.
+    pub fn get_name_or_default<'ctx, V: AnyValue<'ctx>>(
+        &self,
+        value: &V,
+    ) -> String {
+        match self.get_name(value) {
+            Some(name) => name.to_string(),
+            None => value.print_to_string().to_string(),
+        }
+    }
+
+    /// Get the number of mapped values.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Check if no value is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Data structure mapping named structs of a module to their source-level
+/// type names, using the debug information of composite types and typedefs.
+#[derive(Debug, Clone, Default)]
+pub struct SourceTypes {
+    /// Source-level type names of named structs, indexed by struct names.
+    names: HashMap<String, String>,
+}
+
+/// Implement methods for `SourceTypes`.
+impl SourceTypes {
+    /// Constructor, resolving source-level type names of named structs of a
+    /// module.
+    pub fn new(module: &Module) -> Self {
+        let debug_names = get_debug_type_names(module);
+
+        let names = module
+            .get_struct_types()
+            .into_iter()
+            .filter_map(|struct_type| {
+                let name = struct_type.get_name()?.to_string_lossy();
+                let source_name =
+                    resolve_source_type_name(&name, &debug_names)?;
+                Some((name.to_string(), source_name))
+            })
+            .collect();
+
+        SourceTypes { names }
+    }
+
+    /// Get the source-level type name of a named struct, if any.
+    pub fn get_name(&self, struct_type: StructType) -> Option<&str> {
+        let name = struct_type.get_name()?.to_str().ok()?;
+        self.names.get(name).map(|v| v.as_str())
+    }
+
+    /// Get the source-level type name of a struct, or its textual
+    /// representation in LLVM IR if it has no source-level name.
+    pub fn get_name_or_default(&self, struct_type: StructType) -> String {
+        match self.get_name(struct_type) {
+            Some(name) => name.to_string(),
+            None => struct_type.print_to_string(),
+        }
+    }
+
+    /// Get the number of mapped structs.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Check if no struct is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Get the names of structs, classes, unions and typedefs declared in the
+/// debug information of a module.
+pub fn get_debug_type_names(module: &Module) -> HashSet<String> {
+    let regex = Regex::new(concat!(
+        r#"!DI(?:Composite|Derived)Type\(tag: DW_TAG_"#,
+        r#"(?:structure_type|class_type|union_type|typedef), name: "([^"]*)""#
+    ))
+    .unwrap();
+    let module_str = module.print_to_string().to_string();
+
+    regex
+        .captures_iter(&module_str)
+        .map(|cap| cap[1].to_string())
+        .collect()
+}
+
+/// Resolve the source-level type name of a named struct from its name in the
+/// IR, e.g., `struct.Foo` or `my_crate::Foo`, and the type names declared in
+/// debug information.
+///
+/// The name is stripped of the prefix added by Clang and of the numeric
+/// suffix added by LLVM, and is resolved if it, or its last path segment, is
+/// declared in debug information.
+pub fn resolve_source_type_name(
+    struct_name: &str,
+    debug_names: &HashSet<String>,
+) -> Option<String> {
+    let name = strip_numeric_suffix(struct_name);
+    let name = CLANG_STRUCT_PREFIXES
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name);
+
+    if debug_names.contains(name)
+        || debug_names.contains(get_last_path_segment(name))
+    {
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+/// Get the last segment of a path of a type name, e.g., `Foo` of
+/// `my_crate::Foo`.
+fn get_last_path_segment(name: &str) -> &str {
+    // Only split the path before generic arguments, e.g., in `Vec<a::B>`.
+    let path_end = name.find('<').unwrap_or(name.len());
+    match name[..path_end].rfind("::") {
+        Some(idx) => &name[idx + 2..],
+        None => name,
+    }
+}
+
+/// Data structure mapping fields of named structs of a module to their
+/// source-level field names, using the debug information of struct members.
+///
+/// Fields are matched with members by their offsets, since the IR may
+/// reorder fields, e.g., of Rust structs, or add padding fields.
+#[derive(Debug, Clone, Default)]
+pub struct SourceFields {
+    /// Source-level field names, indexed by struct names and field indices.
+    names: HashMap<(String, u32), String>,
+}
+
+/// Implement methods for `SourceFields`.
+impl SourceFields {
+    /// Constructor, resolving source-level field names of named structs of a
+    /// module.
+    pub fn new(module: &Module) -> Self {
+        let members = get_debug_struct_members(module);
+        let debug_names: HashSet<String> = members.keys().cloned().collect();
+        let data_layout = module.get_data_layout();
+        let target_data =
+            TargetData::create(&data_layout.as_str().to_string_lossy());
+
+        let mut names = HashMap::new();
+        for struct_type in module.get_struct_types() {
+            let struct_name = match struct_type.get_name() {
+                Some(name) => name.to_string_lossy().to_string(),
+                None => continue,
+            };
+            let source_name =
+                match resolve_source_type_name(&struct_name, &debug_names) {
+                    Some(name) => name,
+                    None => continue,
+                };
+            let struct_members = members
+                .get(&source_name)
+                .or_else(|| members.get(get_last_path_segment(&source_name)));
+            let struct_members = match struct_members {
+                Some(members) => members,
+                None => continue,
+            };
+
+            for (idx, field_type) in
+                struct_type.get_field_types().iter().enumerate()
+            {
+                // Zero-sized fields share their offsets with the next fields.
+                if target_data.get_store_size(field_type) == 0 {
+                    continue;
+                }
+                let offset = match target_data
+                    .offset_of_element(&struct_type, idx as u32)
+                {
+                    Some(offset) => offset * 8,
+                    None => continue,
+                };
+                let member = struct_members
+                    .iter()
+                    .find(|(member_offset, _)| *member_offset == offset);
+                if let Some((_, member_name)) = member {
+                    let key = (struct_name.clone(), idx as u32);
+                    names.insert(key, member_name.clone());
+                }
+            }
+        }
+
+        SourceFields { names }
+    }
+
+    /// Get the source-level name of a field of a named struct, if any.
+    pub fn get_name(
+        &self,
+        struct_type: StructType,
+        index: u32,
+    ) -> Option<&str> {
+        let struct_name = struct_type.get_name()?.to_string_lossy().to_string();
+        self.names.get(&(struct_name, index)).map(|v| v.as_str())
+    }
+
+    /// Get the source-level name of a field of a struct, or its index if it
+    /// has no source-level name.
+    pub fn get_name_or_default(
+        &self,
+        struct_type: StructType,
+        index: u32,
+    ) -> String {
+        match self.get_name(struct_type, index) {
+            Some(name) => name.to_string(),
+            None => index.to_string(),
+        }
+    }
+
+    /// Get the number of mapped fields.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Check if no field is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Get the members of structs, classes and unions declared in the debug
+/// information of a module, indexed by their type names, or the names of
+/// their typedefs if they are anonymous.
+///
+/// Each member is a pair of its offset in bits and its name, in declaration
+/// order.
+pub fn get_debug_struct_members(
+    module: &Module,
+) -> HashMap<String, Vec<(u64, String)>> {
+    let composite_regex = Regex::new(concat!(
+        r#"^!(\d+) = (?:distinct )?!DICompositeType\(tag: DW_TAG_"#,
+        r#"(?:structure_type|class_type|union_type)(?:, name: "([^"]*)")?"#
+    ))
+    .unwrap();
+    let typedef_regex = Regex::new(concat!(
+        r#"^!\d+ = !DIDerivedType\(tag: DW_TAG_typedef, name: "([^"]*)""#,
+        r#".*baseType: !(\d+)"#
+    ))
+    .unwrap();
+    let member_regex = Regex::new(concat!(
+        r#"^!\d+ = !DIDerivedType\(tag: DW_TAG_member, name: "([^"]*)", "#,
+        r#"scope: !(\d+)"#
+    ))
+    .unwrap();
+    let offset_regex = Regex::new(r"offset: (\d+)").unwrap();
+
+    let mut composite_names: Vec<(String, String)> = vec![];
+    let mut typedef_names: Vec<(String, String)> = vec![];
+    let mut members: HashMap<String, Vec<(u64, String)>> = HashMap::new();
+
+    let module_str = module.print_to_string().to_string();
+    for line in module_str.lines() {
+        if let Some(cap) = composite_regex.captures(line) {
+            if let Some(name) = cap.get(2) {
+                composite_names.push((cap[1].to_string(), name.as_str().into()))
+            }
+        } else if let Some(cap) = typedef_regex.captures(line) {
+            typedef_names.push((cap[2].to_string(), cap[1].to_string()))
+        } else if let Some(cap) = member_regex.captures(line) {
+            // Offsets of members at the start of their structs are omitted.
+            let offset = offset_regex
+                .captures(line)
+                .and_then(|offset| offset[1].parse().ok())
+                .unwrap_or(0);
+            let scope_members = members.entry(cap[2].to_string()).or_default();
+            scope_members.push((offset, cap[1].to_string()))
+        }
+    }
+
+    let mut struct_members = HashMap::new();
+    for (id, name) in composite_names.into_iter().chain(typedef_names) {
+        if let Some(scope_members) = members.get(&id) {
+            struct_members
+                .entry(name)
+                .or_insert_with(|| scope_members.clone());
+        }
+    }
+
+    struct_members
+}
+
+/// Kinds of debug intrinsics describing source-level variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DebugIntrinsicKind {
+    /// `llvm.dbg.declare`, describing the address of a variable for the
+    /// whole function.
+    Declare,
+
+    /// `llvm.dbg.value`, describing the value of a variable from the
+    /// intrinsic on.
+    Value,
+
+    /// `llvm.dbg.addr`, describing the address of a variable from the
+    /// intrinsic on.
+    Addr,
+}
+
+/// Get the kind, the described value, and the `DILocalVariable` metadata
+/// argument of a debug intrinsic call, if the instruction is such a call.
+///
+/// The described value is `None` if it is not available anymore, e.g., when
+/// it was optimized away.
+pub(crate) fn get_debug_intrinsic(
+    inst: InstructionValue,
+) -> Option<(DebugIntrinsicKind, Option<LLVMValueRef>, LLVMValueRef)> {
+    let callee = inst.try_into_call_base()?.get_called_function()?;
+    let kind = match callee.get_name().to_str().ok()? {
+        llvm_lib::LLVM_DBG_DECLARE => DebugIntrinsicKind::Declare,
+        llvm_lib::LLVM_DBG_VALUE => DebugIntrinsicKind::Value,
+        llvm_lib::LLVM_DBG_ADDR => DebugIntrinsicKind::Addr,
+        _ => return None,
+    };
+
+    let inst_ref = inst.as_value_ref();
+    let value = unwrap_metadata_value(unsafe { LLVMGetOperand(inst_ref, 0) })
+        .filter(|value| unsafe { LLVMIsUndef(*value) } == 0);
+    let variable = unsafe { LLVMGetOperand(inst_ref, 1) };
+    ite!(variable.is_null(), None, Some((kind, value, variable)))
+}
+
+/// Get the value and the source-level variable name described by a debug
+/// intrinsic call, if the instruction is such a call.
+fn get_debug_variable(
+    inst: InstructionValue,
+) -> Option<(LLVMValueRef, String)> {
+    let (_, value, variable) = get_debug_intrinsic(inst)?;
+    let name = get_variable_name(variable)?;
+
+    Some((value?, name))
+}
+
+/// Get the value wrapped in a metadata argument of a debug intrinsic.
+fn unwrap_metadata_value(metadata: LLVMValueRef) -> Option<LLVMValueRef> {
+    if metadata.is_null() || unsafe { LLVMIsAMDNode(metadata) }.is_null() {
+        return None;
+    }
+
+    if unsafe { LLVMGetMDNodeNumOperands(metadata) } != 1 {
+        return None;
+    }
+
+    let mut value: LLVMValueRef = ptr::null_mut();
+    unsafe { LLVMGetMDNodeOperands(metadata, &mut value) };
+    ite!(value.is_null(), None, Some(value))
+}
+
+/// Get the name of a `DILocalVariable` metadata argument of a debug intrinsic,
+/// or of another named debug metadata wrapped as a value.
+pub(crate) fn get_variable_name(variable: LLVMValueRef) -> Option<String> {
+    if variable.is_null() {
+        return None;
+    }
+
+    let text = print_value_ref(variable);
+    let regex = Regex::new(r#"name: "([^"]*)""#).unwrap();
+    regex.captures(&text).map(|cap| cap[1].to_string())
+}
+
+/// Print a raw LLVM value, including metadata wrapped as a value, to a string.
+pub(crate) fn print_value_ref(value: LLVMValueRef) -> String {
+    unsafe {
+        let text = llvm_sys::core::LLVMPrintValueToString(value);
+        let res = CStr::from_ptr(text).to_string_lossy().to_string();
+        llvm_sys::core::LLVMDisposeMessage(text);
+        res
+    }
+}
@@ -1,15 +1,28 @@
 //! Module providing additional utilities to handle LLVM `FunctionValue`.
 
-use std::collections::HashSet;
-
-use crate::ir::builtin;
-use inkwell::values::{AnyValue, FunctionValue, GlobalValue};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::OnceLock,
+};
+
+use crate::{analysis::invariant::compute_back_edges, ir::builtin};
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::values::{
+    AnyValue, BasicBlock, BasicValue, BasicValueEnum, FunctionValue,
+    GlobalValue, InstructionValue,
+};
+use regex::Regex;
 use rutil::string::StringExt;
 
-use super::basic_block::BasicBlockExt;
+use super::{
+    basic_block::BasicBlockExt,
+    cfg::ControlFlowGraph,
+    pretty_print::{print_ellipsis, PrettyPrintOptions},
+    AnyCall, InstructionExt, InstructionIndex, ReturnInst,
+};
 
 /// Trait providing additional functions to handle `FunctionValue`
-pub trait FunctionExt {
+pub trait FunctionExt<'ctx> {
     /// Get name of the `FunctionValue` or return a default name.
     fn get_name_or_default(&self) -> String;
 
@@ -22,6 +35,10 @@ pub trait FunctionExt {
     /// Print the `FunctionValue` to string in a pretty format.
     fn print_pretty(&self) -> String;
 
+    /// Print the `FunctionValue` to string in a pretty format, truncating its
+    /// blocks and instructions according to `options`.
+    fn print_pretty_with(&self, options: &PrettyPrintOptions) -> String;
+
     /// Check if the current function is a C library function.
     fn is_c_library(&self) -> bool;
 
@@ -37,14 +54,70 @@ pub trait FunctionExt {
     /// Check if the current function is an assertion checking function.
     fn is_assertion_checking_function(&self) -> bool;
 
+    /// Check if the current function is a Verazt runtime library function.
+    fn is_verazt_library_function(&self) -> bool;
+
+    /// Check if the current function returns a nondeterministic value.
+    fn is_nondet_function(&self) -> bool;
+
     /// Check if the current function is a C main function.
     fn is_c_cpp_main_function(&self) -> bool;
 
     /// Check if the current function is a Solidity entry function.
     fn is_solidity_entry_function(&self) -> bool;
+
+    /// Get position of an instruction in the current function, which is a
+    /// pair of the parent block index and the instruction index in the block.
+    ///
+    /// Return `None` if the instruction does not belong to the function.
+    ///
+    /// If an `InstructionIndex` is provided, the position is looked up from it
+    /// instead of being recomputed.
+    fn get_instruction_position(
+        &self,
+        inst: InstructionValue,
+        index: Option<&InstructionIndex>,
+    ) -> Option<(usize, usize)>;
+
+    /// Compute a semantic hash of the current function.
+    ///
+    /// The hash is computed from the textual IR of the function and of all
+    /// functions it transitively calls directly, excluding debug locations, so
+    /// it changes when a callee changes but is unchanged when only source
+    /// positions change. Callees of indirect calls are not resolved. The hash
+    /// is stable across runs and platforms.
+    fn get_semantic_hash(&self) -> u64;
+
+    /// Check if the current function has the `noreturn` attribute.
+    fn is_noreturn(&self) -> bool;
+
+    /// Get all `ret` instructions of the current function.
+    fn get_return_instructions(&self) -> Vec<ReturnInst<'ctx>>;
+
+    /// Get the unique value returned by the current function.
+    ///
+    /// This is the returned value when all `ret` instructions return the same
+    /// value, or when the function has a single `ret` instruction returning a
+    /// `phi` whose incoming values are all the same.
+    ///
+    /// Return `None` if the function does not return a value or if the
+    /// returned value is not unique.
+    fn get_unique_return_value(&self) -> Option<BasicValueEnum<'ctx>>;
+
+    /// Print the control flow graph of the current function in the GraphViz
+    /// DOT format.
+    fn print_cfg_dot(&self) -> String;
+
+    /// Compute a heuristic score estimating the cost of analyzing the
+    /// current function.
+    ///
+    /// The score grows with the numbers of blocks and instructions, is
+    /// multiplied by the maximum loop nesting depth, and is increased by
+    /// each indirect call. Declared functions have the score 0.
+    fn complexity_score(&self) -> u64;
 }
 
-impl<'a> FunctionExt for FunctionValue<'a> {
+impl<'ctx> FunctionExt<'ctx> for FunctionValue<'ctx> {
     fn get_name_or_default(&self) -> String {
         match self.get_name().to_str() {
             Ok(name) => name.to_string(),
@@ -68,6 +141,10 @@ impl<'a> FunctionExt for FunctionValue<'a> {
     }
 
     fn print_pretty(&self) -> String {
+        self.print_pretty_with(&PrettyPrintOptions::default())
+    }
+
+    fn print_pretty_with(&self, options: &PrettyPrintOptions) -> String {
         let params = self
             .get_param_iter()
             .map(|p| p.print_to_string())
@@ -78,13 +155,22 @@ impl<'a> FunctionExt for FunctionValue<'a> {
             formati!(0, "Function: {}({})", self.get_name_or_default(), params)
                 .indent_tail_lines(2);
 
-        let blocks = self
-            .get_basic_blocks()
+        let blks = self.get_basic_blocks();
+        let num_blks = blks.len();
+        let max_blks = options.max_blocks.unwrap_or(num_blks);
+
+        let mut blocks = blks
             .into_iter()
-            .map(|blk| blk.print_pretty().indent(2))
+            .take(max_blks)
+            .map(|blk| blk.print_pretty_with(options).indent(2))
             .collect::<Vec<String>>()
             .join("\n\n");
 
+        if num_blks > max_blks {
+            blocks += "\n\n";
+            blocks += &print_ellipsis(num_blks - max_blks, "block").indent(2);
+        }
+
         if blocks.is_empty() {
             res += "\n  (Empty body)"
         } else {
@@ -115,6 +201,14 @@ impl<'a> FunctionExt for FunctionValue<'a> {
         builtin::is_assertion_checking_function(&self.get_name_or_default())
     }
 
+    fn is_verazt_library_function(&self) -> bool {
+        builtin::is_verazt_library_function(&self.get_name_or_default())
+    }
+
+    fn is_nondet_function(&self) -> bool {
+        builtin::is_nondet_function(&self.get_name_or_default())
+    }
+
     fn is_c_cpp_main_function(&self) -> bool {
         builtin::is_c_main_function(&self.get_name_or_default())
     }
@@ -122,6 +216,172 @@ impl<'a> FunctionExt for FunctionValue<'a> {
     fn is_solidity_entry_function(&self) -> bool {
         !builtin::is_solidity_library_function(&self.get_name_or_default())
     }
+
+    fn get_instruction_position(
+        &self,
+        inst: InstructionValue,
+        index: Option<&InstructionIndex>,
+    ) -> Option<(usize, usize)> {
+        if let Some(index) = index {
+            return index.get_position(inst);
+        }
+
+        let blk = inst.get_parent()?;
+        if blk.get_parent() != Some(*self) {
+            return None;
+        }
+
+        let blk_idx = self.get_basic_blocks().iter().position(|b| *b == blk)?;
+        Some((blk_idx, inst.get_index_in_block()))
+    }
+
+    fn get_semantic_hash(&self) -> u64 {
+        let mut visited = HashSet::from([*self]);
+        let mut worklist = vec![*self];
+        let mut callees = vec![];
+        while let Some(func) = worklist.pop() {
+            let direct_callees = func
+                .get_basic_blocks()
+                .into_iter()
+                .flat_map(|blk| blk.get_instructions())
+                .filter_map(|inst| inst.try_into_call_base())
+                .filter_map(|call| call.get_called_function());
+            for callee in direct_callees {
+                if visited.insert(callee) {
+                    callees.push(callee);
+                    worklist.push(callee);
+                }
+            }
+        }
+
+        // Sort callees by names, so that the hash does not depend on the
+        // order of calls.
+        callees.sort_by_key(|callee| callee.get_name_or_default());
+
+        let regex = DEBUG_LOCATION_REGEX
+            .get_or_init(|| Regex::new(r",? !dbg !\d+").unwrap());
+        let func_str = std::iter::once(*self)
+            .chain(callees)
+            .map(|func| func.print_to_string().to_string())
+            .collect::<Vec<String>>()
+            .join("\n");
+        let func_str = regex.replace_all(&func_str, "");
+
+        // Use the 64-bit FNV-1a hash, which is stable across runs.
+        func_str.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    fn is_noreturn(&self) -> bool {
+        let kind_id = Attribute::get_named_enum_kind_id("noreturn");
+        self.get_enum_attribute(AttributeLoc::Function, kind_id)
+            .is_some()
+    }
+
+    fn get_return_instructions(&self) -> Vec<ReturnInst<'ctx>> {
+        self.get_basic_blocks()
+            .into_iter()
+            .filter_map(|blk| blk.get_terminator())
+            .filter_map(|inst| inst.try_into_return_inst())
+            .collect()
+    }
+
+    fn get_unique_return_value(&self) -> Option<BasicValueEnum<'ctx>> {
+        let mut values = self
+            .get_return_instructions()
+            .into_iter()
+            .map(|ret| ret.get_returned_value())
+            .collect::<Option<Vec<BasicValueEnum>>>()?;
+        values.dedup();
+
+        if values.len() != 1 {
+            return None;
+        }
+
+        let value = values[0];
+
+        // Look through the `phi` of a merged return block.
+        let phi = value
+            .as_instruction_value()
+            .and_then(|inst| inst.try_into_phi_node());
+        match phi {
+            Some(phi) => {
+                let mut incomings: Vec<BasicValueEnum> =
+                    phi.get_incomings().into_iter().map(|(v, _)| v).collect();
+                incomings.dedup();
+                match incomings[..] {
+                    [incoming] => Some(incoming),
+                    _ => Some(value),
+                }
+            }
+            None => Some(value),
+        }
+    }
+
+    fn print_cfg_dot(&self) -> String {
+        ControlFlowGraph::new(*self).to_dot()
+    }
+
+    fn complexity_score(&self) -> u64 {
+        let blocks = self.get_basic_blocks();
+        let insts: Vec<InstructionValue> = blocks
+            .iter()
+            .flat_map(|blk| blk.get_instructions())
+            .collect();
+        let num_indirect_calls = insts
+            .iter()
+            .filter_map(|inst| inst.try_into_call_base())
+            .filter(|call| call.get_called_function().is_none())
+            .count() as u64;
+        let loop_depth = compute_max_loop_depth(*self) as u64;
+
+        let size = BLOCK_WEIGHT * blocks.len() as u64 + insts.len() as u64;
+        size * (1 + loop_depth) + INDIRECT_CALL_WEIGHT * num_indirect_calls
+    }
+}
+
+/// Regular expression matching debug locations attached to instructions and
+/// to function definitions.
+static DEBUG_LOCATION_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Weight of a block in the complexity score of a function, relative to an
+/// instruction, since each block adds paths to explore.
+const BLOCK_WEIGHT: u64 = 2;
+
+/// Weight of an indirect call in the complexity score of a function, relative
+/// to an instruction, since its callees must be resolved by the analysis.
+const INDIRECT_CALL_WEIGHT: u64 = 20;
+
+/// Compute the maximum nesting depth of natural loops of a function.
+///
+/// The loop of a back edge contains its header and the blocks reaching its
+/// latch without passing through the header. Back edges sharing a header form
+/// a single loop.
+fn compute_max_loop_depth(func: FunctionValue) -> usize {
+    let cfg = ControlFlowGraph::new(func);
+
+    let mut loops: HashMap<BasicBlock, HashSet<BasicBlock>> = HashMap::new();
+    for (latch, header) in compute_back_edges(func) {
+        let body = loops.entry(header).or_insert_with(|| {
+            let mut body = HashSet::new();
+            body.insert(header);
+            body
+        });
+
+        let mut queue = vec![latch];
+        while let Some(blk) = queue.pop() {
+            if body.insert(blk) {
+                queue.extend_from_slice(cfg.get_predecessors(blk))
+            }
+        }
+    }
+
+    cfg.get_blocks()
+        .iter()
+        .map(|blk| loops.values().filter(|body| body.contains(blk)).count())
+        .max()
+        .unwrap_or(0)
 }
 
 /// Trait of utilities for a `Vector` of `GlobalValue`.
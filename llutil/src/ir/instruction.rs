@@ -1,15 +1,23 @@
 //! Module provide additional utilities to handle LLVM `InstructionValue`.
 
+use either::Either::Left;
 use inkwell::values::{
-    BasicValue, BasicValueEnum, FloatValue, InstructionValue, IntValue,
-    PointerValue,
+    AnyValueEnum, BasicValue, BasicValueEnum, FloatValue, InstructionValue,
+    IntValue, PointerValue,
 };
+use rutil::string::StringExt;
 
 use super::{
+    basic_block::BasicBlockExt,
+    color_print::ColorPrinter,
+    debug_info::SourceLocation,
+    pretty_print::print_ellipsis,
+    use_iter::{self, UseIter, UserIter},
     AllocaInst, BinaryOperator, BranchInst, CallBase, CallBrInst, CallInst,
-    CastInst, CmpInst, FCmpInst, ICmpInst, IndirectBrInst, InvokeInst,
-    LoadInst, PhiNode, ReturnInst, SExtInst, StoreInst, SwitchInst,
-    TerminatorInst, TruncInst, UnaryOperator, UnreachableInst, ZExtInst,
+    CastInst, CmpInst, FCmpInst, FenceInst, FreezeInst, FunctionOption,
+    GEPInst, ICmpInst, IndirectBrInst, InvokeInst, LoadInst, MemIntrinsic,
+    PhiNode, ReturnInst, SExtInst, StoreInst, SwitchInst, TerminatorInst,
+    TruncInst, UnaryOperator, UnreachableInst, VAArgInst, ZExtInst,
 };
 
 /// Trait providing additional functions to handle `InstructionValue`.
@@ -17,6 +25,46 @@ pub trait InstructionExt<'ctx> {
     /// Get name of the `InstructionValue` or return a default name.
     fn get_name_or_default(&self) -> String;
 
+    /// Get index of the current `InstructionValue` in its parent block.
+    fn get_index_in_block(&self) -> usize;
+
+    /// Replace the value operand at `index` of the current `InstructionValue`
+    /// by a new value.
+    ///
+    /// The replacement is performed only when the existing operand is a value
+    /// (not a basic block) and has the same type as the new value. Return
+    /// `true` if the operand is replaced, and `false` otherwise.
+    fn set_operand_value<V: BasicValue<'ctx>>(
+        &self,
+        index: u32,
+        value: V,
+    ) -> bool;
+
+    /// Get the source location of the current `InstructionValue`, if it has
+    /// debug information.
+    fn get_source_location(&self) -> Option<SourceLocation>;
+
+    /// Print the current `InstructionValue` with its context for debugging:
+    /// the parent function name, the parent block header, and up to `n`
+    /// instructions before and after it in the block. The current instruction
+    /// is marked by `=>`.
+    fn dump_context(&self, n: usize) -> String;
+
+    /// Iterate lazily over the uses of the current instruction, in LLVM's
+    /// use-list order.
+    fn uses(&self) -> UseIter<'ctx>;
+
+    /// Iterate lazily over the users of the current instruction, in LLVM's
+    /// use-list order, one item for each use.
+    fn users(&self) -> UserIter<'ctx>;
+
+    /// Check if the current instruction has exactly one use.
+    fn has_single_use(&self) -> bool;
+
+    /// Get the unique user of the current instruction, which may use it
+    /// several times.
+    fn get_unique_user(&self) -> Option<AnyValueEnum<'ctx>>;
+
     /// Convert the current `InstructionValue` to `AllocaInst`.
     fn try_into_alloca_inst(self) -> Option<AllocaInst<'ctx>>;
 
@@ -44,6 +92,15 @@ pub trait InstructionExt<'ctx> {
     /// Convert the current `InstructionValue` to `FCmpInst`.
     fn try_into_fcmp_inst(self) -> Option<FCmpInst<'ctx>>;
 
+    /// Convert the current `InstructionValue` to `FenceInst`.
+    fn try_into_fence_inst(self) -> Option<FenceInst<'ctx>>;
+
+    /// Convert the current `InstructionValue` to `FreezeInst`.
+    fn try_into_freeze_inst(self) -> Option<FreezeInst<'ctx>>;
+
+    /// Convert the current `InstructionValue` to `GEPInst`.
+    fn try_into_gep_inst(self) -> Option<GEPInst<'ctx>>;
+
     /// Convert the current `InstructionValue` to `ICmpInst`.
     fn try_into_icmp_inst(self) -> Option<ICmpInst<'ctx>>;
 
@@ -56,6 +113,10 @@ pub trait InstructionExt<'ctx> {
     /// Convert the current `InstructionValue` to `LoadInst`.
     fn try_into_load_inst(self) -> Option<LoadInst<'ctx>>;
 
+    /// Convert the current `InstructionValue` to `MemIntrinsic`, if it is a
+    /// call to `llvm.memcpy`, `llvm.memmove` or `llvm.memset`.
+    fn try_into_mem_intrinsic(self) -> Option<MemIntrinsic<'ctx>>;
+
     /// Convert the current `InstructionValue` to `PhiNode`.
     fn try_into_phi_node(self) -> Option<PhiNode<'ctx>>;
 
@@ -83,6 +144,9 @@ pub trait InstructionExt<'ctx> {
     /// Convert the current `InstructionValue` to `SwitchInst`.
     fn try_into_unreachable_inst(self) -> Option<UnreachableInst<'ctx>>;
 
+    /// Convert the current `InstructionValue` to `VAArgInst`.
+    fn try_into_vaarg_inst(self) -> Option<VAArgInst<'ctx>>;
+
     /// Convert the current `InstructionValue` to `ZExtInst`.
     fn try_into_zext_inst(self) -> Option<ZExtInst<'ctx>>;
 
@@ -111,6 +175,86 @@ impl<'ctx> InstructionExt<'ctx> for InstructionValue<'ctx> {
         String::from("<empty-instruction-name>")
     }
 
+    fn get_index_in_block(&self) -> usize {
+        let mut index = 0;
+        let mut prev_inst = self.get_previous_instruction();
+
+        while let Some(inst) = prev_inst {
+            index += 1;
+            prev_inst = inst.get_previous_instruction();
+        }
+
+        index
+    }
+
+    fn set_operand_value<V: BasicValue<'ctx>>(
+        &self,
+        index: u32,
+        value: V,
+    ) -> bool {
+        match self.get_operand(index) {
+            Some(Left(opr)) => {
+                let value = value.as_basic_value_enum();
+                if opr.get_type() != value.get_type() {
+                    return false;
+                }
+                self.set_operand(index, value)
+            }
+            _ => false,
+        }
+    }
+
+    fn get_source_location(&self) -> Option<SourceLocation> {
+        SourceLocation::from_instruction(*self)
+    }
+
+    fn dump_context(&self, n: usize) -> String {
+        let printer = ColorPrinter::new(false).with_highlight(*self);
+
+        let blk = match self.get_parent() {
+            Some(blk) => blk,
+            None => return printer.print_line(*self),
+        };
+
+        let func_name = blk.get_parent().get_name_or_default();
+        let insts = blk.get_instructions();
+        let index = self.get_index_in_block();
+        let start = index.saturating_sub(n);
+        let end = (index + n + 1).min(insts.len());
+
+        let mut lines =
+            vec![format!("Function: {}", func_name), blk.print_header()];
+        if start > 0 {
+            lines.push(print_ellipsis(start, "instruction").indent(3))
+        }
+        for inst in &insts[start..end] {
+            lines.push(printer.print_line(*inst))
+        }
+        if end < insts.len() {
+            lines.push(
+                print_ellipsis(insts.len() - end, "instruction").indent(3),
+            )
+        }
+
+        lines.join("\n")
+    }
+
+    fn uses(&self) -> UseIter<'ctx> {
+        UseIter::new(self.get_first_use())
+    }
+
+    fn users(&self) -> UserIter<'ctx> {
+        self.uses().users()
+    }
+
+    fn has_single_use(&self) -> bool {
+        use_iter::has_single_use(self.uses())
+    }
+
+    fn get_unique_user(&self) -> Option<AnyValueEnum<'ctx>> {
+        use_iter::get_unique_user(self.users())
+    }
+
     fn try_into_alloca_inst(self) -> Option<AllocaInst<'ctx>> {
         let res: Result<AllocaInst, _> = self.try_into();
         match res {
@@ -183,6 +327,30 @@ impl<'ctx> InstructionExt<'ctx> for InstructionValue<'ctx> {
         }
     }
 
+    fn try_into_fence_inst(self) -> Option<FenceInst<'ctx>> {
+        let res: Result<FenceInst, _> = self.try_into();
+        match res {
+            Ok(inst) => Some(inst),
+            Err(_) => None,
+        }
+    }
+
+    fn try_into_freeze_inst(self) -> Option<FreezeInst<'ctx>> {
+        let res: Result<FreezeInst, _> = self.try_into();
+        match res {
+            Ok(inst) => Some(inst),
+            Err(_) => None,
+        }
+    }
+
+    fn try_into_gep_inst(self) -> Option<GEPInst<'ctx>> {
+        let res: Result<GEPInst, _> = self.try_into();
+        match res {
+            Ok(inst) => Some(inst),
+            Err(_) => None,
+        }
+    }
+
     fn try_into_icmp_inst(self) -> Option<ICmpInst<'ctx>> {
         let res: Result<ICmpInst, _> = self.try_into();
         match res {
@@ -215,6 +383,14 @@ impl<'ctx> InstructionExt<'ctx> for InstructionValue<'ctx> {
         }
     }
 
+    fn try_into_mem_intrinsic(self) -> Option<MemIntrinsic<'ctx>> {
+        let res: Result<MemIntrinsic, _> = self.try_into();
+        match res {
+            Ok(inst) => Some(inst),
+            Err(_) => None,
+        }
+    }
+
     fn try_into_phi_node(self) -> Option<PhiNode<'ctx>> {
         let res: Result<PhiNode, _> = self.try_into();
         match res {
@@ -287,6 +463,14 @@ impl<'ctx> InstructionExt<'ctx> for InstructionValue<'ctx> {
         }
     }
 
+    fn try_into_vaarg_inst(self) -> Option<VAArgInst<'ctx>> {
+        let res: Result<VAArgInst, _> = self.try_into();
+        match res {
+            Ok(inst) => Some(inst),
+            Err(_) => None,
+        }
+    }
+
     fn try_into_zext_inst(self) -> Option<ZExtInst<'ctx>> {
         let res: Result<ZExtInst, _> = self.try_into();
         match res {
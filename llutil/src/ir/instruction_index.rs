@@ -0,0 +1,57 @@
+//! Module providing a cached index of instruction positions in a function.
+
+use std::collections::HashMap;
+
+use inkwell::values::{FunctionValue, InstructionValue};
+
+/// Data structure caching positions of all instructions of a function.
+///
+/// A position is a pair of the parent block index in the function and the
+/// instruction index in its parent block. The index must be rebuilt when the
+/// function is modified.
+#[derive(Debug, Clone)]
+pub struct InstructionIndex<'ctx> {
+    /// Mapping from instructions to their positions.
+    positions: HashMap<InstructionValue<'ctx>, (usize, usize)>,
+}
+
+/// Implement methods for `InstructionIndex`.
+impl<'ctx> InstructionIndex<'ctx> {
+    /// Constructor, which computes positions of all instructions of a
+    /// function.
+    pub fn new(func: FunctionValue<'ctx>) -> Self {
+        let mut positions = HashMap::new();
+
+        for (blk_idx, blk) in func.get_basic_blocks().iter().enumerate() {
+            let insts = blk.get_instructions();
+            for (inst_idx, inst) in insts.into_iter().enumerate() {
+                positions.insert(inst, (blk_idx, inst_idx));
+            }
+        }
+
+        InstructionIndex { positions }
+    }
+
+    /// Get position of an instruction, if it belongs to the indexed function.
+    pub fn get_position(
+        &self,
+        inst: InstructionValue<'ctx>,
+    ) -> Option<(usize, usize)> {
+        self.positions.get(&inst).cloned()
+    }
+
+    /// Check if the instruction `inst1` precedes the instruction `inst2` in
+    /// the layout of the indexed function.
+    ///
+    /// Return `false` if either instruction does not belong to the function.
+    pub fn precedes(
+        &self,
+        inst1: InstructionValue<'ctx>,
+        inst2: InstructionValue<'ctx>,
+    ) -> bool {
+        match (self.get_position(inst1), self.get_position(inst2)) {
+            (Some(pos1), Some(pos2)) => pos1 < pos2,
+            _ => false,
+        }
+    }
+}
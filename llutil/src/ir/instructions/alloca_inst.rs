@@ -1,7 +1,10 @@
-//! Module handling to the `callbr` instruction of LLVM.
+//! Module handling to the `alloca` instruction of LLVM.
 
 use super::{AnyInstruction, AsInstructionValue};
-use inkwell::values::{AnyValue, AsValueRef, InstructionValue};
+use inkwell::{
+    types::BasicTypeEnum,
+    values::{AnyValue, AsValueRef, InstructionValue, IntValue},
+};
 use llvm_sys::prelude::LLVMValueRef;
 use std::fmt::{self, Display, Formatter};
 
@@ -12,12 +15,58 @@ pub struct AllocaInst<'ctx> {
     alloca_inst: InstructionValue<'ctx>,
 }
 
+/// Implement methods for `AllocaInst`.
 impl<'ctx> AllocaInst<'ctx> {
     /// Constructor of a `AllocaInst` instruction.
     pub fn new(inst: InstructionValue<'ctx>) -> Self {
         debug_assert!(inst.is_a_alloca_inst());
         AllocaInst { alloca_inst: inst }
     }
+
+    /// Get the type allocated by the `AllocaInst`.
+    pub fn get_allocated_type(&self) -> BasicTypeEnum<'ctx> {
+        match self.alloca_inst.get_allocated_type() {
+            Ok(typ) => typ,
+            Err(msg) => panic!("Invalid alloca instruction: {}\n{}", self, msg),
+        }
+    }
+
+    /// Get the number of elements allocated by the `AllocaInst`.
+    ///
+    /// This is the count operand, which is `1` when not explicitly specified.
+    pub fn get_array_size(&self) -> IntValue<'ctx> {
+        self.expect_value_operand(0).into_int_value()
+    }
+
+    /// Get the alignment of the `AllocaInst`.
+    pub fn get_alignment(&self) -> u32 {
+        match self.alloca_inst.get_alignment() {
+            Ok(align) => align,
+            Err(msg) => panic!("Invalid alloca instruction: {}\n{}", self, msg),
+        }
+    }
+
+    /// Check if the `AllocaInst` allocates an array, i.e., its array size is
+    /// not the constant `1`.
+    pub fn is_array_allocation(&self) -> bool {
+        self.get_array_size().get_zero_extended_constant() != Some(1)
+    }
+
+    /// Check if the `AllocaInst` is a static alloca, i.e., it is in the entry
+    /// block of its function and its array size is a constant.
+    pub fn is_static_alloca(&self) -> bool {
+        let blk = match self.alloca_inst.get_parent() {
+            Some(blk) => blk,
+            None => return false,
+        };
+
+        let is_in_entry_block = blk
+            .get_parent()
+            .and_then(|func| func.get_first_basic_block())
+            .map_or(false, |entry_blk| entry_blk == blk);
+
+        is_in_entry_block && self.get_array_size().is_const()
+    }
 }
 
 /// Implement the `AsInstructionValue` trait for `AllocaInst`.
@@ -0,0 +1,205 @@
+//! Module classifying arithmetic, shift and bitwise operations of LLVM.
+
+use inkwell::values::InstructionOpcode;
+use std::fmt::{self, Display};
+
+/// Kinds of binary arithmetic, shift and bitwise operations.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum ArithKind {
+    /// Integer addition `add`.
+    Add,
+
+    /// Integer subtraction `sub`.
+    Sub,
+
+    /// Integer multiplication `mul`.
+    Mul,
+
+    /// Unsigned integer division `udiv`.
+    UDiv,
+
+    /// Signed integer division `sdiv`.
+    SDiv,
+
+    /// Unsigned integer remainder `urem`.
+    URem,
+
+    /// Signed integer remainder `srem`.
+    SRem,
+
+    /// Shift left `shl`.
+    Shl,
+
+    /// Logical shift right `lshr`.
+    LShr,
+
+    /// Arithmetic shift right `ashr`.
+    AShr,
+
+    /// Bitwise and `and`.
+    And,
+
+    /// Bitwise or `or`.
+    Or,
+
+    /// Bitwise exclusive or `xor`.
+    Xor,
+
+    /// Floating point addition `fadd`.
+    FAdd,
+
+    /// Floating point subtraction `fsub`.
+    FSub,
+
+    /// Floating point multiplication `fmul`.
+    FMul,
+
+    /// Floating point division `fdiv`.
+    FDiv,
+
+    /// Floating point remainder `frem`.
+    FRem,
+}
+
+/// Implement methods for `ArithKind`.
+impl ArithKind {
+    /// Get the arithmetic kind of an instruction opcode.
+    ///
+    /// Return `None` if the opcode is not a binary arithmetic, shift or
+    /// bitwise operation.
+    pub fn from_opcode(opcode: InstructionOpcode) -> Option<Self> {
+        let kind = match opcode {
+            InstructionOpcode::Add => ArithKind::Add,
+            InstructionOpcode::Sub => ArithKind::Sub,
+            InstructionOpcode::Mul => ArithKind::Mul,
+            InstructionOpcode::UDiv => ArithKind::UDiv,
+            InstructionOpcode::SDiv => ArithKind::SDiv,
+            InstructionOpcode::URem => ArithKind::URem,
+            InstructionOpcode::SRem => ArithKind::SRem,
+            InstructionOpcode::Shl => ArithKind::Shl,
+            InstructionOpcode::LShr => ArithKind::LShr,
+            InstructionOpcode::AShr => ArithKind::AShr,
+            InstructionOpcode::And => ArithKind::And,
+            InstructionOpcode::Or => ArithKind::Or,
+            InstructionOpcode::Xor => ArithKind::Xor,
+            InstructionOpcode::FAdd => ArithKind::FAdd,
+            InstructionOpcode::FSub => ArithKind::FSub,
+            InstructionOpcode::FMul => ArithKind::FMul,
+            InstructionOpcode::FDiv => ArithKind::FDiv,
+            InstructionOpcode::FRem => ArithKind::FRem,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    /// Get the instruction opcode of the arithmetic kind.
+    pub fn get_opcode(&self) -> InstructionOpcode {
+        match self {
+            ArithKind::Add => InstructionOpcode::Add,
+            ArithKind::Sub => InstructionOpcode::Sub,
+            ArithKind::Mul => InstructionOpcode::Mul,
+            ArithKind::UDiv => InstructionOpcode::UDiv,
+            ArithKind::SDiv => InstructionOpcode::SDiv,
+            ArithKind::URem => InstructionOpcode::URem,
+            ArithKind::SRem => InstructionOpcode::SRem,
+            ArithKind::Shl => InstructionOpcode::Shl,
+            ArithKind::LShr => InstructionOpcode::LShr,
+            ArithKind::AShr => InstructionOpcode::AShr,
+            ArithKind::And => InstructionOpcode::And,
+            ArithKind::Or => InstructionOpcode::Or,
+            ArithKind::Xor => InstructionOpcode::Xor,
+            ArithKind::FAdd => InstructionOpcode::FAdd,
+            ArithKind::FSub => InstructionOpcode::FSub,
+            ArithKind::FMul => InstructionOpcode::FMul,
+            ArithKind::FDiv => InstructionOpcode::FDiv,
+            ArithKind::FRem => InstructionOpcode::FRem,
+        }
+    }
+
+    /// Check if the operation is a floating point operation.
+    pub fn is_float(&self) -> bool {
+        matches!(
+            self,
+            ArithKind::FAdd
+                | ArithKind::FSub
+                | ArithKind::FMul
+                | ArithKind::FDiv
+                | ArithKind::FRem
+        )
+    }
+
+    /// Check if the operation is an integer operation.
+    pub fn is_integer(&self) -> bool {
+        !self.is_float()
+    }
+
+    /// Check if the operation is a shift operation.
+    pub fn is_shift(&self) -> bool {
+        matches!(self, ArithKind::Shl | ArithKind::LShr | ArithKind::AShr)
+    }
+
+    /// Check if the operation is a bitwise logical operation.
+    pub fn is_bitwise(&self) -> bool {
+        matches!(self, ArithKind::And | ArithKind::Or | ArithKind::Xor)
+    }
+
+    /// Check if the operation is a division or a remainder operation.
+    pub fn is_division(&self) -> bool {
+        matches!(
+            self,
+            ArithKind::UDiv
+                | ArithKind::SDiv
+                | ArithKind::URem
+                | ArithKind::SRem
+                | ArithKind::FDiv
+                | ArithKind::FRem
+        )
+    }
+
+    /// Check if the operation interprets its integer operands as signed.
+    pub fn is_signed(&self) -> bool {
+        matches!(self, ArithKind::SDiv | ArithKind::SRem | ArithKind::AShr)
+    }
+
+    /// Check if the operation is commutative.
+    pub fn is_commutative(&self) -> bool {
+        matches!(
+            self,
+            ArithKind::Add
+                | ArithKind::Mul
+                | ArithKind::And
+                | ArithKind::Or
+                | ArithKind::Xor
+                | ArithKind::FAdd
+                | ArithKind::FMul
+        )
+    }
+}
+
+/// Implement the `Display` trait for `ArithKind`.
+impl Display for ArithKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ArithKind::Add => "add",
+            ArithKind::Sub => "sub",
+            ArithKind::Mul => "mul",
+            ArithKind::UDiv => "udiv",
+            ArithKind::SDiv => "sdiv",
+            ArithKind::URem => "urem",
+            ArithKind::SRem => "srem",
+            ArithKind::Shl => "shl",
+            ArithKind::LShr => "lshr",
+            ArithKind::AShr => "ashr",
+            ArithKind::And => "and",
+            ArithKind::Or => "or",
+            ArithKind::Xor => "xor",
+            ArithKind::FAdd => "fadd",
+            ArithKind::FSub => "fsub",
+            ArithKind::FMul => "fmul",
+            ArithKind::FDiv => "fdiv",
+            ArithKind::FRem => "frem",
+        };
+        write!(f, "{name}")
+    }
+}
@@ -1,7 +1,9 @@
 //! Module handling to the binary operations of LLVM.
 
-use super::{AnyInstruction, AsInstructionValue};
-use inkwell::values::{AnyValue, AsValueRef, BasicValueEnum, InstructionValue};
+use super::{AnyInstruction, ArithKind, AsInstructionValue};
+use inkwell::values::{
+    AnyValue, AsValueRef, BasicValueEnum, FastMathFlags, InstructionValue,
+};
 use llvm_sys::prelude::LLVMValueRef;
 use std::fmt::{self, Display};
 
@@ -25,38 +27,57 @@ impl<'ctx> BinaryOperator<'ctx> {
         }
     }
 
-    /// Get the first operand of the binary operation.
-    pub fn get_first_operand(&self) -> BasicValueEnum<'ctx> {
-        match self.get_operand(0) {
+    /// Get the kind of the binary operation.
+    pub fn kind(&self) -> ArithKind {
+        match ArithKind::from_opcode(self.get_opcode()) {
+            Some(kind) => kind,
             None => panic!(
                 "Invalid binary operator: {}\n{}",
-                self, "Unable to get the first operand!"
+                self, "Unknown arithmetic operation!"
             ),
-            Some(opr) => match opr.left() {
-                None => panic!(
-                    "Invalid binary operator: {}\n{}",
-                    self, "Unable to get the first operand!"
-                ),
-                Some(v) => v,
-            },
         }
     }
 
+    /// Get the negated operand if the binary operation is the canonical form
+    /// of a negation, i.e., `sub 0, x` or `fsub -0.0, x`.
+    pub fn get_negated_operand(&self) -> Option<BasicValueEnum<'ctx>> {
+        let lhs = self.get_first_operand();
+
+        let is_neg = match self.kind() {
+            ArithKind::Sub => {
+                lhs.is_int_value() && lhs.into_int_value().is_null()
+            }
+            ArithKind::FSub => {
+                lhs.is_float_value()
+                    && matches!(
+                        lhs.into_float_value().get_constant(),
+                        Some((c, _)) if c == 0.0 && c.is_sign_negative()
+                    )
+            }
+            _ => false,
+        };
+
+        if is_neg {
+            Some(self.get_second_operand())
+        } else {
+            None
+        }
+    }
+
+    /// Check if the binary operation is the canonical form of a negation, i.e.,
+    /// `sub 0, x` or `fsub -0.0, x`.
+    pub fn is_negation(&self) -> bool {
+        self.get_negated_operand().is_some()
+    }
+
+    /// Get the first operand of the binary operation.
+    pub fn get_first_operand(&self) -> BasicValueEnum<'ctx> {
+        self.expect_value_operand(0)
+    }
+
     /// Get the second operand of the binary operation.
     pub fn get_second_operand(&self) -> BasicValueEnum<'ctx> {
-        match self.get_operand(1) {
-            None => panic!(
-                "Invalid binary operator: {}\n{}",
-                self, "Unable to get the second operand!"
-            ),
-            Some(opr) => match opr.left() {
-                None => panic!(
-                    "Invalid binary operator: {}\n{}",
-                    self, "Unable to get the second operand!"
-                ),
-                Some(v) => v,
-            },
-        }
+        self.expect_value_operand(1)
     }
 
     /// Check if the current binary operator has the `NoUnSignedWrap` (NUW) flag.
@@ -68,6 +89,60 @@ impl<'ctx> BinaryOperator<'ctx> {
     pub fn has_no_signed_wrap(&self) -> bool {
         self.binary_operator.has_no_signed_wrap()
     }
+
+    /// Set the `NoUnSignedWrap` (NUW) flag of the current binary operator.
+    ///
+    /// The underlying instruction is replaced by a new one with the flag, so
+    /// other copies of this `BinaryOperator` must not be used anymore. Return
+    /// `false`, without changing the operator, if it is not an `add`,
+    /// `sub`, `mul` or `shl`.
+    pub fn set_no_unsigned_wrap(&mut self, value: bool) -> bool {
+        self.binary_operator.set_no_unsigned_wrap(value)
+    }
+
+    /// Set the `NoSignedWrap` (NSW) flag of the current binary operator.
+    ///
+    /// The underlying instruction is replaced by a new one with the flag, so
+    /// other copies of this `BinaryOperator` must not be used anymore. Return
+    /// `false`, without changing the operator, if it is not an `add`,
+    /// `sub`, `mul` or `shl`.
+    pub fn set_no_signed_wrap(&mut self, value: bool) -> bool {
+        self.binary_operator.set_no_signed_wrap(value)
+    }
+
+    /// Check if the current binary operator has the `exact` flag.
+    ///
+    /// Only `udiv`, `sdiv`, `lshr` and `ashr` can have this flag.
+    pub fn is_exact(&self) -> bool {
+        self.binary_operator.is_exact()
+    }
+
+    /// Set the `exact` flag of the current binary operator.
+    ///
+    /// The underlying instruction is replaced by a new one with the flag, so
+    /// other copies of this `BinaryOperator` must not be used anymore. Return
+    /// `false`, without changing the operator, if it is not a `udiv`,
+    /// `sdiv`, `lshr` or `ashr`.
+    pub fn set_exact(&mut self, value: bool) -> bool {
+        self.binary_operator.set_exact(value)
+    }
+
+    /// Get the fast-math flags of the current binary operator.
+    ///
+    /// Return `None` if it is not a floating point operation.
+    pub fn get_fast_math_flags(&self) -> Option<FastMathFlags> {
+        self.binary_operator.get_fast_math_flags()
+    }
+
+    /// Set the fast-math flags of the current binary operator.
+    ///
+    /// The underlying instruction is replaced by a new one with the flags, so
+    /// other copies of this `BinaryOperator` must not be used anymore. Return
+    /// `false`, without changing the operator, if it is not a floating point
+    /// operation.
+    pub fn set_fast_math_flags(&mut self, flags: FastMathFlags) -> bool {
+        self.binary_operator.set_fast_math_flags(flags)
+    }
 }
 
 /// Implement the `AsInstructionValue` trait for `BinaryOperator.`
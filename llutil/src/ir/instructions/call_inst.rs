@@ -3,10 +3,27 @@
 use std::fmt::{self, Display};
 
 use inkwell::values::{AnyValue, AsValueRef, InstructionValue};
-use llvm_sys::prelude::LLVMValueRef;
+use llvm_sys::{core::LLVMSetTailCall, prelude::LLVMValueRef};
+use regex::Regex;
 
 use super::{AnyCall, AnyInstruction, AsInstructionValue};
 
+/// Kinds of tail call markers of a `call` instruction.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum TailCallKind {
+    /// No marker.
+    None,
+
+    /// `tail`, hinting that the call can be tail call optimized.
+    Tail,
+
+    /// `musttail`, requiring the call to be tail call optimized.
+    MustTail,
+
+    /// `notail`, preventing the call from being tail call optimized.
+    NoTail,
+}
+
 /// Data structure modelling a `call` instruction.
 ///
 /// LLVM Language Reference Manual for the [`call`
@@ -24,6 +41,61 @@ impl<'ctx> CallInst<'ctx> {
         debug_assert!(inst.is_a_call_inst());
         CallInst { call_inst: inst }
     }
+
+    /// Get the tail call marker of the current `CallInst`.
+    ///
+    /// The marker is parsed from the textual IR, since the LLVM C API only
+    /// exposes whether a call is a `tail` or `musttail` call.
+    pub fn get_tail_call_kind(&self) -> TailCallKind {
+        let regex =
+            Regex::new(r"^\s*(?:%\S+\s*=\s*)?(tail|musttail|notail)\s+call")
+                .unwrap();
+        let inst_str = self.print_to_string();
+
+        match regex.captures(&inst_str) {
+            Some(caps) => match &caps[1] {
+                "tail" => TailCallKind::Tail,
+                "musttail" => TailCallKind::MustTail,
+                _ => TailCallKind::NoTail,
+            },
+            None => TailCallKind::None,
+        }
+    }
+
+    /// Check if the current `CallInst` is a `tail` or a `musttail` call.
+    pub fn is_tail_call(&self) -> bool {
+        self.call_inst.is_tail_call()
+    }
+
+    /// Check if the current `CallInst` is a `musttail` call.
+    pub fn is_must_tail_call(&self) -> bool {
+        self.get_tail_call_kind() == TailCallKind::MustTail
+    }
+
+    /// Check if the current `CallInst` is a `notail` call.
+    pub fn is_no_tail_call(&self) -> bool {
+        self.get_tail_call_kind() == TailCallKind::NoTail
+    }
+
+    /// Mark the current `CallInst` as a `tail` call, or remove its tail call
+    /// marker, including `musttail` and `notail`.
+    pub fn set_tail_call(&self, is_tail_call: bool) {
+        unsafe { LLVMSetTailCall(self.as_value_ref(), is_tail_call as i32) }
+    }
+
+    /// Relax a `musttail` call into a `tail` call, so that transformations
+    /// which break the constraints of `musttail` calls, e.g., inserting
+    /// instructions between a call and its `ret`, keep the module valid.
+    ///
+    /// Return `true` if the current `CallInst` was a `musttail` call.
+    pub fn strip_must_tail(&self) -> bool {
+        if !self.is_must_tail_call() {
+            return false;
+        }
+
+        self.set_tail_call(true);
+        true
+    }
 }
 
 /// Implement the `AsInstructionValue` trait for `CallInst`.
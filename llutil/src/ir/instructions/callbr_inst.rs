@@ -1,7 +1,8 @@
 //! Module handling to the `callbr` instruction of LLVM.
 
-use super::{AnyCall, AnyInstruction, AsInstructionValue};
-use inkwell::values::{AnyValue, AsValueRef, InstructionValue};
+use super::{AnyCall, AnyInstruction, AnyTerminator, AsInstructionValue};
+use crate::ir::{PathCondition, SuccessorBlock};
+use inkwell::values::{AnyValue, AsValueRef, BasicBlock, InstructionValue};
 use llvm_sys::prelude::LLVMValueRef;
 use std::fmt::{self, Display};
 
@@ -12,12 +13,49 @@ pub struct CallBrInst<'ctx> {
     callbr_inst: InstructionValue<'ctx>,
 }
 
+/// Implement methods for `CallBrInst`.
 impl<'ctx> CallBrInst<'ctx> {
     /// Constructor of a `CallBrInst` instruction.
     pub fn new(inst: InstructionValue<'ctx>) -> Self {
         debug_assert!(inst.is_a_callbr_inst());
         CallBrInst { callbr_inst: inst }
     }
+
+    /// Get the default destination block, which is executed when the inline
+    /// assembly falls through.
+    pub fn get_default_destination(&self) -> BasicBlock<'ctx> {
+        match self.get_successor(0) {
+            Some(blk) => blk,
+            None => panic!(
+                "Invalid callbr instruction: {}\n{}",
+                self, "Unable to get the default destination!"
+            ),
+        }
+    }
+
+    /// Get the number of indirect destination blocks.
+    pub fn get_num_indirect_destinations(&self) -> u32 {
+        self.get_num_successors().saturating_sub(1)
+    }
+
+    /// Get the indirect destination blocks, which the inline assembly may
+    /// jump to.
+    pub fn get_indirect_destinations(&self) -> Vec<BasicBlock<'ctx>> {
+        (1..self.get_num_successors())
+            .filter_map(|i| self.get_successor(i))
+            .collect()
+    }
+
+    /// Get successor blocks and their path conditions.
+    ///
+    /// The destination is chosen by the inline assembly, hence all successors
+    /// have no path condition.
+    pub fn get_conditioned_successors(&self) -> Vec<SuccessorBlock<'ctx>> {
+        self.get_successors()
+            .into_iter()
+            .map(|blk| SuccessorBlock::new(PathCondition::None, blk))
+            .collect()
+    }
 }
 
 /// Implement the `AsInstructionValue` trait for `CallBrInst`.
@@ -40,6 +78,9 @@ impl<'ctx> AnyInstruction<'ctx> for CallBrInst<'ctx> {}
 /// Implement the `AnyCall` trait for `CallBrInst`.
 impl<'ctx> AnyCall<'ctx> for CallBrInst<'ctx> {}
 
+/// Implement the `AnyTerminator` trait for `CallBrInst`.
+impl<'ctx> AnyTerminator<'ctx> for CallBrInst<'ctx> {}
+
 /// Implement the `AnyValue` trait for `CallBrInst`.
 impl<'ctx> AnyValue<'ctx> for CallBrInst<'ctx> {}
 
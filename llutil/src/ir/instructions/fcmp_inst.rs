@@ -1,7 +1,16 @@
 //! Module handling to the `fcmp` instruction of LLVM.
 
-use super::{AnyCmp, AnyInstruction, AsInstructionValue};
-use inkwell::values::{AnyValue, AsValueRef, InstructionValue};
+use super::{
+    icmp_inst::is_constant,
+    predicate::{is_ordered_float_predicate, swap_float_predicate},
+    AnyCmp, AnyInstruction, AsInstructionValue,
+};
+use inkwell::{
+    values::{
+        AnyValue, AsValueRef, BasicValueEnum, FastMathFlags, InstructionValue,
+    },
+    FloatPredicate,
+};
 use llvm_sys::prelude::LLVMValueRef;
 use std::convert::TryFrom;
 use std::fmt::{self, Display};
@@ -20,6 +29,69 @@ impl<'ctx> FCmpInst<'ctx> {
         debug_assert!(inst.is_a_fcmp_inst());
         FCmpInst { fcmp_inst: inst }
     }
+
+    /// Get the float predicate of the `FCmpInst`.
+    pub fn get_float_predicate(&self) -> FloatPredicate {
+        match self.fcmp_inst.get_fcmp_predicate() {
+            Some(pred) => pred,
+            None => panic!(
+                "Invalid fcmp instruction: {}\n{}",
+                self, "Unable to get the comparison predicate!"
+            ),
+        }
+    }
+
+    /// Check if the `FCmpInst` is an ordered comparison, i.e., it is false
+    /// when either operand is a NaN.
+    pub fn is_ordered(&self) -> bool {
+        is_ordered_float_predicate(self.get_float_predicate())
+    }
+
+    /// Check if the `FCmpInst` is a signed comparison.
+    ///
+    /// Floating point comparisons have no signedness, so this is always
+    /// `false`; use `is_ordered` to distinguish their predicates instead.
+    pub fn is_signed(&self) -> bool {
+        false
+    }
+
+    /// Get the fast-math flags of the `FCmpInst`.
+    ///
+    /// Flags such as `nnan` and `ninf` allow the comparison to assume that its
+    /// operands are not NaNs or infinities.
+    pub fn get_fast_math_flags(&self) -> FastMathFlags {
+        self.fcmp_inst.get_fast_math_flags().unwrap_or_default()
+    }
+
+    /// Set the fast-math flags of the `FCmpInst`.
+    ///
+    /// The underlying instruction is replaced by a new one with the flags, so
+    /// other copies of this `FCmpInst` must not be used anymore.
+    pub fn set_fast_math_flags(&mut self, flags: FastMathFlags) -> bool {
+        self.fcmp_inst.set_fast_math_flags(flags)
+    }
+
+    /// Get the predicate of the `FCmpInst` when its two operands are swapped.
+    pub fn swap_operands_predicate(&self) -> FloatPredicate {
+        swap_float_predicate(self.get_float_predicate())
+    }
+
+    /// Get the operands and predicate of the `FCmpInst` in a canonical form,
+    /// where a constant operand, if any, is on the right-hand side.
+    ///
+    /// The instruction itself is not modified.
+    pub fn canonicalize(
+        &self,
+    ) -> (BasicValueEnum<'ctx>, FloatPredicate, BasicValueEnum<'ctx>) {
+        let lhs = self.expect_value_operand(0);
+        let rhs = self.expect_value_operand(1);
+
+        if is_constant(lhs) && !is_constant(rhs) {
+            (rhs, self.swap_operands_predicate(), lhs)
+        } else {
+            (lhs, self.get_float_predicate(), rhs)
+        }
+    }
 }
 
 /// Implement the `AsInstructionValue` trait for `FCmpInst`.
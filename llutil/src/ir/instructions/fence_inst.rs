@@ -0,0 +1,115 @@
+//! Module handling to the `fence` instruction of LLVM.
+
+use super::{AnyInstruction, AsInstructionValue};
+use inkwell::{
+    values::{AnyValue, AsValueRef, InstructionValue},
+    AtomicOrdering,
+};
+use llvm_sys::prelude::LLVMValueRef;
+use regex::Regex;
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+/// Synchronization scope of operations of the same thread only.
+pub const SINGLE_THREAD_SCOPE: &str = "singlethread";
+
+/// Data structure modelling a `fence` instruction.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct FenceInst<'ctx> {
+    /// Instruction value corresponding to the `FenceInst`.
+    fence_inst: InstructionValue<'ctx>,
+}
+
+/// Implement methods for `FenceInst`.
+impl<'ctx> FenceInst<'ctx> {
+    /// Constructor of a `FenceInst` instruction.
+    pub fn new(inst: InstructionValue<'ctx>) -> Self {
+        debug_assert!(inst.is_a_fence_inst());
+        FenceInst { fence_inst: inst }
+    }
+
+    /// Parse the synchronization scope and the ordering of the current
+    /// `FenceInst` from its textual IR, since the LLVM C API does not expose
+    /// them for fences.
+    fn parse_fence(&self) -> (Option<String>, String) {
+        let regex =
+            Regex::new(r#"fence\s+(?:syncscope\("([^"]*)"\)\s+)?(\w+)"#)
+                .unwrap();
+        let inst_str = self.print_to_string();
+
+        match regex.captures(&inst_str) {
+            Some(caps) => {
+                let scope = caps.get(1).map(|m| m.as_str().to_string());
+                (scope, caps[2].to_string())
+            }
+            None => panic!("Invalid Fence instruction: {}", self),
+        }
+    }
+
+    /// Get the atomic ordering of the current `FenceInst`.
+    pub fn get_ordering(&self) -> AtomicOrdering {
+        let (_, ordering) = self.parse_fence();
+        match ordering.as_str() {
+            "acquire" => AtomicOrdering::Acquire,
+            "release" => AtomicOrdering::Release,
+            "acq_rel" => AtomicOrdering::AcquireRelease,
+            "seq_cst" => AtomicOrdering::SequentiallyConsistent,
+            _ => panic!("Invalid Fence ordering: {}", self),
+        }
+    }
+
+    /// Get the synchronization scope of the current `FenceInst`.
+    ///
+    /// Return `None` for the default system scope, which synchronizes with
+    /// operations of all threads.
+    pub fn get_sync_scope(&self) -> Option<String> {
+        let (scope, _) = self.parse_fence();
+        scope
+    }
+
+    /// Check if the current `FenceInst` synchronizes only with operations of
+    /// the same thread, e.g., signal handlers.
+    pub fn is_single_thread(&self) -> bool {
+        self.get_sync_scope().as_deref() == Some(SINGLE_THREAD_SCOPE)
+    }
+}
+
+/// Implement the `AsInstructionValue` trait for `FenceInst`.
+impl<'ctx> AsInstructionValue<'ctx> for FenceInst<'ctx> {
+    fn as_instruction_value(&self) -> InstructionValue<'ctx> {
+        self.fence_inst
+    }
+}
+
+/// Implement the `AsValueRef` trait for `FenceInst`.
+impl<'ctx> AsValueRef for FenceInst<'ctx> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.fence_inst.as_value_ref()
+    }
+}
+
+/// Implement the `AnyInstruction` trait for `FenceInst`.
+impl<'ctx> AnyInstruction<'ctx> for FenceInst<'ctx> {}
+
+/// Implement the `AnyValue` trait for `FenceInst`.
+impl<'ctx> AnyValue<'ctx> for FenceInst<'ctx> {}
+
+/// Implement the `Display` trait for `FenceInst`.
+impl<'ctx> Display for FenceInst<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print_to_llvm_string())
+    }
+}
+
+/// Implement the `TryFrom` trait for `FenceInst`.
+impl<'ctx> TryFrom<InstructionValue<'ctx>> for FenceInst<'ctx> {
+    type Error = ();
+
+    fn try_from(inst: InstructionValue<'ctx>) -> Result<Self, Self::Error> {
+        if inst.is_a_fence_inst() {
+            Ok(FenceInst::new(inst))
+        } else {
+            Err(())
+        }
+    }
+}
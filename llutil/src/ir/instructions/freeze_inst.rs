@@ -0,0 +1,73 @@
+//! Module handling to the `freeze` instruction of LLVM.
+
+use super::{AnyInstruction, AsInstructionValue};
+use either::Either::Left;
+use inkwell::values::{AnyValue, AsValueRef, BasicValueEnum, InstructionValue};
+use llvm_sys::prelude::LLVMValueRef;
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+/// Data structure modelling a `freeze` instruction.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct FreezeInst<'ctx> {
+    /// Instruction value corresponding to the `FreezeInst`.
+    freeze_inst: InstructionValue<'ctx>,
+}
+
+/// Implement methods for `FreezeInst`.
+impl<'ctx> FreezeInst<'ctx> {
+    /// Constructor of a `FreezeInst` instruction.
+    pub fn new(inst: InstructionValue<'ctx>) -> Self {
+        debug_assert!(inst.is_a_freeze_inst());
+        FreezeInst { freeze_inst: inst }
+    }
+
+    /// Get the value operand being frozen by the current `FreezeInst`.
+    pub fn get_value_operand(&self) -> BasicValueEnum<'ctx> {
+        if let Some(Left(v)) = self.get_operand(0) {
+            return v;
+        }
+
+        panic!("Invalid Freeze instruction: {}", self)
+    }
+}
+
+/// Implement the `AsInstructionValue` trait for `FreezeInst`.
+impl<'ctx> AsInstructionValue<'ctx> for FreezeInst<'ctx> {
+    fn as_instruction_value(&self) -> InstructionValue<'ctx> {
+        self.freeze_inst
+    }
+}
+
+/// Implement the `AsValueRef` trait for `FreezeInst`.
+impl<'ctx> AsValueRef for FreezeInst<'ctx> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.freeze_inst.as_value_ref()
+    }
+}
+
+/// Implement the `AnyInstruction` trait for `FreezeInst`.
+impl<'ctx> AnyInstruction<'ctx> for FreezeInst<'ctx> {}
+
+/// Implement the `AnyValue` trait for `FreezeInst`.
+impl<'ctx> AnyValue<'ctx> for FreezeInst<'ctx> {}
+
+/// Implement the `Display` trait for `FreezeInst`.
+impl<'ctx> Display for FreezeInst<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print_to_llvm_string())
+    }
+}
+
+/// Implement the `TryFrom` trait for `FreezeInst`.
+impl<'ctx> TryFrom<InstructionValue<'ctx>> for FreezeInst<'ctx> {
+    type Error = ();
+
+    fn try_from(inst: InstructionValue<'ctx>) -> Result<Self, Self::Error> {
+        if inst.is_a_freeze_inst() {
+            Ok(FreezeInst::new(inst))
+        } else {
+            Err(())
+        }
+    }
+}
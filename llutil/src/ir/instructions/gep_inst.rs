@@ -0,0 +1,118 @@
+//! Module handling to the `getelementptr` instruction of LLVM.
+
+use super::{AnyInstruction, AsInstructionValue};
+use inkwell::{
+    types::BasicTypeEnum,
+    values::{
+        AnyValue, AsValueRef, BasicValueEnum, GEPInst as LLVMGEPInst,
+        InstructionValue, PointerValue,
+    },
+};
+use llvm_sys::prelude::LLVMValueRef;
+use std::fmt::{self, Display, Formatter};
+
+/// Data structure modelling a `getelementptr` instruction.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct GEPInst<'ctx> {
+    /// Instruction value corresponding to the `GEPInst`.
+    gep_inst: InstructionValue<'ctx>,
+}
+
+/// Implement methods for `GEPInst`.
+impl<'ctx> GEPInst<'ctx> {
+    /// Constructor of a `GEPInst` instruction.
+    pub fn new(inst: InstructionValue<'ctx>) -> Self {
+        debug_assert!(inst.is_a_getelementptr_inst());
+        GEPInst { gep_inst: inst }
+    }
+
+    /// Get the typed `getelementptr` wrapper of Inkwell.
+    fn as_llvm_gep_inst(&self) -> LLVMGEPInst<'ctx> {
+        LLVMGEPInst::new(self.gep_inst)
+    }
+
+    /// Get the pointer operand, i.e., the base address of the `GEPInst`.
+    pub fn get_pointer_operand(&self) -> PointerValue<'ctx> {
+        match self.as_llvm_gep_inst().get_pointer_operand() {
+            BasicValueEnum::PointerValue(ptr) => ptr,
+            _ => panic!("Invalid GEP instruction: {}", self),
+        }
+    }
+
+    /// Get the number of index operands of the `GEPInst`.
+    pub fn get_num_indices(&self) -> u32 {
+        self.as_llvm_gep_inst().get_num_indices()
+    }
+
+    /// Get the index operand at a position, starting from 0.
+    pub fn get_index(&self, index: u32) -> Option<BasicValueEnum<'ctx>> {
+        self.as_llvm_gep_inst().get_index(index)
+    }
+
+    /// Get all index operands of the `GEPInst`.
+    pub fn get_indices(&self) -> Vec<BasicValueEnum<'ctx>> {
+        self.as_llvm_gep_inst().get_indices()
+    }
+
+    /// Get the constant value of each index operand, or `None` for
+    /// non-constant indices.
+    pub fn get_constant_indices(&self) -> Vec<Option<i64>> {
+        self.as_llvm_gep_inst().get_constant_indices()
+    }
+
+    /// Check if all index operands of the `GEPInst` are constants.
+    pub fn has_all_constant_indices(&self) -> bool {
+        self.as_llvm_gep_inst().has_all_constant_indices()
+    }
+
+    /// Get the source element type, i.e., the type which the first index
+    /// steps over.
+    pub fn get_source_element_type(&self) -> BasicTypeEnum<'ctx> {
+        self.as_llvm_gep_inst().get_source_element_type()
+    }
+
+    /// Check if the `GEPInst` has the `inbounds` flag.
+    pub fn is_in_bounds(&self) -> bool {
+        self.as_llvm_gep_inst().is_in_bounds()
+    }
+}
+
+/// Implement the `AsInstructionValue` trait for `GEPInst`.
+impl<'ctx> AsInstructionValue<'ctx> for GEPInst<'ctx> {
+    fn as_instruction_value(&self) -> InstructionValue<'ctx> {
+        self.gep_inst
+    }
+}
+
+/// Implement the `AsValueRef` trait for `GEPInst`.
+impl<'ctx> AsValueRef for GEPInst<'ctx> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.gep_inst.as_value_ref()
+    }
+}
+
+/// Implement the `AnyInstruction` trait for `GEPInst`.
+impl<'ctx> AnyInstruction<'ctx> for GEPInst<'ctx> {}
+
+/// Implement the `AnyValue` trait for `GEPInst`.
+impl<'ctx> AnyValue<'ctx> for GEPInst<'ctx> {}
+
+/// Implement the `Display` trait for `GEPInst`.
+impl<'ctx> Display for GEPInst<'ctx> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print_to_llvm_string())
+    }
+}
+
+/// Implement the `TryFrom` trait for `GEPInst`.
+impl<'ctx> TryFrom<InstructionValue<'ctx>> for GEPInst<'ctx> {
+    type Error = ();
+
+    fn try_from(inst: InstructionValue<'ctx>) -> Result<Self, Self::Error> {
+        if inst.is_a_getelementptr_inst() {
+            Ok(GEPInst::new(inst))
+        } else {
+            Err(())
+        }
+    }
+}
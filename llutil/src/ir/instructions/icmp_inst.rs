@@ -1,8 +1,16 @@
 //! Module handling to the `icmp` instruction of LLVM.
 
-use super::{AnyCmp, AnyInstruction, AsInstructionValue};
-use inkwell::values::{AnyValue, AsValueRef, InstructionValue};
-use llvm_sys::prelude::LLVMValueRef;
+use super::{
+    predicate::{
+        is_signed_int_predicate, is_unsigned_int_predicate, swap_int_predicate,
+    },
+    AnyCmp, AnyInstruction, AsInstructionValue,
+};
+use inkwell::{
+    values::{AnyValue, AsValueRef, BasicValueEnum, InstructionValue},
+    IntPredicate,
+};
+use llvm_sys::{core::LLVMIsConstant, prelude::LLVMValueRef};
 use std::convert::TryFrom;
 use std::fmt::{self, Display};
 
@@ -20,6 +28,57 @@ impl<'ctx> ICmpInst<'ctx> {
         debug_assert!(inst.is_a_icmp_inst());
         ICmpInst { icmp_inst: inst }
     }
+
+    /// Get the integer predicate of the `ICmpInst`.
+    pub fn get_int_predicate(&self) -> IntPredicate {
+        match self.icmp_inst.get_icmp_predicate() {
+            Some(pred) => pred,
+            None => panic!(
+                "Invalid icmp instruction: {}\n{}",
+                self, "Unable to get the comparison predicate!"
+            ),
+        }
+    }
+
+    /// Check if the `ICmpInst` is a signed comparison.
+    pub fn is_signed(&self) -> bool {
+        is_signed_int_predicate(self.get_int_predicate())
+    }
+
+    /// Check if the `ICmpInst` is an unsigned comparison.
+    pub fn is_unsigned(&self) -> bool {
+        is_unsigned_int_predicate(self.get_int_predicate())
+    }
+
+    /// Check if the `ICmpInst` is an equality comparison (`eq` or `ne`).
+    pub fn is_equality(&self) -> bool {
+        matches!(
+            self.get_int_predicate(),
+            IntPredicate::EQ | IntPredicate::NE
+        )
+    }
+
+    /// Get the predicate of the `ICmpInst` when its two operands are swapped.
+    pub fn swap_operands_predicate(&self) -> IntPredicate {
+        swap_int_predicate(self.get_int_predicate())
+    }
+
+    /// Get the operands and predicate of the `ICmpInst` in a canonical form,
+    /// where a constant operand, if any, is on the right-hand side.
+    ///
+    /// The instruction itself is not modified.
+    pub fn canonicalize(
+        &self,
+    ) -> (BasicValueEnum<'ctx>, IntPredicate, BasicValueEnum<'ctx>) {
+        let lhs = self.expect_value_operand(0);
+        let rhs = self.expect_value_operand(1);
+
+        if is_constant(lhs) && !is_constant(rhs) {
+            (rhs, self.swap_operands_predicate(), lhs)
+        } else {
+            (lhs, self.get_int_predicate(), rhs)
+        }
+    }
 }
 
 /// Implement the `AsInstructionValue` trait for `ICmpInst`.
@@ -64,3 +123,8 @@ impl<'ctx> TryFrom<InstructionValue<'ctx>> for ICmpInst<'ctx> {
         }
     }
 }
+
+/// Check if a value is a constant.
+pub(super) fn is_constant(value: BasicValueEnum) -> bool {
+    unsafe { LLVMIsConstant(value.as_value_ref()) != 0 }
+}
@@ -1,7 +1,7 @@
 //! Module handling to the `invoke` instruction of LLVM.
 
-use super::{AnyCall, AnyInstruction, AsInstructionValue};
-use inkwell::values::{AnyValue, AsValueRef, InstructionValue};
+use super::{AnyCall, AnyInstruction, AnyTerminator, AsInstructionValue};
+use inkwell::values::{AnyValue, AsValueRef, BasicBlock, InstructionValue};
 use llvm_sys::prelude::LLVMValueRef;
 use std::convert::TryFrom;
 use std::fmt::{self, Display};
@@ -20,6 +20,30 @@ impl<'ctx> InvokeInst<'ctx> {
         debug_assert!(inst.is_a_invoke_inst());
         InvokeInst { invoke_inst: inst }
     }
+
+    /// Get the normal destination block, which is executed when the callee
+    /// returns normally.
+    pub fn get_normal_destination(&self) -> BasicBlock<'ctx> {
+        match self.get_successor(0) {
+            Some(blk) => blk,
+            None => panic!(
+                "Invalid invoke instruction: {}\n{}",
+                self, "Unable to get the normal destination!"
+            ),
+        }
+    }
+
+    /// Get the unwind destination block, which is executed when the callee
+    /// throws an exception.
+    pub fn get_unwind_destination(&self) -> BasicBlock<'ctx> {
+        match self.get_successor(1) {
+            Some(blk) => blk,
+            None => panic!(
+                "Invalid invoke instruction: {}\n{}",
+                self, "Unable to get the unwind destination!"
+            ),
+        }
+    }
 }
 
 /// Implement the `AsInstructionValue` trait for `InvokeInst`.
@@ -42,6 +66,9 @@ impl<'ctx> AnyInstruction<'ctx> for InvokeInst<'ctx> {}
 /// Implement the `AnyCall` trait for `InvokeInst`.
 impl<'ctx> AnyCall<'ctx> for InvokeInst<'ctx> {}
 
+/// Implement the `AnyTerminator` trait for `InvokeInst`.
+impl<'ctx> AnyTerminator<'ctx> for InvokeInst<'ctx> {}
+
 /// Implement the `AnyValue` trait for `InvokeInst`.
 impl<'ctx> AnyValue<'ctx> for InvokeInst<'ctx> {}
 
@@ -1,6 +1,6 @@
 //! Module handling to the `load` instruction of LLVM.
 
-use super::{AnyInstruction, AsInstructionValue};
+use super::{AnyInstruction, AnyMemAccess, AsInstructionValue};
 use either::Either::Left;
 use inkwell::values::{AnyValue, AsValueRef, InstructionValue, PointerValue};
 use llvm_sys::prelude::LLVMValueRef;
@@ -51,6 +51,9 @@ impl<'ctx> AsValueRef for LoadInst<'ctx> {
 /// Implement the `AnyInstruction` trait for `LoadInst`.
 impl<'ctx> AnyInstruction<'ctx> for LoadInst<'ctx> {}
 
+/// Implement the `AnyMemAccess` trait for `LoadInst`.
+impl<'ctx> AnyMemAccess<'ctx> for LoadInst<'ctx> {}
+
 /// Implement the `AnyValue` trait for `LoadInst`.
 impl<'ctx> AnyValue<'ctx> for LoadInst<'ctx> {}
 
@@ -0,0 +1,193 @@
+//! Module handling to calls of the memory intrinsics `llvm.memcpy`,
+//! `llvm.memmove` and `llvm.memset` of LLVM.
+
+use super::{AnyCall, AnyInstruction, AsInstructionValue};
+use crate::ir::builtin::llvm_lib;
+use either::Either::Left;
+use inkwell::values::{
+    AnyValue, AsValueRef, BasicValueEnum, InstructionValue, IntValue,
+    PointerValue,
+};
+use llvm_sys::prelude::LLVMValueRef;
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+/// Kinds of memory intrinsics.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum MemIntrinsicKind {
+    /// `llvm.memcpy`, including `llvm.memcpy.inline`, copying between
+    /// non-overlapping memory blocks.
+    Memcpy,
+
+    /// `llvm.memmove`, copying between possibly overlapping memory blocks.
+    Memmove,
+
+    /// `llvm.memset`, filling a memory block with a byte value.
+    Memset,
+}
+
+/// Data structure modelling a call to a memory intrinsic.
+///
+/// LLVM Language Reference Manual for the [standard C/C++ library
+/// intrinsics](https://llvm.org/docs/LangRef.html#standard-c-c-library-intrinsics)
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct MemIntrinsic<'ctx> {
+    /// Call instruction corresponding to the `MemIntrinsic`.
+    call_inst: InstructionValue<'ctx>,
+
+    /// Kind of the `MemIntrinsic`.
+    kind: MemIntrinsicKind,
+}
+
+/// Implement methods for `MemIntrinsic`.
+impl<'ctx> MemIntrinsic<'ctx> {
+    /// Constructor of a `MemIntrinsic` from a call to a memory intrinsic.
+    pub fn new(inst: InstructionValue<'ctx>) -> Self {
+        let kind = get_mem_intrinsic_kind(inst);
+        debug_assert!(kind.is_some());
+        MemIntrinsic {
+            call_inst: inst,
+            kind: kind.unwrap(),
+        }
+    }
+
+    /// Get the kind of the current `MemIntrinsic`.
+    pub fn get_kind(&self) -> MemIntrinsicKind {
+        self.kind
+    }
+
+    /// Check if the current `MemIntrinsic` copies memory, i.e., it is a
+    /// `llvm.memcpy` or a `llvm.memmove`.
+    pub fn is_transfer(&self) -> bool {
+        self.kind != MemIntrinsicKind::Memset
+    }
+
+    /// Get a value argument of the current `MemIntrinsic`.
+    fn expect_argument(&self, index: u32) -> BasicValueEnum<'ctx> {
+        match self.get_operand(index) {
+            Some(Left(v)) => v,
+            _ => panic!("Invalid memory intrinsic: {}", self),
+        }
+    }
+
+    /// Get the destination pointer of the current `MemIntrinsic`.
+    pub fn get_destination(&self) -> PointerValue<'ctx> {
+        self.expect_argument(0).into_pointer_value()
+    }
+
+    /// Get the source pointer of a `llvm.memcpy` or a `llvm.memmove`.
+    ///
+    /// Return `None` for a `llvm.memset`.
+    pub fn get_source(&self) -> Option<PointerValue<'ctx>> {
+        if !self.is_transfer() {
+            return None;
+        }
+
+        Some(self.expect_argument(1).into_pointer_value())
+    }
+
+    /// Get the byte value written by a `llvm.memset`.
+    ///
+    /// Return `None` for a `llvm.memcpy` or a `llvm.memmove`.
+    pub fn get_value(&self) -> Option<IntValue<'ctx>> {
+        if self.is_transfer() {
+            return None;
+        }
+
+        Some(self.expect_argument(1).into_int_value())
+    }
+
+    /// Get the length in bytes of the accessed memory blocks.
+    pub fn get_length(&self) -> IntValue<'ctx> {
+        self.expect_argument(2).into_int_value()
+    }
+
+    /// Get the length in bytes of the accessed memory blocks, if it is a
+    /// constant.
+    pub fn get_constant_length(&self) -> Option<u64> {
+        self.get_length().get_zero_extended_constant()
+    }
+
+    /// Check if the current `MemIntrinsic` is volatile.
+    pub fn is_volatile(&self) -> bool {
+        let is_volatile = self.expect_argument(3).into_int_value();
+        is_volatile.get_zero_extended_constant() == Some(1)
+    }
+}
+
+/// Get the kind of a memory intrinsic called by an instruction, if any.
+fn get_mem_intrinsic_kind(inst: InstructionValue) -> Option<MemIntrinsicKind> {
+    if !inst.is_a_call_inst() {
+        return None;
+    }
+
+    // The called operand is the last operand of a call.
+    let num_operands = inst.get_num_operands();
+    let callee = match inst.get_operand(num_operands.checked_sub(1)?)? {
+        Left(BasicValueEnum::PointerValue(ptr)) => ptr.as_function()?,
+        _ => return None,
+    };
+    let name = callee.get_name().to_str().ok()?;
+
+    // Intrinsics are overloaded by the types of their arguments, e.g.,
+    // `llvm.memcpy.p0i8.p0i8.i64`.
+    let is_intrinsic = |base: &str| {
+        name.strip_prefix(base).map_or(false, |suffix| {
+            suffix.is_empty() || suffix.starts_with('.')
+        })
+    };
+    if is_intrinsic(llvm_lib::LLVM_MEMCPY) {
+        Some(MemIntrinsicKind::Memcpy)
+    } else if is_intrinsic(llvm_lib::LLVM_MEMMOVE) {
+        Some(MemIntrinsicKind::Memmove)
+    } else if is_intrinsic(llvm_lib::LLVM_MEMSET) {
+        Some(MemIntrinsicKind::Memset)
+    } else {
+        None
+    }
+}
+
+/// Implement the `AsInstructionValue` trait for `MemIntrinsic`.
+impl<'ctx> AsInstructionValue<'ctx> for MemIntrinsic<'ctx> {
+    fn as_instruction_value(&self) -> InstructionValue<'ctx> {
+        self.call_inst
+    }
+}
+
+/// Implement the `AsValueRef` trait for `MemIntrinsic`.
+impl<'ctx> AsValueRef for MemIntrinsic<'ctx> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.call_inst.as_value_ref()
+    }
+}
+
+/// Implement the `AnyCall` trait for `MemIntrinsic`.
+impl<'ctx> AnyCall<'ctx> for MemIntrinsic<'ctx> {}
+
+/// Implement the `AnyInstruction` trait for `MemIntrinsic`.
+impl<'ctx> AnyInstruction<'ctx> for MemIntrinsic<'ctx> {}
+
+/// Implement the `AnyValue` trait for `MemIntrinsic`.
+impl<'ctx> AnyValue<'ctx> for MemIntrinsic<'ctx> {}
+
+/// Implement the `Display` trait for `MemIntrinsic`.
+impl<'ctx> Display for MemIntrinsic<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print_to_llvm_string())
+    }
+}
+
+/// Implement the `TryFrom` trait for `MemIntrinsic`.
+impl<'ctx> TryFrom<InstructionValue<'ctx>> for MemIntrinsic<'ctx> {
+    type Error = ();
+
+    fn try_from(inst: InstructionValue<'ctx>) -> Result<Self, Self::Error> {
+        match get_mem_intrinsic_kind(inst) {
+            Some(kind) => Ok(MemIntrinsic {
+                call_inst: inst,
+                kind,
+            }),
+            None => Err(()),
+        }
+    }
+}
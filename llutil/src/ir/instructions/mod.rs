@@ -2,6 +2,7 @@
 
 // Export instruction submodules
 mod alloca_inst;
+mod arith_kind;
 mod binary_operator;
 mod branch_inst;
 mod call_base;
@@ -10,10 +11,15 @@ mod callbr_inst;
 mod cast_inst;
 mod cmp_inst;
 mod fcmp_inst;
+mod fence_inst;
+mod freeze_inst;
+mod gep_inst;
 mod icmp_inst;
 mod indirectbr_inst;
 mod invoke_inst;
 mod load_inst;
+mod mem_intrinsic;
+mod operand_iter;
 mod phi_node;
 mod predicate;
 mod return_inst;
@@ -25,26 +31,33 @@ mod traits;
 mod trunc_inst;
 mod unary_operator;
 mod unreachable_inst;
+mod vaarg_inst;
 mod zext_inst;
 
 // Re-export traits and data structures from submodules.
 pub use crate::ir::instructions::traits::{
-    AnyCall, AnyCast, AnyCmp, AnyCondition, AnyInstruction, AnyTerminator,
-    AsInstructionValue,
+    AnyCall, AnyCast, AnyCmp, AnyCondition, AnyInstruction, AnyMemAccess,
+    AnyTerminator, AsInstructionValue,
 };
 pub use alloca_inst::AllocaInst;
+pub use arith_kind::ArithKind;
 pub use binary_operator::BinaryOperator;
 pub use branch_inst::BranchInst;
 pub use call_base::CallBase;
-pub use call_inst::CallInst;
+pub use call_inst::{CallInst, TailCallKind};
 pub use callbr_inst::CallBrInst;
 pub use cast_inst::CastInst;
 pub use cmp_inst::CmpInst;
 pub use fcmp_inst::FCmpInst;
+pub use fence_inst::{FenceInst, SINGLE_THREAD_SCOPE};
+pub use freeze_inst::FreezeInst;
+pub use gep_inst::GEPInst;
 pub use icmp_inst::ICmpInst;
 pub use indirectbr_inst::IndirectBrInst;
 pub use invoke_inst::InvokeInst;
 pub use load_inst::LoadInst;
+pub use mem_intrinsic::{MemIntrinsic, MemIntrinsicKind};
+pub use operand_iter::OperandIter;
 pub use phi_node::PhiNode;
 pub use predicate::BinaryPredicate::{self, FloatPred, IntPred};
 pub use return_inst::ReturnInst;
@@ -53,6 +66,7 @@ pub use store_inst::StoreInst;
 pub use switch_inst::SwitchInst;
 pub use terminator_inst::TerminatorInst;
 pub use trunc_inst::TruncInst;
-pub use unary_operator::UnaryOperator;
+pub use unary_operator::{UnaryKind, UnaryOperator};
 pub use unreachable_inst::UnreachableInst;
+pub use vaarg_inst::VAArgInst;
 pub use zext_inst::ZExtInst;
@@ -0,0 +1,49 @@
+//! Module providing an iterator over operands of an LLVM instruction.
+
+use either::Either;
+use inkwell::values::{BasicBlock, BasicValueEnum, InstructionValue};
+
+/// Iterator over operands of an `InstructionValue`.
+///
+/// Each operand is either a `BasicValueEnum` or a `BasicBlock`.
+#[derive(Debug)]
+pub struct OperandIter<'ctx> {
+    /// Instruction whose operands are iterated.
+    inst: InstructionValue<'ctx>,
+
+    /// Index of the next operand.
+    index: u32,
+
+    /// Total number of operands of the instruction.
+    num_operands: u32,
+}
+
+/// Implement methods for `OperandIter`.
+impl<'ctx> OperandIter<'ctx> {
+    /// Constructor of an `OperandIter`.
+    pub fn new(inst: InstructionValue<'ctx>) -> Self {
+        OperandIter {
+            inst,
+            index: 0,
+            num_operands: inst.get_num_operands(),
+        }
+    }
+}
+
+/// Implement the `Iterator` trait for `OperandIter`.
+impl<'ctx> Iterator for OperandIter<'ctx> {
+    type Item = Either<BasicValueEnum<'ctx>, BasicBlock<'ctx>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.num_operands {
+            let operand = self.inst.get_operand(self.index);
+            self.index += 1;
+
+            if operand.is_some() {
+                return operand;
+            }
+        }
+
+        None
+    }
+}
@@ -61,6 +61,101 @@ impl BinaryPredicate {
             }
         }
     }
+
+    /// Get the predicate obtained when swapping the two operands of a
+    /// comparison, e.g., `a < b` becomes `b > a`.
+    pub fn swap(&self) -> Self {
+        match self {
+            BinaryPredicate::IntPred(pred) => {
+                BinaryPredicate::IntPred(swap_int_predicate(*pred))
+            }
+            BinaryPredicate::FloatPred(pred) => {
+                BinaryPredicate::FloatPred(swap_float_predicate(*pred))
+            }
+        }
+    }
+
+    /// Check if the predicate is a signed comparison.
+    ///
+    /// Float predicates have no signedness, so this is always `false` for
+    /// them.
+    pub fn is_signed(&self) -> bool {
+        match self {
+            BinaryPredicate::IntPred(pred) => is_signed_int_predicate(*pred),
+            BinaryPredicate::FloatPred(_) => false,
+        }
+    }
+}
+
+/// Get the integer predicate obtained when swapping the two operands of a
+/// comparison.
+pub fn swap_int_predicate(pred: IntPredicate) -> IntPredicate {
+    match pred {
+        IntPredicate::EQ => IntPredicate::EQ,
+        IntPredicate::NE => IntPredicate::NE,
+        IntPredicate::UGT => IntPredicate::ULT,
+        IntPredicate::UGE => IntPredicate::ULE,
+        IntPredicate::ULT => IntPredicate::UGT,
+        IntPredicate::ULE => IntPredicate::UGE,
+        IntPredicate::SGT => IntPredicate::SLT,
+        IntPredicate::SGE => IntPredicate::SLE,
+        IntPredicate::SLT => IntPredicate::SGT,
+        IntPredicate::SLE => IntPredicate::SGE,
+    }
+}
+
+/// Get the float predicate obtained when swapping the two operands of a
+/// comparison.
+pub fn swap_float_predicate(pred: FloatPredicate) -> FloatPredicate {
+    match pred {
+        FloatPredicate::OGE => FloatPredicate::OLE,
+        FloatPredicate::OGT => FloatPredicate::OLT,
+        FloatPredicate::OLE => FloatPredicate::OGE,
+        FloatPredicate::OLT => FloatPredicate::OGT,
+        FloatPredicate::UGE => FloatPredicate::ULE,
+        FloatPredicate::UGT => FloatPredicate::ULT,
+        FloatPredicate::ULE => FloatPredicate::UGE,
+        FloatPredicate::ULT => FloatPredicate::UGT,
+        // Remaining predicates are symmetric.
+        _ => pred,
+    }
+}
+
+/// Check if an integer predicate is a signed comparison.
+pub fn is_signed_int_predicate(pred: IntPredicate) -> bool {
+    matches!(
+        pred,
+        IntPredicate::SGT
+            | IntPredicate::SGE
+            | IntPredicate::SLT
+            | IntPredicate::SLE
+    )
+}
+
+/// Check if an integer predicate is an unsigned comparison.
+pub fn is_unsigned_int_predicate(pred: IntPredicate) -> bool {
+    matches!(
+        pred,
+        IntPredicate::UGT
+            | IntPredicate::UGE
+            | IntPredicate::ULT
+            | IntPredicate::ULE
+    )
+}
+
+/// Check if a float predicate is an ordered comparison, i.e., it is false
+/// when either operand is a NaN.
+pub fn is_ordered_float_predicate(pred: FloatPredicate) -> bool {
+    matches!(
+        pred,
+        FloatPredicate::OEQ
+            | FloatPredicate::OGE
+            | FloatPredicate::OGT
+            | FloatPredicate::OLE
+            | FloatPredicate::OLT
+            | FloatPredicate::ONE
+            | FloatPredicate::ORD
+    )
 }
 
 /// Implement the `Display` trait for `BinaryPredicate`.
@@ -1,6 +1,6 @@
 //! Module handling to the `store` instruction of LLVM.
 
-use super::{AnyInstruction, AsInstructionValue};
+use super::{AnyInstruction, AnyMemAccess, AsInstructionValue};
 use either::Either::Left;
 use inkwell::values::{
     AnyValue, AsValueRef, BasicValueEnum, InstructionValue, PointerValue,
@@ -62,6 +62,9 @@ impl<'ctx> AsValueRef for StoreInst<'ctx> {
 /// Implement the `AnyInstruction` trait for `StoreInst`.
 impl<'ctx> AnyInstruction<'ctx> for StoreInst<'ctx> {}
 
+/// Implement the `AnyMemAccess` trait for `StoreInst`.
+impl<'ctx> AnyMemAccess<'ctx> for StoreInst<'ctx> {}
+
 /// Implement the `AnyValue` trait for `StoreInst`.
 impl<'ctx> AnyValue<'ctx> for StoreInst<'ctx> {}
 
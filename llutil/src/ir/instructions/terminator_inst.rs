@@ -6,7 +6,7 @@
 
 use super::{
     AnyCondition, AnyInstruction, AnyTerminator, AsInstructionValue,
-    BranchInst, IndirectBrInst, InvokeInst, ReturnInst, SwitchInst,
+    BranchInst, CallBrInst, IndirectBrInst, InvokeInst, ReturnInst, SwitchInst,
     UnreachableInst,
 };
 use crate::ir::{InstructionExt, SuccessorBlock};
@@ -41,6 +41,11 @@ impl<'ctx> TerminatorInst<'ctx> {
         self.terminator_inst.try_into_branch_inst()
     }
 
+    /// Convert to `CallBrInst`.
+    pub fn as_callbr_inst(&self) -> Option<CallBrInst<'ctx>> {
+        self.terminator_inst.try_into_callbr_inst()
+    }
+
     /// Convert to `IndirectBrInst`.
     pub fn as_indirectbr_inst(&self) -> Option<IndirectBrInst<'ctx>> {
         self.terminator_inst.try_into_indirectbr_inst()
@@ -74,6 +79,8 @@ impl<'ctx> TerminatorInst<'ctx> {
             indirectbr_inst.get_conditioned_successors()
         } else if let Some(switch_inst) = self.as_switch_inst() {
             switch_inst.get_conditioned_successors()
+        } else if let Some(callbr_inst) = self.as_callbr_inst() {
+            callbr_inst.get_conditioned_successors()
         } else {
             vec![]
         }
@@ -1,19 +1,21 @@
 //! Module defining traits handling instructions.
 
-use super::BinaryPredicate;
+use super::{BinaryPredicate, OperandIter};
 use either::Either;
 use inkwell::{
-    types::{AnyTypeEnum, BasicTypeEnum},
+    types::{AnyType, AnyTypeEnum, BasicTypeEnum},
     values::{
         AsValueRef, BasicBlock, BasicValueEnum, FunctionValue,
-        InstructionOpcode, InstructionValue, PointerValue,
+        InstructionOpcode, InstructionValue, IntValue, PointerValue,
     },
+    AtomicOrdering,
 };
 use llvm_sys::core::{
-    LLVMGetCondition, LLVMGetNumSuccessors, LLVMGetSuccessor, LLVMIsConditional,
+    LLVMGetCondition, LLVMGetNumArgOperands, LLVMGetNumSuccessors,
+    LLVMGetSuccessor, LLVMIsConditional,
 };
 use std::{fmt::Display, panic};
-use Either::Left;
+use Either::{Left, Right};
 
 /// Trait to convert a specific instruction into an `InstructionValue`.
 pub trait AsInstructionValue<'ctx> {
@@ -46,6 +48,95 @@ pub trait AnyInstruction<'ctx>: AsInstructionValue<'ctx> {
         self.as_instruction_value().get_operand(index)
     }
 
+    /// Get an iterator over all operands.
+    fn operand_iter(&self) -> OperandIter<'ctx> {
+        OperandIter::new(self.as_instruction_value())
+    }
+
+    /// Get all operands, including both values and basic blocks.
+    fn get_operands(
+        &self,
+    ) -> Vec<Either<BasicValueEnum<'ctx>, BasicBlock<'ctx>>> {
+        self.operand_iter().collect()
+    }
+
+    /// Get all operands which are values, skipping basic block operands.
+    fn get_value_operands(&self) -> Vec<BasicValueEnum<'ctx>> {
+        self.operand_iter().filter_map(|opr| opr.left()).collect()
+    }
+
+    /// Get types of all operands.
+    ///
+    /// The type of a basic block operand is `None`.
+    fn get_operand_types(&self) -> Vec<Option<BasicTypeEnum<'ctx>>> {
+        self.operand_iter()
+            .map(|opr| opr.left().map(|v| v.get_type()))
+            .collect()
+    }
+
+    /// Get the value operand at `index`.
+    ///
+    /// Panic with a message describing the instruction if the operand does not
+    /// exist or is a basic block.
+    fn expect_value_operand(&self, index: u32) -> BasicValueEnum<'ctx> {
+        match self.get_operand(index) {
+            Some(Left(v)) => v,
+            Some(Right(blk)) => panic!(
+                "Invalid {:?} instruction: {}\n\
+                 Expect operand {} to be a value, but found block: {}",
+                self.get_opcode(),
+                self.as_instruction_value(),
+                index,
+                blk.get_name().to_string_lossy()
+            ),
+            None => panic!(
+                "Invalid {:?} instruction: {}\n\
+                 Expect operand {}, but found only {} operands!",
+                self.get_opcode(),
+                self.as_instruction_value(),
+                index,
+                self.get_num_operands()
+            ),
+        }
+    }
+
+    /// Get all operands as integer values.
+    ///
+    /// Panic with a message listing the operand types if any operand is not an
+    /// integer value.
+    fn expect_int_operands(&self) -> Vec<IntValue<'ctx>> {
+        let operands = self.get_operands();
+        let is_valid = operands
+            .iter()
+            .all(|opr| matches!(opr, Left(v) if v.is_int_value()));
+
+        if !is_valid {
+            let operand_types = self
+                .get_operand_types()
+                .iter()
+                .map(|typ| match typ {
+                    Some(typ) => typ.print_to_string(),
+                    None => "label".to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            panic!(
+                "Invalid {:?} instruction: {}\n\
+                 Expect all operands to be integers, but found: [{}]",
+                self.get_opcode(),
+                self.as_instruction_value(),
+                operand_types
+            )
+        }
+
+        operands
+            .into_iter()
+            .filter_map(|opr| opr.left())
+            .map(|v| v.into_int_value())
+            .collect()
+    }
+
     // /// Check if the current instruction returns a signed integer.
     // fn is_signed_integer(&self) -> bool {
     //     match self.get_opcode() {
@@ -79,26 +170,23 @@ pub trait AnyCall<'ctx>: AnyInstruction<'ctx> + Sized + Display {
 
     /// Get the arguments a function call instruction.
     ///
-    /// The returned value is vector of called arguments.
+    /// The returned value is vector of called arguments, excluding operands
+    /// of operand bundles, destination blocks of `invoke` and `callbr`, and
+    /// the called operand.
     fn get_called_arguments(&self) -> Vec<BasicValueEnum<'ctx>> {
         match self.get_opcode() {
-            InstructionOpcode::Call => {
-                let mut res = vec![];
-                let n = self.get_num_operands();
-                for i in 0..(n - 1) {
-                    res.push(self.get_operand(i).unwrap().left().unwrap());
-                }
-                res
-            }
-
-            InstructionOpcode::CallBr => {
-                todo!("get_callee_arguments: handle CallBr");
-                vec![]
-            }
-
-            InstructionOpcode::Invoke => {
-                todo!("get_callee_arguments: handle CallBr");
-                vec![]
+            InstructionOpcode::Call
+            | InstructionOpcode::CallBr
+            | InstructionOpcode::Invoke => {
+                // Arguments are the first operands of all call instructions,
+                // followed by operand bundles, the destination blocks of an
+                // `invoke` or a `callbr`, and the called operand.
+                let inst = self.as_instruction_value();
+                let num_args =
+                    unsafe { LLVMGetNumArgOperands(inst.as_value_ref()) };
+                (0..num_args)
+                    .map(|index| self.expect_value_operand(index))
+                    .collect()
             }
 
             _ => vec![],
@@ -145,26 +233,12 @@ pub trait AnyCmp<'ctx>: AnyInstruction<'ctx> + Display + Sized {
 
     /// Get the first operand of the comparison instruction.
     fn get_first_operand(self) -> BasicValueEnum<'ctx> {
-        if let Some(Left(v)) = self.get_operand(0) {
-            return v;
-        }
-
-        panic!(
-            "Invalid comparison instruction: {}\n{}",
-            self, "Unable to get the first operand!"
-        )
+        self.expect_value_operand(0)
     }
 
     /// Get the second operand of the comparison instruction.
     fn get_second_operand(self) -> BasicValueEnum<'ctx> {
-        if let Some(Left(v)) = self.get_operand(1) {
-            return v;
-        }
-
-        panic!(
-            "Invalid comparison instruction: {}\n{}",
-            self, "Unable to get the second operand!"
-        )
+        self.expect_value_operand(1)
     }
 }
 
@@ -178,26 +252,12 @@ pub trait AnyCast<'ctx>: AnyInstruction<'ctx> + Display + Sized {
 
     /// Get the source operand of the cast instruction.
     fn get_source_operand(self) -> BasicValueEnum<'ctx> {
-        if let Some(Left(v)) = self.get_operand(0) {
-            return v;
-        }
-
-        panic!(
-            "Invalid casting instruction: {}\n{}",
-            self, "Unable to get the source operand!"
-        )
+        self.expect_value_operand(0)
     }
 
     /// Get the source type of the cast instruction.
     fn get_source_type(self) -> BasicTypeEnum<'ctx> {
-        if let Some(Left(v)) = self.get_operand(0) {
-            return v.get_type();
-        }
-
-        panic!(
-            "Invalid casting instruction: {}\n{}",
-            self, "Unable to get the source type!"
-        )
+        self.expect_value_operand(0).get_type()
     }
 
     /// Get the destination type of the cast instruction.
@@ -216,6 +276,45 @@ pub trait AnyCast<'ctx>: AnyInstruction<'ctx> + Display + Sized {
     }
 }
 
+/// Trait providing utility functions to handle memory access instructions,
+/// including `LoadInst` and `StoreInst`.
+pub trait AnyMemAccess<'ctx>: AnyInstruction<'ctx> + Display + Sized {
+    /// Check if the memory access is volatile.
+    fn is_volatile(&self) -> bool {
+        match self.as_instruction_value().get_volatile() {
+            Ok(volatile) => volatile,
+            Err(msg) => panic!("Invalid memory access: {}\n{}", self, msg),
+        }
+    }
+
+    /// Get the alignment of the memory access.
+    fn get_alignment(&self) -> u32 {
+        match self.as_instruction_value().get_alignment() {
+            Ok(align) => align,
+            Err(msg) => panic!("Invalid memory access: {}\n{}", self, msg),
+        }
+    }
+
+    /// Get the atomic ordering of the memory access.
+    fn get_ordering(&self) -> AtomicOrdering {
+        match self.as_instruction_value().get_atomic_ordering() {
+            Ok(ordering) => ordering,
+            Err(msg) => panic!("Invalid memory access: {}\n{}", self, msg),
+        }
+    }
+
+    /// Check if the memory access is atomic.
+    fn is_atomic(&self) -> bool {
+        self.get_ordering() != AtomicOrdering::NotAtomic
+    }
+
+    /// Check if the memory access is neither volatile nor atomic, so that it
+    /// can be freely removed or reordered.
+    fn is_simple(&self) -> bool {
+        !self.is_volatile() && !self.is_atomic()
+    }
+}
+
 /// Trait providing utility functions to handle terminator instructions.
 pub trait AnyTerminator<'ctx>: AsValueRef {
     /// Get the number of successor blocks of the current `TerminatorInst`.
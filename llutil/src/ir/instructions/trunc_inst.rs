@@ -11,29 +11,29 @@ use super::{AnyCast, AnyInstruction, AsInstructionValue};
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct TruncInst<'ctx> {
     /// Instruction value corresponding to the `TruncInst`.
-    zext_inst: InstructionValue<'ctx>,
+    trunc_inst: InstructionValue<'ctx>,
 }
 
 /// Implement methods for `TruncInst`.
 impl<'ctx> TruncInst<'ctx> {
     /// Constructor of a `TruncInst` instruction.
     pub fn new(inst: InstructionValue<'ctx>) -> Self {
-        debug_assert!(inst.is_a_zext_inst());
-        TruncInst { zext_inst: inst }
+        debug_assert!(inst.is_a_trunc_inst());
+        TruncInst { trunc_inst: inst }
     }
 }
 
 /// Implement the `AsInstructionValue` trait for `TruncInst`.
 impl<'ctx> AsInstructionValue<'ctx> for TruncInst<'ctx> {
     fn as_instruction_value(&self) -> InstructionValue<'ctx> {
-        self.zext_inst
+        self.trunc_inst
     }
 }
 
 /// Implement the `AsValueRef` trait for `TruncInst`.
 impl<'ctx> AsValueRef for TruncInst<'ctx> {
     fn as_value_ref(&self) -> LLVMValueRef {
-        self.zext_inst.as_value_ref()
+        self.trunc_inst.as_value_ref()
     }
 }
 
@@ -58,7 +58,7 @@ impl<'ctx> TryFrom<InstructionValue<'ctx>> for TruncInst<'ctx> {
     type Error = ();
 
     fn try_from(inst: InstructionValue<'ctx>) -> Result<Self, Self::Error> {
-        if inst.is_a_zext_inst() {
+        if inst.is_a_trunc_inst() {
             Ok(TruncInst::new(inst))
         } else {
             Err(())
@@ -1,11 +1,49 @@
 //! Module handling to the unary operations of LLVM.
 
 use super::{AnyInstruction, AsInstructionValue};
-use inkwell::values::{AnyValue, AsValueRef, InstructionValue};
+use inkwell::values::{
+    AnyValue, AsValueRef, BasicValueEnum, InstructionOpcode, InstructionValue,
+};
 use llvm_sys::prelude::LLVMValueRef;
 use std::convert::TryFrom;
 use std::fmt::{self, Display};
 
+/// Kinds of unary operations.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum UnaryKind {
+    /// Floating point negation `fneg`.
+    FNeg,
+}
+
+/// Implement methods for `UnaryKind`.
+impl UnaryKind {
+    /// Get the unary kind of an instruction opcode.
+    ///
+    /// Return `None` if the opcode is not a unary operation.
+    pub fn from_opcode(opcode: InstructionOpcode) -> Option<Self> {
+        match opcode {
+            InstructionOpcode::FNeg => Some(UnaryKind::FNeg),
+            _ => None,
+        }
+    }
+
+    /// Get the instruction opcode of the unary kind.
+    pub fn get_opcode(&self) -> InstructionOpcode {
+        match self {
+            UnaryKind::FNeg => InstructionOpcode::FNeg,
+        }
+    }
+}
+
+/// Implement the `Display` trait for `UnaryKind`.
+impl Display for UnaryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryKind::FNeg => write!(f, "fneg"),
+        }
+    }
+}
+
 /// Data structure modelling a unary operation.
 ///
 /// The current supported unary operations are: `fneg`.
@@ -19,11 +57,27 @@ pub struct UnaryOperator<'ctx> {
 impl<'ctx> UnaryOperator<'ctx> {
     /// Constructor of a `UnaryOperator`
     pub fn new(inst: InstructionValue<'ctx>) -> Self {
-        debug_assert!(inst.is_a_binary_operator());
+        debug_assert!(inst.is_a_unary_operator());
         UnaryOperator {
             unary_operator: inst,
         }
     }
+
+    /// Get the kind of the unary operation.
+    pub fn kind(&self) -> UnaryKind {
+        match UnaryKind::from_opcode(self.get_opcode()) {
+            Some(kind) => kind,
+            None => panic!(
+                "Invalid unary operator: {}\n{}",
+                self, "Unknown unary operation!"
+            ),
+        }
+    }
+
+    /// Get the operand of the unary operation.
+    pub fn get_operand_value(&self) -> BasicValueEnum<'ctx> {
+        self.expect_value_operand(0)
+    }
 }
 
 /// Implement the `AsInstructionValue` trait for `UnaryOperator`.
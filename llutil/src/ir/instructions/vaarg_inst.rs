@@ -0,0 +1,76 @@
+//! Module handling to the `va_arg` instruction of LLVM.
+
+use super::{AnyInstruction, AsInstructionValue};
+use either::Either::Left;
+use inkwell::values::{AnyValue, AsValueRef, InstructionValue, PointerValue};
+use llvm_sys::prelude::LLVMValueRef;
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+/// Data structure modelling a `va_arg` instruction.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct VAArgInst<'ctx> {
+    /// Instruction value corresponding to the `VAArgInst`.
+    vaarg_inst: InstructionValue<'ctx>,
+}
+
+/// Implement methods for `VAArgInst`.
+impl<'ctx> VAArgInst<'ctx> {
+    /// Constructor of a `VAArgInst` instruction.
+    pub fn new(inst: InstructionValue<'ctx>) -> Self {
+        debug_assert!(inst.is_a_vaarg_inst());
+        VAArgInst { vaarg_inst: inst }
+    }
+
+    /// Get the pointer operand of the current `VAArgInst`, which points to
+    /// the `va_list` being read.
+    pub fn get_pointer_operand(&self) -> PointerValue<'ctx> {
+        if let Some(Left(v)) = self.get_operand(0) {
+            if v.is_pointer_value() {
+                return v.into_pointer_value();
+            }
+        }
+
+        panic!("Invalid VAArg instruction: {}", self)
+    }
+}
+
+/// Implement the `AsInstructionValue` trait for `VAArgInst`.
+impl<'ctx> AsInstructionValue<'ctx> for VAArgInst<'ctx> {
+    fn as_instruction_value(&self) -> InstructionValue<'ctx> {
+        self.vaarg_inst
+    }
+}
+
+/// Implement the `AsValueRef` trait for `VAArgInst`.
+impl<'ctx> AsValueRef for VAArgInst<'ctx> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.vaarg_inst.as_value_ref()
+    }
+}
+
+/// Implement the `AnyInstruction` trait for `VAArgInst`.
+impl<'ctx> AnyInstruction<'ctx> for VAArgInst<'ctx> {}
+
+/// Implement the `AnyValue` trait for `VAArgInst`.
+impl<'ctx> AnyValue<'ctx> for VAArgInst<'ctx> {}
+
+/// Implement the `Display` trait for `VAArgInst`.
+impl<'ctx> Display for VAArgInst<'ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print_to_llvm_string())
+    }
+}
+
+/// Implement the `TryFrom` trait for `VAArgInst`.
+impl<'ctx> TryFrom<InstructionValue<'ctx>> for VAArgInst<'ctx> {
+    type Error = ();
+
+    fn try_from(inst: InstructionValue<'ctx>) -> Result<Self, Self::Error> {
+        if inst.is_a_vaarg_inst() {
+            Ok(VAArgInst::new(inst))
+        } else {
+            Err(())
+        }
+    }
+}
@@ -5,47 +5,84 @@ pub mod any_value;
 pub mod array_value;
 pub mod basic_block;
 pub mod basic_value;
+pub mod block_traversal;
 pub mod builtin;
 pub mod callable_value;
+pub mod cfg;
 pub mod code_file;
+pub mod color_print;
+pub mod debug_info;
 pub mod float;
 pub mod function_value;
 pub mod instruction;
+pub mod instruction_index;
 pub mod instructions;
 pub mod int_value;
 pub mod metadata_value;
 pub mod module;
+pub mod module_index;
 pub mod path_condition;
 pub mod pointer;
 pub mod predecessor_block;
+pub mod pretty_print;
+pub mod source_snippet;
+pub mod struct_layout;
 pub mod struct_value;
 pub mod successor_block;
+pub mod symbol;
+pub mod tag;
+pub mod type_fingerprint;
+pub mod use_iter;
 pub mod vector_value;
 
 // Re-export sub-modules' data structures
 pub use crate::ir::instructions::{
     AllocaInst, AnyCall, AnyCast, AnyCmp, AnyCondition, AnyInstruction,
-    AnyTerminator, AsInstructionValue, BinaryOperator, BinaryPredicate,
-    BranchInst, CallBase, CallBrInst, CallInst, CastInst, CmpInst, FCmpInst,
-    FloatPred, ICmpInst, IndirectBrInst, IntPred, InvokeInst, LoadInst,
-    PhiNode, ReturnInst, SExtInst, StoreInst, SwitchInst, TerminatorInst,
-    TruncInst, UnaryOperator, UnreachableInst, ZExtInst,
+    AnyMemAccess, AnyTerminator, ArithKind, AsInstructionValue, BinaryOperator,
+    BinaryPredicate, BranchInst, CallBase, CallBrInst, CallInst, CastInst,
+    CmpInst, FCmpInst, FenceInst, FloatPred, FreezeInst, GEPInst, ICmpInst,
+    IndirectBrInst, IntPred, InvokeInst, LoadInst, MemIntrinsic,
+    MemIntrinsicKind, OperandIter, PhiNode, ReturnInst, SExtInst, StoreInst,
+    SwitchInst, TailCallKind, TerminatorInst, TruncInst, UnaryKind,
+    UnaryOperator, UnreachableInst, VAArgInst, ZExtInst,
 };
 pub use any_value::AnyValueExt;
 pub use array_value::ArrayExt;
 pub use basic_block::Blocks;
 pub use basic_value::BasicValueExt;
+pub use block_traversal::{
+    compute_post_order, compute_reverse_post_order, iter_breadth_first,
+    iter_post_order, iter_pre_order, iter_reverse_post_order, BreadthFirstIter,
+    PreOrderIter,
+};
 pub use callable_value::CallableExt;
+pub use cfg::{CfgEdge, ControlFlowGraph};
 pub use code_file::CodeFile;
+pub use color_print::ColorPrinter;
+pub use debug_info::{
+    get_inline_stack, is_inlined, DebugIntrinsicKind, InlineFrame,
+    SourceFields, SourceLocation, SourceTypes, SourceVariables,
+};
 pub use float::FloatExt;
 pub use function_value::{FunctionExt, FunctionOption, Functions};
 pub use instruction::InstructionExt;
+pub use instruction_index::InstructionIndex;
 pub use int_value::IntExt;
 pub use metadata_value::MetadataExt;
 pub use module::ModuleExt;
+pub use module_index::ModuleIndex;
 pub use path_condition::PathCondition;
 pub use pointer::PointerExt;
 pub use predecessor_block::PredecessorBlock;
+pub use pretty_print::PrettyPrintOptions;
+pub use source_snippet::{SourceSnippet, DEFAULT_CONTEXT_LINES};
+pub use struct_layout::{
+    diff_struct_layouts, get_named_structs, FieldDifference, StructLayoutDiff,
+};
 pub use struct_value::StructExt;
 pub use successor_block::SuccessorBlock;
+pub use symbol::{find_unresolved_symbols, Symbol, SymbolKind};
+pub use tag::TagExt;
+pub use type_fingerprint::{find_function_definition, TypeFingerprint};
+pub use use_iter::{UseIter, UserIter};
 pub use vector_value::VectorExt;
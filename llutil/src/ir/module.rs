@@ -1,19 +1,159 @@
 //! Module provide additional utilities to handle LLVM `Module`.
 
-use inkwell::module::Module;
+use std::collections::BTreeMap;
+
+use inkwell::{
+    intrinsics::Intrinsic,
+    module::Module,
+    types::{AnyType, BasicTypeEnum, StructType},
+    values::FunctionValue,
+};
+
+use super::{
+    instructions::OperandIter, struct_layout::strip_numeric_suffix, symbol,
+    type_fingerprint::collect_named_structs, FunctionExt, SourceLocation,
+    Symbol,
+};
 
 /// Trait provide utilities to handle `Module`.
-pub trait ModuleExt {
+pub trait ModuleExt<'ctx> {
     /// Get name of the module or return a default name.
     fn get_name_or_default(&self) -> String;
+
+    /// Get all functions of the module in source order.
+    ///
+    /// Functions having debug information are ordered by the source files and
+    /// lines of their declarations, then by their names, and come before
+    /// functions without debug information, which are ordered by their names.
+    /// Functions with the same order keep their relative positions in the
+    /// module.
+    fn get_functions_sorted(&self) -> Vec<FunctionValue<'ctx>>;
+
+    /// Get all symbols of the module, similarly to `llvm-nm`.
+    fn get_symbols(&self) -> Vec<Symbol>;
+
+    /// Get the symbols defined in the module.
+    fn get_defined_symbols(&self) -> Vec<Symbol>;
+
+    /// Get the symbols referenced but not defined in the module.
+    fn get_undefined_symbols(&self) -> Vec<Symbol>;
+
+    /// Get all named structs used by the module, ordered by their names.
+    ///
+    /// The structs are found from the types of functions, global variables,
+    /// instructions and their operands, and of the structs using them.
+    fn get_struct_types(&self) -> Vec<StructType<'ctx>>;
+
+    /// Get a named struct used by the module by its name in the IR, without
+    /// the leading `%`, e.g., `struct.Foo`.
+    ///
+    /// A struct renamed by LLVM with a numeric suffix, e.g., `struct.Foo.0`,
+    /// is found if the module has no struct of the exact name.
+    fn get_struct_type_by_name(&self, name: &str) -> Option<StructType<'ctx>>;
+
+    /// Get or insert the declaration of an LLVM intrinsic by its name, e.g.,
+    /// `llvm.sadd.with.overflow`.
+    ///
+    /// Overloaded intrinsics are declared for the given parameter types, e.g.,
+    /// `llvm.sadd.with.overflow.i64` for `i64`. Return `None` if there is no
+    /// such intrinsic, or if it is overloaded but no type is given.
+    fn get_intrinsic_declaration(
+        &self,
+        name: &str,
+        param_types: &[BasicTypeEnum<'ctx>],
+    ) -> Option<FunctionValue<'ctx>>;
 }
 
 /// Implement the trait `ModuleExt` for `Module`.
-impl<'ctx> ModuleExt for Module<'ctx> {
+impl<'ctx> ModuleExt<'ctx> for Module<'ctx> {
     fn get_name_or_default(&self) -> String {
         match self.get_name().to_str() {
             Ok(name) => name.to_string(),
             _ => "<unknown-module>".to_string(),
         }
     }
+
+    fn get_functions_sorted(&self) -> Vec<FunctionValue<'ctx>> {
+        let mut funcs = self.get_functions().collect::<Vec<_>>();
+
+        funcs.sort_by_cached_key(|func| {
+            let name = func.get_name_or_default();
+            match SourceLocation::from_function(*func) {
+                Some(loc) => (0, loc.file, loc.line, name),
+                None => (1, String::new(), 0, name),
+            }
+        });
+
+        funcs
+    }
+
+    fn get_symbols(&self) -> Vec<Symbol> {
+        symbol::get_symbols(self)
+    }
+
+    fn get_defined_symbols(&self) -> Vec<Symbol> {
+        let symbols = self.get_symbols().into_iter();
+        symbols.filter(|sym| sym.is_defined).collect()
+    }
+
+    fn get_undefined_symbols(&self) -> Vec<Symbol> {
+        let symbols = self.get_symbols().into_iter();
+        symbols.filter(|sym| !sym.is_defined).collect()
+    }
+
+    fn get_struct_types(&self) -> Vec<StructType<'ctx>> {
+        let mut structs = BTreeMap::new();
+
+        for func in self.get_functions() {
+            let ty = func.get_type().as_any_type_enum();
+            collect_named_structs(ty, &mut structs);
+
+            let insts = func
+                .get_basic_blocks()
+                .into_iter()
+                .flat_map(|blk| blk.get_instructions());
+            for inst in insts {
+                collect_named_structs(inst.get_type(), &mut structs);
+                for operand in OperandIter::new(inst) {
+                    if let Some(value) = operand.left() {
+                        let ty = value.get_type().as_any_type_enum();
+                        collect_named_structs(ty, &mut structs)
+                    }
+                }
+            }
+        }
+
+        for global in self.get_globals() {
+            let ty = global.as_pointer_value().get_type().as_any_type_enum();
+            collect_named_structs(ty, &mut structs)
+        }
+
+        structs.into_values().collect()
+    }
+
+    fn get_struct_type_by_name(&self, name: &str) -> Option<StructType<'ctx>> {
+        let structs = self.get_struct_types();
+        let get_name = |struct_type: &StructType| match struct_type.get_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => String::new(),
+        };
+
+        let exact = structs.iter().find(|st| get_name(st) == name);
+        exact
+            .or_else(|| {
+                let base = strip_numeric_suffix(name);
+                structs
+                    .iter()
+                    .find(|st| strip_numeric_suffix(&get_name(st)) == base)
+            })
+            .copied()
+    }
+    fn get_intrinsic_declaration(
+        &self,
+        name: &str,
+        param_types: &[BasicTypeEnum<'ctx>],
+    ) -> Option<FunctionValue<'ctx>> {
+        let intrinsic = Intrinsic::find(name)?;
+        intrinsic.get_declaration(self, param_types)
+    }
 }
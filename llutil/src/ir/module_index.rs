@@ -0,0 +1,83 @@
+//! Module providing a cross-reference index of values in an LLVM `Module`.
+//!
+//! The index maps instructions to their parent functions and blocks, and
+//! global values to the functions they define. It is precomputed once per
+//! module, so that analyses can find the defining function of a value without
+//! calling `FunctionValue::get_parent` or threading the `Module` around.
+
+use std::collections::HashMap;
+
+use inkwell::{
+    module::Module,
+    values::{BasicBlock, FunctionValue, GlobalValue, InstructionValue},
+};
+
+/// Data structure indexing the defining function and block of values in a
+/// module.
+///
+/// The index must be rebuilt when the module is modified.
+#[derive(Debug, Clone)]
+pub struct ModuleIndex<'ctx> {
+    /// Mapping from instructions to their parent functions and blocks.
+    instructions: HashMap<
+        InstructionValue<'ctx>,
+        (FunctionValue<'ctx>, BasicBlock<'ctx>),
+    >,
+
+    /// Mapping from global values to the functions they define.
+    globals: HashMap<GlobalValue<'ctx>, FunctionValue<'ctx>>,
+}
+
+/// Implement methods for `ModuleIndex`.
+impl<'ctx> ModuleIndex<'ctx> {
+    /// Constructor, which indexes all functions of a module.
+    pub fn new(module: &Module<'ctx>) -> Self {
+        let mut instructions = HashMap::new();
+        let mut globals = HashMap::new();
+
+        for func in module.get_functions() {
+            globals.insert(func.as_global_value(), func);
+
+            for blk in func.get_basic_blocks() {
+                for inst in blk.get_instructions() {
+                    instructions.insert(inst, (func, blk));
+                }
+            }
+        }
+
+        ModuleIndex {
+            instructions,
+            globals,
+        }
+    }
+
+    /// Get the parent function of an instruction.
+    pub fn get_parent_function(
+        &self,
+        inst: InstructionValue<'ctx>,
+    ) -> Option<FunctionValue<'ctx>> {
+        self.instructions.get(&inst).map(|(func, _)| *func)
+    }
+
+    /// Get the parent block of an instruction.
+    pub fn get_parent_block(
+        &self,
+        inst: InstructionValue<'ctx>,
+    ) -> Option<BasicBlock<'ctx>> {
+        self.instructions.get(&inst).map(|(_, blk)| *blk)
+    }
+
+    /// Get the function defined by a global value, if the global value is a
+    /// function of the indexed module.
+    pub fn get_global_function(
+        &self,
+        global: GlobalValue<'ctx>,
+    ) -> Option<FunctionValue<'ctx>> {
+        self.globals.get(&global).cloned()
+    }
+
+    /// Check if an instruction belongs to the indexed module.
+    pub fn contains_instruction(&self, inst: InstructionValue<'ctx>) -> bool {
+        self.instructions.contains_key(&inst)
+    }
+}
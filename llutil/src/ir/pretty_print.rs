@@ -0,0 +1,40 @@
+//! Module providing options of pretty printers of LLVM IR.
+
+/// Data structure representing options of pretty printers, used to truncate
+/// the output of huge functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrettyPrintOptions {
+    /// Maximum number of printed instructions per block, if any.
+    pub max_insts_per_block: Option<usize>,
+
+    /// Maximum number of printed blocks per function, if any.
+    pub max_blocks: Option<usize>,
+}
+
+/// Implement methods for `PrettyPrintOptions`.
+impl PrettyPrintOptions {
+    /// Constructor of options printing everything.
+    pub fn new() -> Self {
+        PrettyPrintOptions::default()
+    }
+
+    /// Set the maximum number of printed instructions per block.
+    pub fn with_max_insts_per_block(mut self, max_insts: usize) -> Self {
+        self.max_insts_per_block = Some(max_insts);
+        self
+    }
+
+    /// Set the maximum number of printed blocks per function.
+    pub fn with_max_blocks(mut self, max_blocks: usize) -> Self {
+        self.max_blocks = Some(max_blocks);
+        self
+    }
+}
+
+/// Print an ellipsis line reporting the number of omitted items.
+pub(crate) fn print_ellipsis(num_omitted: usize, item: &str) -> String {
+    match num_omitted {
+        1 => format!("... (1 more {})", item),
+        _ => format!("... ({} more {}s)", num_omitted, item),
+    }
+}
@@ -0,0 +1,191 @@
+//! Module extracting snippets of the original source code at debug locations,
+//! e.g., to show the code of a finding in a report.
+//!
+//! The paths of debug locations are those seen by the compiler, which may not
+//! exist anymore when the bitcode is analyzed, e.g., when it was compiled in
+//! another directory, or when the source file was renamed. The source file is
+//! therefore searched among the following candidates, in order:
+//!
+//! - The path of the debug location.
+//! - The path of the debug location, relative to the directory of the
+//!   original source file of the `CodeFile`.
+//! - The file name of the debug location, in the directory of the original
+//!   source file of the `CodeFile`.
+//! - The original source file of the `CodeFile` itself, if it has the same
+//!   extension as the file of the debug location, assuming that it was
+//!   renamed after compilation.
+//!
+//! No snippet is extracted if no candidate exists or contains the line of the
+//! debug location.
+
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use inkwell::values::InstructionValue;
+
+use super::{CodeFile, SourceLocation};
+
+/// Default number of lines shown before and after the line of a snippet.
+pub const DEFAULT_CONTEXT_LINES: u32 = 2;
+
+/// Data structure representing a snippet of source code around a location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSnippet {
+    /// Location marked by the snippet.
+    pub location: SourceLocation,
+
+    /// Path of the source file from which the snippet is loaded.
+    pub path: PathBuf,
+
+    /// Lines of the snippet, with their line numbers starting from 1.
+    pub lines: Vec<(u32, String)>,
+}
+
+/// Implement methods for `SourceSnippet`.
+impl SourceSnippet {
+    /// Constructor, loading the lines of the source file of a location, with
+    /// `context_lines` lines before and after the line of the location.
+    ///
+    /// Return `None` if the source file cannot be found or read, or if it
+    /// does not contain the line of the location.
+    pub fn load(
+        location: &SourceLocation,
+        code_file: &CodeFile,
+        context_lines: u32,
+    ) -> Option<Self> {
+        find_source_candidates(location, code_file)
+            .into_iter()
+            .find_map(|path| {
+                let content = fs::read_to_string(&path).ok()?;
+                SourceSnippet::from_content(
+                    location,
+                    path,
+                    &content,
+                    context_lines,
+                )
+            })
+    }
+
+    /// Constructor, loading the snippet of the debug location of an
+    /// instruction, if it has one.
+    pub fn from_instruction(
+        inst: InstructionValue,
+        code_file: &CodeFile,
+        context_lines: u32,
+    ) -> Option<Self> {
+        let location = SourceLocation::from_instruction(inst)?;
+        SourceSnippet::load(&location, code_file, context_lines)
+    }
+
+    /// Constructor of a snippet from the content of a source file.
+    fn from_content(
+        location: &SourceLocation,
+        path: PathBuf,
+        content: &str,
+        context_lines: u32,
+    ) -> Option<Self> {
+        let num_lines = content.lines().count() as u32;
+        if location.line == 0 || location.line > num_lines {
+            return None;
+        }
+
+        let first_line = location.line.saturating_sub(context_lines).max(1);
+        let last_line = location.line.saturating_add(context_lines);
+        let lines = content
+            .lines()
+            .zip(1..)
+            .skip_while(|(_, number)| *number < first_line)
+            .take_while(|(_, number)| *number <= last_line)
+            .map(|(line, number)| (number, line.to_string()))
+            .collect();
+
+        Some(SourceSnippet {
+            location: location.clone(),
+            path,
+            lines,
+        })
+    }
+
+    /// Get the source line of the location of the snippet.
+    pub fn get_marked_line(&self) -> Option<&str> {
+        self.lines
+            .iter()
+            .find(|(number, _)| *number == self.location.line)
+            .map(|(_, line)| line.as_str())
+    }
+
+    /// Get the caret marker of the column of the location, aligned with the
+    /// marked line.
+    ///
+    /// Tabs before the column are kept, so that the caret is aligned however
+    /// tabs are displayed. Return `None` if the column is unknown.
+    pub fn get_caret_marker(&self) -> Option<String> {
+        if self.location.column == 0 {
+            return None;
+        }
+
+        let line = self.get_marked_line()?;
+        let prefix: String = line
+            .chars()
+            .take(self.location.column as usize - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        Some(format!("{}^", prefix))
+    }
+}
+
+/// Implement the trait `Display` for `SourceSnippet`.
+impl Display for SourceSnippet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = match self.lines.last() {
+            Some((number, _)) => number.to_string().len(),
+            None => 1,
+        };
+
+        write!(f, "{:width$}--> {}", "", self.location, width = width)?;
+        for (number, line) in &self.lines {
+            write!(f, "\n{:>width$} | {}", number, line, width = width)?;
+            if *number == self.location.line {
+                if let Some(marker) = self.get_caret_marker() {
+                    write!(f, "\n{:width$} | {}", "", marker, width = width)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the candidate paths of the source file of a location.
+fn find_source_candidates(
+    location: &SourceLocation,
+    code_file: &CodeFile,
+) -> Vec<PathBuf> {
+    let loc_path = Path::new(&location.file);
+    let mut candidates = vec![loc_path.to_path_buf()];
+
+    let source_file = match &code_file.source_file_name {
+        Some(source_file) => Path::new(source_file),
+        None => Path::new(&code_file.file_name),
+    };
+    if let Some(dir) = source_file.parent() {
+        if loc_path.is_relative() {
+            candidates.push(dir.join(loc_path));
+        }
+        if let Some(file_name) = loc_path.file_name() {
+            candidates.push(dir.join(file_name));
+        }
+    }
+
+    if source_file.extension() == loc_path.extension() {
+        candidates.push(source_file.to_path_buf());
+    }
+
+    candidates.dedup();
+    candidates.retain(|path| path.is_file());
+    candidates
+}
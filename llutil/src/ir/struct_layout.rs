@@ -0,0 +1,238 @@
+//! Module comparing the layouts of named structs between modules.
+//!
+//! Modules compiled separately, e.g., from different crates, may define
+//! structurally different versions of a named struct, when they are built
+//! against different versions of a shared definition. Such ABI drift makes
+//! unsound any assumption relating accesses to the struct across modules.
+//!
+//! LLVM renames a named struct loaded into a context which already has a
+//! struct of the same name, by adding a numeric suffix such as `.0`. Structs
+//! are therefore matched by their names without these suffixes.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use inkwell::{
+    module::Module,
+    types::{AnyType, StructType},
+};
+use regex::Regex;
+
+use super::{type_fingerprint::print_body, ModuleExt, TypeFingerprint};
+
+/// Field-level difference between two versions of a named struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldDifference {
+    /// A field whose type differs between the two versions.
+    Changed {
+        /// Index of the field.
+        index: usize,
+
+        /// Type of the field in the left version.
+        left_type: String,
+
+        /// Type of the field in the right version.
+        right_type: String,
+    },
+
+    /// A field which exists only in the right version.
+    Added {
+        /// Index of the field.
+        index: usize,
+
+        /// Type of the field.
+        field_type: String,
+    },
+
+    /// A field which exists only in the left version.
+    Removed {
+        /// Index of the field.
+        index: usize,
+
+        /// Type of the field.
+        field_type: String,
+    },
+}
+
+/// Data structure representing the layout difference of a named struct
+/// defined by two modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayoutDiff {
+    /// Name of the struct, without the numeric suffix added by LLVM.
+    pub name: String,
+
+    /// Body of the struct in the left module.
+    pub left_body: String,
+
+    /// Body of the struct in the right module.
+    pub right_body: String,
+
+    /// Differences between fields of the two versions, in field order.
+    pub field_diffs: Vec<FieldDifference>,
+}
+
+/// Implement the trait `Display` for `FieldDifference`.
+impl Display for FieldDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldDifference::Changed {
+                index,
+                left_type,
+                right_type,
+            } => write!(f, "field {}: {} -> {}", index, left_type, right_type),
+            FieldDifference::Added { index, field_type } => {
+                write!(f, "field {}: added {}", index, field_type)
+            }
+            FieldDifference::Removed { index, field_type } => {
+                write!(f, "field {}: removed {}", index, field_type)
+            }
+        }
+    }
+}
+
+/// Implement the trait `Display` for `StructLayoutDiff`.
+impl Display for StructLayoutDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Struct {}:", self.name)?;
+        write!(f, "\n  left:  {}", self.left_body)?;
+        write!(f, "\n  right: {}", self.right_body)?;
+        for diff in &self.field_diffs {
+            write!(f, "\n  - {}", diff)?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the named structs used by a module, indexed by their names without
+/// the numeric suffix added by LLVM.
+pub fn get_named_structs<'ctx>(
+    module: &Module<'ctx>,
+) -> BTreeMap<String, StructType<'ctx>> {
+    // Structs are ordered by their names, so the struct without suffix comes
+    // first and is kept when several names have the same base.
+    let mut named_structs = BTreeMap::new();
+    for struct_type in module.get_struct_types() {
+        let name = match struct_type.get_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => continue,
+        };
+        named_structs
+            .entry(strip_numeric_suffix(&name).to_string())
+            .or_insert(struct_type);
+    }
+
+    named_structs
+}
+
+/// Find the named structs defined by both modules whose layouts differ, and
+/// report their field-level differences.
+///
+/// Opaque structs are only declared, so they are not compared.
+pub fn diff_struct_layouts(
+    left: &Module,
+    right: &Module,
+) -> Vec<StructLayoutDiff> {
+    let right_structs = get_named_structs(right);
+
+    get_named_structs(left)
+        .into_iter()
+        .filter_map(|(name, left_struct)| {
+            let right_struct = *right_structs.get(&name)?;
+            diff_struct_layout(&name, left_struct, right_struct)
+        })
+        .collect()
+}
+
+/// Compare the layouts of two versions of a named struct.
+///
+/// Return `None` if they have the same layout up to the numeric suffixes of
+/// struct names, or if any of them is opaque.
+fn diff_struct_layout(
+    name: &str,
+    left: StructType,
+    right: StructType,
+) -> Option<StructLayoutDiff> {
+    if left.is_opaque() || right.is_opaque() {
+        return None;
+    }
+
+    if TypeFingerprint::new(left) == TypeFingerprint::new(right) {
+        return None;
+    }
+
+    let left_fields = get_field_type_names(left);
+    let right_fields = get_field_type_names(right);
+    if left_fields == right_fields && left.is_packed() == right.is_packed() {
+        return None;
+    }
+
+    let num_fields = left_fields.len().max(right_fields.len());
+
+    let field_diffs = (0..num_fields)
+        .filter_map(|index| {
+            match (left_fields.get(index), right_fields.get(index)) {
+                (Some(left_type), Some(right_type))
+                    if left_type == right_type =>
+                {
+                    None
+                }
+                (Some(left_type), Some(right_type)) => {
+                    Some(FieldDifference::Changed {
+                        index,
+                        left_type: left_type.clone(),
+                        right_type: right_type.clone(),
+                    })
+                }
+                (Some(field_type), None) => Some(FieldDifference::Removed {
+                    index,
+                    field_type: field_type.clone(),
+                }),
+                (None, Some(field_type)) => Some(FieldDifference::Added {
+                    index,
+                    field_type: field_type.clone(),
+                }),
+                (None, None) => None,
+            }
+        })
+        .collect();
+
+    Some(StructLayoutDiff {
+        name: name.to_string(),
+        left_body: print_body(left),
+        right_body: print_body(right),
+        field_diffs,
+    })
+}
+
+/// Get the types of fields of a struct, with the names of named structs
+/// stripped of their numeric suffixes.
+fn get_field_type_names(struct_type: StructType) -> Vec<String> {
+    struct_type
+        .get_field_types()
+        .iter()
+        .map(|ty| strip_struct_suffixes(&ty.print_to_string()))
+        .collect()
+}
+
+/// Strip the numeric suffix, e.g., `.0`, which LLVM adds to the name of a
+/// struct to make it unique in its context.
+pub(crate) fn strip_numeric_suffix(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((base, suffix))
+            if !base.is_empty()
+                && !suffix.is_empty()
+                && suffix.bytes().all(|c| c.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => name,
+    }
+}
+
+/// Strip the numeric suffixes of all struct names of a printed type.
+fn strip_struct_suffixes(type_str: &str) -> String {
+    let regex = Regex::new(r"(%[\w.]+?)\.\d+\b").unwrap();
+    regex.replace_all(type_str, "$1").to_string()
+}
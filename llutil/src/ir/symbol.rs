@@ -0,0 +1,167 @@
+//! Module listing symbols of LLVM modules, similarly to `llvm-nm`.
+//!
+//! A symbol is a function or a global variable which is visible in the
+//! symbol table of the object file compiled from a module. Intrinsics,
+//! globals reserved by LLVM such as `llvm.global_ctors`, and globals of
+//! `private` linkage are therefore not symbols.
+//!
+//! Symbols of several modules can be compared to plan their linking, e.g., to
+//! find the undefined symbols which no module defines.
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+};
+
+use inkwell::{
+    module::{Linkage, Module},
+    values::GlobalValue,
+};
+
+/// Prefix of names of intrinsics and globals reserved by LLVM.
+const LLVM_RESERVED_PREFIX: &str = "llvm.";
+
+/// Kinds of symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// A function.
+    Function,
+
+    /// A global variable.
+    GlobalVariable,
+}
+
+/// Data structure representing a symbol of a module.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Symbol {
+    /// Name of the symbol.
+    pub name: String,
+
+    /// Kind of the symbol.
+    pub kind: SymbolKind,
+
+    /// Linkage of the symbol.
+    pub linkage: Linkage,
+
+    /// Whether the symbol is defined in its module.
+    pub is_defined: bool,
+
+    /// Whether the symbol is a constant global variable.
+    pub is_constant: bool,
+}
+
+/// Implement methods for `Symbol`.
+impl Symbol {
+    /// Constructor of a `Symbol` from a global value and its kind.
+    ///
+    /// Return `None` if the global value is not a symbol.
+    pub fn new(global: GlobalValue, kind: SymbolKind) -> Option<Self> {
+        let name = global.get_name().to_string_lossy().to_string();
+        let linkage = global.get_linkage();
+        if name.is_empty()
+            || name.starts_with(LLVM_RESERVED_PREFIX)
+            || linkage == Linkage::Private
+        {
+            return None;
+        }
+
+        let is_constant = match kind {
+            SymbolKind::Function => false,
+            SymbolKind::GlobalVariable => global.is_constant(),
+        };
+
+        Some(Symbol {
+            name,
+            kind,
+            linkage,
+            is_defined: !global.is_declaration(),
+            is_constant,
+        })
+    }
+
+    /// Check if the symbol is local to its module, i.e., it cannot resolve
+    /// references of other modules.
+    pub fn is_local(&self) -> bool {
+        self.linkage == Linkage::Internal
+    }
+
+    /// Check if the symbol is weak, i.e., it can be overridden by another
+    /// definition of the same name when linking.
+    pub fn is_weak(&self) -> bool {
+        matches!(
+            self.linkage,
+            Linkage::ExternalWeak
+                | Linkage::LinkOnceAny
+                | Linkage::LinkOnceODR
+                | Linkage::WeakAny
+                | Linkage::WeakODR
+                | Linkage::Common
+        )
+    }
+
+    /// Get the type letter of the symbol, as printed by `llvm-nm`.
+    ///
+    /// Upper-case letters denote global symbols, and lower-case letters
+    /// denote local symbols.
+    pub fn get_type_letter(&self) -> char {
+        let letter = match (self.is_defined, self.kind) {
+            (false, _) if self.linkage == Linkage::ExternalWeak => 'w',
+            (false, _) => return 'U',
+            (true, _) if self.linkage == Linkage::Common => 'C',
+            (true, SymbolKind::Function) if self.is_weak() => 'W',
+            (true, SymbolKind::GlobalVariable) if self.is_weak() => 'V',
+            (true, SymbolKind::Function) => 'T',
+            (true, SymbolKind::GlobalVariable) if self.is_constant => 'R',
+            (true, SymbolKind::GlobalVariable) => 'D',
+        };
+
+        if self.is_local() {
+            letter.to_ascii_lowercase()
+        } else {
+            letter
+        }
+    }
+}
+
+/// Implement the trait `Display` for `Symbol`.
+impl Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.get_type_letter(), self.name)
+    }
+}
+
+/// Get all symbols of a module, functions before global variables, in the
+/// order of the module.
+pub fn get_symbols(module: &Module) -> Vec<Symbol> {
+    let funcs = module.get_functions().filter_map(|func| {
+        Symbol::new(func.as_global_value(), SymbolKind::Function)
+    });
+    let globals = module
+        .get_globals()
+        .filter_map(|global| Symbol::new(global, SymbolKind::GlobalVariable));
+
+    funcs.chain(globals).collect()
+}
+
+/// Find the undefined symbols of modules which are not defined by any of
+/// these modules, i.e., which must be provided by external libraries when
+/// linking the modules together.
+///
+/// Each unresolved symbol is returned once, in the order of the modules.
+pub fn find_unresolved_symbols(modules: &[Module]) -> Vec<Symbol> {
+    let symbols: Vec<Symbol> = modules.iter().flat_map(get_symbols).collect();
+
+    let defined_names: HashSet<&str> = symbols
+        .iter()
+        .filter(|sym| sym.is_defined && !sym.is_local())
+        .map(|sym| sym.name.as_str())
+        .collect();
+
+    let mut visited_names = HashSet::new();
+    symbols
+        .iter()
+        .filter(|sym| !sym.is_defined && !defined_names.contains(&*sym.name))
+        .filter(|sym| visited_names.insert(sym.name.clone()))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,79 @@
+//! Module providing tags to annotate functions and basic blocks.
+//!
+//! Tags are key-value strings attached to LLVM values, which allow passes of a
+//! pipeline to communicate with each other, e.g., to record that a function
+//! was specialized from another function. Tags are stored in the IR itself:
+//! function tags are string attributes of the function, and block tags are
+//! metadata of the block terminator.
+
+use inkwell::{
+    attributes::AttributeLoc,
+    values::{BasicBlock, FunctionValue},
+};
+
+/// Prefix of attribute and metadata names used to store tags.
+pub const TAG_PREFIX: &str = "llutil.tag.";
+
+/// Trait providing utilities to put and get tags of LLVM values.
+pub trait TagExt {
+    /// Put a tag `key` with `value` to the current value, overriding the
+    /// existing tag of the same key.
+    ///
+    /// Return `false` if the value cannot be tagged.
+    fn put_tag(&self, key: &str, value: &str) -> bool;
+
+    /// Get value of the tag `key` of the current value, if any.
+    fn get_tag(&self, key: &str) -> Option<String>;
+}
+
+/// Implement the trait `TagExt` for `FunctionValue`.
+impl<'ctx> TagExt for FunctionValue<'ctx> {
+    fn put_tag(&self, key: &str, value: &str) -> bool {
+        let tag_key = TAG_PREFIX.to_owned() + key;
+        let context = self.get_type().get_context();
+        let attr = context.create_string_attribute(&tag_key, value);
+
+        self.remove_string_attribute(AttributeLoc::Function, &tag_key);
+        self.add_attribute(AttributeLoc::Function, attr);
+        true
+    }
+
+    fn get_tag(&self, key: &str) -> Option<String> {
+        let tag_key = TAG_PREFIX.to_owned() + key;
+        let attr =
+            self.get_string_attribute(AttributeLoc::Function, &tag_key)?;
+        attr.get_string_value().to_str().ok().map(|v| v.to_owned())
+    }
+}
+
+/// Implement the trait `TagExt` for `BasicBlock`.
+impl<'ctx> TagExt for BasicBlock<'ctx> {
+    fn put_tag(&self, key: &str, value: &str) -> bool {
+        let terminator = match self.get_terminator() {
+            Some(inst) => inst,
+            None => return false,
+        };
+
+        let context = self.get_context();
+        let kind_id = context.get_kind_id(&(TAG_PREFIX.to_owned() + key));
+        let md_string = context.metadata_string(value);
+        let md_node = context.metadata_node(&[md_string.into()]);
+        terminator.set_metadata(md_node, kind_id).is_ok()
+    }
+
+    fn get_tag(&self, key: &str) -> Option<String> {
+        let terminator = self.get_terminator()?;
+        let context = self.get_context();
+        let kind_id = context.get_kind_id(&(TAG_PREFIX.to_owned() + key));
+        let md_node = terminator.get_metadata(kind_id)?;
+
+        match md_node.get_node_values().first() {
+            Some(md_value) if md_value.is_metadata_value() => md_value
+                .into_metadata_value()
+                .get_string_value()
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_owned()),
+            _ => None,
+        }
+    }
+}
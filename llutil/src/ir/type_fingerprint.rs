@@ -0,0 +1,155 @@
+//! Module computing fingerprints of LLVM types, which identify types
+//! independently of the `Context` owning them.
+//!
+//! Types of different contexts are distinct objects, so they cannot be
+//! compared by the equality of `AnyTypeEnum`, e.g., when matching a function
+//! declared in a module with its definition in another module. A fingerprint
+//! is a textual encoding of a type: the type as printed in the IR, followed by
+//! the bodies of all named structs that it references, sorted by their names.
+//!
+//! Two types therefore have the same fingerprint if they are structurally
+//! equal and their named structs have the same names and bodies.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use inkwell::{
+    module::Module,
+    types::{AnyType, AnyTypeEnum, BasicTypeEnum, StructType},
+    values::FunctionValue,
+};
+
+use super::FunctionExt;
+
+/// Data structure representing the fingerprint of a type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TypeFingerprint {
+    /// Textual encoding of the type.
+    encoding: String,
+}
+
+/// Implement methods for `TypeFingerprint`.
+impl TypeFingerprint {
+    /// Constructor of the fingerprint of a type.
+    pub fn new<'ctx, T: AnyType<'ctx>>(ty: T) -> Self {
+        let ty = ty.as_any_type_enum();
+
+        let mut structs = BTreeMap::new();
+        collect_named_structs(ty, &mut structs);
+
+        let mut encoding = ty.print_to_string();
+        for (name, struct_type) in structs {
+            encoding +=
+                &format!("; {} = type {}", name, print_body(struct_type));
+        }
+
+        TypeFingerprint { encoding }
+    }
+
+    /// Constructor of the fingerprint of the type of a function.
+    pub fn of_function(func: FunctionValue) -> Self {
+        TypeFingerprint::new(func.get_type())
+    }
+
+    /// Get the textual encoding of the fingerprint.
+    pub fn as_str(&self) -> &str {
+        &self.encoding
+    }
+
+    /// Get a hash of the fingerprint, which is stable across runs.
+    pub fn get_hash(&self) -> u64 {
+        // Use the 64-bit FNV-1a hash, like `FunctionExt::get_semantic_hash`.
+        self.encoding
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+    }
+}
+
+/// Implement the trait `Display` for `TypeFingerprint`.
+impl Display for TypeFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encoding)
+    }
+}
+
+/// Find the definition of a declared function among modules, possibly of
+/// different contexts, i.e., a defined function of the same name whose type
+/// has the same fingerprint.
+pub fn find_function_definition<'ctx>(
+    decl: FunctionValue,
+    modules: &[Module<'ctx>],
+) -> Option<FunctionValue<'ctx>> {
+    let name = decl.get_name().to_str().ok()?;
+    let fingerprint = TypeFingerprint::of_function(decl);
+
+    modules
+        .iter()
+        .filter_map(|module| module.get_function(name))
+        .filter(|func| !func.is_only_declared())
+        .find(|func| TypeFingerprint::of_function(*func) == fingerprint)
+}
+
+/// Collect named structs referenced by a type, transitively, indexed by their
+/// printed names.
+pub(crate) fn collect_named_structs<'ctx>(
+    ty: AnyTypeEnum<'ctx>,
+    structs: &mut BTreeMap<String, StructType<'ctx>>,
+) {
+    match ty {
+        AnyTypeEnum::ArrayType(t) => collect_named_structs(
+            t.get_element_type().as_any_type_enum(),
+            structs,
+        ),
+        AnyTypeEnum::VectorType(t) => collect_named_structs(
+            t.get_element_type().as_any_type_enum(),
+            structs,
+        ),
+        AnyTypeEnum::PointerType(t) => {
+            collect_named_structs(t.get_element_type(), structs)
+        }
+        AnyTypeEnum::FunctionType(t) => {
+            let ret_type = t.get_return_type().into_iter();
+            for ty in ret_type.chain(t.get_param_types()) {
+                collect_named_structs(ty.as_any_type_enum(), structs)
+            }
+        }
+        AnyTypeEnum::StructType(t) => {
+            if t.get_name().is_some() {
+                // Visit each named struct once, since it can be recursive.
+                let name = t.print_to_string();
+                if structs.insert(name, t).is_some() {
+                    return;
+                }
+            }
+            for ty in t.get_field_types() {
+                collect_named_structs(ty.as_any_type_enum(), structs)
+            }
+        }
+        AnyTypeEnum::FloatType(_)
+        | AnyTypeEnum::IntType(_)
+        | AnyTypeEnum::VoidType(_) => {}
+    }
+}
+
+/// Print the body of a struct, similarly to its definition in the IR.
+pub(crate) fn print_body(struct_type: StructType) -> String {
+    if struct_type.is_opaque() {
+        return "opaque".to_string();
+    }
+
+    let fields: Vec<String> = struct_type
+        .get_field_types()
+        .iter()
+        .map(BasicTypeEnum::print_to_string)
+        .collect();
+    match (struct_type.is_packed(), fields.is_empty()) {
+        (true, true) => "<{}>".to_string(),
+        (true, false) => format!("<{{ {} }}>", fields.join(", ")),
+        (false, true) => "{}".to_string(),
+        (false, false) => format!("{{ {} }}", fields.join(", ")),
+    }
+}
@@ -0,0 +1,82 @@
+//! Module providing lazy iterators over use lists of LLVM values.
+//!
+//! The iterators walk the use list of a value one use at a time, in LLVM's
+//! use-list order, without collecting all uses first. The use-list order is
+//! not stable across runs, so results which must be deterministic should be
+//! sorted.
+
+use inkwell::values::{AnyValueEnum, BasicValueUse};
+
+/// Iterator over the uses of a value.
+#[derive(Debug, Clone)]
+pub struct UseIter<'ctx> {
+    /// Next use to visit.
+    next_use: Option<BasicValueUse<'ctx>>,
+}
+
+/// Implement methods for `UseIter`.
+impl<'ctx> UseIter<'ctx> {
+    /// Constructor, starting from the first use of a value.
+    pub fn new(first_use: Option<BasicValueUse<'ctx>>) -> Self {
+        UseIter {
+            next_use: first_use,
+        }
+    }
+
+    /// Get the users of the visited uses.
+    pub fn users(self) -> UserIter<'ctx> {
+        UserIter { uses: self }
+    }
+}
+
+/// Implement the trait `Iterator` for `UseIter`.
+impl<'ctx> Iterator for UseIter<'ctx> {
+    type Item = BasicValueUse<'ctx>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value_use = self.next_use?;
+        self.next_use = value_use.get_next_use();
+        Some(value_use)
+    }
+}
+
+/// Iterator over the users of a value, one item for each use.
+///
+/// A user occurs several times if it uses the value several times.
+#[derive(Debug, Clone)]
+pub struct UserIter<'ctx> {
+    /// Iterator over the uses of the value.
+    uses: UseIter<'ctx>,
+}
+
+/// Implement the trait `Iterator` for `UserIter`.
+impl<'ctx> Iterator for UserIter<'ctx> {
+    type Item = AnyValueEnum<'ctx>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.uses.next().map(|value_use| value_use.get_user())
+    }
+}
+
+/// Check if a use list has exactly one use.
+pub fn has_single_use(mut uses: UseIter) -> bool {
+    uses.next().is_some() && uses.next().is_none()
+}
+
+/// Get the unique user of a use list, i.e., the user of all uses, which may
+/// use the value several times.
+///
+/// Return `None` if there is no use or if there are several users.
+pub fn get_unique_user(users: UserIter) -> Option<AnyValueEnum> {
+    let mut unique_user = None;
+
+    for user in users {
+        match unique_user {
+            None => unique_user = Some(user),
+            Some(u) if u == user => {}
+            Some(_) => return None,
+        }
+    }
+
+    unique_user
+}
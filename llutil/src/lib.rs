@@ -25,7 +25,21 @@ extern crate rutil;
 #[allow(unused_extern_crates)]
 extern crate llvm_sys;
 
+// Export the logging facade first, so that its macros are available to other
+// sub modules.
+#[macro_use]
+pub mod logger;
+
 // Export sub modules
+pub mod analysis;
+pub mod annotation;
+pub mod bench;
 pub mod file;
+pub mod instrument;
 pub mod ir;
+pub mod normalize;
+pub mod pass;
+pub mod progress;
+pub mod solver;
 pub mod tool;
+pub mod workspace;
@@ -0,0 +1,175 @@
+//! Module providing a pluggable logging facade.
+//!
+//! Library consumers can install their own `Logger` via `set_logger` to
+//! capture logs of normalization, instrumentation and analyses into their own
+//! sinks. Without an installed logger, debug messages are printed by the
+//! `debug!` macro of `rutil`, and other messages are printed to the standard
+//! error.
+
+use std::{
+    fmt::{self, Display},
+    sync::RwLock,
+};
+
+/// Installed logger, if any.
+static LOGGER: RwLock<Option<Box<dyn Logger>>> = RwLock::new(None);
+
+/// Levels of log messages, ordered from the most to the least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Errors.
+    Error,
+
+    /// Warnings.
+    Warn,
+
+    /// Informational messages.
+    Info,
+
+    /// Debugging messages.
+    Debug,
+
+    /// Very verbose debugging messages.
+    Trace,
+}
+
+/// Implement the trait `Display` for `LogLevel`.
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogLevel::Error => write!(f, "ERROR"),
+            LogLevel::Warn => write!(f, "WARN"),
+            LogLevel::Info => write!(f, "INFO"),
+            LogLevel::Debug => write!(f, "DEBUG"),
+            LogLevel::Trace => write!(f, "TRACE"),
+        }
+    }
+}
+
+/// Data structure representing a log record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord<'a> {
+    /// Level of the record.
+    pub level: LogLevel,
+
+    /// Target of the record, usually the module path of the emitter.
+    pub target: &'a str,
+
+    /// Message of the record.
+    pub message: String,
+
+    /// Structured fields of the record, as pairs of keys and values.
+    pub fields: &'a [(&'a str, String)],
+}
+
+/// Implement the trait `Display` for `LogRecord`.
+impl<'a> Display for LogRecord<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.level, self.target, self.message)?;
+        for (key, value) in self.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Trait of a logger receiving log records.
+pub trait Logger: Send + Sync {
+    /// Check if records of a level and a target are logged.
+    fn enabled(&self, _level: LogLevel, _target: &str) -> bool {
+        true
+    }
+
+    /// Log a record.
+    fn log(&self, record: &LogRecord);
+}
+
+/// Install a logger, replacing the previously installed one.
+pub fn set_logger(logger: Box<dyn Logger>) {
+    *LOGGER.write().unwrap() = Some(logger);
+}
+
+/// Uninstall the current logger, restoring the default output.
+pub fn clear_logger() {
+    *LOGGER.write().unwrap() = None;
+}
+
+/// Log a message with structured fields.
+pub fn log_fields(
+    level: LogLevel,
+    target: &str,
+    fields: &[(&str, String)],
+    message: String,
+) {
+    let record = LogRecord {
+        level,
+        target,
+        message,
+        fields,
+    };
+
+    match LOGGER.read().unwrap().as_ref() {
+        Some(logger) => {
+            if logger.enabled(level, target) {
+                logger.log(&record)
+            }
+        }
+        None => match level {
+            LogLevel::Debug | LogLevel::Trace => debug!("{}", record.message),
+            _ => eprintln!("{}", record),
+        },
+    }
+}
+
+/// Log a message.
+pub fn log(level: LogLevel, target: &str, message: String) {
+    log_fields(level, target, &[], message)
+}
+
+/// Log an error message, formatted as in `format!`.
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)+) => {
+        $crate::logger::log(
+            $crate::logger::LogLevel::Error,
+            module_path!(),
+            format!($($arg)+),
+        )
+    };
+}
+
+/// Log a warning message, formatted as in `format!`.
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)+) => {
+        $crate::logger::log(
+            $crate::logger::LogLevel::Warn,
+            module_path!(),
+            format!($($arg)+),
+        )
+    };
+}
+
+/// Log an informational message, formatted as in `format!`.
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)+) => {
+        $crate::logger::log(
+            $crate::logger::LogLevel::Info,
+            module_path!(),
+            format!($($arg)+),
+        )
+    };
+}
+
+/// Log a debugging message, formatted as in `format!`.
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)+) => {
+        $crate::logger::log(
+            $crate::logger::LogLevel::Debug,
+            module_path!(),
+            format!($($arg)+),
+        )
+    };
+}
@@ -0,0 +1,74 @@
+//! Module normalizing the layout of basic blocks of functions.
+//!
+//! Basic blocks of each function are reordered into reverse post-order (RPO)
+//! of the control flow graph, starting from the entry block. Blocks which are
+//! unreachable from the entry block are kept at the end of the function, in
+//! their original relative order.
+
+use std::collections::HashSet;
+
+use inkwell::{
+    module::Module,
+    values::{BasicBlock, FunctionValue},
+};
+
+use crate::{
+    ir::{
+        basic_block::BasicBlockExt, compute_reverse_post_order, FunctionExt,
+        ModuleExt,
+    },
+    progress::{NoProgress, ProgressSink, ProgressTracker},
+};
+
+/// Reorder basic blocks of all functions in a module into reverse post-order.
+pub fn normalize_module(module: &Module) {
+    normalize_module_with_progress(module, &NoProgress)
+}
+
+/// Reorder basic blocks of all functions in a module into reverse post-order,
+/// reporting the progress to a sink.
+pub fn normalize_module_with_progress(
+    module: &Module,
+    progress: &dyn ProgressSink,
+) {
+    let funcs = module.get_functions_sorted();
+    let mut tracker =
+        ProgressTracker::new(progress, "normalization", funcs.len());
+
+    for func in funcs {
+        tracker.start_item(&func.get_name_or_default());
+        normalize_function(func);
+        tracker.finish_item();
+    }
+
+    tracker.finish()
+}
+
+/// Reorder basic blocks of a function into reverse post-order.
+pub fn normalize_function(func: FunctionValue) {
+    let mut blocks = compute_reverse_post_order(func);
+
+    // Append unreachable blocks, keeping their original order.
+    let visited: HashSet<BasicBlock> = blocks.iter().cloned().collect();
+    for blk in func.get_basic_blocks() {
+        if !visited.contains(&blk) {
+            blocks.push(blk)
+        }
+    }
+
+    // Move each block right after its previous block in the new order.
+    let mut prev_blk: Option<BasicBlock> = None;
+    for blk in blocks {
+        if let Some(prev) = prev_blk {
+            if prev.get_next_basic_block() != Some(blk) {
+                blk.move_after(prev).unwrap_or_else(|_| {
+                    panic!(
+                        "Failed to move block: {}",
+                        blk.get_name_or_default()
+                    )
+                });
+            }
+        }
+        prev_blk = Some(blk)
+    }
+}
@@ -0,0 +1,186 @@
+//! Module folding conditional branches on constant conditions.
+//!
+//! A `br` or `switch` instruction whose condition is a constant is replaced by
+//! an unconditional branch to the taken successor. Incoming values from the
+//! current block are removed from `phi` instructions of the other successors,
+//! and blocks which become dead are then removed.
+
+use inkwell::{
+    builder::Builder,
+    module::Module,
+    values::{BasicBlock, BasicValueEnum, FunctionValue},
+};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+};
+
+use crate::{
+    ir::{
+        basic_block::BasicBlockExt, AnyCondition, AnyTerminator, FunctionExt,
+        InstructionExt, ModuleExt,
+    },
+    pass::{AnalysisResults, Pass},
+};
+
+use super::simplify_func::{
+    eliminate_unreachable_blocks, remove_phi_incomings,
+};
+
+/// Data structure reporting the branches folded in a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchFoldReport {
+    /// Name of the function.
+    pub function: String,
+
+    /// Number of folded branches.
+    pub num_folded_branches: usize,
+
+    /// Number of removed dead blocks.
+    pub num_removed_blocks: usize,
+}
+
+/// Implement the trait `Display` for `BranchFoldReport`.
+impl Display for BranchFoldReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: folded {} branches, removed {} blocks",
+            self.function, self.num_folded_branches, self.num_removed_blocks
+        )
+    }
+}
+
+/// Fold branches on constant conditions in all defined functions of a module.
+///
+/// Return one report for each function.
+pub fn fold_branches_in_module(module: &Module) -> Vec<BranchFoldReport> {
+    module
+        .get_functions_sorted()
+        .into_iter()
+        .filter(|func| !func.is_only_declared())
+        .map(fold_branches)
+        .collect()
+}
+
+/// Fold branches on constant conditions in a function and remove dead blocks.
+pub fn fold_branches(func: FunctionValue) -> BranchFoldReport {
+    let mut num_folded_branches = 0;
+
+    if let Some(blk) = func.get_first_basic_block() {
+        let context = blk.get_context();
+        let builder = context.create_builder();
+        for blk in func.get_basic_blocks() {
+            if fold_branch(&builder, blk) {
+                num_folded_branches += 1;
+            }
+        }
+    }
+
+    let num_removed_blocks = match num_folded_branches {
+        0 => 0,
+        _ => eliminate_unreachable_blocks(func),
+    };
+
+    BranchFoldReport {
+        function: func.get_name_or_default(),
+        num_folded_branches,
+        num_removed_blocks,
+    }
+}
+
+/// Fold the terminator of a block if it branches on a constant condition.
+///
+/// Return `true` if the terminator is folded.
+fn fold_branch(builder: &Builder, blk: BasicBlock) -> bool {
+    let term_inst = match blk.get_terminator() {
+        Some(inst) => inst,
+        None => return false,
+    };
+
+    let taken_blk = if let Some(br) = term_inst.try_into_branch_inst() {
+        if !br.has_condition() {
+            return false;
+        }
+        match get_constant(br.get_condition()) {
+            Some(0) => br.get_second_successor(),
+            Some(_) => Some(br.get_first_successor()),
+            None => None,
+        }
+    } else if let Some(switch) = term_inst.try_into_switch_inst() {
+        get_constant(switch.get_condition()).map(|cond| {
+            (0..switch.get_num_cases())
+                .filter_map(|i| switch.get_case_and_successor(i))
+                .find(|(case, _)| get_constant(*case) == Some(cond))
+                .map_or(switch.get_default_successor(), |(_, succ)| succ)
+        })
+    } else {
+        None
+    };
+
+    let taken_blk = match taken_blk {
+        Some(blk) => blk,
+        None => return false,
+    };
+
+    let succ_blks = match term_inst.try_into_terminator_inst() {
+        Some(term) => term.get_successors(),
+        None => return false,
+    };
+
+    // The taken block must keep exactly one incoming edge from the current
+    // block, so skip it if it is reached by several edges and has `phi`s.
+    let num_taken_edges = succ_blks.iter().filter(|b| **b == taken_blk).count();
+    if num_taken_edges > 1 && !taken_blk.get_phi_instructions().is_empty() {
+        return false;
+    }
+
+    let dead_blks: HashSet<BasicBlock> = succ_blks
+        .into_iter()
+        .filter(|succ_blk| *succ_blk != taken_blk)
+        .collect();
+    for dead_blk in dead_blks {
+        remove_phi_incomings(dead_blk, &HashSet::from([blk]));
+    }
+
+    builder.position_before(&term_inst);
+    builder.build_unconditional_branch(taken_blk);
+    term_inst.erase_from_basic_block();
+
+    log_debug!("Folded branch of block: {}", blk.get_name_or_default());
+
+    true
+}
+
+/// Get the value of a constant integer condition.
+fn get_constant(value: BasicValueEnum) -> Option<u64> {
+    if !value.is_int_value() {
+        return None;
+    }
+
+    let value = value.into_int_value();
+    if value.is_const() {
+        value.get_zero_extended_constant()
+    } else {
+        None
+    }
+}
+
+/// Pass folding branches on constant conditions of all functions, to be
+/// scheduled by the `PassManager`.
+#[derive(Debug, Default)]
+pub struct BranchFoldPass {
+    /// Reports of the last run.
+    pub reports: Vec<BranchFoldReport>,
+}
+
+/// Implement the trait `Pass` for `BranchFoldPass`.
+impl Pass for BranchFoldPass {
+    fn get_name(&self) -> String {
+        "branch-fold".to_string()
+    }
+
+    fn run(&mut self, module: &Module, _results: &AnalysisResults) {
+        self.reports = fold_branches_in_module(module);
+    }
+}
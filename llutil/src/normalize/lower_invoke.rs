@@ -0,0 +1,262 @@
+//! Module lowering `invoke` instructions into `call` instructions.
+//!
+//! Each `invoke` instruction is replaced by a `call` instruction followed by
+//! a branch to its normal destination, and landing pads which become
+//! unreachable are removed. The resulting CFG only contains the happy path of
+//! functions, hence this transformation is unsound for executions which throw
+//! exceptions and should only be used when exceptions are irrelevant.
+//!
+//! Call-site attributes, such as `sret` or `byval`, are copied to the `call`
+//! instructions. Since the C API of LLVM cannot build calls with operand
+//! bundles, `invoke` instructions with operand bundles are not lowered.
+
+use inkwell::{
+    builder::Builder,
+    module::Module,
+    values::{
+        AnyValue, AsValueRef, BasicMetadataValueEnum, BasicValue,
+        CallableValue, FunctionValue,
+    },
+};
+use llvm_sys::{
+    core::{
+        LLVMAddCallSiteAttribute, LLVMGetCallSiteAttributeCount,
+        LLVMGetCallSiteAttributes, LLVMGetInstructionCallConv,
+        LLVMGetNumArgOperands, LLVMInstructionEraseFromParent,
+    },
+    prelude::{LLVMAttributeRef, LLVMValueRef},
+    LLVMAttributeFunctionIndex, LLVMAttributeIndex, LLVMAttributeReturnIndex,
+};
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    fmt::{self, Display},
+};
+
+use crate::{
+    ir::{
+        AnyCall, AnyInstruction, AsInstructionValue, FunctionExt,
+        InstructionExt, InvokeInst, ModuleExt,
+    },
+    pass::{AnalysisResults, Pass},
+};
+
+use super::simplify_func::{
+    eliminate_unreachable_blocks, remove_phi_incomings,
+};
+
+/// Warning about the soundness of lowering `invoke` instructions.
+pub const LOWER_INVOKE_WARNING: &str =
+    "exception edges were removed, results are unsound for executions \
+     which throw exceptions";
+
+/// Data structure reporting the `invoke` instructions lowered in a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LowerInvokeReport {
+    /// Name of the function.
+    pub function: String,
+
+    /// Number of lowered `invoke` instructions.
+    pub num_lowered_invokes: usize,
+
+    /// Number of removed blocks, such as unreachable landing pads.
+    pub num_removed_blocks: usize,
+}
+
+/// Implement the trait `Display` for `LowerInvokeReport`.
+impl Display for LowerInvokeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: lowered {} invokes, removed {} blocks",
+            self.function, self.num_lowered_invokes, self.num_removed_blocks
+        )?;
+
+        if self.num_lowered_invokes > 0 {
+            write!(f, "\n  WARNING: {}", LOWER_INVOKE_WARNING)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lower `invoke` instructions of all defined functions of a module.
+///
+/// Return one report for each function.
+pub fn lower_invokes_in_module(module: &Module) -> Vec<LowerInvokeReport> {
+    let reports: Vec<LowerInvokeReport> = module
+        .get_functions_sorted()
+        .into_iter()
+        .filter(|func| !func.is_only_declared())
+        .map(lower_invokes)
+        .collect();
+
+    if reports.iter().any(|report| report.num_lowered_invokes > 0) {
+        log_warn!("Lowering invokes: {}", LOWER_INVOKE_WARNING);
+    }
+
+    reports
+}
+
+/// Lower `invoke` instructions of a function into `call` instructions, and
+/// remove blocks which become unreachable.
+pub fn lower_invokes(func: FunctionValue) -> LowerInvokeReport {
+    let invokes: Vec<InvokeInst> = func
+        .get_basic_blocks()
+        .into_iter()
+        .filter_map(|blk| blk.get_terminator())
+        .filter_map(|inst| inst.try_into_invoke_inst())
+        .collect();
+
+    let mut num_lowered_invokes = 0;
+    if let Some(blk) = func.get_first_basic_block() {
+        let context = blk.get_context();
+        let builder = context.create_builder();
+        for invoke in invokes {
+            if lower_invoke(&builder, invoke) {
+                num_lowered_invokes += 1;
+            }
+        }
+    }
+
+    let num_removed_blocks = match num_lowered_invokes {
+        0 => 0,
+        _ => eliminate_unreachable_blocks(func),
+    };
+
+    LowerInvokeReport {
+        function: func.get_name_or_default(),
+        num_lowered_invokes,
+        num_removed_blocks,
+    }
+}
+
+/// Lower an `invoke` instruction into a `call` instruction followed by a
+/// branch to its normal destination.
+///
+/// Return `false` if the `invoke` instruction cannot be lowered, such as when
+/// its operand bundles or call-site attributes cannot be preserved.
+fn lower_invoke(builder: &Builder, invoke: InvokeInst) -> bool {
+    let inst = invoke.as_instruction_value();
+    let blk = match inst.get_parent() {
+        Some(blk) => blk,
+        None => return false,
+    };
+
+    if has_operand_bundles(&invoke) {
+        return false;
+    }
+
+    let callable = match invoke.get_called_function() {
+        Some(func) => CallableValue::from(func),
+        None => match CallableValue::try_from(invoke.get_called_operand()) {
+            Ok(callable) => callable,
+            Err(_) => return false,
+        },
+    };
+
+    let args: Vec<BasicMetadataValueEnum> = invoke
+        .get_called_arguments()
+        .into_iter()
+        .map(|arg| arg.into())
+        .collect();
+
+    builder.position_before(&inst);
+    let call = builder.build_call(callable, &args, "");
+    call.set_call_convention(unsafe {
+        LLVMGetInstructionCallConv(inst.as_value_ref())
+    });
+
+    let indices = get_attribute_indices(args.len());
+    for &index in &indices {
+        for attr in get_call_site_attributes(inst.as_value_ref(), index) {
+            unsafe {
+                LLVMAddCallSiteAttribute(call.as_value_ref(), index, attr)
+            }
+        }
+    }
+
+    let is_preserved = indices.iter().all(|&index| {
+        get_call_site_attributes(inst.as_value_ref(), index)
+            == get_call_site_attributes(call.as_value_ref(), index)
+    });
+    if !is_preserved {
+        unsafe { LLVMInstructionEraseFromParent(call.as_value_ref()) };
+        return false;
+    }
+
+    if let Some(call_inst) = call
+        .try_as_basic_value()
+        .left()
+        .and_then(|v| v.as_instruction_value())
+    {
+        inst.replace_all_uses_with(&call_inst);
+        if let Some(name) = inst.get_name() {
+            let _ = call_inst.set_name(&name.to_string_lossy());
+        }
+    }
+
+    let normal_blk = invoke.get_normal_destination();
+    let unwind_blk = invoke.get_unwind_destination();
+    builder.build_unconditional_branch(normal_blk);
+    if unwind_blk != normal_blk {
+        remove_phi_incomings(unwind_blk, &HashSet::from([blk]));
+    }
+    inst.erase_from_basic_block();
+
+    true
+}
+
+/// Check if an `invoke` instruction has operand bundles.
+///
+/// Operand bundles are detected from the printed instruction, since the C API
+/// of LLVM does not expose them and bundles may have no operands.
+fn has_operand_bundles(invoke: &InvokeInst) -> bool {
+    let num_args = unsafe { LLVMGetNumArgOperands(invoke.as_value_ref()) };
+
+    // Operands of an `invoke` are its arguments, the operands of its operand
+    // bundles, its two destination blocks and its called operand.
+    invoke.get_num_operands() != num_args + 3
+        || invoke.print_to_string().contains(" [ \"")
+}
+
+/// Get the attribute indices of the function, the return value and the
+/// arguments of a call with a number of arguments.
+fn get_attribute_indices(num_args: usize) -> Vec<LLVMAttributeIndex> {
+    let mut indices =
+        vec![LLVMAttributeFunctionIndex, LLVMAttributeReturnIndex];
+    indices.extend((1..=num_args).map(|index| index as LLVMAttributeIndex));
+    indices
+}
+
+/// Get the call-site attributes of a call instruction at an attribute index.
+fn get_call_site_attributes(
+    call: LLVMValueRef,
+    index: LLVMAttributeIndex,
+) -> Vec<LLVMAttributeRef> {
+    let count = unsafe { LLVMGetCallSiteAttributeCount(call, index) };
+    let mut attrs = vec![std::ptr::null_mut(); count as usize];
+    if count > 0 {
+        unsafe { LLVMGetCallSiteAttributes(call, index, attrs.as_mut_ptr()) };
+    }
+    attrs
+}
+
+/// Pass lowering `invoke` instructions of all functions, to be scheduled by
+/// the `PassManager` when exceptions are irrelevant.
+#[derive(Debug, Default)]
+pub struct LowerInvokePass {
+    /// Reports of the last run.
+    pub reports: Vec<LowerInvokeReport>,
+}
+
+/// Implement the trait `Pass` for `LowerInvokePass`.
+impl Pass for LowerInvokePass {
+    fn get_name(&self) -> String {
+        "lower-invoke".to_string()
+    }
+
+    fn run(&mut self, module: &Module, _results: &AnalysisResults) {
+        self.reports = lower_invokes_in_module(module);
+    }
+}
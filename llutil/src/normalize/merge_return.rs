@@ -0,0 +1,100 @@
+//! Module merging multiple `ret` instructions of a function into a single
+//! exit block.
+//!
+//! Each `ret` instruction is replaced by a branch to a new unified return
+//! block, which returns a `phi` of the original returned values, similarly to
+//! LLVM's `mergereturn` pass.
+
+use inkwell::{module::Module, values::FunctionValue};
+
+use crate::{
+    ir::{AsInstructionValue, FunctionExt, ModuleExt},
+    pass::{AnalysisResults, Pass},
+};
+
+/// Name of the unified return block.
+pub const UNIFIED_RETURN_BLOCK: &str = "unified.return";
+
+/// Name of the `phi` merging returned values in the unified return block.
+pub const UNIFIED_RETURN_VALUE: &str = "unified.retval";
+
+/// Merge `ret` instructions of all defined functions of a module.
+///
+/// Return the number of transformed functions.
+pub fn merge_returns_in_module(module: &Module) -> usize {
+    module
+        .get_functions_sorted()
+        .into_iter()
+        .filter(|func| !func.is_only_declared())
+        .filter(|func| merge_returns(*func))
+        .count()
+}
+
+/// Merge `ret` instructions of a function into a single exit block.
+///
+/// Return `true` if the function is transformed, i.e., it had more than one
+/// `ret` instruction.
+pub fn merge_returns(func: FunctionValue) -> bool {
+    let returns = func.get_return_instructions();
+    if returns.len() <= 1 {
+        return false;
+    }
+
+    let context = match func.get_first_basic_block() {
+        Some(blk) => blk.get_context(),
+        None => return false,
+    };
+    let builder = context.create_builder();
+    let return_blk = context.append_basic_block(func, UNIFIED_RETURN_BLOCK);
+    builder.position_at_end(return_blk);
+
+    match func.get_type().get_return_type() {
+        Some(return_type) => {
+            let phi = builder.build_phi(return_type, UNIFIED_RETURN_VALUE);
+            for ret in &returns {
+                let blk = ret.as_instruction_value().get_parent().unwrap();
+                match ret.get_returned_value() {
+                    Some(value) => phi.add_incoming(&[(&value, blk)]),
+                    None => panic!("Invalid return instruction: {}", ret),
+                }
+            }
+            builder.build_return(Some(&phi.as_basic_value()));
+        }
+        None => {
+            builder.build_return(None);
+        }
+    }
+
+    // Replace each original `ret` by a branch to the unified return block.
+    for ret in returns {
+        let inst = ret.as_instruction_value();
+        builder.position_before(&inst);
+        builder.build_unconditional_branch(return_blk);
+        inst.erase_from_basic_block();
+    }
+
+    true
+}
+
+/// Pass merging `ret` instructions of all functions, to be scheduled by the
+/// `PassManager`.
+#[derive(Debug, Default)]
+pub struct MergeReturnPass {
+    /// Number of functions transformed by the last run.
+    pub num_transformed_functions: usize,
+}
+
+/// Implement the trait `Pass` for `MergeReturnPass`.
+impl Pass for MergeReturnPass {
+    fn get_name(&self) -> String {
+        "merge-return".to_string()
+    }
+
+    fn run(&mut self, module: &Module, _results: &AnalysisResults) {
+        self.num_transformed_functions = merge_returns_in_module(module);
+        log_debug!(
+            "Merged returns of {} functions",
+            self.num_transformed_functions
+        );
+    }
+}
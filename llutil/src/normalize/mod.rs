@@ -0,0 +1,14 @@
+//! Module containing normalization passes for LLVM IR.
+//!
+//! A normalization pass rewrites a module into a canonical form, so that
+//! semantically identical modules produce identical outputs.
+
+// Export sub modules
+pub mod block_order;
+pub mod branch_fold;
+pub mod lower_invoke;
+pub mod merge_return;
+pub mod simplify_func;
+pub mod size_budget;
+pub mod snapshot;
+pub mod unreachable;
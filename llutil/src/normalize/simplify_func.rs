@@ -0,0 +1,326 @@
+//! Module simplifying functions by eliminating dead instructions and blocks.
+//!
+//! An instruction is trivially dead if it has no uses and executing it has no
+//! side effect. Volatile and atomic loads are never considered dead, and
+//! neither are loads whose pointer is computed by a side-effecting
+//! instruction.
+//!
+//! A block is dead if it is unreachable from the entry block of its function.
+
+use inkwell::{
+    module::Module,
+    values::{
+        AsValueRef, BasicBlock, BasicValue, FunctionValue, InstructionOpcode,
+        InstructionValue,
+    },
+};
+use llvm_sys::core::{LLVMGetUndef, LLVMReplaceAllUsesWith, LLVMTypeOf};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+};
+
+use crate::ir::{
+    basic_block::BasicBlockExt, AnyMemAccess, AsInstructionValue, FunctionExt,
+    InstructionExt, ModuleExt,
+};
+
+/// Data structure reporting the instructions eliminated from a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EliminationReport {
+    /// Name of the function.
+    pub function: String,
+
+    /// Number of eliminated unused loads.
+    pub num_loads: usize,
+
+    /// Total number of eliminated instructions, including loads.
+    pub num_insts: usize,
+}
+
+/// Implement the trait `Display` for `EliminationReport`.
+impl Display for EliminationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: eliminated {} instructions ({} loads)",
+            self.function, self.num_insts, self.num_loads
+        )
+    }
+}
+
+/// Eliminate unused loads of a function.
+///
+/// Return the number of eliminated loads.
+pub fn eliminate_unused_load(func: FunctionValue) -> usize {
+    let mut count = 0;
+
+    for blk in func.get_basic_blocks() {
+        for inst in blk.get_instructions() {
+            if inst.get_opcode() == InstructionOpcode::Load
+                && is_trivially_dead(inst)
+            {
+                inst.erase_from_basic_block();
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+/// Eliminate all trivially dead instructions of a function, repeating until no
+/// more instruction can be eliminated.
+pub fn eliminate_trivially_dead_instructions(
+    func: FunctionValue,
+) -> EliminationReport {
+    let mut num_loads = 0;
+    let mut num_insts = 0;
+
+    loop {
+        let mut changed = false;
+
+        for blk in func.get_basic_blocks() {
+            // Visit instructions backward so that operands of an eliminated
+            // instruction are checked after it.
+            for inst in blk.get_instructions().into_iter().rev() {
+                if is_trivially_dead(inst) {
+                    if inst.get_opcode() == InstructionOpcode::Load {
+                        num_loads += 1;
+                    }
+                    inst.erase_from_basic_block();
+                    num_insts += 1;
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    EliminationReport {
+        function: func.get_name_or_default(),
+        num_loads,
+        num_insts,
+    }
+}
+
+/// Eliminate all trivially dead instructions of all defined functions of a
+/// module.
+///
+/// Return one report for each function.
+pub fn eliminate_trivially_dead_instructions_in_module(
+    module: &Module,
+) -> Vec<EliminationReport> {
+    module
+        .get_functions_sorted()
+        .into_iter()
+        .filter(|func| !func.is_only_declared())
+        .map(eliminate_trivially_dead_instructions)
+        .collect()
+}
+
+/// Eliminate blocks of a function which are unreachable from its entry block.
+///
+/// Incoming values from the eliminated blocks are removed from `phi`
+/// instructions of the remaining blocks.
+///
+/// Return the number of eliminated blocks.
+pub fn eliminate_unreachable_blocks(func: FunctionValue) -> usize {
+    let entry_blk = match func.get_first_basic_block() {
+        Some(blk) => blk,
+        None => return 0,
+    };
+
+    let mut reachable_blks = HashSet::new();
+    let mut stack = vec![entry_blk];
+    while let Some(blk) = stack.pop() {
+        if reachable_blks.insert(blk) {
+            stack.extend(blk.get_successors())
+        }
+    }
+
+    let dead_blks: Vec<BasicBlock> = func
+        .get_basic_blocks()
+        .into_iter()
+        .filter(|blk| !reachable_blks.contains(blk))
+        .collect();
+    let dead_blk_set: HashSet<BasicBlock> = dead_blks.iter().cloned().collect();
+
+    for blk in &dead_blks {
+        for succ_blk in blk.get_successors() {
+            if reachable_blks.contains(&succ_blk) {
+                remove_phi_incomings(succ_blk, &dead_blk_set)
+            }
+        }
+    }
+
+    // Drop all uses of values and blocks among dead blocks before deleting
+    // them, since they may refer to each other.
+    for blk in &dead_blks {
+        for inst in blk.get_instructions() {
+            if inst.get_first_use().is_some() {
+                unsafe {
+                    let value = inst.as_value_ref();
+                    LLVMReplaceAllUsesWith(
+                        value,
+                        LLVMGetUndef(LLVMTypeOf(value)),
+                    )
+                }
+            }
+        }
+    }
+
+    for blk in &dead_blks {
+        if let Some(inst) = blk.get_terminator() {
+            inst.erase_from_basic_block()
+        }
+    }
+
+    for blk in &dead_blks {
+        if unsafe { blk.delete() }.is_err() {
+            panic!("Unable to delete block: {}", blk.get_name_or_default())
+        }
+    }
+
+    dead_blks.len()
+}
+
+/// Remove incoming values from some predecessor blocks of all `phi`
+/// instructions of a block.
+///
+/// Each affected `phi` instruction is replaced by a new `phi` instruction
+/// containing only the remaining incoming values.
+pub fn remove_phi_incomings(
+    blk: BasicBlock,
+    removed_blks: &HashSet<BasicBlock>,
+) {
+    let context = blk.get_context();
+    let builder = context.create_builder();
+
+    for phi in blk.get_phi_instructions() {
+        let incomings = phi.get_incomings();
+        if !incomings.iter().any(|(_, b)| removed_blks.contains(b)) {
+            continue;
+        }
+
+        let inst = phi.as_instruction_value();
+        let name = match phi.get_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => "".to_string(),
+        };
+
+        builder.position_before(&inst);
+        let new_phi = builder.build_phi(incomings[0].0.get_type(), "");
+        for (value, incoming_blk) in incomings {
+            if !removed_blks.contains(&incoming_blk) {
+                new_phi.add_incoming(&[(&value, incoming_blk)])
+            }
+        }
+
+        let new_inst = new_phi.as_instruction();
+        inst.replace_all_uses_with(&new_inst);
+        inst.erase_from_basic_block();
+        let _ = new_inst.set_name(&name);
+    }
+}
+
+/// Check if an instruction has no uses and no side effect.
+pub fn is_trivially_dead(inst: InstructionValue) -> bool {
+    inst.get_first_use().is_none() && !has_side_effect(inst)
+}
+
+/// Check if executing an instruction may have a side effect.
+pub fn has_side_effect(inst: InstructionValue) -> bool {
+    match inst.get_opcode() {
+        InstructionOpcode::Add
+        | InstructionOpcode::Sub
+        | InstructionOpcode::Mul
+        | InstructionOpcode::Shl
+        | InstructionOpcode::LShr
+        | InstructionOpcode::AShr
+        | InstructionOpcode::And
+        | InstructionOpcode::Or
+        | InstructionOpcode::Xor
+        | InstructionOpcode::FAdd
+        | InstructionOpcode::FSub
+        | InstructionOpcode::FMul
+        | InstructionOpcode::FDiv
+        | InstructionOpcode::FRem
+        | InstructionOpcode::FNeg
+        | InstructionOpcode::ICmp
+        | InstructionOpcode::FCmp
+        | InstructionOpcode::Trunc
+        | InstructionOpcode::ZExt
+        | InstructionOpcode::SExt
+        | InstructionOpcode::FPToUI
+        | InstructionOpcode::FPToSI
+        | InstructionOpcode::UIToFP
+        | InstructionOpcode::SIToFP
+        | InstructionOpcode::FPTrunc
+        | InstructionOpcode::FPExt
+        | InstructionOpcode::PtrToInt
+        | InstructionOpcode::IntToPtr
+        | InstructionOpcode::BitCast
+        | InstructionOpcode::AddrSpaceCast
+        | InstructionOpcode::GetElementPtr
+        | InstructionOpcode::Select
+        | InstructionOpcode::Phi
+        | InstructionOpcode::Freeze
+        | InstructionOpcode::ExtractValue
+        | InstructionOpcode::InsertValue
+        | InstructionOpcode::ExtractElement
+        | InstructionOpcode::InsertElement
+        | InstructionOpcode::ShuffleVector
+        | InstructionOpcode::Alloca => false,
+
+        // Integer divisions may trap on a zero divisor.
+        InstructionOpcode::UDiv
+        | InstructionOpcode::SDiv
+        | InstructionOpcode::URem
+        | InstructionOpcode::SRem => !has_non_zero_constant_divisor(inst),
+
+        InstructionOpcode::Load => match inst.try_into_load_inst() {
+            Some(load) => {
+                !load.is_simple()
+                    || has_side_effecting_pointer(load.get_pointer_operand())
+            }
+            None => true,
+        },
+
+        _ => true,
+    }
+}
+
+/// Check if the divisor of a division or remainder is a non-zero constant.
+fn has_non_zero_constant_divisor(inst: InstructionValue) -> bool {
+    match inst.get_operand(1).and_then(|opr| opr.left()) {
+        Some(v) if v.is_int_value() => {
+            let divisor = v.into_int_value();
+            divisor.is_const() && !divisor.is_null()
+        }
+        _ => false,
+    }
+}
+
+/// Check if a pointer is computed, through address computations, from a
+/// side-effecting instruction such as a function call.
+fn has_side_effecting_pointer<'ctx, V: BasicValue<'ctx>>(ptr: V) -> bool {
+    let mut inst_opt = ptr.as_instruction_value();
+
+    while let Some(inst) = inst_opt {
+        match inst.get_opcode() {
+            InstructionOpcode::GetElementPtr
+            | InstructionOpcode::BitCast
+            | InstructionOpcode::AddrSpaceCast => {
+                let base = inst.get_operand(0).and_then(|opr| opr.left());
+                inst_opt = base.and_then(|v| v.as_instruction_value())
+            }
+            _ => return has_side_effect(inst),
+        }
+    }
+
+    false
+}
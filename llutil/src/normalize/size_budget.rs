@@ -0,0 +1,494 @@
+//! Module checking sizes of normalized modules against budgets.
+//!
+//! Deployed programs often have size limits, e.g., Solana programs. After
+//! normalization, size metrics of a module are compared against configurable
+//! budgets, and the largest functions and globals are reported together with
+//! suggestions to shrink the module, such as merging duplicate functions or
+//! constants, and removing dead internal functions or globals.
+//!
+//! Sizes of globals are estimated from their types without padding, since no
+//! target data layout is required.
+
+use inkwell::{
+    module::{Linkage, Module},
+    types::{AnyType, BasicTypeEnum},
+    values::{AnyValue, BasicValue, FunctionValue, GlobalValue},
+};
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt::{self, Display},
+    io,
+};
+
+use crate::{
+    ir::{FunctionExt, ModuleExt},
+    pass::{AnalysisResults, Invalidation, Pass},
+};
+
+/// Default number of the largest functions and globals to report.
+pub const DEFAULT_NUM_LARGEST: usize = 5;
+
+/// Size in bytes of pointers, used to estimate sizes of globals.
+const POINTER_SIZE: u64 = 8;
+
+/// Budgets of module sizes. A budget of `None` is not checked.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SizeBudget {
+    /// Maximum size of the module bitcode, in bytes.
+    pub max_bitcode_bytes: Option<u64>,
+
+    /// Maximum number of defined functions.
+    pub max_functions: Option<u64>,
+
+    /// Maximum number of instructions of all functions.
+    pub max_instructions: Option<u64>,
+
+    /// Maximum number of instructions of a single function.
+    pub max_function_instructions: Option<u64>,
+
+    /// Maximum estimated size of all defined globals, in bytes.
+    pub max_global_bytes: Option<u64>,
+}
+
+/// Implement methods for `SizeBudget`.
+impl SizeBudget {
+    /// Parse budgets from a TOML string, e.g., `max_bitcode_bytes = 1000`.
+    pub fn parse(content: &str) -> io::Result<Self> {
+        toml::from_str(content)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// Data structure representing size metrics of a module.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeMetrics {
+    /// Size of the module bitcode, in bytes.
+    pub bitcode_bytes: u64,
+
+    /// Number of defined functions.
+    pub num_functions: u64,
+
+    /// Number of instructions of all functions.
+    pub num_instructions: u64,
+
+    /// Number of defined globals.
+    pub num_globals: u64,
+
+    /// Estimated size of all defined globals, in bytes.
+    pub global_bytes: u64,
+}
+
+/// Data structure representing the size of a function or a global.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeEntry {
+    /// Name of the function or global.
+    pub name: String,
+
+    /// Number of instructions of a function, or estimated size in bytes of a
+    /// global.
+    pub size: u64,
+}
+
+/// Data structure representing a size metric exceeding its budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetViolation {
+    /// Name of the metric, e.g., `bitcode_bytes`, or of the function for the
+    /// budget of instructions per function.
+    pub metric: String,
+
+    /// Actual value of the metric.
+    pub actual: u64,
+
+    /// Budget of the metric.
+    pub limit: u64,
+}
+
+/// Implement the trait `Display` for `BudgetViolation`.
+impl Display for BudgetViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} exceeds budget {}",
+            self.metric, self.actual, self.limit
+        )
+    }
+}
+
+/// Suggestions to shrink a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShrinkSuggestion {
+    /// Functions with identical bodies, which can be merged.
+    MergeDuplicateFunctions(Vec<String>),
+
+    /// Constant globals with identical initializers, which can be merged.
+    MergeDuplicateGlobals(Vec<String>),
+
+    /// An internal function without uses, which can be removed.
+    RemoveDeadFunction(String),
+
+    /// An internal global without uses, which can be removed.
+    RemoveDeadGlobal(String),
+}
+
+/// Implement the trait `Display` for `ShrinkSuggestion`.
+impl Display for ShrinkSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShrinkSuggestion::MergeDuplicateFunctions(names) => {
+                write!(f, "merge duplicate functions: {}", names.join(", "))
+            }
+            ShrinkSuggestion::MergeDuplicateGlobals(names) => {
+                write!(f, "merge duplicate globals: {}", names.join(", "))
+            }
+            ShrinkSuggestion::RemoveDeadFunction(name) => {
+                write!(f, "remove dead function: {}", name)
+            }
+            ShrinkSuggestion::RemoveDeadGlobal(name) => {
+                write!(f, "remove dead global: {}", name)
+            }
+        }
+    }
+}
+
+/// Data structure reporting the size of a module against budgets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeReport {
+    /// Name of the module.
+    pub module: String,
+
+    /// Size metrics of the module.
+    pub metrics: SizeMetrics,
+
+    /// Metrics exceeding their budgets.
+    pub violations: Vec<BudgetViolation>,
+
+    /// Largest functions by their numbers of instructions, in decreasing
+    /// order.
+    pub largest_functions: Vec<SizeEntry>,
+
+    /// Largest globals by their estimated sizes, in decreasing order.
+    pub largest_globals: Vec<SizeEntry>,
+
+    /// Suggestions to shrink the module.
+    pub suggestions: Vec<ShrinkSuggestion>,
+}
+
+/// Implement methods for `SizeReport`.
+impl SizeReport {
+    /// Check if all metrics are within their budgets.
+    pub fn is_within_budget(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Implement the trait `Display` for `SizeReport`.
+impl Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let metrics = &self.metrics;
+        write!(
+            f,
+            "{}: {} bitcode bytes, {} functions, {} instructions, \
+             {} globals ({} bytes)",
+            self.module,
+            metrics.bitcode_bytes,
+            metrics.num_functions,
+            metrics.num_instructions,
+            metrics.num_globals,
+            metrics.global_bytes
+        )?;
+        for violation in &self.violations {
+            write!(f, "\n  - over budget: {}", violation)?;
+        }
+        for entry in &self.largest_functions {
+            write!(
+                f,
+                "\n  - function {}: {} instructions",
+                entry.name, entry.size
+            )?;
+        }
+        for entry in &self.largest_globals {
+            write!(f, "\n  - global {}: {} bytes", entry.name, entry.size)?;
+        }
+        for suggestion in &self.suggestions {
+            write!(f, "\n  - suggestion: {}", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check the size of a module against budgets, reporting the `num_largest`
+/// largest functions and globals.
+pub fn check_module_size(
+    module: &Module,
+    budget: &SizeBudget,
+    num_largest: usize,
+) -> SizeReport {
+    let funcs: Vec<FunctionValue> = module
+        .get_functions_sorted()
+        .into_iter()
+        .filter(|func| !func.is_only_declared())
+        .collect();
+    let globals: Vec<GlobalValue> = module
+        .get_globals()
+        .filter(|global| !global.is_declaration())
+        .collect();
+
+    let mut func_sizes: Vec<SizeEntry> = funcs
+        .iter()
+        .map(|func| SizeEntry {
+            name: func.get_name_or_default(),
+            size: count_instructions(*func),
+        })
+        .collect();
+    let mut global_sizes: Vec<SizeEntry> = globals
+        .iter()
+        .map(|global| SizeEntry {
+            name: global.get_name().to_string_lossy().to_string(),
+            size: estimate_global_size(*global),
+        })
+        .collect();
+
+    let metrics = SizeMetrics {
+        bitcode_bytes: module.write_bitcode_to_memory().get_size() as u64,
+        num_functions: funcs.len() as u64,
+        num_instructions: func_sizes.iter().map(|entry| entry.size).sum(),
+        num_globals: globals.len() as u64,
+        global_bytes: global_sizes.iter().map(|entry| entry.size).sum(),
+    };
+
+    let violations = find_violations(&metrics, &func_sizes, budget);
+
+    // Stable sorts keep entries of the same size in the module order.
+    func_sizes.sort_by_key(|entry| Reverse(entry.size));
+    func_sizes.truncate(num_largest);
+    global_sizes.sort_by_key(|entry| Reverse(entry.size));
+    global_sizes.truncate(num_largest);
+
+    SizeReport {
+        module: module.get_name_or_default(),
+        metrics,
+        violations,
+        largest_functions: func_sizes,
+        largest_globals: global_sizes,
+        suggestions: find_suggestions(&funcs, &globals),
+    }
+}
+
+/// Find the metrics exceeding their budgets.
+fn find_violations(
+    metrics: &SizeMetrics,
+    func_sizes: &[SizeEntry],
+    budget: &SizeBudget,
+) -> Vec<BudgetViolation> {
+    let checks = [
+        (
+            "bitcode_bytes",
+            metrics.bitcode_bytes,
+            budget.max_bitcode_bytes,
+        ),
+        ("num_functions", metrics.num_functions, budget.max_functions),
+        (
+            "num_instructions",
+            metrics.num_instructions,
+            budget.max_instructions,
+        ),
+        (
+            "global_bytes",
+            metrics.global_bytes,
+            budget.max_global_bytes,
+        ),
+    ];
+
+    let mut violations: Vec<BudgetViolation> = checks
+        .into_iter()
+        .filter_map(|(metric, actual, limit)| match limit {
+            Some(limit) if actual > limit => Some(BudgetViolation {
+                metric: metric.to_string(),
+                actual,
+                limit,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    if let Some(limit) = budget.max_function_instructions {
+        for entry in func_sizes.iter().filter(|entry| entry.size > limit) {
+            violations.push(BudgetViolation {
+                metric: format!("instructions of {}", entry.name),
+                actual: entry.size,
+                limit,
+            })
+        }
+    }
+
+    violations
+}
+
+/// Find suggestions to shrink a module from its defined functions and
+/// globals.
+fn find_suggestions(
+    funcs: &[FunctionValue],
+    globals: &[GlobalValue],
+) -> Vec<ShrinkSuggestion> {
+    let mut suggestions = vec![];
+
+    // Debug locations differ between otherwise identical functions.
+    let dbg_regex = Regex::new(r", !dbg !\d+").unwrap();
+    let mut func_groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for func in funcs {
+        let text = func.print_to_string().to_string();
+        let body = match text.find('{') {
+            Some(idx) => dbg_regex.replace_all(&text[idx..], "").to_string(),
+            None => continue,
+        };
+        let key = format!("{} {}", func.get_type().print_to_string(), body);
+        func_groups
+            .entry(key)
+            .or_default()
+            .push(func.get_name_or_default())
+    }
+    suggestions.extend(
+        func_groups
+            .into_values()
+            .filter(|names| names.len() > 1)
+            .map(ShrinkSuggestion::MergeDuplicateFunctions),
+    );
+
+    let mut global_groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for global in globals.iter().filter(|global| global.is_constant()) {
+        let init = match global.get_initializer() {
+            Some(init) => init,
+            None => continue,
+        };
+        let key = format!(
+            "{} {}",
+            init.get_type().print_to_string(),
+            init.print_to_string()
+        );
+        global_groups
+            .entry(key)
+            .or_default()
+            .push(global.get_name().to_string_lossy().to_string())
+    }
+    suggestions.extend(
+        global_groups
+            .into_values()
+            .filter(|names| names.len() > 1)
+            .map(ShrinkSuggestion::MergeDuplicateGlobals),
+    );
+
+    for func in funcs {
+        if is_dead_internal(func.as_global_value()) {
+            let name = func.get_name_or_default();
+            suggestions.push(ShrinkSuggestion::RemoveDeadFunction(name))
+        }
+    }
+
+    for global in globals {
+        if is_dead_internal(*global) {
+            let name = global.get_name().to_string_lossy().to_string();
+            suggestions.push(ShrinkSuggestion::RemoveDeadGlobal(name))
+        }
+    }
+
+    suggestions
+}
+
+/// Check if a global value is local to its module and has no uses.
+fn is_dead_internal(global: GlobalValue) -> bool {
+    matches!(global.get_linkage(), Linkage::Internal | Linkage::Private)
+        && global.as_pointer_value().get_first_use().is_none()
+}
+
+/// Count the instructions of a function.
+fn count_instructions(func: FunctionValue) -> u64 {
+    func.get_basic_blocks()
+        .iter()
+        .map(|blk| blk.get_instructions().len() as u64)
+        .sum()
+}
+
+/// Estimate the size in bytes of a global from the type of its initializer.
+fn estimate_global_size(global: GlobalValue) -> u64 {
+    match global.get_initializer() {
+        Some(init) => estimate_type_size(init.get_type()),
+        None => 0,
+    }
+}
+
+/// Estimate the size in bytes of a type, without padding.
+pub fn estimate_type_size(typ: BasicTypeEnum) -> u64 {
+    match typ {
+        BasicTypeEnum::IntType(typ) => (typ.get_bit_width() as u64 + 7) / 8,
+        BasicTypeEnum::FloatType(typ) => {
+            match typ.print_to_string().to_string().as_str() {
+                "half" | "bfloat" => 2,
+                "float" => 4,
+                "double" => 8,
+                "x86_fp80" => 10,
+                _ => 16,
+            }
+        }
+        BasicTypeEnum::PointerType(_) => POINTER_SIZE,
+        BasicTypeEnum::ArrayType(typ) => {
+            typ.len() as u64 * estimate_type_size(typ.get_element_type())
+        }
+        BasicTypeEnum::VectorType(typ) => {
+            typ.get_size() as u64 * estimate_type_size(typ.get_element_type())
+        }
+        BasicTypeEnum::StructType(typ) => typ
+            .get_field_types()
+            .into_iter()
+            .map(estimate_type_size)
+            .sum(),
+    }
+}
+
+/// Pass checking the size of a module against budgets, to be scheduled by
+/// the `PassManager` after the normalization passes.
+#[derive(Debug)]
+pub struct SizeBudgetPass {
+    /// Budgets of module sizes.
+    pub budget: SizeBudget,
+
+    /// Number of the largest functions and globals to report.
+    pub num_largest: usize,
+
+    /// Report of the last run.
+    pub report: Option<SizeReport>,
+}
+
+/// Implement methods for `SizeBudgetPass`.
+impl SizeBudgetPass {
+    /// Constructor
+    pub fn new(budget: SizeBudget) -> Self {
+        SizeBudgetPass {
+            budget,
+            num_largest: DEFAULT_NUM_LARGEST,
+            report: None,
+        }
+    }
+}
+
+/// Implement the trait `Pass` for `SizeBudgetPass`.
+impl Pass for SizeBudgetPass {
+    fn get_name(&self) -> String {
+        "size-budget".to_string()
+    }
+
+    fn invalidated_analyses(&self) -> Invalidation {
+        Invalidation::None
+    }
+
+    fn run(&mut self, module: &Module, _results: &AnalysisResults) {
+        let report = check_module_size(module, &self.budget, self.num_largest);
+        for violation in &report.violations {
+            log_warn!("Module size over budget: {}", violation);
+        }
+        self.report = Some(report);
+    }
+}
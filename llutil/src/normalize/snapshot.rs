@@ -0,0 +1,264 @@
+//! Module providing snapshot testing of normalization outputs.
+//!
+//! A fixture, i.e., an LLVM bitcode or IR file, is normalized and its output
+//! IR is compared against a checked-in snapshot next to the fixture, with the
+//! extension `.norm.ll`. The comparison is normalization-aware: the IR is
+//! canonicalized before comparing, e.g., by renumbering unnamed values, so
+//! that a change of a pass shows only the meaningful IR-level differences.
+//!
+//! Snapshots are created or updated instead of being compared when the
+//! environment variable `UPDATE_SNAPSHOTS` is set. A missing snapshot fails the
+//! comparison otherwise.
+
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{self, Display},
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use inkwell::{context::Context, module::Module};
+use regex::{Captures, Regex};
+
+use crate::workspace::load_module_file;
+
+use super::{
+    block_order, branch_fold, lower_invoke, merge_return, unreachable,
+};
+
+/// File extension of normalization snapshots.
+pub const SNAPSHOT_FILE_EXT: &str = "norm.ll";
+
+/// Environment variable enabling the update of snapshots.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// Options of snapshot comparisons.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotOptions {
+    /// Ignore numbering of unnamed values and blocks, e.g., `%3` or `5:`.
+    pub ignore_value_numbering: bool,
+
+    /// Ignore metadata attachments and definitions, e.g., `!dbg !12`.
+    pub ignore_metadata: bool,
+
+    /// Write the actual output to the snapshot instead of comparing.
+    pub update: bool,
+}
+
+/// Implement the trait `Default` for `SnapshotOptions`.
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        SnapshotOptions {
+            ignore_value_numbering: true,
+            ignore_metadata: false,
+            update: env::var_os(UPDATE_SNAPSHOTS_ENV).is_some(),
+        }
+    }
+}
+
+/// Data structure representing a line of a snapshot diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// A line only in the snapshot, with its line number in the snapshot.
+    Removed(usize, String),
+
+    /// A line only in the actual output, with its line number in the output.
+    Added(usize, String),
+}
+
+/// Implement the trait `Display` for `DiffLine`.
+impl Display for DiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffLine::Removed(line, text) => {
+                write!(f, "-{:>5} | {}", line, text)
+            }
+            DiffLine::Added(line, text) => write!(f, "+{:>5} | {}", line, text),
+        }
+    }
+}
+
+/// Data structure representing a mismatch between a snapshot and an actual
+/// normalization output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotMismatch {
+    /// Path of the snapshot.
+    pub snapshot: PathBuf,
+
+    /// Differing lines of the canonicalized IR.
+    pub diff: Vec<DiffLine>,
+}
+
+/// Implement the trait `Display` for `SnapshotMismatch`.
+impl Display for SnapshotMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Snapshot mismatch: {}", self.snapshot.display())?;
+        for line in &self.diff {
+            write!(f, "\n{}", line)?;
+        }
+        write!(f, "\nSet {} to update the snapshot.", UPDATE_SNAPSHOTS_ENV)
+    }
+}
+
+/// Get the path of the snapshot of a fixture.
+pub fn get_snapshot_path(fixture: &Path) -> PathBuf {
+    fixture.with_extension(SNAPSHOT_FILE_EXT)
+}
+
+/// Load a fixture and normalize it with the default normalization passes.
+pub fn normalize_fixture<'ctx>(
+    context: &'ctx Context,
+    fixture: &Path,
+) -> Result<Module<'ctx>, String> {
+    let module = load_module_file(context, fixture)?;
+
+    lower_invoke::lower_invokes_in_module(&module);
+    unreachable::propagate_unreachable_in_module(&module);
+    branch_fold::fold_branches_in_module(&module);
+    merge_return::merge_returns_in_module(&module);
+    block_order::normalize_module(&module);
+
+    Ok(module)
+}
+
+/// Canonicalize a textual LLVM IR according to snapshot options.
+///
+/// Trailing whitespaces and empty lines are removed. Unnamed values and
+/// blocks are renumbered in the order of their first occurrences in each
+/// function if `ignore_value_numbering` is set.
+pub fn canonicalize_ir(ir: &str, options: &SnapshotOptions) -> Vec<String> {
+    let value_regex = Regex::new(r"%(\d+)\b").unwrap();
+    let label_regex = Regex::new(r"^(\d+):").unwrap();
+    let metadata_regex = Regex::new(r", ![\w.]+ !\d+").unwrap();
+
+    let mut values: HashMap<String, usize> = HashMap::new();
+    let mut lines = vec![];
+
+    for line in ir.lines().map(|line| line.trim_end()) {
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut line = line.to_string();
+        if options.ignore_metadata {
+            if line.starts_with('!') {
+                continue;
+            }
+            line = metadata_regex.replace_all(&line, "").to_string();
+        }
+
+        if options.ignore_value_numbering {
+            if line.starts_with("define ") {
+                values.clear()
+            }
+            let mut renumber = |caps: &Captures, fmt: &str| {
+                let num = values.len();
+                let num = *values.entry(caps[1].to_string()).or_insert(num);
+                fmt.replace("{}", &num.to_string())
+            };
+            line = label_regex
+                .replace(&line, |caps: &Captures| renumber(caps, "{}:"))
+                .to_string();
+            line = value_regex
+                .replace_all(&line, |caps: &Captures| renumber(caps, "%{}"))
+                .to_string();
+        }
+
+        lines.push(line)
+    }
+
+    lines
+}
+
+/// Compute the differing lines between a canonicalized snapshot and a
+/// canonicalized output, based on their longest common subsequence.
+pub fn diff_lines(expected: &[String], actual: &[String]) -> Vec<DiffLine> {
+    let (n, m) = (expected.len(), actual.len());
+
+    // `lcs[i][j]` is the length of the longest common subsequence of
+    // `expected[i..]` and `actual[j..]`.
+    let mut lcs = vec![vec![0; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(DiffLine::Removed(i + 1, expected[i].clone()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(j + 1, actual[j].clone()));
+            j += 1;
+        }
+    }
+
+    diff
+}
+
+/// Check the IR of a module against a snapshot.
+///
+/// The snapshot is written instead if `update` is set. A missing snapshot is
+/// reported as a mismatch whose lines are all added.
+pub fn check_snapshot(
+    module: &Module,
+    snapshot: &Path,
+    options: &SnapshotOptions,
+) -> Result<(), SnapshotMismatch> {
+    let actual = module.print_to_string().to_string();
+
+    if options.update {
+        log_warn!("Writing snapshot: {}", snapshot.display());
+        if let Err(err) = fs::write(snapshot, &actual) {
+            panic!("Failed to write snapshot {:?}: {}", snapshot, err)
+        }
+        return Ok(());
+    }
+
+    let expected = match fs::read_to_string(snapshot) {
+        Ok(expected) => expected,
+        Err(err) if err.kind() == ErrorKind::NotFound => "".to_string(),
+        Err(err) => panic!("Failed to read snapshot {:?}: {}", snapshot, err),
+    };
+
+    let diff = diff_lines(
+        &canonicalize_ir(&expected, options),
+        &canonicalize_ir(&actual, options),
+    );
+    if diff.is_empty() {
+        return Ok(());
+    }
+
+    Err(SnapshotMismatch {
+        snapshot: snapshot.to_path_buf(),
+        diff,
+    })
+}
+
+/// Normalize a fixture and check its output against its snapshot.
+///
+/// Panic with a reviewable diff if the output does not match the snapshot.
+pub fn assert_fixture_snapshot(fixture: &Path, options: &SnapshotOptions) {
+    let context = Context::create();
+    let module = match normalize_fixture(&context, fixture) {
+        Ok(module) => module,
+        Err(err) => panic!("Failed to load fixture {:?}: {}", fixture, err),
+    };
+
+    let snapshot = get_snapshot_path(fixture);
+    if let Err(mismatch) = check_snapshot(&module, &snapshot, options) {
+        panic!("{}", mismatch)
+    }
+}
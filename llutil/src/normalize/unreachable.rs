@@ -0,0 +1,256 @@
+//! Module propagating `unreachable` instructions backward.
+//!
+//! The propagation performs the following simplifications until a fixpoint:
+//! - Instructions following a call to a `noreturn` function are replaced by an
+//!   `unreachable` instruction.
+//! - A conditional branch to a block which always reaches `unreachable` is
+//!   folded into an unconditional branch to the other successor.
+//! - An unconditional branch to a block which always reaches `unreachable`
+//!   is replaced by an `unreachable` instruction, if the branching block has
+//!   no side effect.
+//!
+//! Blocks which become dead are then removed.
+
+use inkwell::{
+    builder::Builder,
+    module::Module,
+    values::{AsValueRef, BasicBlock, FunctionValue, InstructionOpcode},
+};
+use llvm_sys::core::{LLVMGetUndef, LLVMReplaceAllUsesWith, LLVMTypeOf};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+};
+
+use crate::{
+    ir::{
+        basic_block::BasicBlockExt, AnyCall, AnyTerminator, AsInstructionValue,
+        FunctionExt, InstructionExt, ModuleExt,
+    },
+    pass::{AnalysisResults, Pass},
+};
+
+use super::simplify_func::{
+    eliminate_unreachable_blocks, has_side_effect, remove_phi_incomings,
+};
+
+/// Data structure reporting the simplifications of a function.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UnreachableReport {
+    /// Name of the function.
+    pub function: String,
+
+    /// Number of blocks truncated after a call to a `noreturn` function.
+    pub num_truncated_blocks: usize,
+
+    /// Number of branches folded or replaced by `unreachable`.
+    pub num_folded_branches: usize,
+
+    /// Number of removed dead blocks.
+    pub num_removed_blocks: usize,
+}
+
+/// Implement the trait `Display` for `UnreachableReport`.
+impl Display for UnreachableReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: truncated {} blocks, folded {} branches, removed {} blocks",
+            self.function,
+            self.num_truncated_blocks,
+            self.num_folded_branches,
+            self.num_removed_blocks
+        )
+    }
+}
+
+/// Propagate `unreachable` instructions in all defined functions of a module.
+///
+/// Return one report for each function.
+pub fn propagate_unreachable_in_module(
+    module: &Module,
+) -> Vec<UnreachableReport> {
+    module
+        .get_functions_sorted()
+        .into_iter()
+        .filter(|func| !func.is_only_declared())
+        .map(propagate_unreachable)
+        .collect()
+}
+
+/// Propagate `unreachable` instructions in a function and remove dead blocks.
+pub fn propagate_unreachable(func: FunctionValue) -> UnreachableReport {
+    let mut report = UnreachableReport {
+        function: func.get_name_or_default(),
+        ..Default::default()
+    };
+
+    let context = match func.get_first_basic_block() {
+        Some(blk) => blk.get_context(),
+        None => return report,
+    };
+    let builder = context.create_builder();
+
+    for blk in func.get_basic_blocks() {
+        if truncate_after_noreturn_call(&builder, blk) {
+            report.num_truncated_blocks += 1;
+        }
+    }
+
+    loop {
+        let mut changed = false;
+        for blk in func.get_basic_blocks() {
+            if fold_branch_to_unreachable(&builder, blk) {
+                report.num_folded_branches += 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    report.num_removed_blocks = eliminate_unreachable_blocks(func);
+
+    report
+}
+
+/// Check if a block always reaches its `unreachable` terminator, i.e., its
+/// other instructions have no side effect.
+pub fn is_unreachable_block(blk: BasicBlock) -> bool {
+    let term = match blk.get_terminator() {
+        Some(inst) => inst,
+        None => return false,
+    };
+
+    term.get_opcode() == InstructionOpcode::Unreachable
+        && blk
+            .get_instructions()
+            .into_iter()
+            .filter(|inst| *inst != term)
+            .all(|inst| !has_side_effect(inst))
+}
+
+/// Replace instructions following the first call to a `noreturn` function in
+/// a block by an `unreachable` instruction.
+///
+/// Return `true` if the block is changed.
+fn truncate_after_noreturn_call(builder: &Builder, blk: BasicBlock) -> bool {
+    let insts = blk.get_instructions();
+
+    let call_idx = insts.iter().position(|inst| {
+        inst.try_into_call_inst()
+            .and_then(|call| call.get_called_function())
+            .map_or(false, |func| func.is_noreturn())
+    });
+
+    let call_idx = match call_idx {
+        Some(idx) => idx,
+        None => return false,
+    };
+
+    let dead_insts = &insts[call_idx + 1..];
+    if let [inst] = dead_insts {
+        if inst.get_opcode() == InstructionOpcode::Unreachable {
+            return false;
+        }
+    }
+
+    // Remove the outgoing edges of the block.
+    let succ_blks: HashSet<BasicBlock> =
+        blk.get_successors().into_iter().collect();
+    for succ_blk in succ_blks {
+        remove_phi_incomings(succ_blk, &HashSet::from([blk]));
+    }
+
+    for inst in dead_insts.iter().rev() {
+        if inst.get_first_use().is_some() {
+            unsafe {
+                let value = inst.as_value_ref();
+                LLVMReplaceAllUsesWith(value, LLVMGetUndef(LLVMTypeOf(value)))
+            }
+        }
+        inst.erase_from_basic_block();
+    }
+
+    builder.position_at_end(blk);
+    builder.build_unreachable();
+
+    true
+}
+
+/// Fold a branch of a block to successors which always reach `unreachable`.
+///
+/// Return `true` if the block is changed.
+fn fold_branch_to_unreachable(builder: &Builder, blk: BasicBlock) -> bool {
+    let term = match blk.get_terminator().and_then(|i| i.try_into_branch_inst())
+    {
+        Some(term) => term,
+        None => return false,
+    };
+
+    let succ_blks = term.get_successors();
+    let live_succ_blks: Vec<BasicBlock> = succ_blks
+        .iter()
+        .filter(|succ_blk| !is_unreachable_block(**succ_blk))
+        .cloned()
+        .collect();
+
+    let term_inst = term.as_instruction_value();
+    match (succ_blks.len(), live_succ_blks.len()) {
+        // A conditional branch with one live successor.
+        (2, 1) => {
+            let dead_blks: HashSet<BasicBlock> = succ_blks
+                .into_iter()
+                .filter(|succ_blk| *succ_blk != live_succ_blks[0])
+                .collect();
+            for dead_blk in dead_blks {
+                remove_phi_incomings(dead_blk, &HashSet::from([blk]));
+            }
+            builder.position_before(&term_inst);
+            builder.build_unconditional_branch(live_succ_blks[0]);
+            term_inst.erase_from_basic_block();
+            true
+        }
+
+        // A branch whose successors are all unreachable.
+        (_, 0) if !is_unreachable_block(blk) => {
+            let has_effect = blk
+                .get_instructions()
+                .into_iter()
+                .filter(|inst| *inst != term_inst)
+                .any(has_side_effect);
+            if has_effect {
+                return false;
+            }
+            for succ_blk in succ_blks.into_iter().collect::<HashSet<_>>() {
+                remove_phi_incomings(succ_blk, &HashSet::from([blk]));
+            }
+            builder.position_before(&term_inst);
+            builder.build_unreachable();
+            term_inst.erase_from_basic_block();
+            true
+        }
+
+        _ => false,
+    }
+}
+
+/// Pass propagating `unreachable` instructions of all functions, to be
+/// scheduled by the `PassManager`.
+#[derive(Debug, Default)]
+pub struct PropagateUnreachablePass {
+    /// Reports of the last run.
+    pub reports: Vec<UnreachableReport>,
+}
+
+/// Implement the trait `Pass` for `PropagateUnreachablePass`.
+impl Pass for PropagateUnreachablePass {
+    fn get_name(&self) -> String {
+        "propagate-unreachable".to_string()
+    }
+
+    fn run(&mut self, module: &Module, _results: &AnalysisResults) {
+        self.reports = propagate_unreachable_in_module(module);
+    }
+}
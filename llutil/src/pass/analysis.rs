@@ -0,0 +1,115 @@
+//! Module defining analyses and their results managed by the pass manager.
+
+use std::{any::Any, collections::HashMap};
+
+use inkwell::module::Module;
+
+use super::AnalysisCache;
+
+/// Data structure representing kinds of analyses.
+#[remain::sorted]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AnalysisKind {
+    /// Alias analysis.
+    Alias,
+
+    /// Custom analysis identified by its name.
+    Custom(&'static str),
+
+    /// Dominator tree analysis.
+    Dominators,
+
+    /// Loop analysis.
+    Loops,
+}
+
+/// Trait of an analysis which can be scheduled by the pass manager.
+pub trait Analysis {
+    /// Get kind of the analysis.
+    fn kind(&self) -> AnalysisKind;
+
+    /// Get analyses required by the current analysis.
+    ///
+    /// These analyses are computed before running the current analysis.
+    fn required_analyses(&self) -> Vec<AnalysisKind> {
+        vec![]
+    }
+
+    /// Run the analysis on a module and return its result.
+    ///
+    /// Results of the required analyses are available in `results`.
+    fn run(&self, module: &Module, results: &AnalysisResults) -> Box<dyn Any>;
+}
+
+/// Data structure storing results of analyses computed by the pass manager.
+///
+/// Each result is stored as a `Box<dyn Any>` and is retrieved by downcasting
+/// to its concrete type, which therefore must be `'static`. Results referring
+/// to IR values should identify them by names or indices.
+///
+/// An optional on-disk `AnalysisCache` is also provided to analyses, so that
+/// expensive per-function results can be reused across runs.
+#[derive(Debug, Default)]
+pub struct AnalysisResults {
+    /// Mapping from analysis kinds to their results.
+    results: HashMap<AnalysisKind, Box<dyn Any>>,
+
+    /// On-disk cache of analysis results, if any.
+    cache: Option<AnalysisCache>,
+}
+
+/// Implement methods for `AnalysisResults`.
+impl AnalysisResults {
+    /// Constructor
+    pub fn new() -> Self {
+        AnalysisResults {
+            results: HashMap::new(),
+            cache: None,
+        }
+    }
+
+    /// Get the on-disk cache of analysis results, if any.
+    pub fn get_cache(&self) -> Option<&AnalysisCache> {
+        self.cache.as_ref()
+    }
+
+    /// Set the on-disk cache of analysis results.
+    pub fn set_cache(&mut self, cache: Option<AnalysisCache>) {
+        self.cache = cache
+    }
+
+    /// Take the on-disk cache of analysis results out, if any.
+    pub fn take_cache(&mut self) -> Option<AnalysisCache> {
+        self.cache.take()
+    }
+
+    /// Check if the result of an analysis is available.
+    pub fn contains(&self, kind: AnalysisKind) -> bool {
+        self.results.contains_key(&kind)
+    }
+
+    /// Get result of an analysis, if it is available and has the type `T`.
+    pub fn get<T: 'static>(&self, kind: AnalysisKind) -> Option<&T> {
+        self.results
+            .get(&kind)
+            .and_then(|res| res.downcast_ref::<T>())
+    }
+
+    /// Insert result of an analysis, replacing the existing result.
+    pub fn insert(&mut self, kind: AnalysisKind, result: Box<dyn Any>) {
+        self.results.insert(kind, result);
+    }
+
+    /// Remove result of an analysis.
+    pub fn invalidate(&mut self, kind: AnalysisKind) {
+        self.results.remove(&kind);
+    }
+
+    /// Remove results of all analyses.
+    ///
+    /// The on-disk cache is kept, since its entries are keyed by function
+    /// hashes and remain valid after the IR is modified.
+    pub fn invalidate_all(&mut self) {
+        self.results.clear()
+    }
+}
@@ -0,0 +1,168 @@
+//! Module implementing an on-disk cache of analysis results.
+//!
+//! Results are keyed by the analysis name and the semantic hash of the
+//! analyzed function, so that repeated runs over unchanged functions can reuse
+//! results computed by previous runs. The semantic hash covers the functions
+//! transitively called by direct calls, but not the callees of indirect calls,
+//! so results of interprocedural analyses resolving indirect calls should not
+//! be cached.
+//!
+//! The cache is stored as a text file, where each line is an entry consisting
+//! of the analysis name, the function hash in hexadecimal and the serialized
+//! result, separated by tabs.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use inkwell::values::FunctionValue;
+
+use crate::ir::FunctionExt;
+
+/// Data structure caching serialized analysis results of functions.
+///
+/// Entries are stored behind a `RefCell`, so that analyses can read and update
+/// the cache while only holding a shared reference to it.
+#[derive(Debug, Default)]
+pub struct AnalysisCache {
+    /// Mapping from analysis names and function hashes to results.
+    entries: RefCell<HashMap<(String, u64), String>>,
+}
+
+/// Implement methods for `AnalysisCache`.
+impl AnalysisCache {
+    /// Constructor of an empty cache.
+    pub fn new() -> Self {
+        AnalysisCache {
+            entries: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Load a cache from a file.
+    ///
+    /// Return an empty cache if the file does not exist.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Ok(AnalysisCache::new())
+            }
+            Err(err) => return Err(err),
+        };
+
+        let mut entries = HashMap::new();
+        for (line_idx, line) in content.lines().enumerate() {
+            let fields = line.splitn(3, '\t').collect::<Vec<&str>>();
+            let hash =
+                fields.get(1).and_then(|h| u64::from_str_radix(h, 16).ok());
+            match (fields.first(), hash, fields.get(2)) {
+                (Some(analysis), Some(hash), Some(result)) => {
+                    let key = (analysis.to_string(), hash);
+                    entries.insert(key, unescape(result));
+                }
+                _ => {
+                    let msg =
+                        format!("Invalid cache entry at line {}", line_idx + 1);
+                    return Err(io::Error::new(ErrorKind::InvalidData, msg));
+                }
+            }
+        }
+
+        Ok(AnalysisCache {
+            entries: RefCell::new(entries),
+        })
+    }
+
+    /// Save the cache to a file.
+    ///
+    /// Entries are sorted, so that saving the same cache always produces the
+    /// same file.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let entries = self.entries.borrow();
+        let mut keys = entries.keys().collect::<Vec<_>>();
+        keys.sort();
+
+        let content = keys
+            .into_iter()
+            .map(|key| {
+                let (analysis, hash) = key;
+                format!(
+                    "{}\t{:016x}\t{}\n",
+                    analysis,
+                    hash,
+                    escape(&entries[key])
+                )
+            })
+            .collect::<String>();
+
+        fs::write(path, content)
+    }
+
+    /// Get the cached result of an analysis on a function, if any.
+    pub fn get(&self, analysis: &str, func: &FunctionValue) -> Option<String> {
+        self.get_by_hash(analysis, func.get_semantic_hash())
+    }
+
+    /// Put the result of an analysis on a function to the cache.
+    pub fn put(&self, analysis: &str, func: &FunctionValue, result: String) {
+        self.put_by_hash(analysis, func.get_semantic_hash(), result)
+    }
+
+    /// Get the cached result of an analysis on a function hash, if any.
+    pub fn get_by_hash(&self, analysis: &str, hash: u64) -> Option<String> {
+        let key = (analysis.to_string(), hash);
+        self.entries.borrow().get(&key).cloned()
+    }
+
+    /// Put the result of an analysis on a function hash to the cache.
+    pub fn put_by_hash(&self, analysis: &str, hash: u64, result: String) {
+        let key = (analysis.to_string(), hash);
+        self.entries.borrow_mut().insert(key, result);
+    }
+
+    /// Get the number of cached entries.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Check if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+}
+
+/// Escape backslashes, tabs and new lines of a result to store it in a line.
+fn escape(result: &str) -> String {
+    result
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Unescape a result stored in a line.
+fn unescape(result: &str) -> String {
+    let mut res = String::new();
+    let mut chars = result.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => res.push('\t'),
+            Some('n') => res.push('\n'),
+            Some('r') => res.push('\r'),
+            Some(c) => res.push(c),
+            None => res.push('\\'),
+        }
+    }
+
+    res
+}
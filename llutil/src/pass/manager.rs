@@ -0,0 +1,182 @@
+//! Module implementing the pass manager.
+
+use std::{collections::HashMap, io, path::Path};
+
+use inkwell::module::Module;
+
+use crate::progress::{NoProgress, ProgressSink, ProgressTracker};
+
+use super::{Analysis, AnalysisCache, AnalysisKind, AnalysisResults};
+
+/// Data structure representing analyses invalidated by a pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invalidation {
+    /// All analyses are invalidated.
+    All,
+
+    /// No analysis is invalidated.
+    None,
+
+    /// Only the listed analyses are invalidated.
+    Only(Vec<AnalysisKind>),
+}
+
+/// Trait of a pass which can be scheduled by the pass manager.
+pub trait Pass {
+    /// Get name of the pass.
+    fn get_name(&self) -> String;
+
+    /// Get analyses required by the current pass.
+    ///
+    /// These analyses are computed, or reused if already available, before
+    /// running the current pass.
+    fn required_analyses(&self) -> Vec<AnalysisKind> {
+        vec![]
+    }
+
+    /// Get analyses invalidated by the current pass.
+    ///
+    /// By default, a pass is assumed to modify the IR and invalidate all
+    /// analyses.
+    fn invalidated_analyses(&self) -> Invalidation {
+        Invalidation::All
+    }
+
+    /// Run the pass on a module.
+    fn run(&mut self, module: &Module, results: &AnalysisResults);
+}
+
+/// Data structure managing and scheduling passes and analyses.
+#[derive(Default)]
+pub struct PassManager {
+    /// Registered analyses, which can be required by passes.
+    analyses: HashMap<AnalysisKind, Box<dyn Analysis>>,
+
+    /// Passes to be run, in order.
+    passes: Vec<Box<dyn Pass>>,
+
+    /// Cached results of analyses.
+    results: AnalysisResults,
+}
+
+/// Implement methods for `PassManager`.
+impl PassManager {
+    /// Constructor
+    pub fn new() -> Self {
+        PassManager {
+            analyses: HashMap::new(),
+            passes: vec![],
+            results: AnalysisResults::new(),
+        }
+    }
+
+    /// Register an analysis, replacing any analysis of the same kind.
+    pub fn register_analysis(&mut self, analysis: Box<dyn Analysis>) {
+        self.analyses.insert(analysis.kind(), analysis);
+    }
+
+    /// Add a pass to the end of the pipeline.
+    pub fn add_pass(&mut self, pass: Box<dyn Pass>) {
+        self.passes.push(pass)
+    }
+
+    /// Get the cached analysis results.
+    pub fn get_results(&self) -> &AnalysisResults {
+        &self.results
+    }
+
+    /// Load the on-disk cache of analysis results from a file, which is then
+    /// provided to all analyses and passes.
+    pub fn load_cache(&mut self, path: &Path) -> io::Result<()> {
+        let cache = AnalysisCache::load(path)?;
+        self.results.set_cache(Some(cache));
+        Ok(())
+    }
+
+    /// Save the on-disk cache of analysis results to a file, if the cache was
+    /// loaded.
+    pub fn save_cache(&self, path: &Path) -> io::Result<()> {
+        match self.results.get_cache() {
+            Some(cache) => cache.save(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Run all passes on a module, in the order they were added.
+    pub fn run(&mut self, module: &Module) {
+        self.run_with_progress(module, &NoProgress)
+    }
+
+    /// Run all passes on a module, in the order they were added, reporting the
+    /// progress over passes to a sink.
+    pub fn run_with_progress(
+        &mut self,
+        module: &Module,
+        progress: &dyn ProgressSink,
+    ) {
+        let mut passes = std::mem::take(&mut self.passes);
+        let mut tracker =
+            ProgressTracker::new(progress, "passes", passes.len());
+
+        for pass in passes.iter_mut() {
+            log_debug!("Running pass: {}", pass.get_name());
+            tracker.start_item(&pass.get_name());
+
+            for kind in pass.required_analyses() {
+                self.compute_analysis(kind, module, &mut vec![]);
+            }
+
+            pass.run(module, &self.results);
+
+            match pass.invalidated_analyses() {
+                Invalidation::All => self.results.invalidate_all(),
+                Invalidation::None => {}
+                Invalidation::Only(kinds) => {
+                    for kind in kinds {
+                        self.results.invalidate(kind)
+                    }
+                }
+            }
+
+            tracker.finish_item();
+        }
+
+        tracker.finish();
+        self.passes = passes;
+    }
+
+    /// Compute an analysis and its required analyses, unless their results
+    /// are already available.
+    ///
+    /// `pending` contains analyses being computed, to detect cyclic
+    /// requirements.
+    fn compute_analysis(
+        &mut self,
+        kind: AnalysisKind,
+        module: &Module,
+        pending: &mut Vec<AnalysisKind>,
+    ) {
+        if self.results.contains(kind) {
+            return;
+        }
+
+        if pending.contains(&kind) {
+            panic!("Cyclic analysis requirement: {:?}", kind);
+        }
+
+        let required_kinds = match self.analyses.get(&kind) {
+            Some(analysis) => analysis.required_analyses(),
+            None => panic!("Analysis not registered: {:?}", kind),
+        };
+
+        pending.push(kind);
+        for required_kind in required_kinds {
+            self.compute_analysis(required_kind, module, pending);
+        }
+        pending.pop();
+
+        log_debug!("Computing analysis: {:?}", kind);
+        let result = self.analyses[&kind].run(module, &self.results);
+        self.results.insert(kind, result);
+    }
+}
@@ -0,0 +1,15 @@
+//! Module providing a lightweight pass manager for LLVM IR.
+//!
+//! Each pass declares the analyses it requires and the analyses it
+//! invalidates, so that analysis results can be computed on demand and reused
+//! across passes instead of being recomputed by every pass.
+
+// Export sub modules
+pub mod analysis;
+pub mod cache;
+pub mod manager;
+
+// Re-export sub-modules' data structures
+pub use analysis::{Analysis, AnalysisKind, AnalysisResults};
+pub use cache::AnalysisCache;
+pub use manager::{Invalidation, Pass, PassManager};
@@ -0,0 +1,119 @@
+//! Module providing progress reporting of long-running pipelines.
+//!
+//! Pipelines such as batch compilation, normalization and whole-module
+//! analyses report their progress to a `ProgressSink`, so that frontends can
+//! display it.
+
+use std::time::{Duration, Instant};
+
+/// Data structure representing the progress of a pipeline stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// Name of the stage, e.g., `normalization`.
+    pub stage: String,
+
+    /// Number of finished items.
+    pub done: usize,
+
+    /// Total number of items.
+    pub total: usize,
+
+    /// Name of the item being processed, e.g., a file or a function name.
+    pub current: Option<String>,
+
+    /// Elapsed time since the stage started.
+    pub elapsed: Duration,
+}
+
+/// Implement methods for `Progress`.
+impl Progress {
+    /// Estimate the remaining time of the stage, assuming that all items take
+    /// the same time.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.done == 0 || self.done > self.total {
+            return None;
+        }
+
+        let per_item = self.elapsed / self.done as u32;
+        Some(per_item * (self.total - self.done) as u32)
+    }
+}
+
+/// Trait of a receiver of progress reports.
+pub trait ProgressSink {
+    /// Receive a progress report.
+    fn on_progress(&self, progress: &Progress);
+
+    /// Receive a notification that a stage is finished.
+    fn on_finish(&self, _stage: &str) {}
+}
+
+/// Progress sink ignoring all reports.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+/// Implement the trait `ProgressSink` for `NoProgress`.
+impl ProgressSink for NoProgress {
+    fn on_progress(&self, _progress: &Progress) {}
+}
+
+/// Data structure tracking the progress of a stage and reporting it to a
+/// sink.
+pub struct ProgressTracker<'a> {
+    /// Sink receiving progress reports.
+    sink: &'a dyn ProgressSink,
+
+    /// Name of the stage.
+    stage: String,
+
+    /// Number of finished items.
+    done: usize,
+
+    /// Total number of items.
+    total: usize,
+
+    /// Starting time of the stage.
+    start: Instant,
+}
+
+/// Implement methods for `ProgressTracker`.
+impl<'a> ProgressTracker<'a> {
+    /// Constructor
+    pub fn new(sink: &'a dyn ProgressSink, stage: &str, total: usize) -> Self {
+        ProgressTracker {
+            sink,
+            stage: stage.to_string(),
+            done: 0,
+            total,
+            start: Instant::now(),
+        }
+    }
+
+    /// Report that an item starts to be processed.
+    pub fn start_item(&self, current: &str) {
+        self.report(Some(current.to_string()))
+    }
+
+    /// Report that an item is finished.
+    pub fn finish_item(&mut self) {
+        self.done += 1;
+        self.report(None)
+    }
+
+    /// Report that the stage is finished.
+    pub fn finish(self) {
+        self.sink.on_finish(&self.stage)
+    }
+
+    /// Send a progress report to the sink.
+    fn report(&self, current: Option<String>) {
+        let progress = Progress {
+            stage: self.stage.clone(),
+            done: self.done,
+            total: self.total,
+            current,
+            elapsed: self.start.elapsed(),
+        };
+        self.sink.on_progress(&progress)
+    }
+}
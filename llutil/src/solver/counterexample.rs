@@ -0,0 +1,144 @@
+//! Module mapping counterexamples returned by SMT solvers back to source code.
+
+use inkwell::values::{AnyValue, FunctionValue, InstructionValue};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    fs,
+};
+
+use crate::ir::{InstructionExt, SourceLocation, SourceVariables};
+
+use super::Model;
+
+/// Data structure representing a counterexample of an assertion, expressed in
+/// terms of source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    /// Source location of the violated assertion, if known.
+    pub assertion: Option<SourceLocation>,
+
+    /// Pairs of source-level variable names and their values.
+    pub values: Vec<(String, String)>,
+
+    /// Source locations along the path reaching the assertion.
+    pub trace: Vec<SourceLocation>,
+}
+
+/// Implement the trait `Display` for `Counterexample`.
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.assertion {
+            Some(loc) => writeln!(f, "Assertion violated at: {}", loc)?,
+            None => writeln!(f, "Assertion violated at unknown location")?,
+        }
+
+        writeln!(f, "Counterexample:")?;
+        for (name, value) in &self.values {
+            writeln!(f, "  {} = {}", name, value)?;
+        }
+
+        writeln!(f, "Trace:")?;
+        let mut sources: HashMap<&str, Option<Vec<String>>> = HashMap::new();
+        for loc in &self.trace {
+            let lines = sources.entry(&loc.file).or_insert_with(|| {
+                fs::read_to_string(&loc.file)
+                    .ok()
+                    .map(|s| s.lines().map(|l| l.to_string()).collect())
+            });
+            let text = lines.as_ref().and_then(|lines| {
+                lines.get((loc.line as usize).checked_sub(1)?)
+            });
+            match text {
+                Some(text) => writeln!(f, "  {}: {}", loc, text.trim())?,
+                None => writeln!(f, "  {}", loc)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Data structure reporting counterexamples of assertions in a function, by
+/// mapping symbolic variables back to source-level names via debug
+/// information.
+#[derive(Debug, Clone)]
+pub struct CounterexampleReporter<'ctx> {
+    /// Function containing the assertions.
+    func: FunctionValue<'ctx>,
+
+    /// Source-level variables of the function.
+    source_vars: SourceVariables,
+}
+
+/// Implement methods for `CounterexampleReporter`.
+impl<'ctx> CounterexampleReporter<'ctx> {
+    /// Constructor
+    pub fn new(func: FunctionValue<'ctx>) -> Self {
+        CounterexampleReporter {
+            func,
+            source_vars: SourceVariables::new(func),
+        }
+    }
+
+    /// Get the function whose counterexamples are reported.
+    pub fn get_function(&self) -> FunctionValue<'ctx> {
+        self.func
+    }
+
+    /// Create a counterexample from a model violating an assertion.
+    ///
+    /// `symbols` maps solver variable names to the LLVM values they model,
+    /// and `path` lists instructions along the path reaching the assertion.
+    /// Solver variables without a value in the model are omitted, and
+    /// consecutive instructions on the same source line are reported once.
+    pub fn create_counterexample<V: AnyValue<'ctx>>(
+        &self,
+        model: &Model,
+        symbols: &[(String, V)],
+        path: &[InstructionValue<'ctx>],
+        assertion: InstructionValue<'ctx>,
+    ) -> Counterexample {
+        let values = symbols
+            .iter()
+            .filter_map(|(symbol, value)| {
+                let model_value = match model.get_bitvector_value(symbol) {
+                    Some(v) => v.to_string(),
+                    None => model.get_value(symbol)?.to_string(),
+                };
+                let name = self.source_vars.get_name_or_default(value);
+                Some((name, model_value))
+            })
+            .collect();
+
+        let mut trace: Vec<SourceLocation> = vec![];
+        for inst in path.iter().chain(std::iter::once(&assertion)) {
+            if let Some(loc) = inst.get_source_location() {
+                let same_line = trace.last().map_or(false, |prev| {
+                    prev.file == loc.file && prev.line == loc.line
+                });
+                if !same_line {
+                    trace.push(loc)
+                }
+            }
+        }
+
+        Counterexample {
+            assertion: assertion.get_source_location(),
+            values,
+            trace,
+        }
+    }
+
+    /// Report a counterexample of an assertion as a string.
+    pub fn report<V: AnyValue<'ctx>>(
+        &self,
+        model: &Model,
+        symbols: &[(String, V)],
+        path: &[InstructionValue<'ctx>],
+        assertion: InstructionValue<'ctx>,
+    ) -> String {
+        self.create_counterexample(model, symbols, path, assertion)
+            .to_string()
+    }
+}
@@ -0,0 +1,259 @@
+//! Module encoding symbolic expressions into SMT-LIB terms.
+//!
+//! Integer expressions are encoded as bitvector terms and logical expressions
+//! as Boolean terms. Symbolic values of bit width 1 are encoded as bitvectors
+//! of width 1 and converted to Boolean terms when needed. Each non-constant
+//! SSA value or memory location becomes a bitvector symbol, named `sym_0`,
+//! `sym_1`, etc. Integer constants wider than 64 bits are encoded from the
+//! words of their arbitrary-precision values.
+
+use std::collections::HashMap;
+
+use inkwell::{
+    types::AnyTypeEnum,
+    values::{BasicValueEnum, InstructionOpcode},
+    IntPredicate,
+};
+
+use crate::analysis::SymExpr;
+
+use super::Solver;
+
+/// Sort of an encoded SMT-LIB term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sort {
+    /// Boolean sort.
+    Bool,
+
+    /// Bitvector sort of a bit width.
+    BitVec(u32),
+}
+
+/// Data structure encoding symbolic expressions into SMT-LIB terms and
+/// recording the symbols they use.
+#[derive(Debug, Clone, Default)]
+pub struct SmtEncoder<'ctx> {
+    /// Encoded symbols, their expressions and their bit widths, in the order
+    /// of their first occurrences.
+    symbols: Vec<(String, SymExpr<'ctx>, u32)>,
+
+    /// Mapping from expressions to their symbol names.
+    names: HashMap<SymExpr<'ctx>, String>,
+}
+
+/// Implement methods for `SmtEncoder`.
+impl<'ctx> SmtEncoder<'ctx> {
+    /// Constructor
+    pub fn new() -> Self {
+        SmtEncoder {
+            symbols: vec![],
+            names: HashMap::new(),
+        }
+    }
+
+    /// Get the encoded symbols, their expressions and their bit widths.
+    pub fn get_symbols(&self) -> &[(String, SymExpr<'ctx>, u32)] {
+        &self.symbols
+    }
+
+    /// Get the symbol name of an expression, if it is encoded.
+    pub fn get_symbol_name(&self, expr: &SymExpr<'ctx>) -> Option<&str> {
+        self.names.get(expr).map(|name| name.as_str())
+    }
+
+    /// Declare all encoded symbols to a solver.
+    pub fn declare_symbols(&self, solver: &mut dyn Solver) {
+        for (name, _, width) in &self.symbols {
+            solver.declare_bitvector(name, *width)
+        }
+    }
+
+    /// Encode an expression into a Boolean SMT-LIB term.
+    ///
+    /// Return `None` if the expression contains unsupported sub-expressions,
+    /// such as non-integer values.
+    pub fn encode_bool(&mut self, expr: &SymExpr<'ctx>) -> Option<String> {
+        let (term, sort) = self.encode(expr)?;
+        to_bool(term, sort)
+    }
+
+    /// Encode an expression into an SMT-LIB term and its sort.
+    fn encode(&mut self, expr: &SymExpr<'ctx>) -> Option<(String, Sort)> {
+        match expr {
+            SymExpr::Bool(b) => Some((b.to_string(), Sort::Bool)),
+            SymExpr::Int(n, w) => {
+                Some((format!("(_ bv{} {})", n, w), Sort::BitVec(*w)))
+            }
+            SymExpr::Var(BasicValueEnum::IntValue(v)) if v.is_const() => {
+                let width = v.get_type().get_bit_width();
+                let n = v.get_big_int_constant()?;
+                Some((format!("(_ bv{} {})", n, width), Sort::BitVec(width)))
+            }
+            SymExpr::Var(_) | SymExpr::Memory(_) => {
+                let width = get_symbol_width(expr)?;
+                let name = self.get_or_insert_symbol(expr, width);
+                Some((name, Sort::BitVec(width)))
+            }
+            SymExpr::Binary(op, l, r) => {
+                let (l, lsort) = self.encode(l)?;
+                let (r, rsort) = self.encode(r)?;
+                let op = get_binary_operator(*op)?;
+                let (l, r) = (to_bitvector(l, lsort), to_bitvector(r, rsort));
+                Some((
+                    format!("({} {} {})", op, l, r),
+                    get_bitvector_sort(lsort),
+                ))
+            }
+            SymExpr::Cmp(pred, l, r) => {
+                let (l, lsort) = self.encode(l)?;
+                let (r, rsort) = self.encode(r)?;
+                let (l, r) = (to_bitvector(l, lsort), to_bitvector(r, rsort));
+                let term = match pred {
+                    IntPredicate::EQ => format!("(= {} {})", l, r),
+                    IntPredicate::NE => format!("(not (= {} {}))", l, r),
+                    _ => format!("({} {} {})", get_predicate(*pred), l, r),
+                };
+                Some((term, Sort::Bool))
+            }
+            SymExpr::Cast(op, e, w) => {
+                let (e, sort) = self.encode(e)?;
+                let width = match sort {
+                    Sort::Bool => 1,
+                    Sort::BitVec(width) => width,
+                };
+                let e = to_bitvector(e, sort);
+                let term = match op {
+                    InstructionOpcode::ZExt if *w >= width => {
+                        format!("((_ zero_extend {}) {})", w - width, e)
+                    }
+                    InstructionOpcode::SExt if *w >= width => {
+                        format!("((_ sign_extend {}) {})", w - width, e)
+                    }
+                    InstructionOpcode::Trunc if *w <= width => {
+                        format!("((_ extract {} 0) {})", w - 1, e)
+                    }
+                    _ => return None,
+                };
+                Some((term, Sort::BitVec(*w)))
+            }
+            SymExpr::Ite(c, t, e) => {
+                let c = self.encode_bool(c)?;
+                let (t, tsort) = self.encode(t)?;
+                let (e, esort) = self.encode(e)?;
+                let (t, e) = (to_bitvector(t, tsort), to_bitvector(e, esort));
+                let term = format!("(ite {} {} {})", c, t, e);
+                Some((term, get_bitvector_sort(tsort)))
+            }
+            SymExpr::Not(e) => {
+                let e = self.encode_bool(e)?;
+                Some((format!("(not {})", e), Sort::Bool))
+            }
+            SymExpr::And(l, r) => {
+                let (l, r) = (self.encode_bool(l)?, self.encode_bool(r)?);
+                Some((format!("(and {} {})", l, r), Sort::Bool))
+            }
+            SymExpr::Or(l, r) => {
+                let (l, r) = (self.encode_bool(l)?, self.encode_bool(r)?);
+                Some((format!("(or {} {})", l, r), Sort::Bool))
+            }
+            SymExpr::Implies(l, r) => {
+                let (l, r) = (self.encode_bool(l)?, self.encode_bool(r)?);
+                Some((format!("(=> {} {})", l, r), Sort::Bool))
+            }
+        }
+    }
+
+    /// Get the symbol name of an expression, creating a new symbol if needed.
+    fn get_or_insert_symbol(
+        &mut self,
+        expr: &SymExpr<'ctx>,
+        width: u32,
+    ) -> String {
+        if let Some(name) = self.names.get(expr) {
+            return name.clone();
+        }
+
+        let name = format!("sym_{}", self.symbols.len());
+        self.symbols.push((name.clone(), expr.clone(), width));
+        self.names.insert(expr.clone(), name.clone());
+        name
+    }
+}
+
+/// Get the bit width of a symbolic value or memory location, if it is a
+/// non-constant integer.
+fn get_symbol_width(expr: &SymExpr) -> Option<u32> {
+    match expr {
+        SymExpr::Var(BasicValueEnum::IntValue(v)) if !v.is_const() => {
+            Some(v.get_type().get_bit_width())
+        }
+        SymExpr::Memory(ptr) => match ptr.get_type().get_element_type() {
+            AnyTypeEnum::IntType(typ) => Some(typ.get_bit_width()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Convert a term to a Boolean term.
+fn to_bool(term: String, sort: Sort) -> Option<String> {
+    match sort {
+        Sort::Bool => Some(term),
+        Sort::BitVec(1) => Some(format!("(= {} #b1)", term)),
+        Sort::BitVec(_) => None,
+    }
+}
+
+/// Convert a term to a bitvector term.
+fn to_bitvector(term: String, sort: Sort) -> String {
+    match sort {
+        Sort::Bool => format!("(ite {} #b1 #b0)", term),
+        Sort::BitVec(_) => term,
+    }
+}
+
+/// Get the bitvector sort of terms of a sort, after converting Boolean terms
+/// to bitvectors.
+fn get_bitvector_sort(sort: Sort) -> Sort {
+    match sort {
+        Sort::Bool => Sort::BitVec(1),
+        sort => sort,
+    }
+}
+
+/// Get the SMT-LIB bitvector operator of an integer binary opcode.
+fn get_binary_operator(op: InstructionOpcode) -> Option<&'static str> {
+    match op {
+        InstructionOpcode::Add => Some("bvadd"),
+        InstructionOpcode::Sub => Some("bvsub"),
+        InstructionOpcode::Mul => Some("bvmul"),
+        InstructionOpcode::UDiv => Some("bvudiv"),
+        InstructionOpcode::SDiv => Some("bvsdiv"),
+        InstructionOpcode::URem => Some("bvurem"),
+        InstructionOpcode::SRem => Some("bvsrem"),
+        InstructionOpcode::Shl => Some("bvshl"),
+        InstructionOpcode::LShr => Some("bvlshr"),
+        InstructionOpcode::AShr => Some("bvashr"),
+        InstructionOpcode::And => Some("bvand"),
+        InstructionOpcode::Or => Some("bvor"),
+        InstructionOpcode::Xor => Some("bvxor"),
+        _ => None,
+    }
+}
+
+/// Get the SMT-LIB bitvector comparison operator of an integer predicate,
+/// other than equality and disequality.
+fn get_predicate(pred: IntPredicate) -> &'static str {
+    match pred {
+        IntPredicate::UGT => "bvugt",
+        IntPredicate::UGE => "bvuge",
+        IntPredicate::ULT => "bvult",
+        IntPredicate::ULE => "bvule",
+        IntPredicate::SGT => "bvsgt",
+        IntPredicate::SGE => "bvsge",
+        IntPredicate::SLT => "bvslt",
+        IntPredicate::SLE => "bvsle",
+        IntPredicate::EQ => "=",
+        IntPredicate::NE => "distinct",
+    }
+}
@@ -0,0 +1,122 @@
+//! Module providing an abstraction of SMT solvers, used to discharge
+//! verification obligations.
+
+// Export sub modules
+pub mod counterexample;
+pub mod encoder;
+pub mod smtlib;
+
+// Re-export sub-modules' data structures
+pub use counterexample::{Counterexample, CounterexampleReporter};
+pub use encoder::SmtEncoder;
+pub use smtlib::SmtLibSolver;
+
+use std::collections::HashMap;
+
+/// Result of checking satisfiability of constraints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SatResult {
+    /// Constraints are satisfiable.
+    Sat,
+
+    /// Constraints are unsatisfiable.
+    Unsat,
+
+    /// The solver cannot decide satisfiability of constraints.
+    Unknown,
+}
+
+/// Result of checking validity of a constraint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Validity {
+    /// The constraint holds under all models of asserted constraints.
+    Valid,
+
+    /// The constraint is violated, with a model of the violation if the
+    /// solver produces one.
+    Invalid(Option<Model>),
+
+    /// The solver cannot decide validity of the constraint.
+    Unknown,
+}
+
+/// Data structure representing a model of satisfiable constraints.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Model {
+    /// Values of variables, in SMT-LIB literal format, e.g. `#x0000000a`.
+    values: HashMap<String, String>,
+}
+
+/// Implement methods for `Model`.
+impl Model {
+    /// Constructor
+    pub fn new(values: HashMap<String, String>) -> Self {
+        Model { values }
+    }
+
+    /// Get the SMT-LIB literal value of a variable.
+    pub fn get_value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|v| v.as_str())
+    }
+
+    /// Get the value of a bitvector variable as an unsigned integer.
+    pub fn get_bitvector_value(&self, name: &str) -> Option<u128> {
+        let value = self.get_value(name)?;
+
+        if let Some(hex) = value.strip_prefix("#x") {
+            u128::from_str_radix(hex, 16).ok()
+        } else if let Some(bin) = value.strip_prefix("#b") {
+            u128::from_str_radix(bin, 2).ok()
+        } else if let Some(dec) = value.strip_prefix("(_ bv") {
+            dec.split_whitespace().next()?.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Get names of all variables in the model.
+    pub fn get_variables(&self) -> Vec<&str> {
+        let mut names: Vec<&str> =
+            self.values.keys().map(|k| k.as_str()).collect();
+        names.sort();
+        names
+    }
+}
+
+/// Trait of an SMT solver handling bitvector constraints.
+///
+/// Constraints are SMT-LIB terms, e.g. `(bvult x #x0000000a)`.
+pub trait Solver {
+    /// Declare a bitvector variable of a bit width.
+    fn declare_bitvector(&mut self, name: &str, width: u32);
+
+    /// Assert a constraint.
+    fn assert(&mut self, constraint: &str);
+
+    /// Save the current assertion scope.
+    fn push(&mut self);
+
+    /// Restore the last saved assertion scope.
+    fn pop(&mut self);
+
+    /// Check satisfiability of asserted constraints.
+    fn check_sat(&mut self) -> SatResult;
+
+    /// Get a model of asserted constraints, if the last satisfiability check
+    /// returned `SatResult::Sat`.
+    fn get_model(&mut self) -> Option<Model>;
+
+    /// Check if a constraint is valid under asserted constraints, i.e., its
+    /// negation is unsatisfiable.
+    fn check_valid(&mut self, constraint: &str) -> Validity {
+        self.push();
+        self.assert(&format!("(not {})", constraint));
+        let res = match self.check_sat() {
+            SatResult::Sat => Validity::Invalid(self.get_model()),
+            SatResult::Unsat => Validity::Valid,
+            SatResult::Unknown => Validity::Unknown,
+        };
+        self.pop();
+        res
+    }
+}
@@ -0,0 +1,139 @@
+//! Module implementing a process-based SMT solver backend, which communicates
+//! with an external solver (Z3 or CVC5) via SMT-LIB scripts.
+
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use crate::tool::smt_solver;
+
+use super::{Model, SatResult, Solver};
+
+/// SMT solver running an external solver process on SMT-LIB scripts.
+///
+/// Each satisfiability check runs a new solver process on all commands issued
+/// so far.
+#[derive(Debug, Clone)]
+pub struct SmtLibSolver {
+    /// Executable name of the external solver.
+    solver: String,
+
+    /// SMT-LIB commands issued so far.
+    commands: Vec<String>,
+
+    /// Numbers of commands at saved assertion scopes.
+    scopes: Vec<usize>,
+
+    /// Model of the last satisfiability check, if it is satisfiable.
+    model: Option<Model>,
+}
+
+/// Implement methods for `SmtLibSolver`.
+impl SmtLibSolver {
+    /// Constructor, using the first available solver in the environment.
+    ///
+    /// Return `None` if no solver is found.
+    pub fn new() -> Option<Self> {
+        smt_solver::find_smt_solver().map(SmtLibSolver::with_solver)
+    }
+
+    /// Constructor, using a given external solver.
+    pub fn with_solver(solver: &str) -> Self {
+        SmtLibSolver {
+            solver: solver.to_string(),
+            commands: vec!["(set-option :produce-models true)".to_string()],
+            scopes: vec![],
+            model: None,
+        }
+    }
+
+    /// Get the SMT-LIB script of commands issued so far.
+    pub fn get_script(&self) -> String {
+        self.commands.join("\n")
+    }
+
+    /// Run the external solver on the current script, followed by commands
+    /// to check satisfiability and get the model.
+    fn run_solver(&self) -> Option<String> {
+        let script = self.get_script() + "\n(check-sat)\n(get-model)\n(exit)\n";
+        let args = smt_solver::get_smt_solver_args(&self.solver);
+
+        let mut child = Command::new(&self.solver)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .ok()?;
+
+        child.stdin.take()?.write_all(script.as_bytes()).ok()?;
+        let output = child.wait_with_output().ok()?;
+
+        String::from_utf8(output.stdout).ok()
+    }
+}
+
+/// Implement the trait `Solver` for `SmtLibSolver`.
+impl Solver for SmtLibSolver {
+    fn declare_bitvector(&mut self, name: &str, width: u32) {
+        let cmd = format!("(declare-const {} (_ BitVec {}))", name, width);
+        self.commands.push(cmd)
+    }
+
+    fn assert(&mut self, constraint: &str) {
+        self.commands.push(format!("(assert {})", constraint))
+    }
+
+    fn push(&mut self) {
+        self.scopes.push(self.commands.len())
+    }
+
+    fn pop(&mut self) {
+        if let Some(len) = self.scopes.pop() {
+            self.commands.truncate(len)
+        }
+    }
+
+    fn check_sat(&mut self) -> SatResult {
+        self.model = None;
+
+        let output = match self.run_solver() {
+            Some(output) => output,
+            None => {
+                log_debug!("Failed to run SMT solver: {}", self.solver);
+                return SatResult::Unknown;
+            }
+        };
+
+        match output.lines().next().map(|line| line.trim()) {
+            Some("sat") => {
+                self.model = Some(parse_model(&output));
+                SatResult::Sat
+            }
+            Some("unsat") => SatResult::Unsat,
+            _ => SatResult::Unknown,
+        }
+    }
+
+    fn get_model(&mut self) -> Option<Model> {
+        self.model.clone()
+    }
+}
+
+/// Parse a model of bitvector variables from the output of `(get-model)`.
+fn parse_model(output: &str) -> Model {
+    let regex = Regex::new(
+        r"\(define-fun\s+(\S+)\s+\(\)\s+\(_\s+BitVec\s+\d+\)\s+(#x[0-9a-fA-F]+|#b[01]+|\(_\s+bv\d+\s+\d+\))\s*\)",
+    )
+    .unwrap();
+
+    let values: HashMap<String, String> = regex
+        .captures_iter(output)
+        .map(|cap| (cap[1].to_string(), cap[2].to_string()))
+        .collect();
+
+    Model::new(values)
+}
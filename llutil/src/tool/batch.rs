@@ -0,0 +1,27 @@
+//! Module compiling multiple input files in a batch, with progress reports.
+
+use crate::progress::{ProgressSink, ProgressTracker};
+
+/// Compile input files one after another by a compilation function, such as
+/// `clang::compile`, and return the output files of each input file.
+pub fn compile_files<F>(
+    input_files: &[&str],
+    compile: F,
+    progress: &dyn ProgressSink,
+) -> Vec<Vec<String>>
+where
+    F: Fn(&str) -> Vec<String>,
+{
+    let mut tracker =
+        ProgressTracker::new(progress, "compilation", input_files.len());
+    let mut output_files = vec![];
+
+    for input_file in input_files {
+        tracker.start_item(input_file);
+        output_files.push(compile(input_file));
+        tracker.finish_item();
+    }
+
+    tracker.finish();
+    output_files
+}
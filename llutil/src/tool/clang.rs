@@ -2,7 +2,7 @@
 
 use regex::Regex;
 use semver::{Version, VersionReq};
-use std::{ffi::OsStr, fs, path::Path, process::Command};
+use std::{ffi::OsStr, fs, io, path::Path, process::Command};
 
 use crate::tool::{self, OUTPUT_DIR};
 use rutil::string::StringExt;
@@ -13,7 +13,7 @@ use super::LLVM_REQUIRED_VERSION;
 /// Checking path of Clang
 fn check_clang_path() {
     match system::path_of_command_from_env(tool::CLANG) {
-        Ok(path) => debug!("Clang path: {}", path),
+        Ok(path) => log_debug!("Clang path: {}", path),
         Err(_) => panic!("Clang path not found!"),
     }
 }
@@ -146,3 +146,27 @@ pub fn compile(
 
     vec![final_output_file.to_owned()]
 }
+
+/// Preprocess a C/C++ file, keeping comments and line markers, and return
+/// the preprocessed code.
+pub fn preprocess(
+    input_file: &str,
+    include_dirs: &[&str],
+) -> io::Result<String> {
+    let mut clang_args = vec!["-E", "-C"];
+    for dir in include_dirs {
+        clang_args.extend(["-I", *dir]);
+    }
+    clang_args.push(input_file);
+
+    let clang_output = Command::new(tool::CLANG).args(&clang_args).output()?;
+
+    if !clang_output.status.success() {
+        let error_msg = String::from_utf8_lossy(&clang_output.stderr);
+        let msg =
+            format!("Failed to preprocess: {}\n{}", input_file, error_msg);
+        return Err(io::Error::new(io::ErrorKind::Other, msg));
+    }
+
+    Ok(String::from_utf8_lossy(&clang_output.stdout).to_string())
+}
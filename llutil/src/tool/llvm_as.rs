@@ -14,7 +14,7 @@ use super::LLVM_REQUIRED_VERSION;
 /// Check path of the LLVM assembler tool (llvm-as)
 fn check_llvm_assembler_path() {
     match system::path_of_command_from_env(tool::LLVM_AS) {
-        Ok(path) => debug!("llvm-as path: {}", path),
+        Ok(path) => log_debug!("llvm-as path: {}", path),
         Err(_) => panic!("llvm-as path not found: {}!", tool::LLVM_AS),
     }
 }
@@ -13,7 +13,7 @@ use super::LLVM_REQUIRED_VERSION;
 /// Check path of the LLVM disassembler tool (llvm-dis)
 fn check_llvm_disassembler_path() {
     match system::path_of_command_from_env(tool::LLVM_DIS) {
-        Ok(path) => debug!("llvm-dis path: {}", path),
+        Ok(path) => log_debug!("llvm-dis path: {}", path),
         Err(_) => panic!("llvm-dis path not found: {}!", tool::LLVM_DIS),
     }
 }
@@ -100,3 +100,54 @@ pub fn disassemble(input_file: &str) {
 
     // debug!("Disassembled bitcode file to: {}", output_file_name)
 }
+
+/// Get the version of an LLVM disassembler command, if it can be run.
+fn get_disassembler_version(command: &str) -> Option<Version> {
+    let output = Command::new(command).args(&["--version"]).output().ok()?;
+    let output_str = String::from_utf8_lossy(&output.stdout);
+    let regex = Regex::new(r"version (\d+\.\d+\.\d+)").unwrap();
+    let capture = regex.captures(&output_str)?;
+    Version::parse(&capture[1]).ok()
+}
+
+/// Find an LLVM disassembler command able to read bitcode produced by a
+/// given LLVM major version.
+///
+/// The versioned command of a package manager, e.g., `llvm-dis-17`, is
+/// preferred over the default one. Return `None` if no installed command is
+/// recent enough.
+pub fn find_disassembler_for_version(major: u64) -> Option<String> {
+    let versioned_command = format!("{}-{}", tool::LLVM_DIS, major);
+    [versioned_command, tool::LLVM_DIS.to_string()]
+        .into_iter()
+        .find(|command| match get_disassembler_version(command) {
+            Some(version) => version.major >= major,
+            None => false,
+        })
+}
+
+/// Disassemble an LLVM bitcode file into an output file, using a given
+/// disassembler command.
+///
+/// Unlike `disassemble`, the error message of the command is returned instead
+/// of panicking, so that callers can fall back to another way of loading the
+/// bitcode.
+pub fn disassemble_with(
+    command: &str,
+    input_file: &Path,
+    output_file: &Path,
+) -> Result<(), String> {
+    let output = Command::new(command)
+        .arg(input_file)
+        .arg("-o")
+        .arg(output_file)
+        .output()
+        .map_err(|err| format!("{}: {}", command, err))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{}: {}", command, error_msg.trim()));
+    }
+
+    Ok(())
+}
@@ -12,7 +12,7 @@ use super::LLVM_REQUIRED_VERSION;
 /// Check path of the LLVM optimization tool (llvm-opt)
 fn check_llvm_optimization_path() {
     match system::path_of_command_from_env(tool::LLVM_OPT) {
-        Ok(path) => debug!("llvm-opt path: {}", path),
+        Ok(path) => log_debug!("llvm-opt path: {}", path),
         Err(_) => panic!("llvm-opt path not found: {}!", tool::LLVM_OPT),
     }
 }
@@ -1,12 +1,14 @@
 //! Module containing front-end external tools of Verazt
 
 // Exporting sub-modules
+pub mod batch;
 pub mod clang;
 pub mod llvm;
 pub mod llvm_as;
 pub mod llvm_dis;
 pub mod llvm_opt;
 pub mod rustc;
+pub mod smt_solver;
 pub mod solang;
 pub mod solc;
 pub mod solana;
@@ -26,6 +28,9 @@ pub const LLVM_LINK: &str = "llvm-link";
 /// Executable file name of the LLVM optimization tool
 pub const LLVM_OPT: &str = "opt";
 
+/// Executable file name of the CVC5 SMT solver
+pub const CVC5: &str = "cvc5";
+
 /// Executable file name of the Rustc compiler
 pub const RUSTC: &str = "rustc";
 
@@ -35,6 +40,9 @@ pub const SOLANG: &str = "solang";
 /// Executable file name of the Solc compiler
 pub const SOLC: &str = "solc";
 
+/// Executable file name of the Z3 SMT solver
+pub const Z3: &str = "z3";
+
 /// Executable file name of the cargo 
 pub const CARGO: &str = "cargo";
 
@@ -15,7 +15,7 @@ const RUSTC_VERSION_REQ: &str = ">=0.8.11";
 /// Check path of the Rustc compiler
 fn check_rustc_path() {
     match system::path_of_command_from_env(tool::RUSTC) {
-        Ok(path) => debug!("Rustc path: {}", path),
+        Ok(path) => log_debug!("Rustc path: {}", path),
         Err(_) => panic!("Rustc path not found!"),
     }
 }
@@ -81,7 +81,7 @@ pub fn compile(input_file: &str, user_options: &[&str]) -> Vec<String> {
 
     let rustc_args = input_file.to_owned()
         + &user_options.join(" ").add_prefix_if_not_empty(" ")
-        + " --emit llvm-bc"
+        + " --emit llvm-bc -g"
         + format!(" -o {}", output_dir_path).as_str();
 
     // debug!("Running command: {} {}", tool::RUSTC, rustc_args);
@@ -0,0 +1,54 @@
+//! Module detecting external SMT solvers supporting the SMT-LIB format.
+
+use std::process::Command;
+
+use crate::tool;
+use rutil::system;
+
+/// Check if an SMT solver is available in the environment.
+fn check_smt_solver_path(solver: &str) -> bool {
+    match system::path_of_command_from_env(solver) {
+        Ok(path) => {
+            log_debug!("{} path: {}", solver, path);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Check if an SMT solver can be executed.
+fn check_smt_solver_version(solver: &str) -> bool {
+    match Command::new(solver).args(&["--version"]).output() {
+        Ok(output) => output.status.success(),
+        Err(_) => false,
+    }
+}
+
+/// Find an available SMT solver, preferring Z3 over CVC5.
+pub fn find_smt_solver() -> Option<&'static str> {
+    [tool::Z3, tool::CVC5].into_iter().find(|solver| {
+        check_smt_solver_path(solver) && check_smt_solver_version(solver)
+    })
+}
+
+/// Get command-line arguments to run an SMT solver reading an SMT-LIB script
+/// from the standard input.
+pub fn get_smt_solver_args(solver: &str) -> Vec<&'static str> {
+    if solver == tool::CVC5 {
+        vec!["--lang", "smt2", "--produce-models", "--incremental"]
+    } else {
+        vec!["-in", "-smt2"]
+    }
+}
+
+/// Check settings of SMT solvers.
+pub fn check_smt_solver_settings() -> &'static str {
+    match find_smt_solver() {
+        Some(solver) => solver,
+        None => panic!(
+            "SMT solver not found: expect {} or {}!",
+            tool::Z3,
+            tool::CVC5
+        ),
+    }
+}
@@ -19,7 +19,7 @@ const CARGO_BUILD_SBF_VERSION_REQ: &str = ">=1.0.0";
 /// check path of cargo
 fn check_cargo_path() {
     match system::path_of_command_from_env(tool::CARGO) {
-        Ok(path) => debug!("Cargo path: {}", path),
+        Ok(path) => log_debug!("Cargo path: {}", path),
         Err(_) => panic!("Cargo path not found!"),
     }
 }
@@ -27,7 +27,7 @@ fn check_cargo_path() {
 /// check path of cargo-build-sbf
 fn check_cargo_build_sbf_path() {
     match system::path_of_command_from_env(tool::CARGO_BUILD_SBF) {
-        Ok(path) => debug!("Cargo-build-sbf path: {}", path),
+        Ok(path) => log_debug!("Cargo-build-sbf path: {}", path),
         Err(_) => panic!("Cargo-build-sbf not found!"),
     }
 }
@@ -141,7 +141,7 @@ pub fn compile(input_file: &str, user_options: &[&str]) -> Vec<String> {
     let solana_args = user_options.add_prefix_if_not_empty(" ")
                             + "--manifest-path " + cargo_toml_path;
 
-    debug!("Running command: {} {}", tool::CARGO_BUILD_BPF, solana_args);
+    log_debug!("Running command: {} {}", tool::CARGO_BUILD_BPF, solana_args);
 
     let solana_output = Command::new(tool::CARGO_BUILD_BPF)
         .args(solana_args.split_whitespace())
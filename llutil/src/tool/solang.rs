@@ -15,7 +15,7 @@ const SOLANG_REQUIRED_VERSION: &str = ">=0.1.13";
 /// Check path of the Solang compiler
 fn check_solang_path() {
     match system::path_of_command_from_env(tool::SOLANG) {
-        Ok(path) => debug!("Solang path: {}", path),
+        Ok(path) => log_debug!("Solang path: {}", path),
         Err(_) => panic!("Solang path not found!"),
     }
 }
@@ -15,7 +15,7 @@ pub const SOLC_REQUIRED_VERSION: &str = ">=0.8.11";
 /// Check path of the Solc compiler
 fn check_solc_path() {
     match system::path_of_command_from_env(tool::SOLC) {
-        Ok(path) => debug!("Solc path: {}", path),
+        Ok(path) => log_debug!("Solc path: {}", path),
         Err(_) => panic!("Solc path not found!"),
     }
 }
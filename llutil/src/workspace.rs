@@ -0,0 +1,348 @@
+//! Module managing the lifetimes of LLVM contexts and modules of a batch run.
+//!
+//! A `WorkspaceContext` owns, for each loaded module, its `Context`, its
+//! `CodeFile` provenance and its analysis cache. Each module is loaded in its
+//! own context, so that unloading a module also frees the types and constants
+//! of its context, which LLVM only frees when the context is disposed. The
+//! memory used by a batch run is therefore bounded by the modules which are
+//! loaded at the same time.
+//!
+//! Bitcode files produced by a newer LLVM than the linked one, e.g., by the
+//! LLVM of a recent rustc, cannot be parsed directly. They are downgraded by a
+//! round-trip through textual IR, using an installed disassembler of the
+//! newer LLVM, or reported by a `BitcodeVersionMismatch` error.
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Display},
+    fs,
+    path::Path,
+};
+
+use inkwell::{context::Context, memory_buffer::MemoryBuffer, module::Module};
+use regex::Regex;
+
+use crate::{
+    file::{ext, FileType},
+    ir::{code_file::PipelineStage, CodeFile},
+    pass::AnalysisCache,
+    tool::{llvm_dis, OUTPUT_DIR},
+};
+
+/// Identifier of a module loaded in a `WorkspaceContext`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModuleId(usize);
+
+/// Data structure storing a loaded module and the data owned with it.
+struct LoadedModule {
+    /// The module, borrowing `context`.
+    ///
+    /// It is declared before `context`, so that it is dropped first.
+    module: Module<'static>,
+
+    /// Provenance of the module.
+    code_file: CodeFile,
+
+    /// Analysis results cached for functions of the module.
+    cache: AnalysisCache,
+
+    /// Context owning the module, boxed so that its address is stable.
+    #[allow(dead_code)] // Only held to keep the context alive.
+    context: Box<Context>,
+}
+
+/// Data structure owning the contexts, modules, provenance and analysis caches
+/// of a batch run.
+#[derive(Default)]
+pub struct WorkspaceContext {
+    /// Loaded modules, indexed by their identifiers.
+    modules: BTreeMap<ModuleId, LoadedModule>,
+
+    /// Identifier of the next loaded module.
+    next_id: usize,
+}
+
+/// Implement methods for `WorkspaceContext`.
+impl WorkspaceContext {
+    /// Constructor of an empty workspace.
+    pub fn new() -> Self {
+        WorkspaceContext::default()
+    }
+
+    /// Load the LLVM bitcode or IR file of a code file in a new context.
+    ///
+    /// The provenance of the code file is restored from its metadata file, if
+    /// it has one.
+    pub fn load(&mut self, code_file: CodeFile) -> Result<ModuleId, String> {
+        let file_name = code_file.file_name.clone();
+        self.load_with(code_file, |context| {
+            load_module_file(context, Path::new(&file_name))
+        })
+    }
+
+    /// Load a module built by a function, e.g., by linking several files, in
+    /// a new context.
+    pub fn load_with<F>(
+        &mut self,
+        mut code_file: CodeFile,
+        build: F,
+    ) -> Result<ModuleId, String>
+    where
+        F: for<'ctx> FnOnce(&'ctx Context) -> Result<Module<'ctx>, String>,
+    {
+        if let Err(err) = code_file.load_metadata() {
+            log_warn!("Failed to load metadata of {}: {}", code_file, err);
+        }
+
+        let context = Box::new(Context::create());
+        let context_ptr = context.as_ref() as *const Context;
+        // SAFETY: extending the context lifetime to `'static` is sound since
+        // the context is heap-allocated and is only dropped after the module,
+        // when the `LoadedModule` owning both is dropped. The module is only
+        // lent by `with_module` to functions generic over its context
+        // lifetime, so its values cannot outlive the context.
+        let context_ref: &'static Context = unsafe { &*context_ptr };
+        let module = build(context_ref)?;
+
+        let id = ModuleId(self.next_id);
+        self.next_id += 1;
+        self.modules.insert(
+            id,
+            LoadedModule {
+                module,
+                code_file,
+                cache: AnalysisCache::new(),
+                context,
+            },
+        );
+
+        Ok(id)
+    }
+
+    /// Unload a module, disposing of its context and analysis cache.
+    ///
+    /// Return `false` if the module is not loaded.
+    pub fn unload(&mut self, id: ModuleId) -> bool {
+        self.modules.remove(&id).is_some()
+    }
+
+    /// Run a function on a loaded module.
+    ///
+    /// The function is generic over the lifetime of the context, so that no
+    /// value of the module can escape it and outlive an `unload` of the
+    /// module. Return `None` if the module is not loaded.
+    pub fn with_module<F, R>(&self, id: ModuleId, f: F) -> Option<R>
+    where
+        F: for<'ctx> FnOnce(&Module<'ctx>, &CodeFile, &AnalysisCache) -> R,
+    {
+        let loaded = self.modules.get(&id)?;
+        Some(f(&loaded.module, &loaded.code_file, &loaded.cache))
+    }
+
+    /// Get the code file of a loaded module.
+    pub fn get_code_file(&self, id: ModuleId) -> Option<&CodeFile> {
+        self.modules.get(&id).map(|loaded| &loaded.code_file)
+    }
+
+    /// Record that a loaded module was transformed into a new artifact by a
+    /// pipeline stage, extending its provenance.
+    pub fn derive_code_file(
+        &mut self,
+        id: ModuleId,
+        file_name: &str,
+        stage: PipelineStage,
+    ) -> Option<&CodeFile> {
+        let loaded = self.modules.get_mut(&id)?;
+        loaded.code_file = loaded.code_file.derive_from_file(file_name, stage);
+        Some(&loaded.code_file)
+    }
+
+    /// Get the analysis cache of a loaded module.
+    pub fn get_cache(&self, id: ModuleId) -> Option<&AnalysisCache> {
+        self.modules.get(&id).map(|loaded| &loaded.cache)
+    }
+
+    /// Get the identifiers of all loaded modules, in loading order.
+    pub fn get_module_ids(&self) -> Vec<ModuleId> {
+        self.modules.keys().copied().collect()
+    }
+
+    /// Get the number of loaded modules.
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    /// Check if no module is loaded.
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
+
+/// Data structure representing the failure to parse a bitcode file produced
+/// by a newer LLVM version than the linked one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitcodeVersionMismatch {
+    /// Name of the bitcode file.
+    pub file_name: String,
+
+    /// LLVM version which produced the bitcode, e.g., `LLVM17.0.6`.
+    pub producer: String,
+
+    /// LLVM version reading the bitcode, e.g., `LLVM 14.0.6`.
+    pub reader: String,
+
+    /// Error message of the bitcode parser.
+    pub message: String,
+
+    /// Error message of the downgrade through textual IR, if it failed.
+    pub downgrade_error: Option<String>,
+}
+
+/// Implement methods for `BitcodeVersionMismatch`.
+impl BitcodeVersionMismatch {
+    /// Constructor from the error message of the bitcode parser.
+    ///
+    /// LLVM appends the producer and reader versions to the messages of
+    /// errors of bitcode files whose producer is known. Return `None` if the
+    /// message does not show a producer newer than the reader.
+    pub fn from_parse_error(file_name: &str, message: &str) -> Option<Self> {
+        let regex =
+            Regex::new(r"Producer: '([^']*)' Reader: '([^']*)'").unwrap();
+        let capture = regex.captures(message)?;
+        let mismatch = BitcodeVersionMismatch {
+            file_name: file_name.to_string(),
+            producer: capture[1].to_string(),
+            reader: capture[2].to_string(),
+            message: message.to_string(),
+            downgrade_error: None,
+        };
+
+        match (
+            mismatch.get_producer_major(),
+            get_major_version(&mismatch.reader),
+        ) {
+            (Some(producer), Some(reader)) if producer > reader => {
+                Some(mismatch)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the major version of the LLVM which produced the bitcode.
+    pub fn get_producer_major(&self) -> Option<u64> {
+        get_major_version(&self.producer)
+    }
+}
+
+/// Implement the trait `Display` for `BitcodeVersionMismatch`.
+impl Display for BitcodeVersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bitcode file {} is produced by {} but read by {}: {}",
+            self.file_name, self.producer, self.reader, self.message
+        )?;
+        if let Some(err) = &self.downgrade_error {
+            write!(f, "\nFailed to downgrade it through textual IR: {}", err)?;
+        }
+        Ok(())
+    }
+}
+
+/// Implement the trait `Error` for `BitcodeVersionMismatch`.
+impl Error for BitcodeVersionMismatch {}
+
+/// Get the major version of an LLVM version string, e.g., 17 of `LLVM17.0.6`.
+fn get_major_version(version: &str) -> Option<u64> {
+    let regex = Regex::new(r"LLVM\s*(\d+)").unwrap();
+    regex.captures(version)?[1].parse().ok()
+}
+
+/// Load an LLVM bitcode or IR file into a module.
+///
+/// A bitcode file produced by a newer LLVM version is downgraded through
+/// textual IR if possible, otherwise the error is a `BitcodeVersionMismatch`.
+pub fn load_module_file<'ctx>(
+    context: &'ctx Context,
+    path: &Path,
+) -> Result<Module<'ctx>, String> {
+    match FileType::new(&path.to_string_lossy()) {
+        FileType::LLVMIR => load_ir_file(context, path),
+        _ => load_bitcode_file(context, path),
+    }
+}
+
+/// Load an LLVM IR file into a module.
+fn load_ir_file<'ctx>(
+    context: &'ctx Context,
+    path: &Path,
+) -> Result<Module<'ctx>, String> {
+    let buffer =
+        MemoryBuffer::create_from_file(path).map_err(|err| err.to_string())?;
+    context
+        .create_module_from_ir(buffer)
+        .map_err(|err| err.to_string())
+}
+
+/// Load an LLVM bitcode file into a module, downgrading it through textual IR
+/// if it is produced by a newer LLVM version.
+fn load_bitcode_file<'ctx>(
+    context: &'ctx Context,
+    path: &Path,
+) -> Result<Module<'ctx>, String> {
+    let message = match Module::parse_bitcode_from_path(path, context) {
+        Ok(module) => return Ok(module),
+        Err(err) => err.to_string(),
+    };
+
+    let file_name = path.to_string_lossy();
+    let mut mismatch =
+        match BitcodeVersionMismatch::from_parse_error(&file_name, &message) {
+            Some(mismatch) => mismatch,
+            None => return Err(message),
+        };
+
+    match downgrade_bitcode_file(context, path, &mismatch) {
+        Ok(module) => {
+            log_warn!(
+                "Downgraded {} from {} to {} through textual IR",
+                file_name,
+                mismatch.producer,
+                mismatch.reader
+            );
+            Ok(module)
+        }
+        Err(err) => {
+            mismatch.downgrade_error = Some(err);
+            Err(mismatch.to_string())
+        }
+    }
+}
+
+/// Downgrade a bitcode file produced by a newer LLVM version, by
+/// disassembling it with a disassembler of that version into the output
+/// directory next to it, then parsing the textual IR.
+fn downgrade_bitcode_file<'ctx>(
+    context: &'ctx Context,
+    path: &Path,
+    mismatch: &BitcodeVersionMismatch,
+) -> Result<Module<'ctx>, String> {
+    let major = mismatch
+        .get_producer_major()
+        .ok_or_else(|| format!("Unknown producer: {}", mismatch.producer))?;
+    let command =
+        llvm_dis::find_disassembler_for_version(major).ok_or_else(|| {
+            format!("No llvm-dis of LLVM {} or newer found", major)
+        })?;
+
+    let file_name = path.file_name().unwrap_or_default();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let parent_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let output_dir = parent_dir.join(OUTPUT_DIR).join(file_name);
+    fs::create_dir_all(&output_dir).map_err(|err| err.to_string())?;
+
+    let output_file = output_dir.join(format!("{}.{}", stem, ext::LL));
+    llvm_dis::disassemble_with(&command, path, &output_file)?;
+    load_ir_file(context, &output_file)
+}
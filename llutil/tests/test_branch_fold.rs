@@ -0,0 +1,143 @@
+//! Tests of folding branches on constant conditions.
+
+use inkwell::{
+    context::Context, memory_buffer::MemoryBuffer, module::Module,
+    values::AnyValue,
+};
+use llutil::normalize::branch_fold::{fold_branches, BranchFoldReport};
+
+/// LLVM IR of functions branching on constant conditions.
+const BRANCH_IR: &str = r#"
+define i32 @switch_fold(i32 %a) {
+entry:
+  switch i32 2, label %default [ i32 1, label %one
+                                 i32 2, label %two ]
+one:
+  br label %exit
+two:
+  br label %exit
+default:
+  br label %exit
+exit:
+  %r = phi i32 [ 1, %one ], [ 2, %two ], [ %a, %default ]
+  ret i32 %r
+}
+
+define i32 @switch_default(i32 %a) {
+entry:
+  switch i32 5, label %default [ i32 1, label %one ]
+one:
+  ret i32 1
+default:
+  ret i32 %a
+}
+
+define i32 @duplicate_edge_with_phi(i32 %a) {
+entry:
+  switch i32 1, label %other [ i32 1, label %join
+                               i32 2, label %join ]
+other:
+  br label %join
+join:
+  %r = phi i32 [ %a, %entry ], [ %a, %entry ], [ 0, %other ]
+  ret i32 %r
+}
+
+define void @duplicate_edge_without_phi() {
+entry:
+  br i1 true, label %join, label %join
+join:
+  ret void
+}
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        BRANCH_IR.as_bytes(),
+        "branch",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Fold branches of a function of the module.
+fn fold(module: &Module, func_name: &str) -> BranchFoldReport {
+    fold_branches(module.get_function(func_name).unwrap())
+}
+
+/// Print the instructions of a function.
+fn print_instructions(module: &Module, func_name: &str) -> Vec<String> {
+    let func = module.get_function(func_name).unwrap();
+    func.get_basic_blocks()
+        .into_iter()
+        .flat_map(|blk| blk.get_instructions())
+        .map(|inst| inst.print_to_string().to_string())
+        .collect()
+}
+
+#[test]
+fn test_fold_switch_to_matching_case() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    let report = fold(&module, "switch_fold");
+    assert_eq!(report.num_folded_branches, 1);
+    assert_eq!(report.num_removed_blocks, 2);
+    assert_eq!(
+        print_instructions(&module, "switch_fold"),
+        vec![
+            "br label %two",
+            "br label %exit",
+            "%r = phi i32 [ 2, %two ]",
+            "ret i32 %r",
+        ]
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_fold_switch_to_default() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    let report = fold(&module, "switch_default");
+    assert_eq!(report.num_folded_branches, 1);
+    assert_eq!(report.num_removed_blocks, 1);
+    assert_eq!(
+        print_instructions(&module, "switch_default"),
+        vec!["br label %default", "ret i32 %a"]
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_keep_duplicate_edge_into_phi_block() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let before = print_instructions(&module, "duplicate_edge_with_phi");
+
+    // Folding would leave two `phi` incomings for a single edge.
+    let report = fold(&module, "duplicate_edge_with_phi");
+    assert_eq!(report.num_folded_branches, 0);
+    assert_eq!(report.num_removed_blocks, 0);
+    assert_eq!(
+        print_instructions(&module, "duplicate_edge_with_phi"),
+        before
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_fold_duplicate_edge_without_phi() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    let report = fold(&module, "duplicate_edge_without_phi");
+    assert_eq!(report.num_folded_branches, 1);
+    assert_eq!(report.num_removed_blocks, 0);
+    assert_eq!(
+        print_instructions(&module, "duplicate_edge_without_phi"),
+        vec!["br label %join", "ret void"]
+    );
+    assert!(module.verify().is_ok());
+}
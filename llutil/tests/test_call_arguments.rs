@@ -0,0 +1,133 @@
+//! Tests of the called arguments of `call`, `invoke` and `callbr`
+//! instructions, including operand bundles and destination blocks.
+
+use inkwell::{
+    context::Context,
+    memory_buffer::MemoryBuffer,
+    module::Module,
+    types::AnyType,
+    values::{AnyValue, BasicValueEnum},
+};
+use llutil::ir::{AnyCall, InstructionExt};
+
+/// LLVM IR of C++-like functions calling other functions by `invoke`.
+const INVOKE_IR: &str = r#"
+declare void @may_throw(i32, i8*, i64)
+declare i32 @compute(i32)
+declare void @no_args()
+declare i32 @__gxx_personality_v0(...)
+
+define i32 @invokes(i32 %a, i8* %p, i64 %n, void (i32)* %fp)
+    personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  invoke void @may_throw(i32 %a, i8* %p, i64 %n)
+          to label %cont1 unwind label %lpad
+cont1:
+  %r = invoke i32 @compute(i32 %a) [ "deopt"(i64 %n, i8* %p) ]
+          to label %cont2 unwind label %lpad
+cont2:
+  invoke void @no_args()
+          to label %cont3 unwind label %lpad
+cont3:
+  invoke void %fp(i32 %r)
+          to label %cont4 unwind label %lpad
+cont4:
+  %s = call i32 @compute(i32 %r) [ "deopt"(i64 %n) ]
+  ret i32 %s
+lpad:
+  %lp = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %lp
+}
+
+define i32 @callbrs(i32 %a, i32 %b) {
+entry:
+  callbr void asm "", "r,r,X"(i32 %a, i32 %b, i8* blockaddress(@callbrs, %indirect))
+          to label %normal [label %indirect]
+normal:
+  ret i32 %a
+indirect:
+  ret i32 %b
+}
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        INVOKE_IR.as_bytes(),
+        "invoke",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Print a value as an operand, i.e., an instruction result is printed by
+/// its type and name instead of the whole instruction.
+fn print_operand(value: &BasicValueEnum) -> String {
+    let text = value.print_to_string().to_string();
+    match text.split_once(" = ") {
+        Some((name, _)) => {
+            format!("{} {}", value.get_type().print_to_string(), name.trim())
+        }
+        None => text,
+    }
+}
+
+/// Print the called arguments of all call instructions of a function.
+fn print_called_arguments(module: &Module, func_name: &str) -> Vec<String> {
+    let func = module.get_function(func_name).unwrap();
+    func.get_basic_blocks()
+        .into_iter()
+        .flat_map(|blk| blk.get_instructions())
+        .filter_map(|inst| inst.try_into_call_base())
+        .map(|call| {
+            let args: Vec<String> = call
+                .get_called_arguments()
+                .iter()
+                .map(print_operand)
+                .collect();
+            args.join(", ")
+        })
+        .collect()
+}
+
+#[test]
+fn test_invoke_arguments() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    assert_eq!(
+        print_called_arguments(&module, "invokes"),
+        vec!["i32 %a, i8* %p, i64 %n", "i32 %a", "", "i32 %r", "i32 %r"]
+    );
+}
+
+#[test]
+fn test_invoke_wrapper_arguments() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("invokes").unwrap();
+
+    let invokes: Vec<_> = func
+        .get_basic_blocks()
+        .into_iter()
+        .flat_map(|blk| blk.get_instructions())
+        .filter_map(|inst| inst.try_into_invoke_inst())
+        .collect();
+    let num_args: Vec<usize> = invokes
+        .iter()
+        .map(|invoke| invoke.get_called_arguments().len())
+        .collect();
+
+    assert_eq!(num_args, vec![3, 1, 0, 1]);
+    assert!(invokes[3].get_called_function().is_none());
+}
+
+#[test]
+fn test_callbr_arguments() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    assert_eq!(
+        print_called_arguments(&module, "callbrs"),
+        vec!["i32 %a, i32 %b, i8* blockaddress(@callbrs, %indirect)"]
+    );
+}
@@ -0,0 +1,154 @@
+//! Tests of the call graph of a module.
+
+use inkwell::{
+    context::Context, memory_buffer::MemoryBuffer, module::Module,
+    values::FunctionValue,
+};
+use llutil::{analysis::CallGraph, ir::FunctionExt};
+
+/// LLVM IR of a module with mutually recursive and self-recursive functions.
+const RECURSIVE_IR: &str = r#"
+define void @main() {
+entry:
+  call void @even(i32 10)
+  call void @loop(i32 3)
+  ret void
+}
+
+define void @even(i32 %n) {
+entry:
+  call void @odd(i32 %n)
+  ret void
+}
+
+define void @odd(i32 %n) {
+entry:
+  call void @even(i32 %n)
+  call void @leaf()
+  call void @leaf()
+  ret void
+}
+
+define void @leaf() {
+entry:
+  call void @ext()
+  ret void
+}
+
+define void @loop(i32 %n) {
+entry:
+  call void @loop(i32 %n)
+  ret void
+}
+
+declare void @ext()
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        RECURSIVE_IR.as_bytes(),
+        "recursive",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Get the names of functions.
+fn get_names(funcs: &[FunctionValue]) -> Vec<String> {
+    funcs
+        .iter()
+        .map(|func| func.get_name_or_default())
+        .collect()
+}
+
+#[test]
+fn test_sccs_in_reverse_topological_order() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let graph = CallGraph::new(&module);
+
+    let sccs: Vec<Vec<String>> = graph.iter_sccs().map(get_names).collect();
+    assert_eq!(
+        sccs,
+        vec![
+            vec!["ext"],
+            vec!["leaf"],
+            vec!["odd", "even"],
+            vec!["loop"],
+            vec!["main"],
+        ]
+    );
+    assert_eq!(
+        get_names(&graph.get_reverse_topological_order()),
+        vec!["ext", "leaf", "odd", "even", "loop", "main"]
+    );
+
+    let func = |name| module.get_function(name).unwrap();
+    assert!(graph.is_recursive(func("even")));
+    assert!(graph.is_recursive(func("odd")));
+    assert!(graph.is_recursive(func("loop")));
+    assert!(!graph.is_recursive(func("main")));
+    assert!(!graph.is_recursive(func("leaf")));
+}
+
+#[test]
+fn test_call_sites_of_callers_and_callees() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let graph = CallGraph::new(&module);
+    let func = |name| module.get_function(name).unwrap();
+
+    assert_eq!(graph.get_call_sites().len(), 8);
+    let call_sites: Vec<String> = graph
+        .get_call_sites_in(func("odd"))
+        .iter()
+        .map(|call_site| call_site.to_string())
+        .collect();
+    assert_eq!(
+        call_sites,
+        vec!["odd -> even", "odd -> leaf", "odd -> leaf"]
+    );
+
+    let callers: Vec<String> = graph
+        .get_call_sites_of(func("even"))
+        .iter()
+        .map(|call_site| call_site.caller.get_name_or_default())
+        .collect();
+    assert_eq!(callers, vec!["main", "odd"]);
+    assert!(graph.get_call_sites_of(func("main")).is_empty());
+    assert!(graph.get_call_sites_in(func("ext")).is_empty());
+
+    assert_eq!(
+        get_names(graph.get_callees(func("odd"))),
+        vec!["even", "leaf"]
+    );
+    assert_eq!(get_names(graph.get_callers(func("leaf"))), vec!["odd"]);
+}
+
+#[test]
+fn test_sccs_of_long_call_chain() {
+    let context = Context::create();
+    let module = context.create_module("chain");
+    let builder = context.create_builder();
+    let fn_type = context.void_type().fn_type(&[], false);
+
+    // Each function `f_i` calls `f_{i+1}`.
+    let num_funcs = 100_000;
+    let funcs: Vec<FunctionValue> = (0..num_funcs)
+        .map(|i| module.add_function(&format!("f_{}", i), fn_type, None))
+        .collect();
+    for (i, func) in funcs.iter().enumerate() {
+        let blk = context.append_basic_block(*func, "entry");
+        builder.position_at_end(blk);
+        if let Some(callee) = funcs.get(i + 1) {
+            builder.build_call(*callee, &[], "");
+        }
+        builder.build_return(None);
+    }
+
+    let graph = CallGraph::new(&module);
+    let order = graph.get_reverse_topological_order();
+    assert_eq!(graph.get_sccs().len(), num_funcs);
+    assert_eq!(order.first(), funcs.last());
+    assert_eq!(order.last(), funcs.first());
+}
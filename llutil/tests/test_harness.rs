@@ -0,0 +1,89 @@
+//! Tests of drivers executing a single function on input bytes.
+
+use inkwell::{
+    context::Context,
+    memory_buffer::MemoryBuffer,
+    module::{Linkage, Module},
+    targets::{InitializationConfig, Target},
+    LLVMReference, OptimizationLevel,
+};
+use llutil::instrument::generate_harness;
+use llvm_sys::execution_engine::LLVMGetGlobalValueAddress;
+
+/// LLVM IR of target functions.
+const TARGET_IR: &str = r#"
+define i32 @add(i32 %a, i8* %p) {
+entry:
+  %v = load i8, i8* %p
+  %z = zext i8 %v to i32
+  %s = add i32 %a, %z
+  ret i32 %s
+}
+
+define void @takes_float(float* %p) {
+entry:
+  ret void
+}
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        TARGET_IR.as_bytes(),
+        "target",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+#[test]
+fn test_run_harness_driver() {
+    Target::initialize_native(&InitializationConfig::default()).unwrap();
+
+    let context = Context::create();
+    let module = parse_module(&context);
+    let target = module.get_function("add").unwrap();
+
+    let harness = generate_harness(&context, &module, target).unwrap();
+    assert_eq!(harness.input_size, 5);
+    let result = harness.result.unwrap();
+    assert_eq!(result.get_linkage(), Linkage::External);
+    assert!(module.verify().is_ok());
+
+    let driver_name = harness.driver.get_name().to_str().unwrap().to_string();
+    let result_name = result.get_name().to_owned();
+    let engine = module
+        .create_jit_execution_engine(OptimizationLevel::None)
+        .unwrap();
+
+    unsafe {
+        let driver = engine
+            .get_function::<unsafe extern "C" fn(*const u8, u64) -> i32>(
+                &driver_name,
+            )
+            .unwrap();
+        let result_ptr =
+            LLVMGetGlobalValueAddress(engine.get_ref(), result_name.as_ptr())
+                as *const i32;
+        assert!(!result_ptr.is_null());
+
+        // The first 4 bytes are `%a`, the last byte is pointed to by `%p`.
+        let input = 40i32.to_ne_bytes();
+        let input = [input[0], input[1], input[2], input[3], 2];
+        assert_eq!(driver.call(input.as_ptr(), input.len() as u64), 0);
+        assert_eq!(*result_ptr, 42);
+
+        // Inputs which are too short are rejected.
+        let input = [1u8, 2, 3, 4];
+        assert_eq!(driver.call(input.as_ptr(), input.len() as u64), -1);
+        assert_eq!(*result_ptr, 42);
+    }
+}
+
+#[test]
+fn test_reject_non_integer_pointer_parameters() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let target = module.get_function("takes_float").unwrap();
+
+    assert!(generate_harness(&context, &module, target).is_none());
+}
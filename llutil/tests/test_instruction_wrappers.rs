@@ -0,0 +1,664 @@
+//! Golden-file tests of instruction wrappers.
+//!
+//! For each instruction wrapper, an exemplar instruction is built, converted
+//! through its `try_into_*` function, and the outputs of all its accessors are
+//! compared against a golden file. The golden file is regenerated when
+//! `UPDATE_SNAPSHOTS` is set.
+
+use std::{collections::HashSet, env, fs, path::Path};
+
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    memory_buffer::MemoryBuffer,
+    module::Module,
+    types::AnyType,
+    values::{AnyValue, BasicBlock, InstructionValue},
+    AddressSpace, AtomicOrdering, FloatPredicate, IntPredicate,
+};
+use llutil::{
+    ir::{
+        AnyCall, AnyCast, AnyCmp, AnyCondition, AnyInstruction, AnyMemAccess,
+        AnyTerminator, InstructionExt,
+    },
+    normalize::snapshot::{diff_lines, UPDATE_SNAPSHOTS_ENV},
+};
+use regex::Regex;
+
+/// Golden file of instruction wrapper accessors.
+const GOLDEN_FILE: &str = "tests/testcases/golden/instruction_wrappers.golden";
+
+/// Source file declaring the `try_into_*` conversions.
+const CONVERSION_FILE: &str = "src/ir/instruction.rs";
+
+/// Conversions of `InstructionExt` which do not produce instruction wrappers.
+const NON_WRAPPER_CONVERSIONS: [&str; 4] = [
+    "basic_value_enum",
+    "float_value",
+    "int_value",
+    "pointer_value",
+];
+
+/// LLVM IR of exemplars which cannot be built by the `Builder`.
+const PARSED_EXEMPLARS: &str = r#"
+define i32 @parsed(i32 %a) {
+entry:
+  %f = freeze i32 %a
+  callbr void asm "", "r,X"(i32 %f, i8* blockaddress(@parsed, %indirect))
+          to label %normal [label %indirect]
+normal:
+  ret i32 %f
+indirect:
+  ret i32 0
+}
+"#;
+
+/// Get the last instruction built by a builder.
+fn get_last_instruction<'ctx>(
+    builder: &Builder<'ctx>,
+) -> InstructionValue<'ctx> {
+    builder
+        .get_insert_block()
+        .and_then(|blk| blk.get_last_instruction())
+        .unwrap()
+}
+
+/// Find an instruction by its opcode name in a module.
+fn find_instruction<'ctx>(
+    module: &Module<'ctx>,
+    opcode: &str,
+) -> InstructionValue<'ctx> {
+    let func = module.get_first_function().unwrap();
+    func.get_basic_blocks()
+        .into_iter()
+        .flat_map(|blk| blk.get_instructions())
+        .find(|inst| format!("{:?}", inst.get_opcode()) == opcode)
+        .unwrap()
+}
+
+/// Build exemplar instructions, keyed by the name of their conversions.
+fn build_exemplars<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    parsed: &Module<'ctx>,
+) -> Vec<(&'static str, InstructionValue<'ctx>)> {
+    let builder = context.create_builder();
+    let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+    let i32_type = context.i32_type();
+    let f32_type = context.f32_type();
+
+    let callee_type = context.void_type().fn_type(&[i32_type.into()], false);
+    let callee = module.add_function("callee", callee_type, None);
+    let func_type = i32_type.fn_type(
+        &[i32_type.into(), f32_type.into(), i8_ptr_type.into()],
+        false,
+    );
+    let func = module.add_function("exemplar", func_type, None);
+    let a = func.get_nth_param(0).unwrap().into_int_value();
+    let x = func.get_nth_param(1).unwrap().into_float_value();
+    let p = func.get_nth_param(2).unwrap().into_pointer_value();
+
+    let entry = context.append_basic_block(func, "entry");
+    let switch_blk = context.append_basic_block(func, "switch");
+    let indirect_blk = context.append_basic_block(func, "indirect");
+    let invoke_blk = context.append_basic_block(func, "invoke");
+    let unreachable_blk = context.append_basic_block(func, "unreachable");
+    let return_blk = context.append_basic_block(func, "return");
+
+    let mut exemplars = vec![];
+    let mut add = |name, builder: &Builder<'ctx>| {
+        exemplars.push((name, get_last_instruction(builder)))
+    };
+
+    builder.position_at_end(entry);
+    let ptr = builder.build_alloca(i32_type, "ptr");
+    add("alloca_inst", &builder);
+    builder.build_store(ptr, a);
+    add("store_inst", &builder);
+    builder.build_load(ptr, "val");
+    add("load_inst", &builder);
+    unsafe { builder.build_in_bounds_gep(ptr, &[a], "gep") };
+    add("gep_inst", &builder);
+    builder.build_int_nsw_add(a, a, "sum");
+    add("binary_operator", &builder);
+    builder.build_float_neg(x, "neg");
+    add("unary_operator", &builder);
+    let cond = builder.build_int_compare(IntPredicate::SLT, a, a, "slt");
+    add("icmp_inst", &builder);
+    add("cmp_inst", &builder);
+    builder.build_float_compare(FloatPredicate::OLT, x, x, "olt");
+    add("fcmp_inst", &builder);
+    builder.build_int_s_extend(a, context.i64_type(), "sext");
+    add("sext_inst", &builder);
+    builder.build_int_z_extend(a, context.i64_type(), "zext");
+    add("zext_inst", &builder);
+    builder.build_int_truncate(a, context.i8_type(), "trunc");
+    add("trunc_inst", &builder);
+    builder.build_bitcast(x, i32_type, "bitcast");
+    add("cast_inst", &builder);
+    builder.build_va_arg(p, i32_type, "vaarg");
+    add("vaarg_inst", &builder);
+    builder.build_fence(AtomicOrdering::Acquire, 1, "");
+    add("fence_inst", &builder);
+    let len = context.i64_type().const_int(4, false);
+    builder.build_memcpy(p, 1, ptr, 4, len).unwrap();
+    add("mem_intrinsic", &builder);
+    builder.build_call(callee, &[a.into()], "");
+    add("call_inst", &builder);
+    add("call_base", &builder);
+    builder.build_conditional_branch(cond, switch_blk, indirect_blk);
+    add("branch_inst", &builder);
+    add("terminator_inst", &builder);
+
+    builder.position_at_end(switch_blk);
+    let zero = i32_type.const_zero();
+    builder.build_switch(a, return_blk, &[(zero, unreachable_blk)]);
+    add("switch_inst", &builder);
+
+    builder.position_at_end(indirect_blk);
+    builder.build_indirect_branch(p, &[invoke_blk]);
+    add("indirectbr_inst", &builder);
+
+    builder.position_at_end(invoke_blk);
+    builder.build_invoke(callee, &[a.into()], return_blk, unreachable_blk, "");
+    add("invoke_inst", &builder);
+
+    builder.position_at_end(unreachable_blk);
+    builder.build_unreachable();
+    add("unreachable_inst", &builder);
+
+    builder.position_at_end(return_blk);
+    let phi = builder.build_phi(i32_type, "phi");
+    phi.add_incoming(&[(&a, switch_blk), (&a, invoke_blk)]);
+    add("phi_node", &builder);
+    builder.build_return(Some(&phi.as_basic_value()));
+    add("return_inst", &builder);
+
+    exemplars.push(("freeze_inst", find_instruction(parsed, "Freeze")));
+    exemplars.push(("callbr_inst", find_instruction(parsed, "CallBr")));
+
+    exemplars
+}
+
+/// Print a block by its name.
+fn print_block(blk: BasicBlock) -> String {
+    blk.get_name().to_string_lossy().to_string()
+}
+
+/// Print a list of blocks by their names.
+fn print_blocks(blks: Vec<BasicBlock>) -> String {
+    let names: Vec<String> = blks.into_iter().map(print_block).collect();
+    format!("[{}]", names.join(", "))
+}
+
+/// Describe the accessors of an instruction converted to a wrapper.
+fn describe_wrapper(name: &str, inst: InstructionValue) -> Vec<String> {
+    let mut lines = vec![
+        format!("inst = {}", inst.print_to_string()),
+        format!("opcode = {:?}", inst.get_opcode()),
+    ];
+
+    match name {
+        "alloca_inst" => {
+            let i = inst.try_into_alloca_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "allocated_type = {}",
+                    i.get_allocated_type().print_to_string()
+                ),
+                format!(
+                    "array_size = {}",
+                    i.get_array_size().print_to_string()
+                ),
+                format!("alignment = {}", i.get_alignment()),
+                format!("is_array_allocation = {}", i.is_array_allocation()),
+                format!("is_static_alloca = {}", i.is_static_alloca()),
+            ])
+        }
+        "binary_operator" => {
+            let i = inst.try_into_binary_operator().unwrap();
+            lines.extend(vec![
+                format!("kind = {}", i.kind()),
+                format!("is_negation = {}", i.is_negation()),
+                format!(
+                    "first_operand = {}",
+                    i.get_first_operand().print_to_string()
+                ),
+                format!(
+                    "second_operand = {}",
+                    i.get_second_operand().print_to_string()
+                ),
+                format!("has_no_unsigned_wrap = {}", i.has_no_unsigned_wrap()),
+                format!("has_no_signed_wrap = {}", i.has_no_signed_wrap()),
+                format!("is_exact = {}", i.is_exact()),
+            ])
+        }
+        "branch_inst" => {
+            let i = inst.try_into_branch_inst().unwrap();
+            lines.extend(vec![
+                format!("has_condition = {}", i.has_condition()),
+                format!("condition = {}", i.get_condition().print_to_string()),
+                format!(
+                    "first_successor = {}",
+                    print_block(i.get_first_successor())
+                ),
+                format!(
+                    "second_successor = {:?}",
+                    i.get_second_successor().map(print_block)
+                ),
+                format!("successors = {}", print_blocks(i.get_successors())),
+            ])
+        }
+        "call_base" => {
+            let i = inst.try_into_call_base().unwrap();
+            lines.extend(vec![
+                format!(
+                    "called_operand_name = {:?}",
+                    i.get_called_operand_name()
+                ),
+                format!("num_arguments = {}", i.get_called_arguments().len()),
+            ])
+        }
+        "call_inst" => {
+            let i = inst.try_into_call_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "called_operand_name = {:?}",
+                    i.get_called_operand_name()
+                ),
+                format!("num_arguments = {}", i.get_called_arguments().len()),
+                format!(
+                    "has_called_function = {}",
+                    i.get_called_function().is_some()
+                ),
+                format!("tail_call_kind = {:?}", i.get_tail_call_kind()),
+                format!("is_tail_call = {}", i.is_tail_call()),
+            ])
+        }
+        "callbr_inst" => {
+            let i = inst.try_into_callbr_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "default_destination = {}",
+                    print_block(i.get_default_destination())
+                ),
+                format!(
+                    "num_indirect_destinations = {}",
+                    i.get_num_indirect_destinations()
+                ),
+                format!(
+                    "indirect_destinations = {}",
+                    print_blocks(i.get_indirect_destinations())
+                ),
+                format!("num_arguments = {}", i.get_called_arguments().len()),
+            ])
+        }
+        "cast_inst" => {
+            let i = inst.try_into_cast_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "source_type = {}",
+                    i.get_source_type().print_to_string()
+                ),
+                format!(
+                    "destination_type = {}",
+                    i.get_destination_type().print_to_string()
+                ),
+            ])
+        }
+        "cmp_inst" => {
+            let i = inst.try_into_cmp_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "first_operand = {}",
+                    i.get_first_operand().print_to_string()
+                ),
+                format!(
+                    "second_operand = {}",
+                    i.get_second_operand().print_to_string()
+                ),
+            ])
+        }
+        "fcmp_inst" => {
+            let i = inst.try_into_fcmp_inst().unwrap();
+            lines.extend(vec![
+                format!("float_predicate = {:?}", i.get_float_predicate()),
+                format!("is_ordered = {}", i.is_ordered()),
+                format!("is_signed = {}", i.is_signed()),
+                format!(
+                    "swap_operands_predicate = {:?}",
+                    i.swap_operands_predicate()
+                ),
+                format!(
+                    "fast_math_flags = {:?}",
+                    i.get_fast_math_flags().as_raw()
+                ),
+            ])
+        }
+        "fence_inst" => {
+            let i = inst.try_into_fence_inst().unwrap();
+            lines.extend(vec![
+                format!("ordering = {:?}", i.get_ordering()),
+                format!("sync_scope = {:?}", i.get_sync_scope()),
+                format!("is_single_thread = {}", i.is_single_thread()),
+            ])
+        }
+        "freeze_inst" => {
+            let i = inst.try_into_freeze_inst().unwrap();
+            lines.push(format!(
+                "value_operand = {}",
+                i.get_value_operand().print_to_string()
+            ))
+        }
+        "gep_inst" => {
+            let i = inst.try_into_gep_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "pointer_operand = {}",
+                    i.get_pointer_operand().print_to_string()
+                ),
+                format!("num_indices = {}", i.get_num_indices()),
+                format!(
+                    "indices = [{}]",
+                    i.get_indices()
+                        .iter()
+                        .map(|idx| idx.print_to_string().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                format!("constant_indices = {:?}", i.get_constant_indices()),
+                format!(
+                    "source_element_type = {}",
+                    i.get_source_element_type().print_to_string()
+                ),
+                format!("is_in_bounds = {}", i.is_in_bounds()),
+            ])
+        }
+        "icmp_inst" => {
+            let i = inst.try_into_icmp_inst().unwrap();
+            lines.extend(vec![
+                format!("int_predicate = {:?}", i.get_int_predicate()),
+                format!("is_signed = {}", i.is_signed()),
+                format!("is_unsigned = {}", i.is_unsigned()),
+                format!("is_equality = {}", i.is_equality()),
+                format!(
+                    "swap_operands_predicate = {:?}",
+                    i.swap_operands_predicate()
+                ),
+            ])
+        }
+        "indirectbr_inst" => {
+            let i = inst.try_into_indirectbr_inst().unwrap();
+            lines.extend(vec![
+                format!("successors = {}", print_blocks(i.get_successors())),
+                format!(
+                    "num_conditioned_successors = {}",
+                    i.get_conditioned_successors().len()
+                ),
+            ])
+        }
+        "invoke_inst" => {
+            let i = inst.try_into_invoke_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "normal_destination = {}",
+                    print_block(i.get_normal_destination())
+                ),
+                format!(
+                    "unwind_destination = {}",
+                    print_block(i.get_unwind_destination())
+                ),
+                format!(
+                    "called_operand_name = {:?}",
+                    i.get_called_operand_name()
+                ),
+                format!("num_arguments = {}", i.get_called_arguments().len()),
+            ])
+        }
+        "load_inst" => {
+            let i = inst.try_into_load_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "pointer_operand = {}",
+                    i.get_pointer_operand().print_to_string()
+                ),
+                format!("is_volatile = {}", i.is_volatile()),
+                format!("is_atomic = {}", i.is_atomic()),
+                format!("is_simple = {}", i.is_simple()),
+            ])
+        }
+        "mem_intrinsic" => {
+            let i = inst.try_into_mem_intrinsic().unwrap();
+            lines.extend(vec![
+                format!("kind = {:?}", i.get_kind()),
+                format!(
+                    "destination = {}",
+                    i.get_destination().print_to_string()
+                ),
+                format!(
+                    "source = {:?}",
+                    i.get_source().map(|src| src.print_to_string())
+                ),
+                format!(
+                    "value = {:?}",
+                    i.get_value().map(|val| val.print_to_string())
+                ),
+                format!("constant_length = {:?}", i.get_constant_length()),
+                format!("is_volatile = {}", i.is_volatile()),
+            ])
+        }
+        "phi_node" => {
+            let i = inst.try_into_phi_node().unwrap();
+            let incomings: Vec<String> = i
+                .get_incomings()
+                .into_iter()
+                .map(|(val, blk)| {
+                    format!("({}, {})", val.print_to_string(), print_block(blk))
+                })
+                .collect();
+            lines.extend(vec![
+                format!("name = {:?}", i.get_name()),
+                format!("count_incoming = {}", i.count_incoming()),
+                format!("incomings = [{}]", incomings.join(", ")),
+            ])
+        }
+        "return_inst" => {
+            let i = inst.try_into_return_inst().unwrap();
+            lines.push(format!(
+                "returned_value = {:?}",
+                i.get_returned_value().map(|v| v.print_to_string())
+            ))
+        }
+        "sext_inst" | "trunc_inst" | "zext_inst" => {
+            let (src, dst) = match name {
+                "sext_inst" => {
+                    let i = inst.try_into_sext_inst().unwrap();
+                    (i.get_source_type(), i.get_destination_type())
+                }
+                "trunc_inst" => {
+                    let i = inst.try_into_trunc_inst().unwrap();
+                    (i.get_source_type(), i.get_destination_type())
+                }
+                _ => {
+                    let i = inst.try_into_zext_inst().unwrap();
+                    (i.get_source_type(), i.get_destination_type())
+                }
+            };
+            lines.extend(vec![
+                format!("source_type = {}", src.print_to_string()),
+                format!("destination_type = {}", dst.print_to_string()),
+            ])
+        }
+        "store_inst" => {
+            let i = inst.try_into_store_inst().unwrap();
+            lines.extend(vec![
+                format!(
+                    "value_operand = {}",
+                    i.get_value_operand().print_to_string()
+                ),
+                format!(
+                    "pointer_operand = {}",
+                    i.get_pointer_operand().print_to_string()
+                ),
+                format!("alignment = {}", i.get_alignment()),
+                format!("ordering = {:?}", i.get_ordering()),
+            ])
+        }
+        "switch_inst" => {
+            let i = inst.try_into_switch_inst().unwrap();
+            lines.extend(vec![
+                format!("condition = {}", i.get_condition().print_to_string()),
+                format!("num_cases = {}", i.get_num_cases()),
+                format!(
+                    "default_successor = {}",
+                    print_block(i.get_default_successor())
+                ),
+                format!(
+                    "case_0 = {:?}",
+                    i.get_case(0).map(|v| v.print_to_string())
+                ),
+                format!(
+                    "successor_0 = {:?}",
+                    i.get_successor(0).map(print_block)
+                ),
+            ])
+        }
+        "terminator_inst" => {
+            let i = inst.try_into_terminator_inst().unwrap();
+            lines.extend(vec![
+                format!("is_branch_inst = {}", i.as_branch_inst().is_some()),
+                format!("num_successors = {}", i.get_num_successors()),
+                format!(
+                    "num_conditioned_successors = {}",
+                    i.get_conditioned_successors().len()
+                ),
+            ])
+        }
+        "unary_operator" => {
+            let i = inst.try_into_unary_operator().unwrap();
+            lines.extend(vec![
+                format!("kind = {}", i.kind()),
+                format!(
+                    "operand_value = {}",
+                    i.get_operand_value().print_to_string()
+                ),
+            ])
+        }
+        "unreachable_inst" => {
+            let i = inst.try_into_unreachable_inst().unwrap();
+            lines.push(format!("num_operands = {}", i.get_num_operands()))
+        }
+        "vaarg_inst" => {
+            let i = inst.try_into_vaarg_inst().unwrap();
+            lines.push(format!(
+                "pointer_operand = {}",
+                i.get_pointer_operand().print_to_string()
+            ))
+        }
+        _ => panic!("No accessor description of wrapper: {}", name),
+    }
+
+    // Instructions such as `switch` are printed on several lines, which are
+    // joined so that each accessor is described by one line.
+    lines
+        .into_iter()
+        .map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            format!("{}: {}", name, words.join(" "))
+        })
+        .collect()
+}
+
+/// Generate the golden output of all instruction wrappers.
+fn generate_golden_output() -> Vec<String> {
+    let context = Context::create();
+    let module = context.create_module("exemplars");
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        PARSED_EXEMPLARS.as_bytes(),
+        "parsed",
+    );
+    let parsed = context.create_module_from_ir(buffer).unwrap();
+
+    let mut exemplars = build_exemplars(&context, &module, &parsed);
+    exemplars.sort_by_key(|(name, _)| *name);
+
+    exemplars
+        .into_iter()
+        .flat_map(|(name, inst)| describe_wrapper(name, inst))
+        .collect()
+}
+
+/// Get names of the wrapper conversions declared by `InstructionExt`.
+fn get_wrapper_conversions() -> HashSet<String> {
+    let source = fs::read_to_string(CONVERSION_FILE).unwrap();
+    let regex = Regex::new(r"fn try_into_(\w+)\(self\)").unwrap();
+
+    regex
+        .captures_iter(&source)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !NON_WRAPPER_CONVERSIONS.contains(&name.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_instruction_wrapper_coverage() {
+    let context = Context::create();
+    let module = context.create_module("exemplars");
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        PARSED_EXEMPLARS.as_bytes(),
+        "parsed",
+    );
+    let parsed = context.create_module_from_ir(buffer).unwrap();
+
+    let covered: HashSet<String> = build_exemplars(&context, &module, &parsed)
+        .into_iter()
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+    let mut missing: Vec<String> = get_wrapper_conversions()
+        .difference(&covered)
+        .cloned()
+        .collect();
+    missing.sort();
+
+    assert!(
+        missing.is_empty(),
+        "Wrappers without exemplars: {:?}",
+        missing
+    );
+}
+
+#[test]
+fn test_instruction_wrapper_golden() {
+    let actual = generate_golden_output();
+    let golden = Path::new(GOLDEN_FILE);
+
+    if env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+        fs::create_dir_all(golden.parent().unwrap()).unwrap();
+        fs::write(golden, actual.join("\n") + "\n").unwrap();
+        return;
+    }
+
+    assert!(
+        golden.exists(),
+        "Golden file not found: {}\nSet {} to generate it.",
+        GOLDEN_FILE,
+        UPDATE_SNAPSHOTS_ENV
+    );
+
+    let expected: Vec<String> = fs::read_to_string(golden)
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+    let diff = diff_lines(&expected, &actual);
+    let diff: Vec<String> = diff.iter().map(|line| line.to_string()).collect();
+
+    assert!(
+        diff.is_empty(),
+        "Golden file mismatch: {}\n{}\nSet {} to update the golden file.",
+        GOLDEN_FILE,
+        diff.join("\n"),
+        UPDATE_SNAPSHOTS_ENV
+    );
+}
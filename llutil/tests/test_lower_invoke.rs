@@ -0,0 +1,102 @@
+//! Tests of lowering `invoke` instructions into `call` instructions.
+
+use inkwell::{
+    context::Context, memory_buffer::MemoryBuffer, module::Module,
+    values::AnyValue,
+};
+use llutil::normalize::lower_invoke::lower_invokes;
+
+/// LLVM IR of functions calling other functions by `invoke`.
+const INVOKE_IR: &str = r#"
+%struct.S = type { i64, i64 }
+
+declare void @make(%struct.S* sret(%struct.S), i32)
+declare i32 @compute(i32)
+declare i32 @__gxx_personality_v0(...)
+
+define i64 @with_sret(i32 %a)
+    personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  %s = alloca %struct.S, align 8
+  invoke void @make(%struct.S* noalias nonnull sret(%struct.S) align 8 %s, i32 signext %a) #0
+          to label %cont unwind label %lpad
+cont:
+  %f = getelementptr %struct.S, %struct.S* %s, i32 0, i32 0
+  %v = load i64, i64* %f
+  ret i64 %v
+lpad:
+  %lp = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %lp
+}
+
+define i32 @with_bundle(i32 %a)
+    personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  %r = invoke i32 @compute(i32 %a) [ "deopt"() ]
+          to label %cont unwind label %lpad
+cont:
+  ret i32 %r
+lpad:
+  %lp = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %lp
+}
+
+attributes #0 = { nounwind }
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        INVOKE_IR.as_bytes(),
+        "invoke",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Print all instructions of a function.
+fn print_instructions(module: &Module, func_name: &str) -> Vec<String> {
+    let func = module.get_function(func_name).unwrap();
+    func.get_basic_blocks()
+        .into_iter()
+        .flat_map(|blk| blk.get_instructions())
+        .map(|inst| inst.print_to_string().to_string())
+        .collect()
+}
+
+#[test]
+fn test_lower_sret_invoke() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("with_sret").unwrap();
+
+    let report = lower_invokes(func);
+    assert_eq!(report.num_lowered_invokes, 1);
+    assert_eq!(report.num_removed_blocks, 1);
+
+    let insts = print_instructions(&module, "with_sret");
+    assert_eq!(
+        insts[1],
+        "call void @make(%struct.S* noalias nonnull sret(%struct.S) align 8 \
+         %s, i32 signext %a) #0"
+    );
+    assert_eq!(insts[2], "br label %cont");
+    assert!(insts.iter().all(|inst| !inst.contains("invoke")));
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_keep_invoke_with_operand_bundles() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("with_bundle").unwrap();
+
+    let report = lower_invokes(func);
+    assert_eq!(report.num_lowered_invokes, 0);
+    assert_eq!(report.num_removed_blocks, 0);
+
+    let insts = print_instructions(&module, "with_bundle");
+    assert!(insts[0]
+        .starts_with("%r = invoke i32 @compute(i32 %a) [ \"deopt\"() ]"));
+    assert_eq!(insts.len(), 4);
+    assert!(module.verify().is_ok());
+}
@@ -0,0 +1,127 @@
+//! Tests of merging `ret` instructions into a single exit block.
+
+use inkwell::{
+    context::Context, memory_buffer::MemoryBuffer, module::Module,
+    values::AnyValue,
+};
+use llutil::{
+    ir::FunctionExt,
+    normalize::merge_return::{
+        merge_returns, merge_returns_in_module, UNIFIED_RETURN_BLOCK,
+    },
+};
+
+/// LLVM IR of functions with several `ret` instructions.
+const RETURNS_IR: &str = r#"
+define i32 @sign(i32 %a) {
+entry:
+  %is_pos = icmp sgt i32 %a, 0
+  br i1 %is_pos, label %pos, label %check
+check:
+  %is_zero = icmp eq i32 %a, 0
+  br i1 %is_zero, label %zero, label %neg
+pos:
+  ret i32 %a
+zero:
+  ret i32 0
+neg:
+  %n = sub i32 0, %a
+  ret i32 %n
+}
+
+define void @log(i1 %c) {
+entry:
+  br i1 %c, label %yes, label %no
+yes:
+  ret void
+no:
+  ret void
+}
+
+define i32 @single(i32 %a) {
+entry:
+  ret i32 %a
+}
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        RETURNS_IR.as_bytes(),
+        "ret",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Print the instructions of a block of a function.
+fn print_block(
+    module: &Module,
+    func_name: &str,
+    blk_name: &str,
+) -> Vec<String> {
+    let func = module.get_function(func_name).unwrap();
+    let blk = func
+        .get_basic_blocks()
+        .into_iter()
+        .find(|blk| blk.get_name().to_str() == Ok(blk_name))
+        .unwrap();
+    blk.get_instructions()
+        .into_iter()
+        .map(|inst| inst.print_to_string().to_string())
+        .collect()
+}
+
+#[test]
+fn test_merge_returns_with_phi() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("sign").unwrap();
+
+    assert!(merge_returns(func));
+    assert_eq!(func.get_return_instructions().len(), 1);
+    assert_eq!(
+        print_block(&module, "sign", UNIFIED_RETURN_BLOCK),
+        vec![
+            "%unified.retval = phi i32 [ %a, %pos ], [ 0, %zero ], \
+             [ %n, %neg ]",
+            "ret i32 %unified.retval",
+        ]
+    );
+    for blk_name in ["pos", "zero"] {
+        assert_eq!(
+            print_block(&module, "sign", blk_name),
+            vec!["br label %unified.return"]
+        );
+    }
+    assert_eq!(
+        print_block(&module, "sign", "neg"),
+        vec!["%n = sub i32 0, %a", "br label %unified.return"]
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_merge_void_returns() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("log").unwrap();
+
+    assert!(merge_returns(func));
+    assert_eq!(
+        print_block(&module, "log", UNIFIED_RETURN_BLOCK),
+        vec!["ret void"]
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_merge_returns_in_module() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    // The function `single` already has a single return.
+    assert_eq!(merge_returns_in_module(&module), 2);
+    assert_eq!(merge_returns_in_module(&module), 0);
+    let func = module.get_function("single").unwrap();
+    assert_eq!(func.count_basic_blocks(), 1);
+}
@@ -0,0 +1,86 @@
+//! Tests of the utilities of LLVM modules.
+
+use inkwell::{
+    context::Context, memory_buffer::MemoryBuffer, module::Module,
+    values::FunctionValue,
+};
+use llutil::ir::{FunctionExt, ModuleExt, SourceLocation};
+
+/// LLVM IR of functions declared in two source files.
+const MODULE_IR: &str = r#"
+define void @b1() !dbg !10 {
+  ret void
+}
+
+define void @a0() !dbg !11 {
+  ret void
+}
+
+define void @a2() !dbg !12 {
+  ret void
+}
+
+define void @nodebug() {
+  ret void
+}
+
+define void @a1() !dbg !13 {
+  ret void
+}
+
+declare void @ext()
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3, !4}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "clang", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug)
+!1 = !DIFile(filename: "b.c", directory: "/src")
+!2 = !DIFile(filename: "a.c", directory: "/src")
+!3 = !{i32 7, !"Dwarf Version", i32 4}
+!4 = !{i32 2, !"Debug Info Version", i32 3}
+!5 = !DISubroutineType(types: !6)
+!6 = !{null}
+!10 = distinct !DISubprogram(name: "b1", scope: !1, file: !1, line: 1, type: !5, scopeLine: 1, spFlags: DISPFlagDefinition, unit: !0)
+!11 = distinct !DISubprogram(name: "a0", scope: !2, file: !2, line: 9, type: !5, scopeLine: 9, spFlags: DISPFlagDefinition, unit: !0)
+!12 = distinct !DISubprogram(name: "a2", scope: !2, file: !2, line: 5, type: !5, scopeLine: 5, spFlags: DISPFlagDefinition, unit: !0)
+!13 = distinct !DISubprogram(name: "a1", scope: !2, file: !2, line: 5, type: !5, scopeLine: 5, spFlags: DISPFlagDefinition, unit: !0)
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        MODULE_IR.as_bytes(),
+        "module",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Get the name of a function.
+fn get_name(func: &FunctionValue) -> String {
+    func.get_name_or_default()
+}
+
+#[test]
+fn test_functions_sorted_by_file_line_and_name() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    let names: Vec<String> =
+        module.get_functions_sorted().iter().map(get_name).collect();
+    assert_eq!(names, vec!["a1", "a2", "a0", "b1", "ext", "nodebug"]);
+}
+
+#[test]
+fn test_source_location_of_functions() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    let func = module.get_function("a0").unwrap();
+    assert_eq!(
+        SourceLocation::from_function(func),
+        Some(SourceLocation::new("/src/a.c", 9, 0))
+    );
+
+    let func = module.get_function("nodebug").unwrap();
+    assert_eq!(SourceLocation::from_function(func), None);
+}
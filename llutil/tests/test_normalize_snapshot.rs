@@ -0,0 +1,124 @@
+//! Snapshot tests of the normalization pipeline.
+//!
+//! Each fixture is normalized by the default normalization passes and its
+//! output is compared against the `.norm.ll` snapshot next to it.
+
+use std::{env, fs, path::Path};
+
+use inkwell::context::Context;
+use llutil::normalize::snapshot::{
+    assert_fixture_snapshot, canonicalize_ir, check_snapshot,
+    get_snapshot_path, normalize_fixture, DiffLine, SnapshotOptions,
+};
+
+/// Fixture whose constant branch is folded and whose returns are merged.
+const MERGE_AND_FOLD_FIXTURE: &str =
+    "tests/testcases/normalize/merge_and_fold.ll";
+
+/// Get snapshot options comparing against the checked-in snapshots.
+fn get_compare_options(ignore_value_numbering: bool) -> SnapshotOptions {
+    SnapshotOptions {
+        ignore_value_numbering,
+        ignore_metadata: false,
+        update: false,
+    }
+}
+
+#[test]
+fn test_merge_and_fold_snapshot() {
+    let fixture = Path::new(MERGE_AND_FOLD_FIXTURE);
+    assert_fixture_snapshot(fixture, &SnapshotOptions::default());
+}
+
+#[test]
+fn test_normalized_fixture_has_single_return() {
+    let context = Context::create();
+    let fixture = Path::new(MERGE_AND_FOLD_FIXTURE);
+    let module = normalize_fixture(&context, fixture).unwrap();
+
+    let func = module.get_function("sign").unwrap();
+    let names: Vec<String> = func
+        .get_basic_blocks()
+        .iter()
+        .map(|blk| blk.get_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["entry", "check", "positive", "negative", "unified.return"]
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_snapshot_ignores_value_numbering() {
+    let context = Context::create();
+    let fixture = Path::new(MERGE_AND_FOLD_FIXTURE);
+    let module = normalize_fixture(&context, fixture).unwrap();
+
+    // Renumber the unnamed `icmp` of the checked-in snapshot.
+    let snapshot = fs::read_to_string(get_snapshot_path(fixture)).unwrap();
+    let renumbered = snapshot.replace("%0", "%3");
+    assert_ne!(snapshot, renumbered);
+    let renumbered_path =
+        env::temp_dir().join("merge_and_fold.renumbered.norm.ll");
+    fs::write(&renumbered_path, renumbered).unwrap();
+
+    let options = get_compare_options(true);
+    assert!(check_snapshot(&module, &renumbered_path, &options).is_ok());
+
+    let options = get_compare_options(false);
+    let mismatch =
+        check_snapshot(&module, &renumbered_path, &options).unwrap_err();
+    let num_removed = mismatch
+        .diff
+        .iter()
+        .filter(|line| matches!(line, DiffLine::Removed(..)))
+        .count();
+    assert_eq!(num_removed, 2);
+    assert_eq!(mismatch.diff.len(), 4);
+
+    fs::remove_file(renumbered_path).unwrap();
+}
+
+#[test]
+fn test_canonicalize_ir_renumbers_per_function() {
+    let ir = "define void @f() {\n  %5 = add i32 1, 2\n  br label %7\n\n\
+              7:\n  ret void\n}\n\
+              define void @g() {\n  %9 = add i32 1, 2\n  ret void\n}\n";
+
+    let lines = canonicalize_ir(ir, &get_compare_options(true));
+    assert_eq!(
+        lines,
+        vec![
+            "define void @f() {",
+            "  %0 = add i32 1, 2",
+            "  br label %1",
+            "1:",
+            "  ret void",
+            "}",
+            "define void @g() {",
+            "  %0 = add i32 1, 2",
+            "  ret void",
+            "}",
+        ]
+    );
+
+    let lines = canonicalize_ir(ir, &get_compare_options(false));
+    assert_eq!(lines[1], "  %5 = add i32 1, 2");
+}
+
+#[test]
+fn test_missing_snapshot_is_mismatch() {
+    let context = Context::create();
+    let fixture = Path::new(MERGE_AND_FOLD_FIXTURE);
+    let module = normalize_fixture(&context, fixture).unwrap();
+
+    let missing = env::temp_dir().join("missing.norm.ll");
+    let options = get_compare_options(true);
+    let mismatch = check_snapshot(&module, &missing, &options).unwrap_err();
+    assert!(mismatch
+        .diff
+        .iter()
+        .all(|line| matches!(line, DiffLine::Added(..))));
+    assert!(!missing.exists());
+}
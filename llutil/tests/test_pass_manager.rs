@@ -0,0 +1,179 @@
+//! Tests of scheduling passes and cached analyses by the pass manager.
+
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use inkwell::{context::Context, module::Module};
+use llutil::pass::{
+    Analysis, AnalysisKind, AnalysisResults, Invalidation, Pass, PassManager,
+};
+
+/// Kind of the analysis counting functions.
+const NUM_FUNCTIONS: AnalysisKind = AnalysisKind::Custom("num_functions");
+
+/// Log of analyses computed and passes run, in order.
+type EventLog = Rc<RefCell<Vec<String>>>;
+
+/// Analysis logging its runs and returning the number of functions.
+struct LoggedAnalysis {
+    kind: AnalysisKind,
+    required: Vec<AnalysisKind>,
+    log: EventLog,
+}
+
+impl Analysis for LoggedAnalysis {
+    fn kind(&self) -> AnalysisKind {
+        self.kind
+    }
+
+    fn required_analyses(&self) -> Vec<AnalysisKind> {
+        self.required.clone()
+    }
+
+    fn run(&self, module: &Module, results: &AnalysisResults) -> Box<dyn Any> {
+        for kind in &self.required {
+            assert!(results.contains(*kind));
+        }
+        self.log.borrow_mut().push(format!("{:?}", self.kind));
+        Box::new(module.get_functions().count())
+    }
+}
+
+/// Pass logging its runs and the results of its required analyses.
+struct LoggedPass {
+    name: &'static str,
+    required: Vec<AnalysisKind>,
+    invalidated: Invalidation,
+    log: EventLog,
+}
+
+impl Pass for LoggedPass {
+    fn get_name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn required_analyses(&self) -> Vec<AnalysisKind> {
+        self.required.clone()
+    }
+
+    fn invalidated_analyses(&self) -> Invalidation {
+        self.invalidated.clone()
+    }
+
+    fn run(&mut self, _module: &Module, results: &AnalysisResults) {
+        for kind in &self.required {
+            assert_eq!(results.get::<usize>(*kind), Some(&2));
+        }
+        self.log.borrow_mut().push(self.name.to_string());
+    }
+}
+
+/// Create a module with two function declarations.
+fn create_module(context: &Context) -> Module {
+    let module = context.create_module("passes");
+    let fn_type = context.void_type().fn_type(&[], false);
+    module.add_function("f", fn_type, None);
+    module.add_function("g", fn_type, None);
+    module
+}
+
+/// Create a pass manager whose loop analysis requires the analysis counting
+/// functions.
+fn create_pass_manager(log: &EventLog) -> PassManager {
+    let mut manager = PassManager::new();
+    manager.register_analysis(Box::new(LoggedAnalysis {
+        kind: NUM_FUNCTIONS,
+        required: vec![],
+        log: log.clone(),
+    }));
+    manager.register_analysis(Box::new(LoggedAnalysis {
+        kind: AnalysisKind::Loops,
+        required: vec![NUM_FUNCTIONS],
+        log: log.clone(),
+    }));
+    manager
+}
+
+#[test]
+fn test_reuse_and_invalidate_analyses() {
+    let context = Context::create();
+    let module = create_module(&context);
+    let log = EventLog::default();
+    let mut manager = create_pass_manager(&log);
+
+    let passes = [
+        ("keep", AnalysisKind::Loops, Invalidation::None),
+        (
+            "drop_loops",
+            AnalysisKind::Loops,
+            Invalidation::Only(vec![AnalysisKind::Loops]),
+        ),
+        ("modify", AnalysisKind::Loops, Invalidation::All),
+        ("count", NUM_FUNCTIONS, Invalidation::None),
+    ];
+    for (name, required, invalidated) in passes {
+        manager.add_pass(Box::new(LoggedPass {
+            name,
+            required: vec![required],
+            invalidated,
+            log: log.clone(),
+        }));
+    }
+
+    manager.run(&module);
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "Custom(\"num_functions\")",
+            "Loops",
+            "keep",
+            "drop_loops",
+            "Loops",
+            "modify",
+            "Custom(\"num_functions\")",
+            "count",
+        ]
+    );
+
+    // Only the analysis required after the last invalidation is cached.
+    let results = manager.get_results();
+    assert_eq!(results.get::<usize>(NUM_FUNCTIONS), Some(&2));
+    assert!(!results.contains(AnalysisKind::Loops));
+    assert!(results.get::<String>(NUM_FUNCTIONS).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Cyclic analysis requirement")]
+fn test_cyclic_analysis_requirement() {
+    let context = Context::create();
+    let module = create_module(&context);
+    let log = EventLog::default();
+    let mut manager = create_pass_manager(&log);
+    manager.register_analysis(Box::new(LoggedAnalysis {
+        kind: NUM_FUNCTIONS,
+        required: vec![AnalysisKind::Loops],
+        log: log.clone(),
+    }));
+    manager.add_pass(Box::new(LoggedPass {
+        name: "cyclic",
+        required: vec![AnalysisKind::Loops],
+        invalidated: Invalidation::None,
+        log,
+    }));
+    manager.run(&module);
+}
+
+#[test]
+#[should_panic(expected = "Analysis not registered")]
+fn test_unregistered_analysis() {
+    let context = Context::create();
+    let module = create_module(&context);
+    let log = EventLog::default();
+    let mut manager = create_pass_manager(&log);
+    manager.add_pass(Box::new(LoggedPass {
+        name: "aliases",
+        required: vec![AnalysisKind::Alias],
+        invalidated: Invalidation::None,
+        log,
+    }));
+    manager.run(&module);
+}
@@ -0,0 +1,49 @@
+//! Tests of parsing bug annotations of Rust source code.
+
+use llutil::annotation::{
+    nom_lexing, nom_lexing_rust, parse_rust_bug_annotations, BugAnnotation,
+};
+
+/// Rust source code whose lifetimes and loop labels precede annotations.
+const RUST_SOURCE: &str = r#"fn f(x: &'a u8) -> u8 { *x + 1 } // bug: integer_overflow, it's here
+
+fn g(c: char) -> bool {
+    'outer: loop {
+        break 'outer c == '\'' // bug: logic_error
+    }
+}
+"#;
+
+#[test]
+fn test_lifetimes_are_not_char_literals() {
+    let texts: Vec<String> = nom_lexing_rust(RUST_SOURCE)
+        .unwrap()
+        .into_iter()
+        .map(|token| token.text)
+        .collect();
+    assert_eq!(
+        texts,
+        vec![" bug: integer_overflow, it's here", " bug: logic_error"]
+    );
+
+    // The C lexer reads the lifetime as the start of a character literal
+    // ending in the first comment.
+    let texts: Vec<String> = nom_lexing(RUST_SOURCE)
+        .unwrap()
+        .into_iter()
+        .map(|token| token.text)
+        .collect();
+    assert_eq!(texts, vec![" bug: logic_error"]);
+}
+
+#[test]
+fn test_annotations_after_lifetimes() {
+    let annots = parse_rust_bug_annotations(RUST_SOURCE).unwrap();
+    assert_eq!(
+        annots,
+        vec![
+            BugAnnotation::new("integer_overflow", 1, 0),
+            BugAnnotation::new("logic_error", 5, 0),
+        ]
+    );
+}
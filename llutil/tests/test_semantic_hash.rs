@@ -0,0 +1,102 @@
+//! Tests of the semantic hashes of functions keying the analysis cache.
+
+use inkwell::{context::Context, memory_buffer::MemoryBuffer, module::Module};
+use llutil::ir::FunctionExt;
+
+/// LLVM IR of a function with debug locations.
+const DEBUG_IR: &str = r#"
+define i32 @f(i32 %a) !dbg !4 {
+entry:
+  %r = add i32 %a, 1, !dbg !6
+  ret i32 %r, !dbg !6
+}
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "clang", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug)
+!1 = !DIFile(filename: "a.c", directory: "/tmp")
+!3 = !{i32 2, !"Debug Info Version", i32 3}
+!4 = distinct !DISubprogram(name: "f", scope: !1, file: !1, line: 1, type: !5, scopeLine: 1, spFlags: DISPFlagDefinition, unit: !0)
+!5 = !DISubroutineType(types: !{})
+!6 = !DILocation(line: 2, column: 3, scope: !4)
+"#;
+
+/// LLVM IR of the same function, whose debug metadata are numbered
+/// differently.
+const RENUMBERED_DEBUG_IR: &str = r#"
+define i32 @f(i32 %a) !dbg !4 {
+entry:
+  %r = add i32 %a, 1, !dbg !6
+  ret i32 %r, !dbg !6
+}
+
+!llvm.ident = !{!7}
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "clang", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug)
+!1 = !DIFile(filename: "a.c", directory: "/tmp")
+!3 = !{i32 2, !"Debug Info Version", i32 3}
+!4 = distinct !DISubprogram(name: "f", scope: !1, file: !1, line: 1, type: !5, scopeLine: 1, spFlags: DISPFlagDefinition, unit: !0)
+!5 = !DISubroutineType(types: !{})
+!6 = !DILocation(line: 2, column: 3, scope: !4)
+!7 = !{!"clang version 14"}
+"#;
+
+/// LLVM IR of a function calling another function through a third one.
+const CALLER_IR: &str = r#"
+define i32 @leaf(i32 %a) {
+entry:
+  %r = add i32 %a, 1
+  ret i32 %r
+}
+
+define i32 @middle(i32 %a) {
+entry:
+  %r = call i32 @leaf(i32 %a)
+  ret i32 %r
+}
+
+define i32 @caller(i32 %a) {
+entry:
+  %r = call i32 @middle(i32 %a)
+  ret i32 %r
+}
+"#;
+
+/// Parse an IR string into a module.
+fn parse_module<'ctx>(context: &'ctx Context, ir: &str) -> Module<'ctx> {
+    let buffer =
+        MemoryBuffer::create_from_memory_range_copy(ir.as_bytes(), "ir");
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Get the semantic hash of a function of an IR string.
+fn get_hash(ir: &str, func_name: &str) -> u64 {
+    let context = Context::create();
+    let module = parse_module(&context, ir);
+    module.get_function(func_name).unwrap().get_semantic_hash()
+}
+
+#[test]
+fn test_semantic_hash_ignores_debug_locations() {
+    assert_eq!(get_hash(DEBUG_IR, "f"), get_hash(RENUMBERED_DEBUG_IR, "f"));
+}
+
+#[test]
+fn test_semantic_hash_covers_transitive_callees() {
+    let changed_ir = CALLER_IR.replace("add i32 %a, 1", "add i32 %a, 2");
+    assert_ne!(changed_ir, CALLER_IR);
+
+    assert_eq!(get_hash(CALLER_IR, "caller"), get_hash(CALLER_IR, "caller"));
+    assert_ne!(
+        get_hash(CALLER_IR, "caller"),
+        get_hash(&changed_ir, "caller")
+    );
+    assert_ne!(
+        get_hash(CALLER_IR, "middle"),
+        get_hash(&changed_ir, "middle")
+    );
+    assert_ne!(get_hash(CALLER_IR, "caller"), get_hash(CALLER_IR, "middle"));
+}
@@ -0,0 +1,125 @@
+//! Tests of checking module sizes against budgets.
+
+use inkwell::{context::Context, memory_buffer::MemoryBuffer, module::Module};
+use llutil::normalize::size_budget::{
+    check_module_size, BudgetViolation, ShrinkSuggestion, SizeBudget,
+};
+
+/// LLVM IR of a module with duplicate and dead functions and globals.
+const SIZE_IR: &str = r#"
+@g1 = constant [4 x i32] zeroinitializer
+@g2 = constant [4 x i32] zeroinitializer
+@dead = internal global i64 0
+
+define i32 @big(i32 %a) {
+entry:
+  %x = add i32 %a, 1
+  %y = mul i32 %x, 2
+  %z = sub i32 %y, 3
+  %w = xor i32 %z, 4
+  ret i32 %w
+}
+
+define i32 @inc1(i32 %a) {
+entry:
+  %r = add i32 %a, 1
+  ret i32 %r
+}
+
+define i32 @inc2(i32 %a) {
+entry:
+  %r = add i32 %a, 1
+  ret i32 %r
+}
+
+define internal void @unused() {
+entry:
+  ret void
+}
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer =
+        MemoryBuffer::create_from_memory_range_copy(SIZE_IR.as_bytes(), "size");
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+#[test]
+fn test_size_budget_violations() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let budget = SizeBudget::parse(
+        "max_functions = 3\n\
+         max_instructions = 10\n\
+         max_function_instructions = 4\n\
+         max_global_bytes = 32\n",
+    )
+    .unwrap();
+
+    let report = check_module_size(&module, &budget, 2);
+    assert_eq!(report.metrics.num_functions, 4);
+    assert_eq!(report.metrics.num_instructions, 10);
+    assert_eq!(report.metrics.num_globals, 3);
+    assert_eq!(report.metrics.global_bytes, 40);
+    assert!(report.metrics.bitcode_bytes > 0);
+
+    // The number of instructions is within its budget.
+    assert!(!report.is_within_budget());
+    assert_eq!(
+        report.violations,
+        vec![
+            BudgetViolation {
+                metric: "num_functions".to_string(),
+                actual: 4,
+                limit: 3,
+            },
+            BudgetViolation {
+                metric: "global_bytes".to_string(),
+                actual: 40,
+                limit: 32,
+            },
+            BudgetViolation {
+                metric: "instructions of big".to_string(),
+                actual: 5,
+                limit: 4,
+            },
+        ]
+    );
+
+    let largest: Vec<(&str, u64)> = report
+        .largest_functions
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.size))
+        .collect();
+    assert_eq!(largest, vec![("big", 5), ("inc1", 2)]);
+}
+
+#[test]
+fn test_size_shrink_suggestions() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    let report = check_module_size(&module, &SizeBudget::default(), 5);
+    assert!(report.is_within_budget());
+    assert_eq!(
+        report.suggestions,
+        vec![
+            ShrinkSuggestion::MergeDuplicateFunctions(vec![
+                "inc1".to_string(),
+                "inc2".to_string()
+            ]),
+            ShrinkSuggestion::MergeDuplicateGlobals(vec![
+                "g1".to_string(),
+                "g2".to_string()
+            ]),
+            ShrinkSuggestion::RemoveDeadFunction("unused".to_string()),
+            ShrinkSuggestion::RemoveDeadGlobal("dead".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_invalid_size_budget() {
+    assert!(SizeBudget::parse("max_functions = \"many\"").is_err());
+}
@@ -0,0 +1,30 @@
+//! Tests of encoding symbolic expressions into SMT-LIB terms.
+
+use inkwell::{context::Context, values::BasicValueEnum, IntPredicate};
+use llutil::{analysis::SymExpr, solver::SmtEncoder};
+
+#[test]
+fn test_encode_wide_integer_constant() {
+    let context = Context::create();
+    let module = context.create_module("wide");
+    let i128_type = context.i128_type();
+    let fn_type = i128_type.fn_type(&[i128_type.into()], false);
+    let func = module.add_function("f", fn_type, None);
+    let arg = func.get_nth_param(0).unwrap();
+
+    // 2^64 + 1 does not fit in 64 bits.
+    let wide = i128_type.const_int_arbitrary_precision(&[1, 1]);
+    let wide = SymExpr::from_value(BasicValueEnum::IntValue(wide));
+    assert!(matches!(wide, SymExpr::Var(_)));
+
+    let expr = SymExpr::Cmp(
+        IntPredicate::EQ,
+        Box::new(SymExpr::from_value(arg)),
+        Box::new(wide.clone()),
+    );
+    let mut encoder = SmtEncoder::new();
+    let term = encoder.encode_bool(&expr).unwrap();
+    assert_eq!(term, "(= sym_0 (_ bv18446744073709551617 128))");
+    assert_eq!(encoder.get_symbols().len(), 1);
+    assert!(encoder.get_symbol_name(&wide).is_none());
+}
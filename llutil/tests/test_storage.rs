@@ -0,0 +1,83 @@
+//! Tests of the storage model of Solang contracts.
+
+use inkwell::{context::Context, memory_buffer::MemoryBuffer, module::Module};
+use llutil::analysis::{StorageAccessKind, StorageKey, StorageModel, SymExpr};
+
+/// LLVM IR of a function writing and reading storage slots.
+const STORAGE_IR: &str = r#"
+declare void @storageLoad(i8*, i8*)
+declare void @storageStore(i8*, i8*)
+declare void @hash(i8*)
+
+define void @slots(i256 %v) {
+entry:
+  %key1 = alloca i256
+  %val1 = alloca i256
+  store i256 7, i256* %key1
+  store i256 %v, i256* %val1
+  %k1 = bitcast i256* %key1 to i8*
+  %v1 = bitcast i256* %val1 to i8*
+  call void @storageStore(i8* %k1, i8* %v1)
+  %key2 = alloca i256
+  %out2 = alloca i256
+  store i256 7, i256* %key2
+  %k2 = bitcast i256* %key2 to i8*
+  %o2 = bitcast i256* %out2 to i8*
+  call void @storageLoad(i8* %k2, i8* %o2)
+  %key3 = alloca i256
+  %out3 = alloca i256
+  store i256 8, i256* %key3
+  %k3 = bitcast i256* %key3 to i8*
+  %o3 = bitcast i256* %out3 to i8*
+  call void @storageLoad(i8* %k3, i8* %o3)
+  %key4 = alloca i256
+  %out4 = alloca i256
+  store i256 7, i256* %key4
+  %k4 = bitcast i256* %key4 to i8*
+  call void @hash(i8* %k4)
+  %o4 = bitcast i256* %out4 to i8*
+  call void @storageLoad(i8* %k4, i8* %o4)
+  ret void
+}
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        STORAGE_IR.as_bytes(),
+        "slots",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+#[test]
+fn test_slot_keys_are_stored_values() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("slots").unwrap();
+    let blk = func.get_first_basic_block().unwrap();
+    let param = SymExpr::from_value(func.get_nth_param(0).unwrap());
+
+    let mut model = StorageModel::new();
+    let accesses = model.execute_block(blk);
+    assert_eq!(accesses.len(), 4);
+
+    // Slots written and read through different buffers holding the same key
+    // are the same slot.
+    assert_eq!(accesses[0].kind, StorageAccessKind::Write);
+    assert_eq!(accesses[0].value, param);
+    assert_eq!(accesses[1].kind, StorageAccessKind::Read);
+    assert_eq!(accesses[1].key, accesses[0].key);
+    assert_eq!(accesses[1].value, param);
+
+    // A different key is a different slot.
+    assert_ne!(accesses[2].key, accesses[0].key);
+    assert!(matches!(accesses[2].value, SymExpr::Memory(_)));
+
+    // The key buffer may be modified by the call to `hash`.
+    assert!(matches!(
+        accesses[3].key,
+        StorageKey::Slot(SymExpr::Memory(_))
+    ));
+    assert_eq!(model.get_written_keys(), vec![&accesses[0].key]);
+}
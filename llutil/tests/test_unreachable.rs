@@ -0,0 +1,145 @@
+//! Tests of propagating `unreachable` instructions backward.
+
+use inkwell::{
+    context::Context, memory_buffer::MemoryBuffer, module::Module,
+    values::AnyValue,
+};
+use llutil::normalize::unreachable::{
+    propagate_unreachable, propagate_unreachable_in_module, UnreachableReport,
+};
+
+/// LLVM IR of functions reaching `unreachable` instructions.
+const UNREACHABLE_IR: &str = r#"
+declare void @abort() noreturn
+declare void @log(i32)
+
+define i32 @check(i32 %a) {
+entry:
+  %c = icmp sgt i32 %a, 0
+  br i1 %c, label %ok, label %fail
+fail:
+  call void @abort()
+  %x = add i32 %a, 1
+  call void @log(i32 %x)
+  br label %ok
+ok:
+  ret i32 %a
+}
+
+define void @dead_end(i1 %c) {
+entry:
+  br i1 %c, label %left, label %right
+left:
+  br label %end
+right:
+  br label %end
+end:
+  unreachable
+}
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(
+        UNREACHABLE_IR.as_bytes(),
+        "unreachable",
+    );
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Print the blocks of a function, each as its name and its instructions.
+fn print_blocks(
+    module: &Module,
+    func_name: &str,
+) -> Vec<(String, Vec<String>)> {
+    let func = module.get_function(func_name).unwrap();
+    func.get_basic_blocks()
+        .into_iter()
+        .map(|blk| {
+            let insts = blk
+                .get_instructions()
+                .into_iter()
+                .map(|inst| inst.print_to_string().to_string())
+                .collect();
+            (blk.get_name().to_string_lossy().to_string(), insts)
+        })
+        .collect()
+}
+
+#[test]
+fn test_truncate_after_noreturn_call() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("check").unwrap();
+
+    let report = propagate_unreachable(func);
+    assert_eq!(
+        report,
+        UnreachableReport {
+            function: "check".to_string(),
+            num_truncated_blocks: 1,
+            num_folded_branches: 0,
+            num_removed_blocks: 0,
+        }
+    );
+
+    // The call to `abort` has side effects, hence the branch to its block is
+    // not folded.
+    assert_eq!(
+        print_blocks(&module, "check"),
+        vec![
+            (
+                "entry".to_string(),
+                vec![
+                    "%c = icmp sgt i32 %a, 0".to_string(),
+                    "br i1 %c, label %ok, label %fail".to_string()
+                ]
+            ),
+            (
+                "fail".to_string(),
+                vec![
+                    "call void @abort()".to_string(),
+                    "unreachable".to_string()
+                ]
+            ),
+            ("ok".to_string(), vec!["ret i32 %a".to_string()]),
+        ]
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_fold_branches_to_unreachable() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("dead_end").unwrap();
+
+    // Branches of `left` and `right` are folded first, then the branch of
+    // `entry`, which then reaches `unreachable` on both successors.
+    let report = propagate_unreachable(func);
+    assert_eq!(report.num_truncated_blocks, 0);
+    assert_eq!(report.num_folded_branches, 3);
+    assert_eq!(report.num_removed_blocks, 3);
+    assert_eq!(
+        print_blocks(&module, "dead_end"),
+        vec![("entry".to_string(), vec!["unreachable".to_string()])]
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_propagate_unreachable_in_module() {
+    let context = Context::create();
+    let module = parse_module(&context);
+
+    let reports = propagate_unreachable_in_module(&module);
+    let functions: Vec<&str> = reports
+        .iter()
+        .map(|report| report.function.as_str())
+        .collect();
+    assert_eq!(functions, vec!["check", "dead_end"]);
+
+    // The propagation reaches a fixpoint.
+    let reports = propagate_unreachable_in_module(&module);
+    assert!(reports.iter().all(|report| report.num_folded_branches == 0));
+}
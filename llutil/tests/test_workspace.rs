@@ -0,0 +1,119 @@
+//! Tests of the workspace owning contexts and modules of batch runs, and of
+//! the errors of bitcode files produced by newer LLVM versions.
+
+use inkwell::memory_buffer::MemoryBuffer;
+use llutil::{
+    ir::{code_file::EntryPoint, CodeFile},
+    workspace::{BitcodeVersionMismatch, WorkspaceContext},
+};
+
+/// IR file loaded into workspaces.
+const IR_FILE: &str = "tests/testcases/normalize/merge_and_fold.ll";
+
+/// LLVM IR of a module built in a workspace.
+const BUILT_IR: &str = r#"
+define i32 @built() {
+entry:
+  ret i32 0
+}
+"#;
+
+#[test]
+fn test_load_with_module_and_unload() {
+    let mut workspace = WorkspaceContext::new();
+    assert!(workspace.is_empty());
+
+    let code_file = CodeFile::new(IR_FILE, EntryPoint::AllFunctions);
+    let id = workspace.load(code_file).unwrap();
+    assert_eq!(workspace.len(), 1);
+    assert_eq!(workspace.get_code_file(id).unwrap().file_name, IR_FILE);
+
+    let names = workspace.with_module(id, |module, code_file, cache| {
+        assert_eq!(code_file.file_name, IR_FILE);
+        let func = module.get_function("sign").unwrap();
+        assert!(cache.get("test", &func).is_none());
+        module
+            .get_functions()
+            .map(|func| func.get_name().to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+    });
+    assert_eq!(names, Some(vec!["sign".to_string()]));
+
+    assert!(workspace.unload(id));
+    assert!(!workspace.unload(id));
+    assert!(workspace.is_empty());
+    assert!(workspace.with_module(id, |_, _, _| ()).is_none());
+    assert!(workspace.get_code_file(id).is_none());
+}
+
+#[test]
+fn test_load_modules_in_separate_contexts() {
+    let mut workspace = WorkspaceContext::new();
+
+    let code_file = CodeFile::new(IR_FILE, EntryPoint::AllFunctions);
+    let loaded_id = workspace.load(code_file).unwrap();
+    let code_file = CodeFile::new("built.ll", EntryPoint::AllFunctions);
+    let built_id = workspace
+        .load_with(code_file, |context| {
+            let buffer = MemoryBuffer::create_from_memory_range_copy(
+                BUILT_IR.as_bytes(),
+                "built",
+            );
+            context
+                .create_module_from_ir(buffer)
+                .map_err(|err| err.to_string())
+        })
+        .unwrap();
+    assert_ne!(loaded_id, built_id);
+    assert_eq!(workspace.get_module_ids(), vec![loaded_id, built_id]);
+
+    // Unloading a module keeps the other module usable.
+    assert!(workspace.unload(loaded_id));
+    let has_built = workspace.with_module(built_id, |module, _, _| {
+        module.get_function("built").is_some() && module.verify().is_ok()
+    });
+    assert_eq!(has_built, Some(true));
+    assert_eq!(workspace.get_module_ids(), vec![built_id]);
+}
+
+#[test]
+fn test_load_missing_file() {
+    let mut workspace = WorkspaceContext::new();
+
+    let code_file = CodeFile::new("missing.ll", EntryPoint::AllFunctions);
+    assert!(workspace.load(code_file).is_err());
+    assert!(workspace.is_empty());
+}
+
+#[test]
+fn test_bitcode_version_mismatch_from_parse_error() {
+    let message = "Unknown attribute kind (86) (Producer: 'LLVM17.0.6' \
+                   Reader: 'LLVM 14.0.6')";
+    let mismatch =
+        BitcodeVersionMismatch::from_parse_error("new.bc", message).unwrap();
+    assert_eq!(mismatch.file_name, "new.bc");
+    assert_eq!(mismatch.producer, "LLVM17.0.6");
+    assert_eq!(mismatch.reader, "LLVM 14.0.6");
+    assert_eq!(mismatch.message, message);
+    assert_eq!(mismatch.get_producer_major(), Some(17));
+    assert!(mismatch.downgrade_error.is_none());
+    assert!(mismatch
+        .to_string()
+        .starts_with("Bitcode file new.bc is produced by LLVM17.0.6"));
+}
+
+#[test]
+fn test_bitcode_version_mismatch_needs_newer_producer() {
+    // The producer is older than the reader.
+    let message =
+        "Invalid record (Producer: 'LLVM12.0.1' Reader: 'LLVM 14.0.6')";
+    assert!(
+        BitcodeVersionMismatch::from_parse_error("old.bc", message).is_none()
+    );
+
+    // The producer is unknown.
+    let message = "Invalid bitcode signature";
+    assert!(
+        BitcodeVersionMismatch::from_parse_error("bad.bc", message).is_none()
+    );
+}
@@ -0,0 +1,154 @@
+//! Tests of weakest preconditions across straight-line code.
+
+use inkwell::{
+    context::Context,
+    memory_buffer::MemoryBuffer,
+    module::Module,
+    values::{BasicValueEnum, FunctionValue, InstructionOpcode},
+    IntPredicate,
+};
+use llutil::{
+    analysis::{SymExpr, WpGenerator},
+    ir::InstructionExt,
+};
+
+/// LLVM IR of functions with straight-line blocks.
+const WP_IR: &str = r#"
+declare void @escape(i32*)
+
+define i32 @block(i32 %a, i32 %b) {
+entry:
+  %x = alloca i32
+  store i32 %a, i32* %x
+  %v = load i32, i32* %x
+  %s = add i32 %v, %b
+  %c = icmp sgt i32 %s, 10
+  ret i32 %s
+}
+
+define i32 @escaping(i32 %a) {
+entry:
+  %x = alloca i32
+  store i32 %a, i32* %x
+  call void @escape(i32* %x)
+  %v = load i32, i32* %x
+  ret i32 %v
+}
+
+define i32 @path(i32 %a) {
+entry:
+  %c = icmp ult i32 %a, 5
+  br i1 %c, label %then, label %else
+then:
+  %r = add i32 %a, 1
+  ret i32 %r
+else:
+  ret i32 0
+}
+"#;
+
+/// Parse the test IR into a module.
+fn parse_module(context: &Context) -> Module {
+    let buffer =
+        MemoryBuffer::create_from_memory_range_copy(WP_IR.as_bytes(), "wp");
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+/// Get the value defined by the instruction of a name in a function.
+fn get_value<'ctx>(
+    func: FunctionValue<'ctx>,
+    name: &str,
+) -> BasicValueEnum<'ctx> {
+    func.get_basic_blocks()
+        .into_iter()
+        .flat_map(|blk| blk.get_instructions())
+        .find(|inst| {
+            inst.get_name().and_then(|n| n.to_str().ok()) == Some(name)
+        })
+        .and_then(|inst| inst.try_into_basic_value_enum())
+        .unwrap()
+}
+
+/// Get a parameter of a function as a symbolic expression.
+fn get_param(func: FunctionValue, index: u32) -> SymExpr {
+    SymExpr::Var(func.get_nth_param(index).unwrap())
+}
+
+#[test]
+fn test_block_wp_substitutes_definitions() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("block").unwrap();
+    let generator = WpGenerator::new(func);
+
+    let x = get_value(func, "x").into_pointer_value();
+    assert!(generator.is_non_escaping_alloca(x));
+
+    // The postcondition `%c` becomes `(%a + %b) > 10` through the alloca.
+    let post = SymExpr::Var(get_value(func, "c"));
+    let blk = func.get_first_basic_block().unwrap();
+    let wp = generator.compute_block_wp(blk, post);
+    let sum = SymExpr::Binary(
+        InstructionOpcode::Add,
+        Box::new(get_param(func, 0)),
+        Box::new(get_param(func, 1)),
+    );
+    assert_eq!(
+        wp,
+        SymExpr::Cmp(
+            IntPredicate::SGT,
+            Box::new(sum),
+            Box::new(SymExpr::Int(10, 32))
+        )
+    );
+}
+
+#[test]
+fn test_block_wp_keeps_escaping_memory() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("escaping").unwrap();
+    let generator = WpGenerator::new(func);
+
+    let x = get_value(func, "x").into_pointer_value();
+    assert!(!generator.is_non_escaping_alloca(x));
+
+    // The load from an escaping alloca is not modelled.
+    let v = SymExpr::Var(get_value(func, "v"));
+    let blk = func.get_first_basic_block().unwrap();
+    assert_eq!(generator.compute_block_wp(blk, v.clone()), v);
+}
+
+#[test]
+fn test_path_wp_assumes_branch_conditions() {
+    let context = Context::create();
+    let module = parse_module(&context);
+    let func = module.get_function("path").unwrap();
+    let generator = WpGenerator::new(func);
+
+    let blks = func.get_basic_blocks();
+    let post = SymExpr::Cmp(
+        IntPredicate::ULT,
+        Box::new(SymExpr::Var(get_value(func, "r"))),
+        Box::new(SymExpr::Int(6, 32)),
+    );
+    let wp = generator.compute_path_wp(&blks[..2], post);
+
+    let a = get_param(func, 0);
+    let cond = SymExpr::Cmp(
+        IntPredicate::ULT,
+        Box::new(a.clone()),
+        Box::new(SymExpr::Int(5, 32)),
+    );
+    let sum = SymExpr::Binary(
+        InstructionOpcode::Add,
+        Box::new(a),
+        Box::new(SymExpr::Int(1, 32)),
+    );
+    let post = SymExpr::Cmp(
+        IntPredicate::ULT,
+        Box::new(sum),
+        Box::new(SymExpr::Int(6, 32)),
+    );
+    assert_eq!(wp, SymExpr::Implies(Box::new(cond), Box::new(post)));
+}
@@ -0,0 +1,21 @@
+; Fixture of the normalization pipeline: the constant branch of `check` is
+; folded, the block `dead` is removed, and the returns are merged.
+source_filename = "merge_and_fold.c"
+
+define i32 @sign(i32 %x) {
+entry:
+  %0 = icmp slt i32 %x, 0
+  br i1 %0, label %negative, label %check
+
+check:
+  br i1 true, label %positive, label %dead
+
+negative:
+  ret i32 -1
+
+positive:
+  ret i32 1
+
+dead:
+  ret i32 0
+}
@@ -0,0 +1,21 @@
+; ModuleID = 'tests/testcases/normalize/merge_and_fold.ll'
+source_filename = "merge_and_fold.c"
+
+define i32 @sign(i32 %x) {
+entry:
+  %0 = icmp slt i32 %x, 0
+  br i1 %0, label %negative, label %check
+
+check:                                            ; preds = %entry
+  br label %positive
+
+positive:                                         ; preds = %check
+  br label %unified.return
+
+negative:                                         ; preds = %entry
+  br label %unified.return
+
+unified.return:                                   ; preds = %positive, %negative
+  %unified.retval = phi i32 [ -1, %negative ], [ 1, %positive ]
+  ret i32 %unified.retval
+}
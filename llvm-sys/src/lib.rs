@@ -486,6 +486,20 @@ pub const LLVMAttributeFunctionIndex: ::libc::c_uint = !0; // -1
 /// number from 1 to N.
 pub type LLVMAttributeIndex = ::libc::c_uint;
 
+/// Bitmask of fast-math flags of a floating point instruction, using the
+/// `LLVMFastMath*` constants.
+pub type LLVMFastMathFlags = ::libc::c_uint;
+
+pub const LLVMFastMathAllowReassoc: LLVMFastMathFlags = 1 << 0;
+pub const LLVMFastMathNoNaNs: LLVMFastMathFlags = 1 << 1;
+pub const LLVMFastMathNoInfs: LLVMFastMathFlags = 1 << 2;
+pub const LLVMFastMathNoSignedZeros: LLVMFastMathFlags = 1 << 3;
+pub const LLVMFastMathAllowReciprocal: LLVMFastMathFlags = 1 << 4;
+pub const LLVMFastMathAllowContract: LLVMFastMathFlags = 1 << 5;
+pub const LLVMFastMathApproxFunc: LLVMFastMathFlags = 1 << 6;
+pub const LLVMFastMathNone: LLVMFastMathFlags = 0;
+pub const LLVMFastMathAll: LLVMFastMathFlags = 0x7f;
+
 pub type LLVMDiagnosticHandler = Option<
     extern "C" fn(arg1: LLVMDiagnosticInfoRef, arg2: *mut ::libc::c_void),
 >;